@@ -0,0 +1,231 @@
+;; V2EX TUI configuration
+;; ~/.config/v2ex/config.lisp
+;;
+;; This file was generated on first run. Delete it to restore the defaults.
+
+;; ============================================
+;; Auto-refresh
+;; ============================================
+
+;; Refresh the current list view every N seconds (0 = off)
+(set! auto-refresh-interval 0)
+
+;; Per-view overrides: notifications, topic-list, aggregate, and
+;; topic-detail, which only updates the reply, click, favorite and thank
+;; counts of the open topic
+;; (set-refresh-interval 'notifications 60)
+;; (set-refresh-interval 'topic-list 600)
+;; (set-refresh-interval 'aggregate 300)
+;; (set-refresh-interval 'topic-detail 120)
+
+;; ============================================
+;; Topic lists
+;; ============================================
+
+;; Leave topics you have already opened out of topic lists
+(set! hide-read-topics #f)
+
+;; Quietly load the first page of each quick-switch node (1-9) after
+;; startup so switching to them is instant. Requests are spaced out and
+;; stop when the API budget runs low.
+(set! prefetch-favorite-nodes #f)
+
+;; Fetch the next page of topics or replies in the background once the
+;; selection is this many items from the end (0 = wait until n reaches it)
+(set! load-ahead 0)
+
+;; Wrap titles too long for the window onto a second, indented line
+;; instead of cutting them off
+(set! wrap-topic-titles #f)
+
+;; Columns of each topic list row, left to right: heat, unread, node,
+;; title, author, replies, created and last-touched. The title takes the
+;; width left over; on narrow terminals columns are hidden from the right.
+(set! topic-list-columns '(heat node title replies created))
+
+;; Lines of content shown when previewing a topic with SPC in the topic list
+(set! topic-preview-lines 12)
+
+;; ============================================
+;; Aggregated topics
+;; ============================================
+
+;; Show a tab fetched less than this many seconds ago again instead of
+;; fetching it when switching back to it (0 = always fetch)
+(set! aggregate-cache-ttl 300)
+
+;; Tabs fetched in the background, all at once, along with the one shown
+;; so switching to them is instant. Empty means the tabs on either side of
+;; it in the header.
+;; (set! aggregate-prefetch-tabs '(tech hot latest))
+(set! aggregate-prefetch-tabs '())
+
+;; Look up the topics of an RSS tab in the v1 API to show their nodes,
+;; when the feed has no categories, and when they were last replied to
+;; (reply counts come with the feed). Only topics on screen are looked
+;; up, each once a session, but that is still one request per topic and
+;; the v1 API allows about 120 an hour.
+(set! aggregate-resolve-topics #f)
+
+;; Extra aggregate tabs from any RSS/Atom feed: key, tab name and URL.
+;; URLs starting with / are on the site. Keys of built-in tabs and of the
+;; aggregate view itself are taken. Add feeds before naming them in
+;; aggregate-prefetch-tabs.
+;; (add-feed "w" 'rust "/feed/rust.xml")
+;; (add-feed "P" "planet" "https://planet.example.com/atom.xml")
+
+;; ============================================
+;; Notifications
+;; ============================================
+
+;; Check for new notifications every N seconds in the background (0 = off)
+(set! notification-poll-interval 300)
+
+;; Also show a desktop notification when new ones arrive
+(set! desktop-notifications #f)
+
+;; ============================================
+;; Display
+;; ============================================
+
+;; Colour scheme: dark, light, solarized or gruvbox (C-t cycles at runtime)
+(set! theme 'dark)
+
+;; Override single colours of any theme with #rrggbb or a colour name.
+;; Slots: primary secondary accent foreground background muted error canvas
+;;        op-badge thanks selection selection-text
+;; (set-theme-color 'accent "#fe8019")
+;; (set-theme-color 'canvas "reset")   ; keep the terminal background
+
+;; For slow SSH links: ASCII borders, no colours (the selection is shown
+;; reversed) and at most one redraw every 200ms
+(set! low-bandwidth #f)
+
+;; Command that reads text from stdin aloud, used by R in topic detail.
+;; It runs through the shell; output is discarded. Empty disables it.
+;; (set! tts-command "espeak")       ; Linux
+;; (set! tts-command "say")          ; macOS
+(set! tts-command "")
+
+;; Command that reads text from stdin and prints its translation, used by
+;; L in topic detail. It runs through the shell. Empty disables it.
+;; (set! translate-command "trans -b :en")   ; translate-shell
+(set! translate-command "")
+
+;; Look up the titles of GitHub, YouTube and Twitter links in the post
+;; being read and show them next to the link mode labels. Titles are
+;; cached in ~/.cache/v2ex/link-titles.json, the latest 500 of them.
+(set! unfurl-links #f)
+
+;; Topic detail with replies open: the topic pane's share in percent
+;; (20-80), replies first instead of the topic, and always one pane above
+;; the other. C-w < > x s = adjust these at runtime.
+(set! split-ratio 40)
+(set! split-swapped #f)
+(set! split-stacked #f)
+
+;; Width of the text column in focus mode (z in topic detail), which hides
+;; headers, borders and the status bar (0 = full width)
+(set! focus-width 80)
+
+;; Save each topic you favorite (F) with its replies as v2ex-<id>.md in
+;; this directory, so it survives the topic being deleted (empty = off)
+(set! favorite-archive-dir "")
+
+;; Where D (export-topic) saves the open topic with all of its replies.
+;; {id}, {node} and {title} are filled in and ~/ is expanded; a path
+;; ending in .html is written as HTML, anything else as Markdown.
+(set! export-path "v2ex-{id}.md")
+
+;; Status bar text. {message} is the usual status message; {view},
+;; {node}, {page}, {unread} (notifications) and {time} are filled in too.
+;; Empty shows just the message.
+(set! status-format "")
+
+;; How reply, click and topic counts are shortened in lists and headers:
+;; western (1.2k, 3.4M) or chinese (1.2万, 3.4亿)
+(set! count-format 'western)
+
+;; Retry requests that failed with a network or server (5xx) error,
+;; waiting retry-base-delay ms and doubling the wait each time up to
+;; retry-max-delay ms. Auth errors and rate limiting are not retried.
+(set! retry-max-attempts 3)
+(set! retry-base-delay 500)
+(set! retry-max-delay 8000)
+;; Randomise the waits a little so retries don't arrive all at once
+(set! retry-jitter #t)
+
+;; User-Agent sent with every request, e.g. so a mirror's admin knows who
+;; to contact. Empty sends the built-in one. Read at startup.
+;; (set! user-agent "v2ex-tui (you@example.org)")
+(set! user-agent "")
+;; Keep at least this many ms between any two requests, API or not
+;; (0 = only the built-in rate limiting). Read at startup.
+(set! request-delay 0)
+
+;; Mirrors and self-hosted gateways: where the API (v2 under v2/, v1
+;; right under it) and the RSS feeds and topic pages are served. Empty
+;; uses www.v2ex.com. Read at startup.
+;; (set! api-base-url "https://v2ex.example.org/api")
+;; (set! site-base-url "https://v2ex.example.org")
+(set! api-base-url "")
+(set! site-base-url "")
+
+;; Never touch the network and only show the archived favorites above
+;; and topics cached in ~/.cache/v2ex, e.g. on a plane (same as starting
+;; with --offline)
+;; (offline t)
+
+;; Nodes listed in the topic list header; the first nine are switched to
+;; with 1-9 (this is the default list), and 0 lists the newest topics of
+;; all of them together
+(set! favorite-nodes '(python programmer share create jobs go rust javascript linux))
+
+;; Bind another key (one not already used in the topic list) to a node,
+;; or point a number key at a different one
+;; (set-quick-node-key "Q" 'qna)
+
+;; Collapse topics and replies by these users into a one-line
+;; "[blocked: username]" placeholder; B reveals them for the session
+;; (ignore-user "username" "another")
+
+;; ============================================
+;; Key bindings
+;; ============================================
+
+;; (define-key KEYMAP KEYS ACTION) binds Emacs-style KEYS ("C-x C-s",
+;; "M-v", "SPC", "<f5>") in one view, or everywhere with 'global.
+;; ACTION is an action name or a string of built-in keys to replay.
+;; Keymaps: global topic-list topic-detail notifications profile help
+;;          node-select aggregate reply-input favorites member history
+;;          bookmarks tokens
+;;
+;; While a multi-key sequence is being typed the status bar echoes it
+;; ("C-x -"); C-g cancels it.
+;; Milliseconds to wait for the next key of a sequence (0 = wait forever)
+(set! key-sequence-timeout 2000)
+
+;; (define-key 'global "j" 'next)
+;; (define-key 'global "k" 'previous)
+;; (define-key 'reply-input "C-x C-s" 'send-reply)
+;; (define-key 'topic-detail "C-c C-o" "o")
+
+;; (define-action NAME STEPS...) adds an action running built-in actions
+;; and (lambda () FORMS...) in turn; the forms are those allowed in hooks
+;; below. Define it before binding it with define-key.
+;; (define-action 'open-first 'first 'open)
+;; (define-key 'topic-list "C-c f" 'open-first)
+
+;; ============================================
+;; Hooks
+;; ============================================
+
+;; (on-startup FORMS...) runs FORMS once the app has started and
+;; (on-topic-open FORMS...) after each topic is opened, with topic-id,
+;; topic-title, topic-node and topic-author bound. Hooks can use
+;; (message "text"), (open-node 'name), (run-action 'action) (pressing
+;; the action's default key in the current view), if, equal and concat.
+;; (on-startup (open-node 'rust))
+;; (on-topic-open
+;;   (if (equal topic-node "qna")
+;;       (message (concat "Q&A: " topic-title))))
@@ -2,94 +2,63 @@
 ;; ~/.config/v2ex/config.lisp
 
 ;; ============================================
-;; Platform-Specific Settings
+;; Layout
 ;; ============================================
 
-(when (eq system-type 'macos)
-  ;; macOS-specific browser
-  (set-browser "open")
-  
-  ;; macOS specific key
-  (bind-global "M-o" 'open-in-browser))
+;; Columns of the topic list, left to right
+(set! topic-list-columns '(heat unread node title author replies))
 
-(when (eq system-type 'linux)
-  ;; Check for specific terminal
-  (when (string=? (getenv "TERM") "xterm-kitty")
-    (set-image-protocol 'kitty)
-    (set-inline-images #t)))
+;; Replies below the topic, taking two thirds of the height
+(set! split-stacked #t)
+(set! split-ratio 33)
 
 ;; ============================================
-;; Custom Functions
+;; Extra Aggregate Tabs
 ;; ============================================
 
-;; Quick navigation to first unread
-(define (goto-first-unread)
-  (first-topic)
-  ;; Custom logic could go here
-  (open-topic))
-
-;; Smart load: load more if at end, else just move
-(define (smart-next)
-  (if (at-last-topic?)
-      (load-more-topics)
-      (next-topic)))
-
-;; Open and mark as read (custom workflow)
-(define (open-and-mark)
-  (open-topic)
-  ;; Future: mark as read functionality
-  )
+(add-feed "w" 'rust "/feed/rust.xml")
+(set! aggregate-prefetch-tabs '(tech rust))
 
 ;; ============================================
-;; Complex Keybindings
+;; Custom Actions
 ;; ============================================
 
-;; Leader key style (like Vim)
-(define-key "SPC t" 'toggle-replies)
-(define-key "SPC o" 'open-in-browser)
-(define-key "SPC r" 'refresh)
-(define-key "SPC n" 'go-to-notifications)
-(define-key "SPC p" 'go-to-profile)
-(define-key "SPC a" 'go-to-aggregate)
+;; Open the first topic of the list
+(define-action 'open-first 'first 'open)
 
-;; Quick save/load bookmarks (future feature)
-(define-key "C-c b s" 'save-bookmark)
-(define-key "C-c b l" 'load-bookmark)
-(define-key "C-c b b" 'list-bookmarks)
+;; Refresh and say so
+(define-action 'loud-refresh
+  'refresh
+  (lambda () (message "Refreshing")))
 
 ;; ============================================
-;; Mode-Specific Configurations
+;; Complex Keybindings
 ;; ============================================
 
-(with-view 'topic-detail
-  ;; Custom link selection with additional keys
-  (with-mode 'link-selection
-    ;; Numbers for quick access
-    (bind "1" '(link-select "a"))
-    (bind "2" '(link-select "o"))
-    (bind "3" '(link-select "e"))
-    ;; Keep Esc to exit
-    (bind "Esc" 'exit-link-mode))
-  
-  ;; Enhanced replies mode
-  (with-mode 'replies
-    ;; Vote up/down (future feature)
-    (bind "+" 'upvote-reply)
-    (bind "-" 'downvote-reply)
-    ;; Reply to specific comment
-    (bind "r" 'reply-to-comment)))
+;; Leader key style, on C-x since no view uses it
+(define-key 'global "C-x t" 'toggle-replies)
+(define-key 'global "C-x o" 'open-in-browser)
+(define-key 'global "C-x r" 'loud-refresh)
+(define-key 'global "C-x n" 'notifications)
+(define-key 'global "C-x p" 'profile)
+(define-key 'global "C-x a" 'aggregate)
+(define-key 'topic-list "C-x f" 'open-first)
+
+;; Bookmarks
+(define-key 'global "C-x b s" 'bookmark)
+(define-key 'global "C-x b b" 'bookmarks)
+
+;; Replay built-in keys: M-RET opens the topic in the browser like o
+(define-key 'topic-detail "M-RET" "o")
 
 ;; ============================================
-;; Conditional Keybindings
+;; Hooks
 ;; ============================================
 
-;; Different bindings for different times of day
-;; (just for fun/example)
-(define (is-evening?)
-  (>= (current-hour) 18))
+;; Start in the Rust node
+(on-startup (open-node 'rust))
 
-(when (is-evening?)
-  ;; Evening mode: darker theme
-  (set-custom-theme
-    '((foreground "#a89984")
-      (background "#1d2021"))))
+;; Point out Q&A topics as they are opened
+(on-topic-open
+  (if (equal topic-node 'qna)
+      (message (concat "Q&A: " topic-title))))
@@ -5,53 +5,41 @@
 ;; Personal Settings
 ;; ============================================
 
-;; Start in notifications view
-(set! initial-view 'notifications)
+;; Refresh the topic list every ten minutes, notifications every minute
+(set! auto-refresh-interval 600)
+(set-refresh-interval 'notifications 60)
 
-;; Load 30 items per page
-(set! topics-per-page 30)
-(set! replies-per-page 30)
+;; Dark theme with an orange accent
+(set! theme 'dark)
+(set-theme-color 'accent "#fe8019")
 
-;; Use Firefox for links
-(set-browser "firefox")
-
-;; Dark theme
-(set-theme 'dark)
-
-;; Relative timestamps ("2 hours ago")
-(set-timestamp-format 'relative)
+;; Leave topics already read out of the lists
+(set! hide-read-topics #t)
 
 ;; ============================================
 ;; My Favorite Nodes
 ;; ============================================
 
-(set-favorite-nodes
-  '((rust "Rust")
-    (go "Go 编程语言")
-    (python "Python")
-    (linux "Linux")
-    (jobs "酷工作")))
+;; 1-5 switch to these
+(set! favorite-nodes '(rust go python linux jobs))
 
-(set-quick-keys
-  '(1 rust 2 go 3 python 4 linux 5 jobs))
+;; And Q to the Q&A node
+(set-quick-node-key "Q" 'qna)
 
 ;; ============================================
 ;; Key Bindings
 ;; ============================================
 
-;; Reload config quickly
-(bind-global "C-c C-r" 'reload-config)
-
 ;; Topic list
-(with-view 'topic-list
-  (bind "j" 'next-topic)      ; Vim-style
-  (bind "k" 'previous-topic)) ; Vim-style
+(define-key 'topic-list "j" 'next)        ; Vim-style
+(define-key 'topic-list "k" 'previous)    ; Vim-style
 
 ;; Topic detail
-(with-view 'topic-detail
-  ;; Vim-style navigation
-  (bind "j" 'next-reply)
-  (bind "k" 'previous-reply)
-  
-  ;; Quick open in browser
-  (bind "O" 'open-in-browser))
+(define-key 'topic-detail "j" 'next)
+(define-key 'topic-detail "k" 'previous)
+
+;; Quick open in browser
+(define-key 'topic-detail "O" 'open-in-browser)
+
+;; Send a reply the Emacs way
+(define-key 'reply-input "C-x C-s" 'send-reply)
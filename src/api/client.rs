@@ -0,0 +1,622 @@
+//! Request plumbing shared by every endpoint: authentication, retries,
+//! rate limiting and turning responses into values or errors.
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+use reqwest::{Method, StatusCode};
+use std::path::PathBuf;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use super::{ApiResponse, OfflineError, PartialList, V2exError};
+use crate::retry::{jitter_seed, should_retry, RetryNotice, RetryPolicy, RetryTracker};
+use crate::scheduler::{Priority, RateLimit, RequestScheduler};
+
+/// Where both APIs live unless `api-base-url` points elsewhere. The v2
+/// API is under `v2/`; the public v1 API, used for lookups the v2 API does
+/// not offer, is right under it.
+pub const DEFAULT_API_BASE: &str = "https://www.v2ex.com/api";
+/// Host of the RSS feeds and topic pages unless `site-base-url` says otherwise
+pub const DEFAULT_SITE_BASE: &str = "https://www.v2ex.com";
+
+/// Which API an endpoint belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Api {
+    /// Token-authenticated, rate limited, wraps results in `ApiResponse`
+    V2,
+    /// Unauthenticated, returns bare JSON and reports errors in the body
+    V1,
+}
+
+/// One API call. The endpoint modules build these from typed arguments so
+/// paths and parameters can be checked without a network.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Endpoint {
+    pub method: Method,
+    api: Api,
+    pub path: String,
+    pub query: Vec<(&'static str, String)>,
+    /// Whether a failed attempt may be made again
+    pub retried: bool,
+}
+
+impl Endpoint {
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            api: Api::V2,
+            path: path.into(),
+            query: Vec::new(),
+            retried: true,
+        }
+    }
+
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(Method::GET, path)
+    }
+
+    pub fn post(path: impl Into<String>) -> Self {
+        Self::new(Method::POST, path)
+    }
+
+    pub fn delete(path: impl Into<String>) -> Self {
+        Self::new(Method::DELETE, path)
+    }
+
+    /// GET from the v1 API
+    pub fn v1(path: impl Into<String>) -> Self {
+        Self {
+            api: Api::V1,
+            ..Self::get(path)
+        }
+    }
+
+    pub fn query(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.query.push((name, value.to_string()));
+        self
+    }
+
+    /// Send it once, however it fails, for requests the user should see
+    /// fail rather than wait on
+    pub fn once(mut self) -> Self {
+        self.retried = false;
+        self
+    }
+
+    /// Page number of a paginated list, starting at 1
+    pub fn page(self, page: i32) -> Self {
+        self.query("p", page)
+    }
+
+    /// The endpoint's address under the API at `api_base`
+    pub fn url(&self, api_base: &str) -> String {
+        match self.api {
+            Api::V2 => format!("{}/v2/{}", api_base, self.path),
+            Api::V1 => format!("{}/{}", api_base, self.path),
+        }
+    }
+}
+
+/// User-Agent sent unless `user-agent` sets another
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (compatible; v2ex-tui/0.1.0)";
+
+#[derive(Clone)]
+pub struct V2exClient {
+    /// Shared by every handle, so switching accounts reaches them all
+    token: Arc<RwLock<String>>,
+    /// `Cookie` header of a signed-in browser session, for the website
+    /// features the API lacks
+    cookie: Option<String>,
+    client: reqwest::Client,
+    user_agent: String,
+    api_base: String,
+    site_base: String,
+    scheduler: Arc<RequestScheduler>,
+    priority: Priority,
+    /// Refuse every request instead of touching the network
+    offline: bool,
+    retry: RetryPolicy,
+    retries: Arc<RetryTracker>,
+}
+
+impl V2exClient {
+    pub fn new(token: String) -> Self {
+        Self {
+            token: Arc::new(RwLock::new(token)),
+            cookie: None,
+            client: http_client(DEFAULT_USER_AGENT),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            api_base: DEFAULT_API_BASE.to_string(),
+            site_base: DEFAULT_SITE_BASE.to_string(),
+            scheduler: Arc::new(RequestScheduler::new()),
+            priority: Priority::Interactive,
+            offline: false,
+            retry: RetryPolicy::default(),
+            retries: Arc::new(RetryTracker::default()),
+        }
+    }
+
+    /// Client that retries transient failures according to `policy`
+    pub fn with_retry(self, policy: RetryPolicy) -> Self {
+        Self {
+            retry: policy,
+            ..self
+        }
+    }
+
+    /// Client that identifies itself as `user_agent` to every site
+    pub fn with_user_agent(self, user_agent: &str) -> Self {
+        Self {
+            client: http_client(user_agent),
+            user_agent: user_agent.to_string(),
+            ..self
+        }
+    }
+
+    /// Client for a mirror or gateway serving both APIs under `api_base`
+    pub fn with_api_base(self, api_base: &str) -> Self {
+        Self {
+            api_base: api_base.trim_end_matches('/').to_string(),
+            ..self
+        }
+    }
+
+    /// Client fetching the RSS feeds and topic pages from `site_base`
+    pub fn with_site_base(self, site_base: &str) -> Self {
+        Self {
+            site_base: site_base.trim_end_matches('/').to_string(),
+            ..self
+        }
+    }
+
+    /// Handle sending `token` instead, e.g. to look at another account's
+    /// token, leaving this one and its other handles alone
+    pub fn with_token(&self, token: &str) -> Self {
+        Self {
+            token: Arc::new(RwLock::new(token.to_string())),
+            ..self.clone()
+        }
+    }
+
+    /// Send `token` from now on, from this and every other handle
+    /// A panic elsewhere can't leave the token half-written, so a poisoned
+    /// lock still holds a usable one
+    pub fn set_token(&self, token: &str) {
+        *self.token.write().unwrap_or_else(PoisonError::into_inner) = token.to_string();
+    }
+
+    pub fn token(&self) -> String {
+        self.token
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Client signed in to the website with a browser's `cookie`
+    pub fn with_cookie(self, cookie: &str) -> Self {
+        Self {
+            cookie: Some(cookie.to_string()),
+            ..self
+        }
+    }
+
+    /// Client that sends requests at least `delay` apart, across every
+    /// handle made from it afterwards
+    pub fn with_request_delay(self, delay: Duration) -> Self {
+        Self {
+            scheduler: Arc::new(RequestScheduler::with_spacing(delay)),
+            ..self
+        }
+    }
+
+    /// Client that fails every request with a clear message, for `--offline`
+    pub fn offline(self) -> Self {
+        Self {
+            offline: true,
+            ..self
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub(super) fn ensure_online(&self) -> Result<()> {
+        if self.offline {
+            return Err(OfflineError.into());
+        }
+        Ok(())
+    }
+
+    /// Handle for automatic work; shares the rate limit budget but yields
+    /// the reserve to user-initiated requests
+    pub fn background(&self) -> Self {
+        Self {
+            priority: Priority::Background,
+            ..self.clone()
+        }
+    }
+
+    /// Whether this handle's next API request would be admitted
+    pub fn can_schedule(&self) -> bool {
+        !self.offline && self.scheduler.can_schedule(self.priority)
+    }
+
+    /// Remaining API budget, once a response has reported it
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.scheduler.rate_limit()
+    }
+
+    /// The request currently waiting to be retried, if any
+    pub fn retry_notice(&self) -> Option<RetryNotice> {
+        self.retries.pending()
+    }
+
+    pub fn load_token() -> Result<String> {
+        let config_dir = Self::config_dir()?;
+        let token_path = config_dir.join("token.txt");
+        let token = std::fs::read_to_string(&token_path)
+            .with_context(|| format!("Failed to read token from {:?}", token_path))?;
+        Ok(token.trim().to_string())
+    }
+
+    /// The browser session in `cookie.txt`, if there is one
+    pub fn load_cookie() -> Option<String> {
+        let cookie = std::fs::read_to_string(Self::config_dir().ok()?.join("cookie.txt")).ok()?;
+        let cookie = cookie.trim();
+        (!cookie.is_empty()).then(|| cookie.to_string())
+    }
+
+    pub fn config_dir() -> Result<PathBuf> {
+        let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
+        let config_dir = base_dirs.config_dir().join("v2ex");
+        std::fs::create_dir_all(&config_dir)?;
+        Ok(config_dir)
+    }
+
+    /// The HTTP client, for pages outside the API
+    pub(super) fn http(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    pub(super) fn cookie(&self) -> Option<&str> {
+        self.cookie.as_deref()
+    }
+
+    pub(super) fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Host of the RSS feeds and topic pages, without a trailing slash
+    pub fn site_base(&self) -> &str {
+        &self.site_base
+    }
+
+    /// Call a v2 endpoint and return the object in its `result`, naming
+    /// `what` was expected when there is none
+    pub(super) async fn fetch<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+        what: &str,
+    ) -> Result<T> {
+        let response: ApiResponse<T> = self.send(endpoint).await?;
+        response
+            .result
+            .ok_or_else(|| V2exError::Decode(format!("No {} data in response", what)).into())
+    }
+
+    /// Call a list endpoint of either API, keeping the items that parse
+    pub(super) async fn fetch_list<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+    ) -> Result<PartialList<T>> {
+        let values = match endpoint.api {
+            Api::V2 => {
+                let response: ApiResponse<Vec<serde_json::Value>> = self.send(endpoint).await?;
+                response.result.unwrap_or_default()
+            }
+            Api::V1 => serde_json::from_value(self.fetch_v1(endpoint).await?)
+                .map_err(|e| V2exError::Decode(format!("Expected a list: {}", e)))?,
+        };
+        Ok(PartialList::from_values(values))
+    }
+
+    pub(super) async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+    ) -> Result<ApiResponse<T>> {
+        self.send_with_optional_body::<T, ()>(endpoint, None).await
+    }
+
+    pub(super) async fn send_with_body<T: serde::de::DeserializeOwned, B: serde::Serialize>(
+        &self,
+        endpoint: &Endpoint,
+        body: &B,
+    ) -> Result<ApiResponse<T>> {
+        self.send_with_optional_body(endpoint, Some(body)).await
+    }
+
+    /// GET from the unauthenticated v1 API
+    pub(super) async fn fetch_v1(&self, endpoint: &Endpoint) -> Result<serde_json::Value> {
+        self.ensure_online()?;
+        let url = endpoint.url(&self.api_base);
+        let request = self.client.get(&url).query(&endpoint.query);
+        let response = self.send_with_retry(request, false).await?;
+        let status = response.status();
+        let value: serde_json::Value = response.json().await.map_err(|e| {
+            V2exError::Decode(format!("Failed to parse response from {}: {}", url, e))
+        })?;
+        check_v1_response(status, value)
+    }
+
+    /// Send a request, retrying transient failures with exponential backoff.
+    /// Only GET and HEAD are retried once they may have reached the server;
+    /// other methods retry just failed connections, so nothing is posted
+    /// twice. `scheduled` requests draw from the rate limit budget on every
+    /// attempt.
+    pub(super) async fn send_with_retry(
+        &self,
+        request: reqwest::RequestBuilder,
+        scheduled: bool,
+    ) -> Result<reqwest::Response> {
+        self.send_attempts(request, scheduled, self.retry.max_attempts.max(1))
+            .await
+    }
+
+    /// `send_with_retry`, giving up after `max_attempts`
+    async fn send_attempts(
+        &self,
+        request: reqwest::RequestBuilder,
+        scheduled: bool,
+        max_attempts: u32,
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 1;
+        loop {
+            if scheduled {
+                self.scheduler.acquire(self.priority).await?;
+            }
+            self.scheduler.pace().await;
+            let this_attempt = request
+                .try_clone()
+                .context("Request body cannot be sent twice")?;
+            // Split so the request goes out through the client that built it
+            let (client, this_attempt) = this_attempt.build_split();
+            let this_attempt = this_attempt.map_err(V2exError::Network)?;
+            let method = this_attempt.method().clone();
+            let reason = match client.execute(this_attempt).await {
+                Ok(response)
+                    if attempt < max_attempts && should_retry(&method, Ok(response.status())) =>
+                {
+                    response.status().to_string()
+                }
+                Err(e) if attempt < max_attempts && should_retry(&method, Err(&e)) => e.to_string(),
+                result => {
+                    if attempt > 1 {
+                        self.retries.clear();
+                    }
+                    return result.map_err(|e| {
+                        let e = anyhow::Error::from(V2exError::Network(e));
+                        if attempt > 1 {
+                            e.context(format!("Gave up after {} attempts", attempt))
+                        } else {
+                            e
+                        }
+                    });
+                }
+            };
+
+            let delay = self.retry.delay(attempt, jitter_seed());
+            attempt += 1;
+            self.retries.set(RetryNotice {
+                attempt,
+                max_attempts,
+                reason,
+                retry_at: Instant::now() + delay,
+            });
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn send_with_optional_body<T: serde::de::DeserializeOwned, B: serde::Serialize>(
+        &self,
+        endpoint: &Endpoint,
+        body: Option<&B>,
+    ) -> Result<ApiResponse<T>> {
+        self.ensure_online()?;
+
+        let mut request = self
+            .client
+            .request(endpoint.method.clone(), endpoint.url(&self.api_base))
+            .query(&endpoint.query)
+            .header("Authorization", format!("Bearer {}", self.token()));
+
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .json(body);
+        }
+
+        let response = if endpoint.retried {
+            self.send_with_retry(request, true).await?
+        } else {
+            self.send_attempts(request, true, 1).await?
+        };
+        self.scheduler.update(response.headers());
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = self
+                .scheduler
+                .rate_limit()
+                .map(|rate_limit| Duration::from_secs(rate_limit.resets_in().max(0) as u64))
+                .or_else(|| retry_after(response.headers()));
+            return Err(V2exError::RateLimited { retry_after }.into());
+        }
+        let text = response.text().await.map_err(V2exError::Network)?;
+        parse_v2_response(status, &text)
+    }
+}
+
+/// HTTP client sending `user_agent`, which config checked is a valid
+/// header value
+fn http_client(user_agent: &str) -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(user_agent)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Seconds to wait from a `Retry-After` header, when given as a number
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Turn a v2 response body into an `ApiResponse`, failing on error statuses
+fn parse_v2_response<T: serde::de::DeserializeOwned>(
+    status: StatusCode,
+    text: &str,
+) -> Result<ApiResponse<T>> {
+    // Error pages aren't always JSON, so the status decides the error and
+    // the body only adds V2EX's explanation when it has one
+    if !status.is_success() {
+        let message = serde_json::from_str::<ApiResponse<serde_json::Value>>(text)
+            .ok()
+            .and_then(|response| response.message);
+        return Err(V2exError::from_status(status, message).into());
+    }
+
+    if text.trim().is_empty() {
+        return Err(
+            V2exError::Decode(format!("API returned empty response (status: {})", status)).into(),
+        );
+    }
+
+    serde_json::from_str(text).map_err(|e| {
+        V2exError::Decode(format!(
+            "Failed to parse API response: {}. Status: {}. Raw response: {}",
+            e,
+            status,
+            &text[..text.floor_char_boundary(500)]
+        ))
+        .into()
+    })
+}
+
+/// The v1 API reports errors as `{"status": "error", "message": ...}`
+fn check_v1_response(status: StatusCode, value: serde_json::Value) -> Result<serde_json::Value> {
+    if value.get("status").and_then(|s| s.as_str()) == Some("error") {
+        let message = value
+            .get("message")
+            .and_then(|m| m.as_str())
+            .map(str::to_string);
+        let error = match status {
+            _ if status.is_success() => V2exError::Api {
+                status: None,
+                message,
+            },
+            status => V2exError::from_status(status, message),
+        };
+        return Err(error.into());
+    }
+    if !status.is_success() {
+        return Err(V2exError::from_status(status, None).into());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::is_network_unavailable;
+
+    #[test]
+    fn test_endpoint_urls() {
+        let endpoint = Endpoint::get("nodes/rust/topics").page(2);
+        assert_eq!(
+            endpoint.url(DEFAULT_API_BASE),
+            "https://www.v2ex.com/api/v2/nodes/rust/topics"
+        );
+        assert_eq!(
+            endpoint.url("https://mirror.example.org/v2ex-api"),
+            "https://mirror.example.org/v2ex-api/v2/nodes/rust/topics"
+        );
+        assert_eq!(endpoint.query, [("p", "2".to_string())]);
+
+        let endpoint = Endpoint::v1("members/show.json").query("username", "alice");
+        assert_eq!(endpoint.method, Method::GET);
+        assert_eq!(
+            endpoint.url(DEFAULT_API_BASE),
+            "https://www.v2ex.com/api/members/show.json"
+        );
+    }
+
+    #[test]
+    fn test_parse_v2_response() {
+        let response: ApiResponse<i64> =
+            parse_v2_response(StatusCode::OK, r#"{"success": true, "result": 7}"#).unwrap();
+        assert_eq!(response.result, Some(7));
+
+        let error = parse_v2_response::<i64>(
+            StatusCode::NOT_FOUND,
+            r#"{"success": false, "message": "Topic not found"}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("404"));
+        assert!(error.to_string().contains("Topic not found"));
+        assert!(parse_v2_response::<i64>(StatusCode::OK, " ").is_err());
+        assert!(parse_v2_response::<i64>(StatusCode::BAD_GATEWAY, "<html>").is_err());
+    }
+
+    #[test]
+    fn test_check_v1_response() {
+        let value = serde_json::json!({"status": "error", "message": "Member not found"});
+        let error = check_v1_response(StatusCode::OK, value).unwrap_err();
+        assert_eq!(error.to_string(), "API error: Member not found");
+        assert!(check_v1_response(StatusCode::OK, serde_json::json!([])).is_ok());
+        assert!(check_v1_response(StatusCode::BAD_GATEWAY, serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_errors_are_classified() {
+        let kind = |error: anyhow::Error| format!("{:?}", V2exError::of(&error).unwrap());
+
+        let error = parse_v2_response::<i64>(StatusCode::UNAUTHORIZED, "").unwrap_err();
+        assert!(matches!(
+            V2exError::of(&error),
+            Some(V2exError::Unauthorized(None))
+        ));
+        let error = parse_v2_response::<i64>(
+            StatusCode::NOT_FOUND,
+            r#"{"success": false, "message": "Topic not found"}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            V2exError::of(&error),
+            Some(V2exError::NotFound(Some(_)))
+        ));
+        // Error pages that aren't JSON still report their status
+        let error = parse_v2_response::<i64>(StatusCode::BAD_GATEWAY, "<html>").unwrap_err();
+        assert!(kind(error).starts_with("Api { status: Some(502"));
+        let error = parse_v2_response::<i64>(StatusCode::OK, "<html>").unwrap_err();
+        assert!(kind(error).starts_with("Decode"));
+
+        let error = check_v1_response(StatusCode::UNAUTHORIZED, serde_json::json!({})).unwrap_err();
+        assert!(matches!(
+            V2exError::of(&error.context("Failed to load member")),
+            Some(V2exError::Unauthorized(_))
+        ));
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(reqwest::header::RETRY_AFTER, "30".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+    }
+
+    #[tokio::test]
+    async fn test_offline_client_reports_network_unavailable() {
+        let client = V2exClient::new(String::new()).offline();
+        let error = client.get_topic(1).await.unwrap_err();
+        assert!(is_network_unavailable(&error));
+        assert!(!is_network_unavailable(&anyhow::anyhow!("API error: 404")));
+    }
+}
@@ -0,0 +1,216 @@
+//! Favorite topics. The API has no favorites, so these go through the
+//! website as the member signed in there, with the session cookie from
+//! `cookie.txt`. Thanks go the same way, with the requests made here.
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::sync::LazyLock;
+
+use super::topics::topic_page_url;
+use super::{PartialList, Topic, V2exClient};
+
+const NO_COOKIE: &str = "Favorites and thanks need a V2EX website session: put your browser's \
+                         Cookie header for v2ex.com in ~/.config/v2ex/cookie.txt and restart";
+
+/// A row of `/my/topics`, up to the next one
+static ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<span class="item_title"><a href="/t/(\d+)[^"]*"[^>]*>(.*?)</a>"#).unwrap()
+});
+static NODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<a class="node" href="/go/([^"]+)">(.*?)</a>"#).unwrap());
+static AUTHOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<strong><a href="/member/([^"]+)""#).unwrap());
+static LAST_REPLY_BY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"最后回复来自 <strong><a href="/member/([^"]+)""#).unwrap());
+static TOUCHED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"title="(\d{4}-\d\d-\d\d \d\d:\d\d:\d\d [+-]\d\d:\d\d)""#).unwrap()
+});
+static REPLIES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"class="count_\w+">(\d+)</a>"#).unwrap());
+
+/// What the favorite link on a topic page says: whether the topic is
+/// favorited, and the one-time code that changes it
+#[derive(Debug, Clone, PartialEq)]
+struct FavoriteLink {
+    favorited: bool,
+    once: String,
+}
+
+impl FavoriteLink {
+    fn from_html(html: &str, topic_id: i64) -> Option<Self> {
+        let link = Regex::new(&format!(r"/(un)?favorite/topic/{}\?once=(\d+)", topic_id)).ok()?;
+        let found = link.captures(html)?;
+        Some(Self {
+            favorited: found.get(1).is_some(),
+            once: found[2].to_string(),
+        })
+    }
+
+    /// The path that flips the state
+    fn toggle_path(&self, topic_id: i64) -> String {
+        let action = if self.favorited {
+            "unfavorite"
+        } else {
+            "favorite"
+        };
+        format!("/{}/topic/{}?once={}", action, topic_id, self.once)
+    }
+}
+
+/// The topics listed on a page of `/my/topics`. Rows are read like API
+/// items, so one that doesn't parse is counted in `skipped`.
+fn favorite_topics_from_html(html: &str) -> PartialList<Topic> {
+    let starts: Vec<_> = ITEM.find_iter(html).map(|found| found.start()).collect();
+    let rows = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| &html[start..starts.get(i + 1).copied().unwrap_or(html.len())]);
+    let values = rows
+        .map(|row| {
+            let Some(item) = ITEM.captures(row) else {
+                return serde_json::Value::Null;
+            };
+            let node = NODE.captures(row).map(
+                |node| serde_json::json!({ "id": 0, "name": &node[1], "title": text(&node[2]) }),
+            );
+            let author = AUTHOR
+                .captures(row)
+                .map(|author| serde_json::json!({ "username": &author[1] }));
+            let touched = TOUCHED.captures(row).and_then(|touched| {
+                chrono::DateTime::parse_from_str(&touched[1], "%Y-%m-%d %H:%M:%S %:z").ok()
+            });
+            serde_json::json!({
+                "id": item[1].parse::<i64>().ok(),
+                "title": text(&item[2]),
+                "node": node,
+                "member": author,
+                "last_reply_by": LAST_REPLY_BY.captures(row).map(|by| by[1].to_string()),
+                "last_touched": touched.map(|touched| touched.timestamp()),
+                "replies": REPLIES.captures(row).map(|replies| replies[1].to_string()),
+            })
+        })
+        .collect();
+    PartialList::from_values(values)
+}
+
+/// Markup in a title or node name as plain text
+pub(super) fn text(html: &str) -> String {
+    html2text::from_read(html.as_bytes(), usize::MAX)
+        .trim()
+        .to_string()
+}
+
+impl V2exClient {
+    /// A page of the website as the signed-in member
+    pub(super) async fn fetch_signed_in_page(&self, path: &str) -> Result<String> {
+        self.signed_in(reqwest::Method::GET, path).await
+    }
+
+    /// Post to the website as the signed-in member, returning the answer.
+    /// Like other requests that change something, it is only sent again
+    /// if it never reached V2EX.
+    pub(super) async fn post_signed_in(&self, path: &str) -> Result<String> {
+        self.signed_in(reqwest::Method::POST, path).await
+    }
+
+    async fn signed_in(&self, method: reqwest::Method, path: &str) -> Result<String> {
+        self.ensure_online()?;
+        let Some(cookie) = self.cookie() else {
+            bail!(NO_COOKIE);
+        };
+        let url = format!("{}{}", self.site_base(), path);
+        let request = self
+            .http()
+            .request(method, &url)
+            .header(reqwest::header::COOKIE, cookie);
+        let response = self
+            .send_with_retry(request, false)
+            .await
+            .and_then(|response| Ok(response.error_for_status()?))
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        // An expired session is sent to the sign-in page
+        if response.url().path().starts_with("/signin") {
+            bail!("The V2EX website session in cookie.txt has expired; copy a fresh one from the browser");
+        }
+        response
+            .text()
+            .await
+            .with_context(|| format!("Failed to read {}", url))
+    }
+
+    /// A page of the topics the member has favorited, newest first
+    pub async fn get_favorite_topics(&self, page: i32) -> Result<PartialList<Topic>> {
+        let html = self
+            .fetch_signed_in_page(&format!("/my/topics?p={}", page))
+            .await?;
+        Ok(favorite_topics_from_html(&html))
+    }
+
+    /// Favorite the topic, or unfavorite it if it already is, going by its
+    /// page as it is now rather than what was seen before. Returns whether
+    /// the topic ends up favorited.
+    pub async fn toggle_topic_favorite(&self, topic_id: i64) -> Result<bool> {
+        let topic_path = topic_page_url("", topic_id);
+        let html = self.fetch_signed_in_page(&topic_path).await?;
+        let link = FavoriteLink::from_html(&html, topic_id)
+            .context("The topic page has no favorite link; is cookie.txt signed in?")?;
+        let html = self
+            .fetch_signed_in_page(&link.toggle_path(topic_id))
+            .await?;
+        // V2EX sends us back to the topic, whose link shows the new state
+        Ok(FavoriteLink::from_html(&html, topic_id)
+            .map_or(!link.favorited, |after| after.favorited))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_favorite_link() {
+        let html = r#"<a href="/favorite/topic/42?once=12345" class="op">加入收藏</a>"#;
+        let link = FavoriteLink::from_html(html, 42).unwrap();
+        assert!(!link.favorited);
+        assert_eq!(link.toggle_path(42), "/favorite/topic/42?once=12345");
+
+        let html = r#"<a href="/unfavorite/topic/42?once=678" class="op">取消收藏</a>"#;
+        let link = FavoriteLink::from_html(html, 42).unwrap();
+        assert!(link.favorited);
+        assert_eq!(link.toggle_path(42), "/unfavorite/topic/42?once=678");
+        assert_eq!(FavoriteLink::from_html(html, 4), None);
+    }
+
+    #[test]
+    fn test_favorite_topics_from_html() {
+        let html = r#"
+<div class="cell item"><table><tr>
+<td><span class="item_title"><a href="/t/42#reply12" class="topic-link">Rust &amp; <b>async</b></a></span>
+<span class="topic_info"><a class="node" href="/go/rust">Rust</a> &nbsp;•&nbsp;
+<strong><a href="/member/alice">alice</a></strong> &nbsp;•&nbsp;
+<span title="2024-01-02 03:04:05 +08:00">1 天前</span> &nbsp;•&nbsp;
+最后回复来自 <strong><a href="/member/bob">bob</a></strong></span></td>
+<td><a href="/t/42#reply12" class="count_livid">12</a></td>
+</tr></table></div>
+<div class="cell item"><table><tr>
+<td><span class="item_title"><a href="/t/7" class="topic-link">No replies</a></span>
+<span class="topic_info"><a class="node" href="/go/qna">问与答</a></span></td>
+</tr></table></div>"#;
+        let list = favorite_topics_from_html(html);
+        assert_eq!(list.skipped, 0);
+        let [first, second] = &list.items[..] else {
+            panic!("expected two topics, got {:?}", list.items);
+        };
+        assert_eq!(first.id, 42);
+        assert_eq!(first.title, "Rust & async");
+        assert_eq!(first.node.as_ref().unwrap().name, "rust");
+        assert_eq!(first.author_name(), "alice");
+        assert_eq!(first.last_reply_by.as_deref(), Some("bob"));
+        assert_eq!(first.last_touched, Some(1704135845));
+        assert_eq!(first.replies, 12);
+        assert_eq!(second.id, 7);
+        assert_eq!(second.node_title(), "问与答");
+        assert!(second.member.is_none());
+        assert_eq!(second.replies, 0);
+    }
+}
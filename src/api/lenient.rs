@@ -0,0 +1,44 @@
+//! Field deserializers tolerating the occasionally inconsistent API payloads
+
+use serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+/// Treat an explicit `null` like a missing field
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Nested object that falls back to `None` instead of failing its parent
+pub fn or_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// Integer that may arrive as `null`, a float or a numeric string
+pub fn int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    Ok(opt_int(deserializer)?.unwrap_or_default())
+}
+
+pub fn opt_int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    Ok(match Option::<Number>::deserialize(deserializer)? {
+        Some(Number::Int(n)) => Some(n),
+        Some(Number::Float(f)) => Some(f as i64),
+        Some(Number::Str(s)) => s.trim().parse().ok(),
+        None => None,
+    })
+}
@@ -0,0 +1,341 @@
+//! Links found in topics: V2EX pages that open in the app, titles of
+//! pages on other sites for link unfurling, and downloads from link mode
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use super::V2exClient;
+
+/// Sites whose links get a title next to them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSite {
+    GitHub,
+    YouTube,
+    Twitter,
+}
+
+impl LinkSite {
+    /// The site `url` is on, if it is one titles are fetched for
+    pub fn of(url: &str) -> Option<Self> {
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))?;
+        let host = rest
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        let host = host.strip_prefix("m.").unwrap_or(host);
+        match host {
+            "github.com" => Some(Self::GitHub),
+            "youtube.com" | "youtu.be" => Some(Self::YouTube),
+            "twitter.com" | "x.com" | "mobile.twitter.com" => Some(Self::Twitter),
+            _ => None,
+        }
+    }
+}
+
+/// A V2EX page that has a view of its own in the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SiteLink {
+    Topic(i64),
+    Member(String),
+}
+
+impl SiteLink {
+    /// What `url` points to, when it is a topic or member page on V2EX or
+    /// on `site`; relative links count too
+    fn parse(url: &str, site: &str) -> Option<Self> {
+        let path = match url.split_once("://") {
+            Some((_, rest)) => {
+                let (host, path) = rest.split_at(rest.find('/')?);
+                let host = host.to_ascii_lowercase();
+                let site_host = site
+                    .split_once("://")
+                    .map_or(site, |(_, rest)| rest)
+                    .split('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                if host != "v2ex.com" && !host.ends_with(".v2ex.com") && host != site_host {
+                    return None;
+                }
+                path
+            }
+            None if url.starts_with('/') => url,
+            None => return None,
+        };
+        let path = path.split(['#', '?']).next().unwrap_or_default();
+        let path = path.strip_suffix('/').unwrap_or(path);
+        if let Some(id) = path.strip_prefix("/t/") {
+            if !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()) {
+                return id.parse().ok().map(Self::Topic);
+            }
+        } else if let Some(name) = path.strip_prefix("/member/") {
+            if !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Some(Self::Member(name.to_string()));
+            }
+        }
+        None
+    }
+}
+
+/// The part of an oEmbed response that names what is linked
+#[derive(Debug, Deserialize)]
+struct OEmbed {
+    title: Option<String>,
+    author_name: Option<String>,
+}
+
+impl V2exClient {
+    /// The topic or member a link points to, when the app can show it
+    pub fn site_link(&self, url: &str) -> Option<SiteLink> {
+        SiteLink::parse(url, self.site_base())
+    }
+
+    /// A short title for a GitHub, YouTube or Twitter link. This goes
+    /// to the linked site, not V2EX, so it is sent once, without the V2EX
+    /// pacing or retry notices; a link without a title just shows none.
+    pub async fn get_link_title(&self, url: &str) -> Result<String> {
+        self.ensure_online()?;
+        let site = LinkSite::of(url).with_context(|| format!("No title lookup for {}", url))?;
+        let request = match site {
+            LinkSite::GitHub => self.http().get(url),
+            LinkSite::YouTube => self
+                .http()
+                .get("https://www.youtube.com/oembed")
+                .query(&[("url", url), ("format", "json")]),
+            LinkSite::Twitter => self
+                .http()
+                .get("https://publish.twitter.com/oembed")
+                .query(&[("url", url), ("omit_script", "true")]),
+        };
+        let body = request
+            .timeout(LINK_TITLE_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .text()
+            .await
+            .with_context(|| format!("Failed to read {}", url))?;
+        let title = match site {
+            LinkSite::GitHub => page_title(&body),
+            LinkSite::YouTube | LinkSite::Twitter => oembed_title(&body),
+        };
+        title.with_context(|| format!("No title found for {}", url))
+    }
+
+    /// Save what `url` points to in `dir`, named after the end of the URL,
+    /// and return where it went. Existing files are never replaced, and
+    /// anything over [`MAX_DOWNLOAD_BYTES`] is given up on.
+    pub async fn download(&self, url: &str, dir: &Path) -> Result<PathBuf> {
+        self.ensure_online()?;
+        let mut response = self
+            .send_with_retry(self.http().get(url), false)
+            .await
+            .and_then(|response| Ok(response.error_for_status()?))
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_DOWNLOAD_BYTES)
+        {
+            bail!("{} is larger than {} MiB", url, MAX_DOWNLOAD_BYTES >> 20);
+        }
+
+        let (mut file, path) = create_free_file(dir, &file_name(url))?;
+        let mut written = 0;
+        let result = async {
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .with_context(|| format!("Failed to read {}", url))?
+            {
+                written += chunk.len() as u64;
+                if written > MAX_DOWNLOAD_BYTES {
+                    bail!("{} is larger than {} MiB", url, MAX_DOWNLOAD_BYTES >> 20);
+                }
+                file.write_all(&chunk)
+                    .with_context(|| format!("Failed to write {:?}", path))?;
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            // Don't leave a partial file behind
+            drop(file);
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+        Ok(path)
+    }
+}
+
+/// Largest download link mode saves
+pub const MAX_DOWNLOAD_BYTES: u64 = 100 << 20;
+
+/// How long a link title lookup may take before it is given up on
+const LINK_TITLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// The last path segment of `url`, made safe to use as a file name
+fn file_name(url: &str) -> String {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    let path = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let name: String = path
+        .split_once('/')
+        .map_or("", |(_, path)| path)
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && !r#"\:*?"<>|"#.contains(*c))
+        .collect();
+    if name.is_empty() || name.starts_with('.') {
+        "download".to_string()
+    } else {
+        name
+    }
+}
+
+/// Create `dir/name`, or `dir/name-2.ext` and so on if that is taken.
+/// Each name is claimed atomically, so nothing that appears in the
+/// meantime is overwritten.
+fn create_free_file(dir: &Path, name: &str) -> Result<(File, PathBuf)> {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    let candidates = std::iter::once(dir.join(name))
+        .chain((2..1000).map(|n| dir.join(format!("{}-{}{}", stem, n, ext))));
+    for path in candidates {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to create {:?}", path)),
+        }
+    }
+    bail!("No free file name for {} in {:?}", name, dir)
+}
+
+/// The `<title>` of an HTML page, with entities decoded
+fn page_title(html: &str) -> Option<String> {
+    let start = html.find("<title")?;
+    let start = start + html[start..].find('>')? + 1;
+    let end = start + html[start..].find("</title>")?;
+    let title = html2text::from_read(&html.as_bytes()[start..end], usize::MAX);
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// The title of an oEmbed response, or whose post it is for sites that
+/// have none, such as tweets
+fn oembed_title(json: &str) -> Option<String> {
+    let oembed: OEmbed = serde_json::from_str(json).ok()?;
+    oembed
+        .title
+        .filter(|title| !title.trim().is_empty())
+        .or_else(|| {
+            oembed
+                .author_name
+                .map(|author| format!("Post by {}", author))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_sites_and_titles() {
+        assert_eq!(
+            LinkSite::of("https://github.com/rust-lang/rust"),
+            Some(LinkSite::GitHub)
+        );
+        assert_eq!(
+            LinkSite::of("https://m.youtube.com/watch?v=x"),
+            Some(LinkSite::YouTube)
+        );
+        assert_eq!(LinkSite::of("https://youtu.be/x"), Some(LinkSite::YouTube));
+        assert_eq!(
+            LinkSite::of("https://x.com/a/status/1"),
+            Some(LinkSite::Twitter)
+        );
+        assert_eq!(LinkSite::of("https://www.v2ex.com/t/1"), None);
+        assert_eq!(LinkSite::of("https://github.com.example.org/"), None);
+
+        let html = "<head><title>\n  GitHub - rust-lang/rust: Empowering &amp; more\n</title>";
+        assert_eq!(
+            page_title(html).unwrap(),
+            "GitHub - rust-lang/rust: Empowering & more"
+        );
+        assert_eq!(page_title("<title></title>"), None);
+        assert_eq!(
+            oembed_title(r#"{"title": "A video", "author_name": "Someone"}"#).unwrap(),
+            "A video"
+        );
+        assert_eq!(
+            oembed_title(r#"{"author_name": "Someone", "html": "..."}"#).unwrap(),
+            "Post by Someone"
+        );
+    }
+
+    #[test]
+    fn test_site_links() {
+        let site = "https://www.v2ex.com";
+        assert_eq!(
+            SiteLink::parse("https://www.v2ex.com/t/1180785#reply20", site),
+            Some(SiteLink::Topic(1180785))
+        );
+        assert_eq!(
+            SiteLink::parse("https://v2ex.com/t/42?p=2", site),
+            Some(SiteLink::Topic(42))
+        );
+        assert_eq!(SiteLink::parse("/t/42", site), Some(SiteLink::Topic(42)));
+        assert_eq!(
+            SiteLink::parse("https://www.v2ex.com/member/Livid", site),
+            Some(SiteLink::Member("Livid".to_string()))
+        );
+        assert_eq!(
+            SiteLink::parse("http://localhost:8080/t/7", "http://localhost:8080"),
+            Some(SiteLink::Topic(7))
+        );
+        assert_eq!(SiteLink::parse("https://example.com/t/42", site), None);
+        assert_eq!(SiteLink::parse("https://www.v2ex.com/go/rust", site), None);
+        assert_eq!(SiteLink::parse("https://www.v2ex.com/t/", site), None);
+        assert_eq!(SiteLink::parse("https://www.v2ex.com/t/1/2", site), None);
+        assert_eq!(SiteLink::parse("https://www.v2ex.com", site), None);
+    }
+
+    #[test]
+    fn test_download_names() {
+        assert_eq!(
+            file_name("https://example.com/files/report.pdf?raw=1#page=2"),
+            "report.pdf"
+        );
+        assert_eq!(file_name("https://example.com/dir/"), "dir");
+        assert_eq!(file_name("https://example.com"), "download");
+        assert_eq!(file_name("https://example.com/.env"), "download");
+
+        let dir = std::env::temp_dir().join(format!(
+            "v2ex-download-{}-{:x}",
+            std::process::id(),
+            crate::retry::jitter_seed()
+        ));
+        std::fs::create_dir(&dir).unwrap();
+        let created = |name| create_free_file(&dir, name).unwrap().1;
+        assert_eq!(created("a.txt"), dir.join("a.txt"));
+        std::fs::write(dir.join("a-2.txt"), "").unwrap();
+        assert_eq!(created("a.txt"), dir.join("a-3.txt"));
+        assert_eq!(created("download"), dir.join("download"));
+        assert_eq!(created("download"), dir.join("download-2"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+//! The signed-in member, other members and the topics they posted. The
+//! API has nothing on what members replied, so that comes from their page
+//! on the website.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+use super::client::Endpoint;
+use super::favorites::text;
+use super::{lenient, PartialList, Topic, V2exClient};
+
+/// A reply on `/member/<name>/replies`, up to the next one
+static REPLY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<div class="dock_area">"#).unwrap());
+static TOPIC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<a href="/t/(\d+)[^"]*">(.*?)</a>"#).unwrap());
+static NODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<a href="/go/([^"]+)">"#).unwrap());
+static CREATED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<span class="fade" title="(\d{4}-\d\d-\d\d \d\d:\d\d:\d\d [+-]\d\d:\d\d)""#)
+        .unwrap()
+});
+static AGO: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<span class="fade"[^>]*>(.*?)</span>"#).unwrap());
+static CONTENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)<div class="reply_content">(.*?)</div>"#).unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub username: String,
+    pub url: Option<String>,
+    pub website: Option<String>,
+    pub twitter: Option<String>,
+    pub psn: Option<String>,
+    pub github: Option<String>,
+    pub btc: Option<String>,
+    pub location: Option<String>,
+    pub tagline: Option<String>,
+    pub bio: Option<String>,
+    pub avatar: Option<String>,
+    pub avatar_mini: Option<String>,
+    pub avatar_normal: Option<String>,
+    pub avatar_large: Option<String>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub created: i64,
+    #[serde(default, deserialize_with = "lenient::opt_int")]
+    pub last_modified: Option<i64>,
+}
+
+/// A reply a member posted, as their replies page lists it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberReply {
+    pub topic_id: i64,
+    pub topic_title: String,
+    /// Name of the topic's node
+    pub node: Option<String>,
+    /// The reply as plain text
+    pub content: String,
+    /// When it was posted, if the page gave the exact time
+    pub created: Option<i64>,
+    /// When it was posted as the page put it, like `3 天前`
+    #[serde(default)]
+    pub ago: String,
+}
+
+/// What `GET /token` says about the token in use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenInfo {
+    pub token: String,
+    pub scope: String,
+    pub expiration: i64,
+    pub good_for_days: i64,
+    pub total_used: i64,
+    pub last_used: Option<i64>,
+    pub last_use_ip: Option<String>,
+    pub created: i64,
+}
+
+fn member_endpoint() -> Endpoint {
+    Endpoint::get("member")
+}
+
+fn token_endpoint() -> Endpoint {
+    Endpoint::get("token")
+}
+
+fn member_by_username_endpoint(username: &str) -> Endpoint {
+    Endpoint::v1("members/show.json").query("username", username)
+}
+
+fn member_topics_endpoint(username: &str) -> Endpoint {
+    Endpoint::v1("topics/show.json").query("username", username)
+}
+
+/// The replies listed on a member's replies page. Rows are read like API
+/// items, so one that doesn't parse is counted in `skipped`.
+fn member_replies_from_html(html: &str) -> PartialList<MemberReply> {
+    let starts: Vec<_> = REPLY.find_iter(html).map(|found| found.start()).collect();
+    let rows = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| &html[start..starts.get(i + 1).copied().unwrap_or(html.len())]);
+    let values = rows
+        .map(|row| {
+            let Some(topic) = TOPIC.captures(row) else {
+                return serde_json::Value::Null;
+            };
+            let created = CREATED.captures(row).and_then(|created| {
+                chrono::DateTime::parse_from_str(&created[1], "%Y-%m-%d %H:%M:%S %:z").ok()
+            });
+            serde_json::json!({
+                "topic_id": topic[1].parse::<i64>().ok(),
+                "topic_title": text(&topic[2]),
+                "node": NODE.captures(row).map(|node| node[1].to_string()),
+                "content": CONTENT.captures(row).map(|content| text(&content[1])),
+                "created": created.map(|created| created.timestamp()),
+                "ago": AGO.captures(row).map(|ago| text(&ago[1])),
+            })
+        })
+        .collect();
+    PartialList::from_values(values)
+}
+
+impl V2exClient {
+    pub async fn get_member(&self) -> Result<Member> {
+        self.fetch(&member_endpoint(), "member").await
+    }
+
+    /// Scope, expiry and use of the token this client sends
+    pub async fn get_token_info(&self) -> Result<TokenInfo> {
+        self.fetch(&token_endpoint(), "token").await
+    }
+
+    /// Look up any member by username
+    pub async fn get_member_by_username(&self, username: &str) -> Result<Member> {
+        let value = self
+            .fetch_v1(&member_by_username_endpoint(username))
+            .await?;
+        serde_json::from_value(value)
+            .with_context(|| format!("Unexpected member data for '{}'", username))
+    }
+
+    /// Recent topics created by a member
+    pub async fn get_member_topics(&self, username: &str) -> Result<PartialList<Topic>> {
+        self.fetch_list(&member_topics_endpoint(username)).await
+    }
+
+    /// Recent replies of a member, from their page on the website
+    pub async fn get_member_replies(&self, username: &str) -> Result<PartialList<MemberReply>> {
+        self.ensure_online()?;
+        let url = format!("{}/member/{}/replies", self.site_base(), username);
+        let mut request = self.http().get(&url);
+        // Some members only show their replies to those signed in
+        if let Some(cookie) = self.cookie() {
+            request = request.header(reqwest::header::COOKIE, cookie);
+        }
+        let html = self
+            .send_with_retry(request, false)
+            .await
+            .and_then(|response| Ok(response.error_for_status()?))
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .text()
+            .await
+            .with_context(|| format!("Failed to read {}", url))?;
+        Ok(member_replies_from_html(&html))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::DEFAULT_API_BASE;
+
+    #[test]
+    fn test_member_endpoints() {
+        assert_eq!(
+            member_endpoint().url(DEFAULT_API_BASE),
+            "https://www.v2ex.com/api/v2/member"
+        );
+        assert_eq!(token_endpoint().path, "token");
+        let endpoint = member_by_username_endpoint("alice");
+        assert_eq!(
+            endpoint.url(DEFAULT_API_BASE),
+            "https://www.v2ex.com/api/members/show.json"
+        );
+        assert_eq!(endpoint.query, [("username", "alice".to_string())]);
+        assert_eq!(member_topics_endpoint("alice").path, "topics/show.json");
+    }
+
+    #[test]
+    fn test_member_replies_from_html() {
+        let html = r#"
+<div class="dock_area"><table><tr><td><div class="fr"><span class="fade" title="2024-05-01 10:00:00 +08:00">3 天前</span></div>
+<span class="gray">回复了 <a href="/member/bob">bob</a> 创建的主题 <span class="chevron">›</span> <a href="/go/python">Python</a> <span class="chevron">›</span> <a href="/t/1034567#reply12">Asyncio &amp; threads</a></span></td></tr></table></div>
+<div class="inner"><div class="reply_content">Use <a href="https://docs.python.org">the docs</a></div></div>
+<div class="dock_area"><span class="fade">2 小时前</span><span class="gray">回复了 <a href="/member/carol">carol</a> 创建的主题 › <a href="/go/rust">Rust</a> › <a href="/t/42#reply3">Lifetimes</a></span></div>
+<div class="inner"><div class="reply_content">+1</div></div>
+<div class="dock_area"><span class="gray">nothing here</span></div>
+"#;
+        let list = member_replies_from_html(html);
+        assert_eq!(list.skipped, 1);
+        let [first, second] = &list.items[..] else {
+            panic!("expected two replies, got {:?}", list.items);
+        };
+        assert_eq!(first.topic_id, 1034567);
+        assert_eq!(first.topic_title, "Asyncio & threads");
+        assert_eq!(first.node.as_deref(), Some("python"));
+        assert!(first.content.starts_with("Use"));
+        assert_eq!(first.created, Some(1714528800));
+        assert_eq!(second.topic_id, 42);
+        assert_eq!(second.created, None);
+        assert_eq!(second.ago, "2 小时前");
+        assert_eq!(second.content, "+1");
+    }
+
+    #[test]
+    fn test_member_tolerates_string_numbers() {
+        let member: Member =
+            serde_json::from_str(r#"{"id": "7", "username": "alice", "created": null}"#).unwrap();
+        assert_eq!(member.id, 7);
+        assert_eq!(member.created, 0);
+    }
+}
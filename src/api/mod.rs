@@ -0,0 +1,204 @@
+//! V2EX API client. `client` holds the request plumbing shared by every
+//! endpoint; each other module adds the endpoints for one kind of resource
+//! along with the types they return.
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+mod client;
+mod favorites;
+mod lenient;
+mod links;
+mod members;
+mod nodes;
+mod notifications;
+mod replies;
+mod rss;
+mod topics;
+
+pub use client::V2exClient;
+pub use links::{LinkSite, SiteLink};
+pub use members::{Member, MemberReply, TokenInfo};
+pub use nodes::Node;
+pub use notifications::Notification;
+pub use replies::{Reply, REPLIES_PER_PAGE};
+pub use rss::RssItem;
+pub use topics::{reply_page_url, topic_page_url, Topic, TopicStats};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    #[serde(default)]
+    pub success: bool,
+    pub message: Option<String>,
+    pub result: Option<T>,
+}
+
+/// Items of a list endpoint that parsed, plus a count of malformed ones
+#[derive(Debug, Clone, Default)]
+pub struct PartialList<T> {
+    pub items: Vec<T>,
+    pub skipped: usize,
+}
+
+impl<T: serde::de::DeserializeOwned> PartialList<T> {
+    /// Parse each item on its own so one bad entry doesn't blank the page
+    fn from_values(values: Vec<serde_json::Value>) -> Self {
+        let mut list = Self {
+            items: Vec::with_capacity(values.len()),
+            skipped: 0,
+        };
+        for value in values {
+            match serde_json::from_value(value) {
+                Ok(item) => list.items.push(item),
+                Err(_) => list.skipped += 1,
+            }
+        }
+        list
+    }
+}
+
+/// Returned for every request made in offline mode
+#[derive(Debug)]
+pub struct OfflineError;
+
+impl std::fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("offline mode, no network access")
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+/// Why V2EX won't take a thank, told apart from other failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThankRefused {
+    /// The member thanked it before
+    AlreadyThanked,
+    /// It is the member's own topic or reply
+    OwnPost,
+}
+
+impl std::fmt::Display for ThankRefused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::AlreadyThanked => "already thanked",
+            Self::OwnPost => "can't thank your own post",
+        })
+    }
+}
+
+impl std::error::Error for ThankRefused {}
+
+/// How a request to V2EX failed, for callers that react to the kind of
+/// failure rather than only show it. Travels inside `anyhow::Error`; find
+/// it with [`V2exError::of`].
+#[derive(Debug)]
+pub enum V2exError {
+    /// The token was rejected (401)
+    Unauthorized(Option<String>),
+    /// Too many requests (429); `retry_after` is when the budget resets,
+    /// if V2EX said
+    RateLimited { retry_after: Option<Duration> },
+    /// 404
+    NotFound(Option<String>),
+    /// No answer came back
+    Network(reqwest::Error),
+    /// The answer couldn't be understood
+    Decode(String),
+    /// Any other error status, or an error V2EX reported in the body
+    Api {
+        status: Option<StatusCode>,
+        message: Option<String>,
+    },
+}
+
+impl V2exError {
+    /// The error for an unsuccessful `status`, with V2EX's explanation
+    fn from_status(status: StatusCode, message: Option<String>) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized(message),
+            StatusCode::NOT_FOUND => Self::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { retry_after: None },
+            _ => Self::Api {
+                status: Some(status),
+                message,
+            },
+        }
+    }
+
+    /// The `V2exError` behind `error`, under any context added since
+    pub fn of(error: &anyhow::Error) -> Option<&Self> {
+        error.chain().find_map(|cause| cause.downcast_ref())
+    }
+}
+
+impl std::fmt::Display for V2exError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let with_message =
+            |f: &mut std::fmt::Formatter<'_>, status, message: &Option<String>| match message {
+                Some(message) => write!(f, "API error: {} - {}", status, message),
+                None => write!(f, "API error: {}", status),
+            };
+        match self {
+            Self::Unauthorized(message) => with_message(f, StatusCode::UNAUTHORIZED, message),
+            Self::NotFound(message) => with_message(f, StatusCode::NOT_FOUND, message),
+            Self::RateLimited {
+                retry_after: Some(wait),
+            } => write!(f, "Rate limited by V2EX, resets in {}s", wait.as_secs()),
+            Self::RateLimited { retry_after: None } => f.write_str("Rate limited by V2EX"),
+            Self::Network(e) => write!(f, "{}", e),
+            Self::Decode(message) => f.write_str(message),
+            Self::Api {
+                status: Some(status),
+                message,
+            } => with_message(f, *status, message),
+            Self::Api {
+                status: None,
+                message,
+            } => write!(
+                f,
+                "API error: {}",
+                message.as_deref().unwrap_or("unknown error")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for V2exError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            // Its message is already ours, so go straight to what caused it
+            Self::Network(e) => std::error::Error::source(e),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a request failed for lack of a network rather than because of
+/// what V2EX answered, so cached data is a fair substitute
+pub fn is_network_unavailable(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.is::<OfflineError>()
+            || matches!(
+                cause.downcast_ref::<V2exError>(),
+                Some(V2exError::Network(e)) if crate::retry::is_transient_error(e)
+            )
+            || cause
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(crate::retry::is_transient_error)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_list_keeps_valid_items() {
+        let values = serde_json::from_str(r#"[{"id": 1}, {"title": "no id"}, {"id": 3}]"#).unwrap();
+        let list: PartialList<Topic> = PartialList::from_values(values);
+        assert_eq!(list.items.iter().map(|t| t.id).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(list.skipped, 1);
+    }
+}
@@ -0,0 +1,76 @@
+//! Nodes and the topics posted in them.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::client::Endpoint;
+use super::{lenient, PartialList, Topic, V2exClient};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Node {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub url: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub title: String,
+    pub title_alternative: Option<String>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub topics: i64,
+    pub header: Option<String>,
+    pub footer: Option<String>,
+    pub avatar: Option<String>,
+    pub avatar_mini: Option<String>,
+    pub avatar_normal: Option<String>,
+    pub avatar_large: Option<String>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub created: i64,
+    #[serde(default, deserialize_with = "lenient::opt_int")]
+    pub last_modified: Option<i64>,
+}
+
+fn node_endpoint(node_name: &str) -> Endpoint {
+    Endpoint::get(format!("nodes/{}", node_name))
+}
+
+fn node_topics_endpoint(node_name: &str, page: i32) -> Endpoint {
+    Endpoint::get(format!("nodes/{}/topics", node_name)).page(page)
+}
+
+impl V2exClient {
+    pub async fn get_node(&self, node_name: &str) -> Result<Node> {
+        self.fetch(&node_endpoint(node_name), "node").await
+    }
+
+    pub async fn get_node_topics(&self, node_name: &str, page: i32) -> Result<PartialList<Topic>> {
+        self.fetch_list(&node_topics_endpoint(node_name, page))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::DEFAULT_API_BASE;
+
+    #[test]
+    fn test_node_endpoints() {
+        assert_eq!(
+            node_endpoint("rust").url(DEFAULT_API_BASE),
+            "https://www.v2ex.com/api/v2/nodes/rust"
+        );
+        let endpoint = node_topics_endpoint("rust", 3);
+        assert_eq!(endpoint.path, "nodes/rust/topics");
+        assert_eq!(endpoint.query, [("p", "3".to_string())]);
+    }
+
+    #[test]
+    fn test_node_tolerates_null_fields() {
+        let node: Node =
+            serde_json::from_str(r#"{"id": 1, "name": "rust", "title": null, "topics": "12"}"#)
+                .unwrap();
+        assert_eq!(node.title, "");
+        assert_eq!(node.topics, 12);
+    }
+}
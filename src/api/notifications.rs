@@ -0,0 +1,151 @@
+//! Notifications of the signed-in member.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::client::Endpoint;
+use super::{lenient, ApiResponse, Member, PartialList, V2exClient};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NotificationPayload {
+    String(String),
+    Object {
+        topic_id: Option<i64>,
+        topic_title: Option<String>,
+        reply_id: Option<i64>,
+        body: Option<String>,
+    },
+}
+
+impl NotificationPayload {
+    pub fn extract_body(&self) -> Option<String> {
+        match self {
+            NotificationPayload::String(s) => {
+                // Parse format like "@chingyat #20\r\n\r\nsystemd 会在后面的章节里面。"
+                // Extract everything after the second newline
+                let parts: Vec<&str> = s.splitn(3, '\n').collect();
+                if parts.len() >= 3 {
+                    Some(parts[2].trim().to_string())
+                } else {
+                    Some(s.clone())
+                }
+            }
+            NotificationPayload::Object { body, .. } => body.clone(),
+        }
+    }
+
+    pub fn extract_reply_id(&self) -> Option<i64> {
+        match self {
+            NotificationPayload::String(s) => {
+                // Try to extract reply ID from format like "@chingyat #20"
+                if let Some(hash_pos) = s.find('#') {
+                    let after_hash = &s[hash_pos + 1..];
+                    let end_pos = after_hash
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(after_hash.len());
+                    after_hash[..end_pos].parse().ok()
+                } else {
+                    None
+                }
+            }
+            NotificationPayload::Object { reply_id, .. } => *reply_id,
+        }
+    }
+}
+
+impl Notification {
+    /// Extract topic ID from notification text
+    pub fn extract_topic_id(&self) -> Option<i64> {
+        // Look for pattern like /t/1180785 in the text
+        let text = &self.text;
+
+        // Find /t/ pattern
+        if let Some(t_pos) = text.find("/t/") {
+            let after_t = &text[t_pos + 3..];
+            let end_pos = after_t
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after_t.len());
+            after_t[..end_pos].parse().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Extract reply ID from notification
+    pub fn extract_reply_id(&self) -> Option<i64> {
+        self.payload.as_ref().and_then(|p| p.extract_reply_id())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub member_id: i64,
+    #[serde(default, deserialize_with = "lenient::or_none")]
+    pub member: Option<Member>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub for_member_id: i64,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub text: String,
+    #[serde(default, deserialize_with = "lenient::or_none")]
+    pub payload: Option<NotificationPayload>,
+    pub payload_rendered: Option<String>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub created: i64,
+}
+
+fn notifications_endpoint(page: i32) -> Endpoint {
+    Endpoint::get("notifications").page(page)
+}
+
+/// Sent once: the user confirmed it and sees at once whether it failed
+fn delete_notification_endpoint(notification_id: i64) -> Endpoint {
+    Endpoint::delete(format!("notifications/{}", notification_id)).once()
+}
+
+impl V2exClient {
+    pub async fn get_notifications(&self, page: i32) -> Result<PartialList<Notification>> {
+        self.fetch_list(&notifications_endpoint(page)).await
+    }
+
+    pub async fn delete_notification(&self, notification_id: i64) -> Result<()> {
+        let _: ApiResponse<serde_json::Value> = self
+            .send(&delete_notification_endpoint(notification_id))
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_endpoints() {
+        let endpoint = notifications_endpoint(2);
+        assert_eq!(endpoint.path, "notifications");
+        assert_eq!(endpoint.query, [("p", "2".to_string())]);
+        let endpoint = delete_notification_endpoint(9);
+        assert_eq!(endpoint.method, reqwest::Method::DELETE);
+        assert_eq!(endpoint.path, "notifications/9");
+        assert!(!endpoint.retried);
+    }
+
+    #[test]
+    fn test_notification_ids_from_text_and_payload() {
+        let notification: Notification = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "text": "<a href=\"/t/1180785#reply20\">topic</a>",
+            "payload": "@alice #20\r\n\r\nsystemd 会在后面的章节里面。",
+        }))
+        .unwrap();
+        assert_eq!(notification.extract_topic_id(), Some(1180785));
+        assert_eq!(notification.extract_reply_id(), Some(20));
+        assert_eq!(
+            notification.payload.unwrap().extract_body().as_deref(),
+            Some("systemd 会在后面的章节里面。")
+        );
+    }
+}
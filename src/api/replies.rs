@@ -0,0 +1,114 @@
+//! Replies to topics: reading them page by page, posting and thanking.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::client::Endpoint;
+use super::{lenient, ApiResponse, Member, PartialList, V2exClient};
+
+/// Replies returned per page by `topics/:id/replies`
+pub const REPLIES_PER_PAGE: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reply {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient::or_none")]
+    pub member: Option<Member>,
+    pub content: Option<String>,
+    pub content_rendered: Option<String>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub created: i64,
+    #[serde(default, deserialize_with = "lenient::opt_int")]
+    pub last_modified: Option<i64>,
+    /// Times the reply was thanked. The API leaves it out; it is filled in
+    /// from the topic page's [`TopicStats`](super::TopicStats).
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub thanks: i64,
+}
+
+/// Body of `POST topics/:id/replies`
+#[derive(Debug, Serialize)]
+struct NewReply<'a> {
+    content: &'a str,
+}
+
+fn topic_replies_endpoint(topic_id: i64, page: i32) -> Endpoint {
+    Endpoint::get(format!("topics/{}/replies", topic_id)).page(page)
+}
+
+fn create_reply_endpoint(topic_id: i64) -> Endpoint {
+    Endpoint::post(format!("topics/{}/replies", topic_id))
+}
+
+impl V2exClient {
+    pub async fn get_topic_replies(&self, topic_id: i64, page: i32) -> Result<PartialList<Reply>> {
+        self.fetch_list(&topic_replies_endpoint(topic_id, page))
+            .await
+    }
+
+    /// Every reply of a topic with `total` replies, fetched page by page
+    pub async fn get_all_topic_replies(
+        &self,
+        topic_id: i64,
+        total: usize,
+    ) -> Result<PartialList<Reply>> {
+        let mut all = PartialList {
+            items: Vec::with_capacity(total),
+            skipped: 0,
+        };
+        let mut page = 1;
+        while all.items.len() + all.skipped < total {
+            let list = self.get_topic_replies(topic_id, page).await?;
+            if list.items.is_empty() && list.skipped == 0 {
+                break;
+            }
+            all.items.extend(list.items);
+            all.skipped += list.skipped;
+            page += 1;
+        }
+        Ok(all)
+    }
+
+    /// Thank the author of a reply to `topic_id`, which costs the member
+    /// coins
+    pub async fn thank_reply(&self, topic_id: i64, reply_id: i64) -> Result<()> {
+        self.thank(topic_id, Some(reply_id)).await
+    }
+
+    pub async fn create_reply(&self, topic_id: i64, content: &str) -> Result<Reply> {
+        let response: ApiResponse<Reply> = self
+            .send_with_body(&create_reply_endpoint(topic_id), &NewReply { content })
+            .await?;
+        response.result.context("No reply data in response")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::DEFAULT_API_BASE;
+
+    #[test]
+    fn test_reply_endpoints() {
+        let endpoint = topic_replies_endpoint(42, 2);
+        assert_eq!(
+            endpoint.url(DEFAULT_API_BASE),
+            "https://www.v2ex.com/api/v2/topics/42/replies"
+        );
+        assert_eq!(endpoint.query, [("p", "2".to_string())]);
+        let endpoint = create_reply_endpoint(42);
+        assert_eq!(endpoint.method, reqwest::Method::POST);
+        assert_eq!(endpoint.path, "topics/42/replies");
+        assert_eq!(
+            serde_json::to_string(&NewReply { content: "+1" }).unwrap(),
+            r#"{"content":"+1"}"#
+        );
+    }
+
+    #[test]
+    fn test_reply_tolerates_broken_member() {
+        let reply: Reply =
+            serde_json::from_str(r#"{"id": 1, "content": "hi", "member": "broken"}"#).unwrap();
+        assert!(reply.member.is_none());
+    }
+}
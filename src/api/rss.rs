@@ -0,0 +1,292 @@
+//! The aggregate tabs: Atom feeds of the site's tabs, plus the hot and
+//! latest lists of the v1 API shown the same way.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::{PartialList, Topic, V2exClient};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RssItem {
+    pub title: String,
+    pub link: String,
+    pub date: String,
+    #[allow(dead_code)] // Not currently used, but kept for future display
+    pub author: Option<String>,
+    /// Unix timestamp for relative time display
+    pub timestamp: Option<i64>,
+    /// Node name, from the API or the feed entry's category
+    pub node: Option<String>,
+    /// Reply count, from the `#replyN` anchor of feed links or the API
+    pub replies: Option<i64>,
+    /// When the topic was last replied to, known once looked up in the API
+    pub last_touched: Option<i64>,
+}
+
+impl RssItem {
+    /// List entry for a topic fetched from the API instead of a feed
+    pub fn from_topic(topic: &Topic) -> Self {
+        let link = if topic.url.is_empty() {
+            format!("https://www.v2ex.com/t/{}", topic.id)
+        } else {
+            topic.url.clone()
+        };
+        let date = chrono::DateTime::from_timestamp(topic.created, 0)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "Unknown date".to_string());
+        let mut item = Self {
+            title: topic.title.clone(),
+            link,
+            date,
+            author: topic.member.as_ref().map(|m| m.username.clone()),
+            timestamp: (topic.created > 0).then_some(topic.created),
+            node: None,
+            replies: None,
+            last_touched: None,
+        };
+        item.enrich(topic);
+        item
+    }
+
+    /// Fill in what the API knows about the topic and the feed doesn't say.
+    /// A reply count from a feed fetched since the lookup may be higher.
+    pub fn enrich(&mut self, topic: &Topic) {
+        if let Some(node) = topic.node.as_ref().filter(|node| !node.name.is_empty()) {
+            self.node = Some(node.name.clone());
+        }
+        self.replies = Some(self.replies.map_or(topic.replies, |n| n.max(topic.replies)));
+        self.last_touched = topic.last_touched.filter(|&at| at > 0);
+    }
+
+    /// Extract topic ID from RSS item link
+    /// V2EX links are typically: https://www.v2ex.com/t/123456 or https://www.v2ex.com/t/123456#reply1
+    pub fn extract_topic_id(&self) -> Option<i64> {
+        // Find /t/ pattern in the link
+        if let Some(t_pos) = self.link.find("/t/") {
+            let after_t = &self.link[t_pos + 3..];
+            let end_pos = after_t
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after_t.len());
+            after_t[..end_pos].parse().ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// Reply count in a feed link, which points at the last reply as
+/// `/t/123#reply45`
+fn replies_in_link(link: &str) -> Option<i64> {
+    let (_, anchor) = link.split_once("#reply")?;
+    anchor.parse().ok()
+}
+
+/// Atom feed of an aggregate tab on `site`; `index` is the front page
+fn feed_url(site: &str, tab: &str) -> String {
+    if tab == "index" {
+        format!("{}/index.xml", site)
+    } else {
+        format!("{}/feed/tab/{}.xml", site, tab)
+    }
+}
+
+/// URL of a feed added with `add-feed`; paths are on the site
+fn custom_feed_url(site: &str, feed: &str) -> String {
+    if feed.starts_with('/') {
+        format!("{}{}", site, feed)
+    } else {
+        feed.to_string()
+    }
+}
+
+/// How long a feed from another site may take before it is given up on
+const EXTERNAL_FEED_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+impl V2exClient {
+    /// Items of an aggregate tab; `hot` and `latest` come from the v1 API,
+    /// every other tab from its RSS feed, or from `feed` for tabs added with
+    /// `add-feed`
+    pub async fn get_aggregate_items(
+        &self,
+        tab: &str,
+        feed: Option<&str>,
+    ) -> Result<PartialList<RssItem>> {
+        let topics = match (tab, feed) {
+            ("hot", None) => self.get_hot_topics().await?,
+            ("latest", None) => self.get_latest_topics().await?,
+            _ => {
+                let url = match feed {
+                    Some(feed) => custom_feed_url(self.site_base(), feed),
+                    None => feed_url(self.site_base(), tab),
+                };
+                let items = self.get_rss_feed(&url).await?;
+                return Ok(PartialList { items, skipped: 0 });
+            }
+        };
+        Ok(PartialList {
+            items: topics.items.iter().map(RssItem::from_topic).collect(),
+            skipped: topics.skipped,
+        })
+    }
+
+    pub async fn get_rss_feed(&self, url: &str) -> Result<Vec<RssItem>> {
+        use atom_syndication::Feed;
+
+        self.ensure_online()?;
+
+        // Create a client with custom settings for RSS fetching
+        let rss_client = reqwest::Client::builder()
+            .user_agent(self.user_agent())
+            .danger_accept_invalid_certs(true)
+            .build()
+            .context("Failed to create RSS client")?;
+
+        // Only the site's own feeds are paced and retried; other sites don't
+        // share its rate limit, so they are asked once
+        let response = if url.starts_with(self.site_base()) {
+            self.send_with_retry(rss_client.get(url), false).await
+        } else {
+            rss_client
+                .get(url)
+                .timeout(EXTERNAL_FEED_TIMEOUT)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(Into::into)
+        }
+        .with_context(|| format!("Failed to fetch RSS feed from {}", url))?;
+
+        let content = response
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to read RSS feed content from {}", url))?;
+
+        // Convert to string for debugging
+        let content_str = String::from_utf8_lossy(&content);
+
+        let feed = match Feed::read_from(&content[..]) {
+            Ok(feed) => feed,
+            Err(e) => {
+                let preview = if content_str.len() > 200 {
+                    &content_str[..200]
+                } else {
+                    &content_str
+                };
+                return Err(anyhow::anyhow!(
+                    "Failed to parse Atom feed from {}: {}. Content preview: {}",
+                    url,
+                    e,
+                    preview
+                ));
+            }
+        };
+
+        let items: Vec<RssItem> = feed
+            .entries()
+            .iter()
+            .map(|entry| {
+                let title = entry.title().to_string();
+                let link = entry
+                    .links()
+                    .first()
+                    .map(|link| link.href().to_string())
+                    .unwrap_or_else(|| "".to_string());
+
+                // Format date and extract timestamp
+                let (date, timestamp) = entry
+                    .published()
+                    .or_else(|| Some(entry.updated()))
+                    .map(|d| {
+                        // Convert to chrono DateTime and format as YYYY-MM-DD HH:MM
+                        let dt: chrono::DateTime<chrono::Utc> = (*d).into();
+                        let formatted = dt.format("%Y-%m-%d %H:%M").to_string();
+                        let ts = dt.timestamp();
+                        (formatted, Some(ts))
+                    })
+                    .unwrap_or_else(|| ("Unknown date".to_string(), None));
+
+                let author = entry
+                    .authors()
+                    .first()
+                    .map(|author| author.name().to_string());
+                let node = entry
+                    .categories()
+                    .first()
+                    .map(|category| category.term().to_string());
+                let replies = replies_in_link(&link);
+
+                RssItem {
+                    title,
+                    link,
+                    date,
+                    author,
+                    timestamp,
+                    node,
+                    replies,
+                    last_touched: None,
+                }
+            })
+            .collect();
+
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_url() {
+        let site = "https://www.v2ex.com";
+        assert_eq!(feed_url(site, "index"), "https://www.v2ex.com/index.xml");
+        assert_eq!(
+            feed_url(site, "tech"),
+            "https://www.v2ex.com/feed/tab/tech.xml"
+        );
+        assert_eq!(
+            custom_feed_url(site, "/feed/rust.xml"),
+            "https://www.v2ex.com/feed/rust.xml"
+        );
+        assert_eq!(
+            custom_feed_url(site, "https://example.com/atom.xml"),
+            "https://example.com/atom.xml"
+        );
+    }
+
+    #[test]
+    fn test_replies_in_link() {
+        assert_eq!(
+            replies_in_link("https://www.v2ex.com/t/42#reply17"),
+            Some(17)
+        );
+        assert_eq!(replies_in_link("https://www.v2ex.com/t/42"), None);
+        assert_eq!(replies_in_link("https://www.v2ex.com/t/42#reply"), None);
+    }
+
+    #[test]
+    fn test_rss_item_from_topic() {
+        let topic: Topic = serde_json::from_str(
+            r#"{"id": 42, "title": "Hello", "created": 1700000000, "member": {"id": 1, "username": "alice"}}"#,
+        )
+        .unwrap();
+        let item = RssItem::from_topic(&topic);
+        assert_eq!(item.link, "https://www.v2ex.com/t/42");
+        assert_eq!(item.extract_topic_id(), Some(42));
+        assert_eq!(item.date, "2023-11-14 22:13");
+        assert_eq!(item.timestamp, Some(1700000000));
+        assert_eq!(item.author.as_deref(), Some("alice"));
+        assert_eq!(item.replies, Some(0));
+        assert_eq!(item.node, None);
+
+        let topic: Topic = serde_json::from_str(
+            r#"{"id": 42, "replies": 9, "last_touched": 1700000600, "node": {"id": 1, "name": "rust", "title": "Rust"}}"#,
+        )
+        .unwrap();
+        let mut item = RssItem::from_topic(&topic);
+        item.enrich(&topic);
+        assert_eq!(item.node.as_deref(), Some("rust"));
+        assert_eq!(item.replies, Some(9));
+        assert_eq!(item.last_touched, Some(1700000600));
+    }
+}
@@ -0,0 +1,416 @@
+//! Topics: single topics, site-wide lists and thanking.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use super::client::Endpoint;
+use super::{lenient, ApiResponse, Member, Node, PartialList, ThankRefused, V2exClient, V2exError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Topic {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient::or_none")]
+    pub node: Option<Node>,
+    #[serde(default, deserialize_with = "lenient::or_none")]
+    pub member: Option<Member>,
+    pub last_reply_by: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_int")]
+    pub last_touched: Option<i64>,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub title: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub url: String,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub created: i64,
+    #[serde(default, deserialize_with = "lenient::opt_int")]
+    pub deleted: Option<i64>,
+    pub content: Option<String>,
+    pub content_rendered: Option<String>,
+    #[serde(default, deserialize_with = "lenient::opt_int")]
+    pub last_modified: Option<i64>,
+    #[serde(default, deserialize_with = "lenient::int")]
+    pub replies: i64,
+}
+
+impl Topic {
+    /// Get the node title for display
+    pub fn node_title(&self) -> &str {
+        self.node
+            .as_ref()
+            .map(|n| n.title.as_str())
+            .unwrap_or("Unknown")
+    }
+
+    /// Get the member username for display
+    pub fn author_name(&self) -> &str {
+        self.member
+            .as_ref()
+            .map(|m| m.username.as_str())
+            .unwrap_or("Unknown")
+    }
+}
+
+/// Where a reply's cell starts on a topic page
+static REPLY_CELL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<div id="r_(\d+)""#).unwrap());
+/// The heart and count shown on a thanked reply
+static REPLY_THANKS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<img [^>]*heart[^>]*>\s*(\d+)"#).unwrap());
+/// The one-time code in a page's links, or in the hidden field of its
+/// reply form
+static ONCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"[?&]once=(\d+)|name="once" value="(\d+)"|value="(\d+)" name="once""#).unwrap()
+});
+
+/// Popularity counters shown on a topic's web page
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TopicStats {
+    pub clicks: Option<i64>,
+    pub favorites: Option<i64>,
+    pub thanks: Option<i64>,
+    /// Thanks of the replies on the page by reply id, leaving out those
+    /// nobody thanked. Only the first page of a long topic is read.
+    pub reply_thanks: HashMap<i64, i64>,
+}
+
+impl TopicStats {
+    /// Pick counters such as `1234 次点击` out of a topic page; counters the
+    /// page does not show (e.g. no thanks yet) stay `None`
+    pub fn from_html(html: &str) -> Self {
+        Self {
+            clicks: count_before(html, "次点击"),
+            favorites: count_before(html, "人收藏"),
+            thanks: count_before(html, "人感谢"),
+            reply_thanks: reply_thanks_from_html(html),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clicks.is_none()
+            && self.favorites.is_none()
+            && self.thanks.is_none()
+            && self.reply_thanks.is_empty()
+    }
+
+    /// Take the counters `newer` has, keeping the others as they were
+    pub fn update(&mut self, newer: TopicStats) {
+        self.clicks = newer.clicks.or(self.clicks);
+        self.favorites = newer.favorites.or(self.favorites);
+        self.thanks = newer.thanks.or(self.thanks);
+        self.reply_thanks.extend(newer.reply_thanks);
+    }
+}
+
+/// The replies on a topic page, each with its markup up to the next one
+fn reply_cells(html: &str) -> Vec<(i64, &str)> {
+    let starts: Vec<_> = REPLY_CELL
+        .captures_iter(html)
+        .filter_map(|found| Some((found.get(0)?.start(), found[1].parse::<i64>().ok()?)))
+        .collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &(start, reply_id))| {
+            let end = starts.get(i + 1).map_or(html.len(), |&(next, _)| next);
+            (reply_id, &html[start..end])
+        })
+        .collect()
+}
+
+/// Thanks counts of the replies on a topic page, by reply id
+fn reply_thanks_from_html(html: &str) -> HashMap<i64, i64> {
+    reply_cells(html)
+        .into_iter()
+        .filter_map(|(reply_id, cell)| {
+            let thanks = REPLY_THANKS.captures(cell)?[1].parse().ok()?;
+            Some((reply_id, thanks))
+        })
+        .collect()
+}
+
+/// The one-time code a signed-in page sends its links and forms with
+fn once_from_html(html: &str) -> Option<&str> {
+    let found = ONCE.captures(html)?;
+    found
+        .iter()
+        .skip(1)
+        .flatten()
+        .next()
+        .map(|code| code.as_str())
+}
+
+/// Whether the thank button in `section` of a topic page, which runs the
+/// script `call`, can be used. V2EX shows a note instead once thanked, and
+/// no button at all on the member's own posts.
+fn thank_button(section: &str, call: &str) -> Result<(), ThankRefused> {
+    if section.contains("感谢已发送") {
+        Err(ThankRefused::AlreadyThanked)
+    } else if section.contains(call) {
+        Ok(())
+    } else {
+        Err(ThankRefused::OwnPost)
+    }
+}
+
+/// What V2EX answered a thank with. The answer is JSON, where a refusal
+/// comes as a message rather than a status.
+fn thank_answer(body: &str) -> Result<()> {
+    let Ok(answer) = serde_json::from_str::<ApiResponse<serde_json::Value>>(body) else {
+        return Ok(());
+    };
+    if answer.success {
+        return Ok(());
+    }
+    let message = answer.message.unwrap_or_default();
+    if message.contains("已经感谢") || message.to_lowercase().contains("already thanked") {
+        Err(ThankRefused::AlreadyThanked.into())
+    } else if message.contains("自己") {
+        Err(ThankRefused::OwnPost.into())
+    } else {
+        Err(V2exError::Api {
+            status: None,
+            message: Some(message),
+        }
+        .into())
+    }
+}
+
+/// The number written just before the first occurrence of `label`
+fn count_before(html: &str, label: &str) -> Option<i64> {
+    let end = html.find(label)?;
+    let prefix = html[..end].trim_end();
+    let digits_start = prefix
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_digit())
+        .last()
+        .map(|(i, _)| i)?;
+    prefix[digits_start..].parse().ok()
+}
+
+fn topic_endpoint(topic_id: i64) -> Endpoint {
+    Endpoint::get(format!("topics/{}", topic_id))
+}
+
+/// The topic's web page on `site`, which has counters the API lacks
+pub fn topic_page_url(site: &str, topic_id: i64) -> String {
+    format!("{}/t/{}", site, topic_id)
+}
+
+/// Permalink of a reply on its topic's page on `site`
+pub fn reply_page_url(site: &str, topic_id: i64, reply_id: i64) -> String {
+    format!("{}#r_{}", topic_page_url(site, topic_id), reply_id)
+}
+
+impl V2exClient {
+    pub async fn get_topic(&self, topic_id: i64) -> Result<Topic> {
+        self.fetch(&topic_endpoint(topic_id), "topic").await
+    }
+
+    /// A topic from the v1 API, which needs no token and doesn't draw on
+    /// the v2 budget
+    pub async fn get_topic_v1(&self, topic_id: i64) -> Result<Topic> {
+        let endpoint = Endpoint::v1("topics/show.json").query("id", topic_id);
+        let list: PartialList<Topic> = self.fetch_list(&endpoint).await?;
+        list.items
+            .into_iter()
+            .next()
+            .ok_or_else(|| V2exError::NotFound(Some(format!("Topic {}", topic_id))).into())
+    }
+
+    /// Click, favorite and thank counts from the topic's web page, since
+    /// the API does not expose them
+    pub async fn get_topic_stats(&self, topic_id: i64) -> Result<TopicStats> {
+        self.ensure_online()?;
+        let url = topic_page_url(self.site_base(), topic_id);
+        let request = self.http().get(&url);
+        let html = self
+            .send_with_retry(request, false)
+            .await
+            .and_then(|response| Ok(response.error_for_status()?))
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .text()
+            .await
+            .with_context(|| format!("Failed to read {}", url))?;
+        Ok(TopicStats::from_html(&html))
+    }
+
+    /// Thank the topic's author, which costs the member coins
+    pub async fn thank_topic(&self, topic_id: i64) -> Result<()> {
+        self.thank(topic_id, None).await
+    }
+
+    /// Thank a topic or, given `reply_id`, one of its replies through the
+    /// website. The topic page tells whether that can be done and has the
+    /// once-code to send; a reply past its first page is only checked by
+    /// V2EX.
+    pub(super) async fn thank(&self, topic_id: i64, reply_id: Option<i64>) -> Result<()> {
+        let html = self
+            .fetch_signed_in_page(&topic_page_url("", topic_id))
+            .await?;
+        let once = once_from_html(&html)
+            .context("The topic page has no once code; is cookie.txt signed in?")?;
+        let path = match reply_id {
+            Some(reply_id) => {
+                let cell = reply_cells(&html)
+                    .into_iter()
+                    .find_map(|(id, cell)| (id == reply_id).then_some(cell));
+                if let Some(cell) = cell {
+                    thank_button(cell, "thankReply(")?;
+                }
+                format!("/thank/reply/{}?once={}", reply_id, once)
+            }
+            None => {
+                let replies_start = REPLY_CELL
+                    .find(&html)
+                    .map_or(html.len(), |found| found.start());
+                thank_button(&html[..replies_start], "thankTopic(")?;
+                format!("/thank/topic/{}?once={}", topic_id, once)
+            }
+        };
+        thank_answer(&self.post_signed_in(&path).await?)
+    }
+
+    /// Topics trending site-wide today
+    pub async fn get_hot_topics(&self) -> Result<PartialList<Topic>> {
+        self.fetch_list(&Endpoint::v1("topics/hot.json")).await
+    }
+
+    /// Newest topics across all nodes
+    pub async fn get_latest_topics(&self) -> Result<PartialList<Topic>> {
+        self.fetch_list(&Endpoint::v1("topics/latest.json")).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::DEFAULT_API_BASE;
+
+    #[test]
+    fn test_topic_endpoints() {
+        assert_eq!(
+            topic_endpoint(42).url(DEFAULT_API_BASE),
+            "https://www.v2ex.com/api/v2/topics/42"
+        );
+        assert_eq!(
+            topic_page_url("https://www.v2ex.com", 42),
+            "https://www.v2ex.com/t/42"
+        );
+        assert_eq!(
+            reply_page_url("https://v2ex.example.org", 42, 7),
+            "https://v2ex.example.org/t/42#r_7"
+        );
+    }
+
+    #[test]
+    fn test_topic_stats_from_html() {
+        let html = r#"<small class="gray"><a href="/member/foo">foo</a> · 3 小时前 · 1234 次点击</small>
+            <div class="fr topic_stats">1234 次点击 &nbsp;∙&nbsp; 5 人收藏 &nbsp;</div>"#;
+        let stats = TopicStats::from_html(html);
+        assert_eq!(stats.clicks, Some(1234));
+        assert_eq!(stats.favorites, Some(5));
+        assert_eq!(stats.thanks, None);
+        assert!(TopicStats::from_html("<html></html>").is_empty());
+
+        let mut seen = stats.clone();
+        seen.thanks = Some(2);
+        seen.update(TopicStats::from_html("1300 次点击"));
+        assert_eq!(seen.clicks, Some(1300));
+        assert_eq!(seen.favorites, Some(5));
+        assert_eq!(seen.thanks, Some(2));
+    }
+
+    #[test]
+    fn test_reply_thanks_from_html() {
+        let html = r#"<div class="fr topic_stats">1234 次点击 &nbsp;∙&nbsp; 3 人感谢 &nbsp;</div>
+<div id="r_101" class="cell"><strong><a href="/member/alice">alice</a></strong>
+<span class="small fade"><img src="/static/img/heart_neue_red.png" width="14" align="absmiddle" alt="❤️" /> 3</span>
+<div class="reply_content">first</div></div>
+<div id="r_102" class="cell"><strong><a href="/member/bob">bob</a></strong>
+<div class="reply_content">nobody thanked 12 of these</div></div>
+<div id="r_103" class="cell"><span class="small fade"><img src="/static/img/heart_neue_red.png" /> 12</span></div>"#;
+        let stats = TopicStats::from_html(html);
+        assert_eq!(stats.thanks, Some(3));
+        assert_eq!(stats.reply_thanks, HashMap::from([(101, 3), (103, 12)]));
+
+        // Replies off the page fetched now keep what was read before
+        let mut seen = stats.clone();
+        seen.update(TopicStats::from_html(
+            r#"<div id="r_101" class="cell"><img src="/static/img/heart.png"> 4</div>"#,
+        ));
+        assert_eq!(seen.reply_thanks, HashMap::from([(101, 4), (103, 12)]));
+    }
+
+    #[test]
+    fn test_once_from_html() {
+        let html = r#"<a href="/favorite/topic/42?once=12345" class="op">加入收藏</a>"#;
+        assert_eq!(once_from_html(html), Some("12345"));
+        let html = r#"<form method="post" action="/t/42"><input type="hidden" value="678" name="once" /></form>"#;
+        assert_eq!(once_from_html(html), Some("678"));
+        let html = r#"<input type="hidden" name="once" value="910" />"#;
+        assert_eq!(once_from_html(html), Some("910"));
+        // A signed-out page has no code
+        assert_eq!(
+            once_from_html(r#"<a href="/signin?next=/t/42">登录</a>"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_thank_button() {
+        let offered = r##"<a href="#;" onclick="if (confirm('确认花费 10 个铜币？')) { thankReply(101); }" class="thank">感谢回复者</a>"##;
+        assert_eq!(thank_button(offered, "thankReply("), Ok(()));
+        let thanked = r#"<div id="thank_area_101" class="thank_area thanked">感谢已发送</div>"#;
+        assert_eq!(
+            thank_button(thanked, "thankReply("),
+            Err(ThankRefused::AlreadyThanked)
+        );
+        assert_eq!(
+            thank_button(r#"<div class="reply_content">mine</div>"#, "thankReply("),
+            Err(ThankRefused::OwnPost)
+        );
+    }
+
+    #[test]
+    fn test_thank_answer() {
+        assert!(thank_answer(r#"{"success": true, "once": 123}"#).is_ok());
+        assert!(thank_answer("<html>redirected back</html>").is_ok());
+        let refused = |body| {
+            thank_answer(body)
+                .unwrap_err()
+                .downcast_ref::<ThankRefused>()
+                .copied()
+        };
+        assert_eq!(
+            refused(r#"{"success": false, "message": "你已经感谢过这条回复了"}"#),
+            Some(ThankRefused::AlreadyThanked)
+        );
+        assert_eq!(
+            refused(r#"{"success": false, "message": "不能感谢自己的回复"}"#),
+            Some(ThankRefused::OwnPost)
+        );
+        assert_eq!(
+            refused(r#"{"success": false, "message": "铜币不足"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_topic_tolerates_null_and_missing_fields() {
+        let topic: Topic = serde_json::from_str(
+            r#"{"id": 1, "title": null, "created": "1700000000", "replies": null}"#,
+        )
+        .unwrap();
+        assert_eq!(topic.title, "");
+        assert_eq!(topic.created, 1700000000);
+        assert_eq!(topic.replies, 0);
+        assert!(topic.node.is_none());
+
+        let topic: Topic =
+            serde_json::from_str(r#"{"id": 2, "node": {"title": "broken"}}"#).unwrap();
+        assert!(topic.node.is_none());
+    }
+}
@@ -1,229 +1,2818 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
     Frame,
 };
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
-use crate::api::{Member, V2exClient};
+use crate::api::{
+    Member, Notification, PartialList, Reply, RssItem, SiteLink, ThankRefused, TokenInfo, Topic,
+    TopicStats, V2exClient, V2exError, REPLIES_PER_PAGE,
+};
+use crate::archive::ArchivedTopic;
+use crate::bookmarks::{parse_tags, Bookmarks};
 use crate::browser::Browser;
-use crate::state::{AggregateState, NodeState, NotificationState, TokenState, TopicState, UiState};
-use crate::ui::{render_error, render_loading, render_status_bar, render_token_input};
-use crate::views::aggregate::AggregateView;
-use crate::views::help::HelpView;
-use crate::views::node_select::NodeSelectView;
-use crate::views::notifications::NotificationsView;
-use crate::views::profile::ProfileView;
+use crate::config::engine::{Engine, HookEffect, Value};
+use crate::config::watcher::ConfigWatcher;
+use crate::config::{Config, ALL_FAVORITES_NODE};
+use crate::disk_cache::DiskCache;
+use crate::feed_topics::FeedTopics;
+use crate::keymap::key_hint;
+use crate::loads::{
+    self, AfterProfile, AfterReplies, AfterTopics, FetchedMember, FetchedReplies, FetchedTopic,
+    Finished, Loaded, Loads, Slot, TopicLoad,
+};
+use crate::minibuffer::PromptHistory;
+use crate::mouse::MouseRegions;
+use crate::prefetch::{
+    near_end, AggregateCache, AggregateTabs, LoadAhead, LoadedPage, NextPage, PrefetchedTopics,
+};
+use crate::profiler;
+use crate::read_tracker::{ReadTracker, SeenNotifications, SeenReplies, VisitedTopics};
+use crate::state::{
+    AggregateState, BookmarksState, CachedTopic, ClipboardHistory, CodePicker, FavoritesState,
+    HistoryBrowser, HistoryEntry, LinkHandler, MemberState, NodeState, NodeTab, NotificationState,
+    ReplyFilter, ReplyInputState, ReplySubmissions, Retry, TabsState, TokenState, TopicCache,
+    TopicPreviews, TopicState, UiState, ViewHistory, VisitedState,
+};
+use crate::translate::{Translation, Translator};
+use crate::tts::ReadAloud;
+use crate::ui::{
+    format_status, render_error_banner, render_status_bar, simplify_for_low_bandwidth, Loading,
+    NetworkStatus, StatusFields, Theme,
+};
+use crate::unfurl::LinkTitles;
+use crate::views;
+use crate::views::clipboard::ClipboardView;
+use crate::views::code_picker::CodePickerView;
+use crate::views::history::HistoryView;
+use crate::views::node_info::NodeInfoView;
 use crate::views::topic_detail::TopicDetailView;
-use crate::views::topic_list::TopicListView;
+use crate::views::topic_preview::TopicPreviewView;
+use crate::views::translation::TranslationView;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum View {
+    TopicList,
+    TopicDetail,
+    Notifications,
+    Profile,
+    Help,
+    NodeSelect,
+    TokenInput,
+    Aggregate,
+    ReplyInput,
+    Favorites,
+    Member,
+    History,
+    Bookmarks,
+    Tokens,
+}
+
+/// Result of a reply posted in the background
+#[derive(Debug)]
+pub struct ReplyOutcome {
+    pub topic_id: i64,
+    pub content: String,
+    pub result: Result<Reply, String>,
+}
+
+#[derive(Debug)]
+pub struct App {
+    pub view: View,
+    pub topic_state: TopicState,
+    /// Topics opened earlier this session, shared by every list they appear in
+    pub topic_cache: TopicCache,
+    /// Topics previewed from the topic list with SPC
+    pub topic_previews: TopicPreviews,
+    pub notification_state: NotificationState,
+    pub favorites_state: FavoritesState,
+    pub profile: Option<Member>,
+    pub member_state: MemberState,
+    pub node_state: NodeState,
+    /// Nodes open side by side in the topic list
+    pub tabs: TabsState,
+    /// Topic lists of nodes shown earlier this session, by node name, so
+    /// switching back needs no request
+    pub node_lists: HashMap<String, NodeTab>,
+    pub token_state: TokenState,
+    pub reply_input_state: ReplyInputState,
+    pub reply_submissions: ReplySubmissions,
+    reply_tx: mpsc::UnboundedSender<ReplyOutcome>,
+    reply_rx: mpsc::UnboundedReceiver<ReplyOutcome>,
+    /// Pages fetched by the background notification poller, when enabled
+    pub notification_updates: Option<mpsc::UnboundedReceiver<Vec<Notification>>>,
+    /// First pages of favorite nodes arriving from the startup prefetch
+    pub prefetch_updates: Option<mpsc::UnboundedReceiver<(String, Vec<Topic>)>>,
+    pub prefetched_topics: PrefetchedTopics,
+    /// Next page of the topic or reply list, fetched ahead of the selection
+    pub load_ahead: LoadAhead,
+    /// Reports edits to `config.lisp`, when it could be watched
+    pub config_watcher: Option<ConfigWatcher>,
+    /// Actions asked for by config hooks, run by the event handler next
+    pub queued_actions: Vec<String>,
+    /// Loads the user is waiting for
+    pub loads: Loads,
+    /// Whether the load being applied was started by auto-refresh
+    applying_quietly: bool,
+    pub ui_state: UiState,
+    pub aggregate_state: AggregateState,
+    pub aggregate_cache: AggregateCache,
+    pub config: Config,
+    pub read_tracker: ReadTracker,
+    /// Notification IDs the user has opened or marked read
+    pub read_notifications: ReadTracker,
+    /// How far the notifications view was seen, across sessions
+    pub seen_notifications: SeenNotifications,
+    pub seen_replies: SeenReplies,
+    /// Every topic opened, for the history view
+    pub visited_topics: VisitedTopics,
+    pub visited_state: VisitedState,
+    /// Topics bookmarked locally, with tags
+    pub bookmarks: Bookmarks,
+    pub bookmarks_state: BookmarksState,
+    pub prompt_history: PromptHistory,
+    /// Topic lists and topics to fall back on when the network is down
+    pub disk_cache: DiskCache,
+    pub clipboard_history: ClipboardHistory,
+    pub code_picker: CodePicker,
+    /// Favorites read from `favorite-archive-dir` in offline mode
+    pub archived_topics: Vec<ArchivedTopic>,
+    pub read_aloud: ReadAloud,
+    pub translator: Translator,
+    /// Titles of GitHub, YouTube and Twitter links, for link mode
+    pub link_titles: LinkTitles,
+    /// Topics of aggregate feed items, looked up for their nodes
+    pub feed_topics: FeedTopics,
+    pub terminal_height: usize,
+    /// Clickable regions of the last rendered frame
+    pub mouse_regions: MouseRegions,
+    // History navigation
+    pub history: ViewHistory,
+    /// History entry shown without its data, loaded by the main loop
+    pub history_reload: Option<HistoryEntry>,
+    pub history_browser: HistoryBrowser,
+}
+
+impl App {
+    pub fn new(config: Config) -> Self {
+        // Offline there is nothing to aggregate, only archived favorites
+        let initial_view = if config.offline {
+            View::Favorites
+        } else {
+            View::Aggregate
+        };
+        let (reply_tx, reply_rx) = mpsc::unbounded_channel();
+        let mut ui_state = UiState::new();
+        ui_state.theme_preset = config.theme;
+        ui_state.theme = Theme::with_custom(config.theme, &config.custom_theme);
+        ui_state.split = config.split_layout;
+        Self {
+            view: initial_view,
+            topic_state: TopicState::default(),
+            topic_cache: TopicCache::default(),
+            topic_previews: TopicPreviews::default(),
+            notification_state: NotificationState::default(),
+            favorites_state: FavoritesState::default(),
+            profile: None,
+            member_state: MemberState::default(),
+            node_state: NodeState::new(&config),
+            tabs: TabsState::default(),
+            node_lists: HashMap::new(),
+            token_state: TokenState::default(),
+            reply_input_state: ReplyInputState::default(),
+            reply_submissions: ReplySubmissions::default(),
+            reply_tx,
+            reply_rx,
+            notification_updates: None,
+            loads: Loads::default(),
+            applying_quietly: false,
+            config_watcher: None,
+            queued_actions: Vec::new(),
+            prefetch_updates: None,
+            prefetched_topics: PrefetchedTopics::default(),
+            load_ahead: LoadAhead::default(),
+            ui_state,
+            aggregate_state: AggregateState::new(),
+            aggregate_cache: AggregateCache::default(),
+            config,
+            read_tracker: ReadTracker::default(),
+            read_notifications: ReadTracker::default(),
+            seen_notifications: SeenNotifications::default(),
+            seen_replies: SeenReplies::default(),
+            visited_topics: VisitedTopics::default(),
+            visited_state: VisitedState::default(),
+            bookmarks: Bookmarks::default(),
+            bookmarks_state: BookmarksState::default(),
+            prompt_history: PromptHistory::default(),
+            disk_cache: DiskCache::default(),
+            clipboard_history: ClipboardHistory::default(),
+            code_picker: CodePicker::default(),
+            archived_topics: Vec::new(),
+            read_aloud: ReadAloud::default(),
+            translator: Translator::default(),
+            link_titles: LinkTitles::default(),
+            feed_topics: FeedTopics::default(),
+            terminal_height: 24, // Default height
+            mouse_regions: MouseRegions::default(),
+            history: ViewHistory::new(HistoryEntry {
+                view: initial_view,
+                topic_id: None,
+            }),
+            history_reload: None,
+            history_browser: HistoryBrowser::default(),
+        }
+    }
+
+    // Data loading methods
+    /// Fetch the current node's page of topics, or append the next page.
+    /// Fetching the list on screen again keeps the same topic selected, or
+    /// the same place if that topic is gone.
+    pub fn load_topics(&mut self, client: &V2exClient, append: bool) {
+        self.start_topics_load(client, append, AfterTopics::Nothing, false);
+    }
+
+    /// `load_topics`, doing `then` with the page once it arrived
+    pub fn load_topics_then(&mut self, client: &V2exClient, append: bool, then: AfterTopics) {
+        self.start_topics_load(client, append, then, false);
+    }
+
+    fn start_topics_load(
+        &mut self,
+        client: &V2exClient,
+        append: bool,
+        then: AfterTopics,
+        quiet: bool,
+    ) {
+        let node = self.node_state.current_node.clone();
+        let page = if append {
+            self.node_state.page + 1
+        } else {
+            self.node_state.page
+        };
+        let all_favorites = node == ALL_FAVORITES_NODE;
+        if !quiet {
+            self.ui_state.error = None;
+            if !append && !all_favorites {
+                self.load_node_info(client, false);
+            }
+            let prefetched = (!append && page == 1)
+                .then(|| self.prefetched_topics.take(&node))
+                .flatten();
+            if let Some(items) = prefetched {
+                self.loads.cancel(Slot::Topics);
+                let list = PartialList { items, skipped: 0 };
+                self.finish_topics(client, node, page, append, then, Ok((list, Vec::new())));
+                return;
+            }
+        }
+
+        let favorites = all_favorites.then(|| self.config.favorite_nodes());
+        let client = client.clone();
+        self.start_load(Slot::Topics, quiet, async move {
+            let result = match favorites {
+                Some(nodes) => loads::fetch_all_favorites(&client, nodes, page).await,
+                None => client
+                    .get_node_topics(&node, page)
+                    .await
+                    .map(|list| (list, Vec::new())),
+            };
+            Loaded::Topics {
+                node,
+                page,
+                append,
+                then,
+                result,
+            }
+        });
+    }
+
+    /// Show a page of topics fetched by `load_topics`, unless another node
+    /// or page was shown meanwhile
+    fn finish_topics(
+        &mut self,
+        client: &V2exClient,
+        node: String,
+        page: i32,
+        append: bool,
+        then: AfterTopics,
+        result: anyhow::Result<(PartialList<Topic>, Vec<String>)>,
+    ) {
+        let expected = if append {
+            self.node_state.page + 1
+        } else {
+            self.node_state.page
+        };
+        if node != self.node_state.current_node || page != expected {
+            return;
+        }
+        let previous = self.topic_state.selected;
+        let selected = self
+            .topic_state
+            .topics()
+            .get(previous)
+            .map(|topic| topic.id);
+        let all_favorites = node == ALL_FAVORITES_NODE;
+        match result {
+            Ok((list, failed_nodes)) => {
+                let skipped = list.skipped;
+                let mut new_topics = list.items;
+                self.topic_state.topics_cached_at = None;
+                if page == 1 && all_favorites {
+                    // Not cached, so there is nothing to compare with
+                    self.topic_state.reply_deltas.clear();
+                } else if page == 1 {
+                    // The previous snapshot tells which threads picked up replies
+                    self.topic_state.reply_deltas = self
+                        .disk_cache
+                        .load_topics(&node)
+                        .map(|cached| reply_deltas(&cached.value, &new_topics))
+                        .unwrap_or_default();
+                    // The cache is best effort; a failed write costs nothing now
+                    let _ = self.disk_cache.save_topics(&node, &new_topics);
+                }
+                if self.config.hide_read_topics {
+                    new_topics.retain(|topic| !self.read_tracker.is_read(topic.id));
+                }
+                if append {
+                    let count = new_topics.len();
+                    let first_new = self.topic_state.topics().len();
+                    self.node_state.page = page;
+                    self.topic_state.append_topics(new_topics);
+                    self.ui_state.status_message = format!(
+                        "Loaded {} more topics (total: {}) from {}",
+                        count,
+                        self.topic_state.topics().len(),
+                        node
+                    );
+                    self.note_skipped(skipped);
+                    self.after_topics(client, then, first_new, count > 0);
+                } else {
+                    self.topic_previews.close();
+                    self.topic_state.set_topics(new_topics);
+                    self.topic_state.selected =
+                        reselect(self.topic_state.topics(), selected, previous, |topic| {
+                            topic.id
+                        });
+                    self.topic_state.show_selection();
+                    self.ui_state.status_message = format!(
+                        "Loaded {} topics from {}",
+                        self.topic_state.topics().len(),
+                        node
+                    );
+                    self.note_skipped(skipped);
+                }
+                if !failed_nodes.is_empty() {
+                    self.ui_state
+                        .status_message
+                        .push_str(&format!(" (failed: {})", failed_nodes.join(", ")));
+                }
+            }
+            // Nothing more to move on to, which is no error
+            Err(_) if then != AfterTopics::Nothing => {
+                self.after_topics(client, then, 0, false);
+            }
+            Err(e) if !append && crate::api::is_network_unavailable(&e) => {
+                match self.disk_cache.load_topics(&node) {
+                    Some(cached) => {
+                        let mut topics = cached.value;
+                        if self.config.hide_read_topics {
+                            topics.retain(|topic| !self.read_tracker.is_read(topic.id));
+                        }
+                        self.topic_previews.close();
+                        self.topic_state.set_topics(topics);
+                        self.topic_state.selected =
+                            reselect(self.topic_state.topics(), selected, previous, |topic| {
+                                topic.id
+                            });
+                        self.topic_state.show_selection();
+                        self.topic_state.topics_cached_at = Some(cached.saved_at);
+                        self.ui_state.status_message = format!(
+                            "{}: showing {} topics cached {}",
+                            e,
+                            node,
+                            crate::util::format_relative_time(cached.saved_at)
+                        );
+                    }
+                    None => {
+                        self.load_failed(
+                            &e,
+                            format!("Failed to load topics: {}", e),
+                            Retry::Topics { append },
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                self.load_failed(
+                    &e,
+                    format!("Failed to load topics: {}", e),
+                    Retry::Topics { append },
+                );
+            }
+        }
+    }
+
+    /// Move on past the last topic once more were appended from
+    /// `first_new`, if any came
+    fn after_topics(
+        &mut self,
+        client: &V2exClient,
+        then: AfterTopics,
+        first_new: usize,
+        added: bool,
+    ) {
+        if then == AfterTopics::Nothing {
+            return;
+        }
+        if !added {
+            self.ui_state.status_message = "Already at the last topic".to_string();
+            return;
+        }
+        match then {
+            AfterTopics::SelectNext => self.topic_state.next_topic(),
+            AfterTopics::OpenNext => {
+                if let Some(topic_id) = self.topic_state.topics().get(first_new).map(|t| t.id) {
+                    self.show_topic(client, topic_id);
+                    self.ui_state.status_message =
+                        format!("Switched to next topic (#{})", first_new + 1);
+                }
+            }
+            AfterTopics::Nothing => {}
+        }
+    }
+
+    /// Fetch the current node's title, topic count and description once,
+    /// opening the node details popup with them when `show`. They only
+    /// decorate the topic list, so failures are ignored.
+    pub fn load_node_info(&mut self, client: &V2exClient, show: bool) {
+        let name = self.node_state.current_node.clone();
+        if self.node_state.node_info.contains_key(&name) || client.is_offline() {
+            if show {
+                self.show_node_info();
+            }
+            return;
+        }
+        let client = client.clone();
+        self.loads.start(Slot::NodeInfo, async move {
+            let result = client.get_node(&name).await;
+            Loaded::NodeInfo { name, show, result }
+        });
+    }
+
+    fn show_node_info(&mut self) {
+        if self.node_state.current_info().is_some() {
+            self.node_state.show_info = true;
+        } else {
+            self.ui_state.status_message = format!(
+                "No details available for node {}",
+                self.node_state.current_node
+            );
+        }
+    }
+
+    /// Show the start of the selected topic over the topic list, from a
+    /// topic opened earlier or else fetched once
+    pub fn preview_selected_topic(&mut self, client: &V2exClient) {
+        let Some(topic_id) = self.topic_state.selected_topic().map(|topic| topic.id) else {
+            return;
+        };
+        if !self.topic_previews.contains(topic_id) {
+            if let Some(cached) = self.topic_cache.get(topic_id) {
+                self.topic_previews.insert(cached.topic.clone());
+            } else {
+                let client = client.clone();
+                self.loads.start(Slot::Preview, async move {
+                    let result = client.get_topic(topic_id).await;
+                    Loaded::Preview { topic_id, result }
+                });
+                return;
+            }
+        }
+        self.loads.cancel(Slot::Preview);
+        self.topic_previews.show(topic_id);
+    }
+
+    /// Show a topic fetched for its preview, if it is still selected
+    fn finish_preview(&mut self, topic_id: i64, result: anyhow::Result<Topic>) {
+        match result {
+            Ok(topic) => {
+                self.topic_previews.insert(topic);
+                if self.topic_state.selected_topic().map(|topic| topic.id) == Some(topic_id) {
+                    self.topic_previews.show(topic_id);
+                }
+            }
+            Err(e) => {
+                self.topic_previews.close();
+                self.ui_state.status_message =
+                    format!("Failed to preview topic {}: {:#}", topic_id, e);
+            }
+        }
+    }
+
+    /// Open a topic in detail view, whichever list it was picked from.
+    ///
+    /// Every entry point goes through here so the same topic always maps to
+    /// one in-app copy: the topic already on screen is reused, one opened
+    /// earlier comes back from the cache where it was left, and a detail view
+    /// replacing another does not push a second history entry.
+    pub fn open_topic(&mut self, client: &V2exClient, topic_id: i64) {
+        if self.start_topic_load(client, topic_id, TopicLoad::Open) {
+            self.enter_topic_detail();
+        }
+    }
+
+    fn enter_topic_detail(&mut self) {
+        if self.view == View::TopicDetail {
+            let entry = self.history_entry(View::TopicDetail);
+            self.history.replace_current(entry);
+        } else {
+            self.navigate_to(View::TopicDetail);
+        }
+    }
+
+    /// Put a topic in `topic_state`, from the cache when possible
+    pub fn show_topic(&mut self, client: &V2exClient, topic_id: i64) {
+        self.start_topic_load(client, topic_id, TopicLoad::Show);
+    }
+
+    /// Show a topic kept in memory, or else clear the topic and fetch it.
+    /// Returns whether it is shown already.
+    fn start_topic_load(&mut self, client: &V2exClient, topic_id: i64, how: TopicLoad) -> bool {
+        let current_id = self.topic_state.current.as_ref().map(|topic| topic.id);
+        if current_id == Some(topic_id) {
+            self.ui_state.status_message = format!("Topic {}", topic_id);
+            return true;
+        }
+
+        if let Some(snapshot) = self.topic_state.snapshot() {
+            self.topic_cache.insert(snapshot);
+        }
+        self.topic_state.show_replies = false;
+        if let Some(cached) = self.topic_cache.get(topic_id).cloned() {
+            if let Err(e) = self.read_tracker.mark_read(topic_id) {
+                self.ui_state.error = Some(format!("Failed to save read state: {}", e));
+            }
+            self.topic_state.restore(cached);
+            self.ui_state.status_message =
+                format!("Topic {} (cached, press g to refresh)", topic_id);
+        } else if let Some(archived) = self
+            .archived_topics
+            .iter()
+            .find(|archived| archived.topic.id == topic_id)
+            .cloned()
+        {
+            if let Err(e) = self.read_tracker.mark_read(topic_id) {
+                self.ui_state.error = Some(format!("Failed to save read state: {}", e));
+            }
+            let replies_page = (archived.replies.len() / REPLIES_PER_PAGE) as i32 + 1;
+            self.topic_state.restore(CachedTopic {
+                topic: archived.topic,
+                stats: None,
+                replies: archived.replies,
+                replies_page,
+                scroll: 0,
+                selected_reply: 0,
+                cached_at: None,
+                first_unread: None,
+            });
+            self.ui_state.status_message = format!("Topic {} (archived)", topic_id);
+        } else {
+            self.topic_state.current = None;
+            self.topic_state.first_unread = None;
+            self.topic_state.set_replies(Vec::new());
+            self.topic_state.reset_scroll();
+            self.ui_state.error = None;
+            self.loads.cancel(Slot::Replies);
+            let client = client.clone();
+            self.loads.start(Slot::Topic, async move {
+                let result = loads::fetch_topic(&client, topic_id, 1).await;
+                Loaded::Topic {
+                    topic_id,
+                    how,
+                    result,
+                }
+            });
+            return false;
+        }
+        self.loads.cancel(Slot::Topic);
+        self.loads.cancel(Slot::Replies);
+        self.topic_opened(client);
+        true
+    }
+
+    /// Show a topic fetched by `open_topic` or `show_topic`, unless another
+    /// one was shown meanwhile
+    fn finish_topic(
+        &mut self,
+        client: &V2exClient,
+        topic_id: i64,
+        how: TopicLoad,
+        result: anyhow::Result<FetchedTopic>,
+    ) {
+        if how == TopicLoad::Refresh {
+            self.finish_refresh(topic_id, result);
+            return;
+        }
+        if self.topic_state.current.is_some() {
+            return;
+        }
+        match result {
+            Ok(fetched) => {
+                self.show_fetched_topic(topic_id, fetched.topic, fetched.stats);
+                self.apply_replies(topic_id, 1, fetched.replies);
+            }
+            Err(e)
+                if crate::api::is_network_unavailable(&e)
+                    && self.restore_from_disk_cache(topic_id, &e) => {}
+            Err(e) => {
+                self.load_failed(
+                    &e,
+                    format!("Failed to load topic: {}", e),
+                    Retry::Topic(topic_id),
+                );
+                return;
+            }
+        }
+        self.topic_opened(client);
+        if how == TopicLoad::Open {
+            self.enter_topic_detail();
+        }
+    }
+
+    /// Make a fetched topic the open one, with its counters
+    fn show_fetched_topic(&mut self, topic_id: i64, topic: Topic, stats: Option<TopicStats>) {
+        if let Err(e) = self.read_tracker.mark_read(topic_id) {
+            self.ui_state.error = Some(format!("Failed to save read state: {}", e));
+        }
+        self.topic_state.current = Some(topic);
+        self.topic_state.cached_at = None;
+        self.topic_state.set_stats(stats);
+        self.topic_state.detect_links();
+        self.ui_state.status_message = format!("Loaded topic {}", topic_id);
+    }
+
+    /// Note what a just-shown topic means for the read markers and history,
+    /// and run the `on-topic-open` hooks
+    fn topic_opened(&mut self, client: &V2exClient) {
+        self.note_seen_replies();
+        self.note_visited_topic();
+        self.run_topic_open_hooks(client);
+    }
+
+    /// Run the `on-startup` hooks from the config
+    pub fn run_startup_hooks(&mut self, client: &V2exClient) {
+        let forms = self.config.hooks.startup.clone();
+        self.run_hook(client, "on-startup", &forms, HashMap::new());
+    }
+
+    /// Run the `on-topic-open` hooks with the shown topic's details bound
+    fn run_topic_open_hooks(&mut self, client: &V2exClient) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        if self.config.hooks.topic_open.is_empty() {
+            return;
+        }
+        let vars = HashMap::from([
+            ("topic-id".to_string(), Value::Int(topic.id)),
+            ("topic-title".to_string(), Value::Str(topic.title.clone())),
+            (
+                "topic-node".to_string(),
+                Value::Str(
+                    topic
+                        .node
+                        .as_ref()
+                        .map(|node| node.name.clone())
+                        .unwrap_or_default(),
+                ),
+            ),
+            (
+                "topic-author".to_string(),
+                Value::Str(
+                    topic
+                        .member
+                        .as_ref()
+                        .map(|member| member.username.clone())
+                        .unwrap_or_default(),
+                ),
+            ),
+        ]);
+        let forms = self.config.hooks.topic_open.clone();
+        self.run_hook(client, "on-topic-open", &forms, vars);
+    }
+
+    /// Run an action from `define-action`, reporting failures in the
+    /// status bar. Built-in actions it runs are queued for the event handler.
+    pub fn run_custom_action(&mut self, client: &V2exClient, name: &str) {
+        let Some(forms) = self.config.actions.get(name).map(<[Value]>::to_vec) else {
+            self.ui_state.status_message = format!("Unknown action '{}'", name);
+            return;
+        };
+        self.run_forms(client, &format!("Action {}", name), &forms, HashMap::new());
+    }
+
+    fn run_hook(
+        &mut self,
+        client: &V2exClient,
+        name: &str,
+        forms: &[Value],
+        vars: HashMap<String, Value>,
+    ) {
+        if forms.is_empty() {
+            return;
+        }
+        self.run_forms(client, &format!("Hook {}", name), forms, vars);
+    }
+
+    /// Evaluate hook or action `forms` and carry out what they asked for
+    fn run_forms(
+        &mut self,
+        client: &V2exClient,
+        label: &str,
+        forms: &[Value],
+        vars: HashMap<String, Value>,
+    ) {
+        let effects = match Engine::run_hook(forms, vars, &self.config.actions) {
+            Ok(effects) => effects,
+            Err(e) => {
+                self.ui_state.status_message = format!("{} failed: {:#}", label, e);
+                return;
+            }
+        };
+        for effect in effects {
+            match effect {
+                HookEffect::Message(message) => self.ui_state.status_message = message,
+                HookEffect::Action(action) => self.queued_actions.push(action),
+                HookEffect::OpenNode(node) => {
+                    self.switch_to_node(client, &node);
+                    self.navigate_to(View::TopicList);
+                }
+            }
+        }
+    }
+
+    fn note_visited_topic(&mut self) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        if let Err(e) = self.visited_topics.record(topic) {
+            self.ui_state.error = Some(format!("Failed to save visited topics: {}", e));
+        }
+    }
+
+    /// Open the history view with every visited topic listed
+    pub fn open_visited_topics(&mut self) {
+        self.visited_state.prompt.clear();
+        self.visited_state
+            .update_matches(self.visited_topics.entries());
+        self.navigate_to(View::History);
+    }
+
+    /// Record the open topic's reply count, marking where the replies
+    /// posted since the previous visit start
+    fn note_seen_replies(&mut self) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        match self.seen_replies.record(topic.id, topic.replies) {
+            Ok(Some(seen)) if seen < topic.replies => {
+                self.topic_state.first_unread = Some(seen as usize);
+                self.ui_state.status_message = format!(
+                    "{} new replies since your last visit (. to jump)",
+                    topic.replies - seen
+                );
+            }
+            Ok(_) => {}
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to save seen replies: {}", e));
+            }
+        }
+    }
+
+    /// Show all replies, then the topic author's, then those mentioning us
+    pub fn cycle_reply_filter(&mut self, client: &V2exClient) {
+        // Who "us" is needs the profile, which may not have been opened yet
+        if self.topic_state.reply_filter == ReplyFilter::Op
+            && self.profile.is_none()
+            && !client.is_offline()
+        {
+            self.start_profile_load(client, AfterProfile::CycleReplyFilter);
+            return;
+        }
+        self.next_reply_filter();
+    }
+
+    fn next_reply_filter(&mut self) {
+        let me = self.profile.as_ref().map(|member| member.username.clone());
+        self.topic_state.cycle_reply_filter(me.as_deref());
+        self.topic_state.show_replies = true;
+        self.ui_state.status_message = match &self.topic_state.reply_filter {
+            ReplyFilter::All => "Showing all replies".to_string(),
+            ReplyFilter::Op => "Showing replies by the topic author".to_string(),
+            ReplyFilter::Mentioning(me) => format!("Showing replies mentioning @{}", me),
+        };
+    }
+
+    /// Select the first reply posted since the previous visit
+    pub fn jump_to_first_unread(&mut self, client: &V2exClient) {
+        match self.topic_state.first_unread {
+            Some(index) => self.jump_to_reply(client, index + 1),
+            None => {
+                self.ui_state.status_message = "No new replies since your last visit".to_string()
+            }
+        }
+    }
+
+    /// Show a topic saved in the disk cache after `error` kept it from
+    /// loading. Returns false if it was never cached.
+    fn restore_from_disk_cache(&mut self, topic_id: i64, error: &anyhow::Error) -> bool {
+        let Some(cached) = self.disk_cache.load_topic(topic_id) else {
+            return false;
+        };
+        if let Err(e) = self.read_tracker.mark_read(topic_id) {
+            self.ui_state.error = Some(format!("Failed to save read state: {}", e));
+        }
+        let replies = cached.value.replies;
+        self.topic_state.restore(CachedTopic {
+            topic: cached.value.topic,
+            stats: None,
+            replies_page: (replies.len() / REPLIES_PER_PAGE) as i32 + 1,
+            replies,
+            scroll: 0,
+            selected_reply: 0,
+            cached_at: Some(cached.saved_at),
+            first_unread: None,
+        });
+        self.ui_state.status_message = format!(
+            "{}: showing topic {} cached {}",
+            error,
+            topic_id,
+            crate::util::format_relative_time(cached.saved_at)
+        );
+        true
+    }
+
+    pub fn load_topic_replies(&mut self, client: &V2exClient, topic_id: i64, append: bool) {
+        let page = if append {
+            self.topic_state.replies_page
+        } else {
+            1
+        };
+        self.start_replies_load(client, topic_id, page, 1, AfterReplies::Nothing);
+    }
+
+    /// Fetch reply pages of the open topic from `page` on until `wanted`
+    /// more replies came, doing `then` once they arrived
+    fn start_replies_load(
+        &mut self,
+        client: &V2exClient,
+        topic_id: i64,
+        page: i32,
+        wanted: usize,
+        then: AfterReplies,
+    ) {
+        self.ui_state.error = None;
+        let client = client.clone();
+        self.loads.start(Slot::Replies, async move {
+            let replies = loads::fetch_replies(&client, topic_id, page, wanted).await;
+            Loaded::Replies {
+                topic_id,
+                page,
+                then,
+                replies,
+            }
+        });
+    }
+
+    /// Add replies fetched by `start_replies_load` to the open topic, if
+    /// it is still open and no other load got there first
+    fn finish_replies(
+        &mut self,
+        topic_id: i64,
+        page: i32,
+        then: AfterReplies,
+        replies: FetchedReplies,
+    ) {
+        let current = self.topic_state.current.as_ref().map(|topic| topic.id);
+        if current != Some(topic_id) || (page > 1 && page != self.topic_state.replies_page) {
+            return;
+        }
+        let loaded = self.topic_state.replies().len();
+        let failed = replies.error.is_some();
+        self.apply_replies(topic_id, page, replies);
+        self.after_replies(then, loaded, failed);
+    }
+
+    /// Do what replies were loaded for, `loaded` being how many there were
+    /// before
+    fn after_replies(&mut self, then: AfterReplies, loaded: usize, failed: bool) {
+        match then {
+            AfterReplies::Nothing => {}
+            AfterReplies::SelectNext => {
+                if self.topic_state.replies().len() > loaded && self.topic_state.has_next_reply() {
+                    self.topic_state.next_reply();
+                } else {
+                    self.ui_state.status_message = "Already at the last reply".to_string();
+                }
+            }
+            AfterReplies::Jump(floor) => self.select_floor(floor),
+            AfterReplies::ExportText if !failed => self.write_topic_text(),
+            AfterReplies::Export if !failed => self.write_topic_export(),
+            AfterReplies::ExportText | AfterReplies::Export => {}
+        }
+    }
+
+    /// Put replies fetched from `page` on in the open topic, after those
+    /// loaded already unless `page` is the first
+    fn apply_replies(&mut self, topic_id: i64, page: i32, replies: FetchedReplies) {
+        let append = page > 1;
+        if replies.pages > 0 {
+            let replies_len = replies.items.len();
+            if append {
+                self.topic_state.append_replies(replies.items);
+                self.ui_state.status_message = format!(
+                    "Loaded {} more replies (total: {})",
+                    replies_len,
+                    self.topic_state.replies().len()
+                );
+            } else {
+                let is_empty = replies.items.is_empty();
+                self.topic_state.set_replies(replies.items);
+                self.topic_state.selected_reply = 0;
+                if is_empty {
+                    self.topic_state.replies_list_state.select(None);
+                } else {
+                    self.topic_state.replies_list_state.select(Some(0));
+                }
+                self.ui_state.status_message = format!("Loaded {} replies", replies_len);
+            }
+            self.note_skipped(replies.skipped);
+            self.topic_state.replies_page = page + replies.pages;
+            if let Some(topic) = &self.topic_state.current {
+                let _ = self
+                    .disk_cache
+                    .save_topic(topic, self.topic_state.replies());
+            }
+            // Update links after loading replies
+            self.topic_state.detect_links();
+        }
+        if let Some(e) = replies.error {
+            self.load_failed(
+                &e,
+                format!("Failed to load replies: {}", e),
+                Retry::Replies { topic_id, append },
+            );
+        }
+    }
+
+    /// Select the reply after the selected one, loading the next page first
+    /// when the last loaded reply is selected and the topic has more
+    pub fn next_reply_or_load(&mut self, client: &V2exClient) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        let topic_id = topic.id;
+        let has_more = self.topic_state.replies().len() < topic.replies as usize;
+        if self.topic_state.has_next_reply() {
+            self.topic_state.next_reply();
+        } else if has_more {
+            let page = self.topic_state.replies_page;
+            self.start_replies_load(client, topic_id, page, 1, AfterReplies::SelectNext);
+        } else {
+            self.ui_state.status_message = "Already at the last reply".to_string();
+        }
+    }
+
+    /// Fetch the open topic and as many pages of replies as were loaded
+    /// again, keeping the same reply selected and the topic scrolled where
+    /// it was
+    pub fn refresh_topic(&mut self, client: &V2exClient) {
+        let Some(topic_id) = self.topic_state.current.as_ref().map(|topic| topic.id) else {
+            return;
+        };
+        let loaded = self.topic_state.replies().len();
+        self.ui_state.error = None;
+        self.loads.cancel(Slot::Replies);
+        let client = client.clone();
+        self.loads.start(Slot::Topic, async move {
+            let result = loads::fetch_topic(&client, topic_id, loaded).await;
+            Loaded::Topic {
+                topic_id,
+                how: TopicLoad::Refresh,
+                result,
+            }
+        });
+    }
+
+    /// Show the open topic fetched again by `refresh_topic`, with the
+    /// reply selected now still selected
+    fn finish_refresh(&mut self, topic_id: i64, result: anyhow::Result<FetchedTopic>) {
+        if self.topic_state.current.as_ref().map(|topic| topic.id) != Some(topic_id) {
+            return;
+        }
+        let state = &self.topic_state;
+        let selected = state
+            .replies()
+            .get(state.selected_reply)
+            .map(|reply| reply.id);
+        let (previous, scroll) = (state.selected_reply, state.scroll);
+
+        match result {
+            Ok(fetched) => {
+                // Counters that can't be read this time keep their last value
+                let mut stats = self.topic_state.stats.take().unwrap_or_default();
+                if let Some(fresh) = fetched.stats {
+                    stats.update(fresh);
+                }
+                self.show_fetched_topic(topic_id, fetched.topic, Some(stats));
+                self.apply_replies(topic_id, 1, fetched.replies);
+            }
+            Err(e)
+                if crate::api::is_network_unavailable(&e)
+                    && self.restore_from_disk_cache(topic_id, &e) => {}
+            Err(e) => {
+                self.load_failed(
+                    &e,
+                    format!("Failed to load topic: {}", e),
+                    Retry::Topic(topic_id),
+                );
+                return;
+            }
+        }
+
+        let state = &mut self.topic_state;
+        // Through select_reply, so the list and the link labels follow
+        let index = reselect(state.replies(), selected, previous, |reply| reply.id);
+        state.select_reply(index);
+        state.scroll = scroll;
+        if self.ui_state.error.is_none() {
+            self.ui_state.status_message = format!(
+                "Refreshed topic {} ({} replies)",
+                topic_id,
+                self.topic_state.replies().len()
+            );
+        }
+    }
+
+    /// Start fetching the next page of the topic or reply list once the
+    /// selection is within `load-ahead` items of its end
+    pub fn start_load_ahead(&mut self, client: &V2exClient) {
+        let distance = self.config.load_ahead as usize;
+        if distance == 0 || client.is_offline() {
+            return;
+        }
+        let state = &self.topic_state;
+        let page = match self.view {
+            View::TopicList
+                if self.node_state.current_node != ALL_FAVORITES_NODE
+                    && state.topics_cached_at.is_none()
+                    && near_end(
+                        state.topic_position().unwrap_or(0),
+                        state.topic_order().len(),
+                        distance,
+                    ) =>
+            {
+                NextPage::Topics {
+                    node: self.node_state.current_node.clone(),
+                    page: self.node_state.page + 1,
+                }
+            }
+            View::TopicDetail if state.show_replies => {
+                let Some(topic) = state.current.as_ref() else {
+                    return;
+                };
+                let order = state.reply_order();
+                let position = state.reply_position().unwrap_or(0);
+                if state.replies().len() >= topic.replies as usize
+                    || !near_end(position, order.len(), distance)
+                {
+                    return;
+                }
+                NextPage::Replies {
+                    topic_id: topic.id,
+                    page: state.replies_page,
+                }
+            }
+            _ => return,
+        };
+        self.load_ahead.start(client, page);
+    }
+
+    /// Append a page fetched by `start_load_ahead`, if its list is still
+    /// the one on screen and no other load got there first. Failures are
+    /// not shown; pressing n at the end loads the page as usual.
+    pub fn finish_load_ahead(&mut self, page: NextPage, result: anyhow::Result<LoadedPage>) {
+        match (&page, result) {
+            (NextPage::Topics { node, page: number }, Ok(LoadedPage::Topics(list))) => {
+                if *node != self.node_state.current_node || *number != self.node_state.page + 1 {
+                    return;
+                }
+                let mut topics = list.items;
+                if topics.is_empty() {
+                    self.load_ahead.skip(page);
+                    return;
+                }
+                if self.config.hide_read_topics {
+                    topics.retain(|topic| !self.read_tracker.is_read(topic.id));
+                }
+                self.node_state.page = *number;
+                self.topic_state.append_topics(topics);
+                self.ui_state.status_message = format!(
+                    "Loaded page {} of {} ({} topics)",
+                    number,
+                    node,
+                    self.topic_state.topics().len()
+                );
+                self.note_skipped(list.skipped);
+            }
+            (
+                NextPage::Replies {
+                    topic_id,
+                    page: number,
+                },
+                Ok(LoadedPage::Replies(list)),
+            ) => {
+                let current = self.topic_state.current.as_ref().map(|topic| topic.id);
+                if current != Some(*topic_id) || *number != self.topic_state.replies_page {
+                    return;
+                }
+                if list.items.is_empty() {
+                    self.load_ahead.skip(page);
+                    return;
+                }
+                self.topic_state.append_replies(list.items);
+                self.topic_state.replies_page += 1;
+                self.ui_state.status_message =
+                    format!("Loaded {} replies", self.topic_state.replies().len());
+                self.note_skipped(list.skipped);
+                if let Some(topic) = &self.topic_state.current {
+                    let _ = self
+                        .disk_cache
+                        .save_topic(topic, self.topic_state.replies());
+                }
+                self.topic_state.detect_links();
+            }
+            _ => self.load_ahead.skip(page),
+        }
+    }
+
+    /// Look up the titles of the links in the post being read, when link
+    /// unfurling is on
+    pub fn start_link_titles(&mut self, client: &V2exClient) {
+        if !self.config.unfurl_links || client.is_offline() || self.view != View::TopicDetail {
+            return;
+        }
+        if let Some(links) = self.topic_state.selected_links() {
+            self.link_titles.start(client, links.iter());
+        }
+    }
+
+    /// Look up the topics of the aggregate items on screen, when that is
+    /// turned on with `aggregate-resolve-topics`
+    pub fn start_feed_topics(&mut self, client: &V2exClient) {
+        if !self.config.aggregate_resolve_topics
+            || client.is_offline()
+            || self.view != View::Aggregate
+        {
+            return;
+        }
+        let Some(list) = self.mouse_regions.list.as_ref() else {
+            return;
+        };
+        let items = &self.aggregate_state.items;
+        let rows = crate::feed_topics::visible_rows(
+            list.offset,
+            list.area.height as usize,
+            self.aggregate_state.selected,
+            items.len(),
+        );
+        self.feed_topics.start(client, &items[rows]);
+    }
+
+    /// Show what the lookup of a feed item's topic found
+    pub fn finish_feed_topic(&mut self, topic: Topic) {
+        self.feed_topics.insert(topic);
+        self.feed_topics.enrich(&mut self.aggregate_state.items);
+    }
+
+    /// Keep a title found by link unfurling. A link whose lookup failed
+    /// just shows no title.
+    pub fn finish_link_title(&mut self, url: String, result: anyhow::Result<String>) {
+        if let Ok(title) = result {
+            self.link_titles.insert(url, title);
+        }
+    }
+
+    /// Keep the link titles found this session for the next one. Without
+    /// a cache they are just looked up again.
+    pub fn save_link_titles(&mut self) {
+        if let Some(titles) = self.link_titles.unsaved() {
+            let _ = self.disk_cache.save_link_titles(titles);
+        }
+    }
+
+    /// Load the reply pages missing before reply `floor`, then do `then`;
+    /// right away if they are all loaded
+    fn load_replies_through(&mut self, client: &V2exClient, floor: usize, then: AfterReplies) {
+        let Some(topic_id) = self.topic_state.current.as_ref().map(|topic| topic.id) else {
+            return;
+        };
+        let loaded = self.topic_state.replies().len();
+        if floor <= loaded {
+            self.after_replies(then, loaded, false);
+            return;
+        }
+        let page = if loaded > 0 {
+            self.topic_state.replies_page
+        } else {
+            1
+        };
+        self.start_replies_load(client, topic_id, page, floor - loaded, then);
+    }
+
+    /// Write the open topic and all of its replies as plain text to
+    /// `v2ex-<id>.txt` in the current directory
+    pub fn export_topic_text(&mut self, client: &V2exClient) {
+        let total = self.topic_state.current.as_ref().map_or(0, |t| t.replies);
+        self.load_replies_through(client, total as usize, AfterReplies::ExportText);
+    }
+
+    fn write_topic_text(&mut self) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        let topic_id = topic.id;
+        let text = crate::cli_output::format_printable(
+            topic,
+            self.topic_state.replies(),
+            crate::cli_output::PRINT_WIDTH,
+        );
+        let path = std::path::PathBuf::from(format!("v2ex-{}.txt", topic_id));
+        match std::fs::write(&path, text) {
+            Ok(()) => {
+                let shown = std::fs::canonicalize(&path).unwrap_or(path);
+                self.ui_state.status_message = format!(
+                    "Saved topic with {} replies to {}",
+                    self.topic_state.replies().len(),
+                    shown.display()
+                );
+            }
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to save {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    /// Write the open topic and all of its replies to the file named by
+    /// `export-path`, as HTML for a `.html` path and Markdown otherwise
+    pub fn export_topic(&mut self, client: &V2exClient) {
+        let total = self.topic_state.current.as_ref().map_or(0, |t| t.replies);
+        self.load_replies_through(client, total as usize, AfterReplies::Export);
+    }
+
+    fn write_topic_export(&mut self) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        let Some(path) = self.config.export_path_for(topic) else {
+            self.ui_state.error = Some("No home directory to expand ~/ in export-path".to_string());
+            return;
+        };
+
+        let is_html = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+        let replies = self.topic_state.replies();
+        let document = if is_html {
+            crate::cli_output::format_html(topic, replies)
+        } else {
+            crate::cli_output::format_markdown(topic, replies)
+        };
+        let written = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => std::fs::create_dir_all(dir),
+            _ => Ok(()),
+        }
+        .and_then(|()| std::fs::write(&path, document));
+        match written {
+            Ok(()) => {
+                let shown = std::fs::canonicalize(&path).unwrap_or(path);
+                self.ui_state.status_message = format!(
+                    "Exported topic with {} replies to {}",
+                    replies.len(),
+                    shown.display()
+                );
+            }
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to save {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    /// Bookmark the open topic with the tags typed at the `M` prompt,
+    /// replacing the tags if it is bookmarked already
+    pub fn bookmark_topic(&mut self, input: &str) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        let tags = parse_tags(input);
+        let described = if tags.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " as {}",
+                tags.iter()
+                    .map(|tag| format!("#{}", tag))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )
+        };
+        match self.bookmarks.add(topic, tags) {
+            Ok(()) => {
+                self.ui_state.status_message = format!("Bookmarked topic {}{}", topic.id, described)
+            }
+            Err(e) => self.ui_state.error = Some(format!("Failed to save bookmarks: {}", e)),
+        }
+    }
+
+    /// Bookmarks shown in the bookmarks view, under its tag filter
+    pub fn visible_bookmarks(&self) -> Vec<&crate::bookmarks::Bookmark> {
+        self.bookmarks.filtered(self.bookmarks_state.tag.as_deref())
+    }
+
+    pub fn selected_bookmark_id(&self) -> Option<i64> {
+        self.visible_bookmarks()
+            .get(self.bookmarks_state.selected)
+            .map(|bookmark| bookmark.id)
+    }
+
+    pub fn remove_selected_bookmark(&mut self) {
+        let Some(topic_id) = self.selected_bookmark_id() else {
+            return;
+        };
+        match self.bookmarks.remove(topic_id) {
+            Ok(Some(bookmark)) => {
+                // Showing all bookmarks again once the last with the tag is gone
+                if self.visible_bookmarks().is_empty() {
+                    self.bookmarks_state.tag = None;
+                }
+                let len = self.visible_bookmarks().len();
+                self.bookmarks_state.selected =
+                    self.bookmarks_state.selected.min(len.saturating_sub(1));
+                self.ui_state.status_message = format!("Removed bookmark \"{}\"", bookmark.title);
+            }
+            Ok(None) => {}
+            Err(e) => self.ui_state.error = Some(format!("Failed to save bookmarks: {}", e)),
+        }
+    }
+
+    /// Write the bookmarks under the current tag filter as a Markdown list
+    /// to `v2ex-bookmarks.md` (or `v2ex-bookmarks-<tag>.md`) in the current
+    /// directory
+    pub fn export_bookmarks(&mut self) {
+        let tag = self.bookmarks_state.tag.as_deref();
+        let path = std::path::PathBuf::from(match tag {
+            Some(tag) => format!(
+                "v2ex-bookmarks-{}.md",
+                tag.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "-")
+            ),
+            None => "v2ex-bookmarks.md".to_string(),
+        });
+        let count = self.bookmarks.filtered(tag).len();
+        match std::fs::write(&path, self.bookmarks.to_markdown(tag)) {
+            Ok(()) => {
+                let shown = std::fs::canonicalize(&path).unwrap_or(path);
+                self.ui_state.status_message =
+                    format!("Exported {} bookmarks to {}", count, shown.display());
+            }
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to save {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    /// Select reply `floor`, loading the pages before it first so floors
+    /// keep matching list positions
+    pub fn jump_to_reply(&mut self, client: &V2exClient, floor: usize) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        let total = topic.replies as usize;
+        if floor == 0 || floor > total {
+            self.ui_state.status_message =
+                format!("No reply #{} (this topic has {} replies)", floor, total);
+            return;
+        }
+
+        self.load_replies_through(client, floor, AfterReplies::Jump(floor));
+    }
+
+    /// Select reply `floor` once the replies before it are loaded
+    fn select_floor(&mut self, floor: usize) {
+        if floor > self.topic_state.replies().len() {
+            if self.ui_state.error.is_none() {
+                self.ui_state.status_message = format!("Reply #{} could not be loaded", floor);
+            }
+            return;
+        }
+        self.topic_state.show_replies = true;
+        self.topic_state.select_reply(floor - 1);
+        self.ui_state.status_message = format!(
+            "Jumped to reply #{} (page {})",
+            floor,
+            (floor - 1) / crate::api::REPLIES_PER_PAGE + 1
+        );
+    }
+
+    pub fn load_notifications(&mut self, client: &V2exClient) {
+        self.start_notifications_load(client, 1, false);
+    }
+
+    /// Add the next page of notifications below those loaded
+    pub fn load_more_notifications(&mut self, client: &V2exClient) {
+        let page = self.notification_state.page + 1;
+        self.start_notifications_load(client, page, false);
+    }
+
+    fn start_notifications_load(&mut self, client: &V2exClient, page: i32, quiet: bool) {
+        if !quiet {
+            self.ui_state.error = None;
+        }
+        let client = client.clone();
+        self.start_load(Slot::Notifications, quiet, async move {
+            let result = client.get_notifications(page).await;
+            Loaded::Notifications { page, result }
+        });
+    }
+
+    /// Show the first page of notifications, keeping the same one
+    /// selected, or add a further page below those loaded
+    fn finish_notifications(
+        &mut self,
+        page: i32,
+        result: anyhow::Result<PartialList<Notification>>,
+    ) {
+        let append = page > 1;
+        if append && page != self.notification_state.page + 1 {
+            return;
+        }
+        match result {
+            Ok(list) if append && list.items.is_empty() && list.skipped == 0 => {
+                self.ui_state.status_message = "No more notifications".to_string();
+            }
+            Ok(list) if append => {
+                let added = self.notification_state.append_page(page, list.items);
+                self.ui_state.status_message = format!(
+                    "Loaded {} more notifications ({} in all)",
+                    added,
+                    self.notification_state.notifications.len()
+                );
+                self.note_skipped(list.skipped);
+            }
+            Ok(list) => {
+                let state = &mut self.notification_state;
+                let selected = state.notifications.get(state.selected).map(|n| n.id);
+                state.replace_first_page(list.items);
+                state.selected = reselect(&state.notifications, selected, state.selected, |n| n.id);
+                self.mark_notifications_seen();
+                self.ui_state.status_message = format!(
+                    "Loaded {} notifications",
+                    self.notification_state.notifications.len()
+                );
+                self.note_skipped(list.skipped);
+            }
+            Err(e) => {
+                let message = if append {
+                    format!("Failed to load more notifications: {}", e)
+                } else {
+                    format!("Failed to load notifications: {}", e)
+                };
+                self.load_failed(&e, message, Retry::Notifications { append });
+            }
+        }
+    }
+
+    /// Clear the unread badge now that the notifications are on screen, and
+    /// remember how far they go for the next session
+    fn mark_notifications_seen(&mut self) {
+        self.notification_state.mark_all_seen();
+        let Some(id) = self.notification_state.last_seen_id else {
+            return;
+        };
+        if let Err(e) = self.seen_notifications.record(id) {
+            self.ui_state.error = Some(format!("Failed to save seen notifications: {}", e));
+        }
+    }
+
+    /// Delete the selected notification on V2EX and drop it from the list,
+    /// once asked twice in a row
+    pub fn delete_selected_notification(&mut self, client: &V2exClient) {
+        let state = &mut self.notification_state;
+        let Some(id) = state.notifications.get(state.selected).map(|n| n.id) else {
+            return;
+        };
+        if state.delete_pending.take() != Some(id) {
+            state.delete_pending = Some(id);
+            self.ui_state.status_message = "Press d again to delete this notification".to_string();
+            return;
+        }
+        self.ui_state.status_message = "Deleting notification...".to_string();
+        let client = client.clone();
+        self.loads.start_action(async move {
+            let result = client.delete_notification(id).await;
+            Loaded::NotificationDeleted { id, result }
+        });
+    }
+
+    fn finish_notification_delete(&mut self, id: i64, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.notification_state.remove(id);
+                self.ui_state.status_message = "Notification deleted".to_string();
+            }
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to delete notification: {}", e));
+            }
+        }
+    }
+
+    /// Flip the read state of the selected notification
+    pub fn toggle_notification_read(&mut self) {
+        let state = &self.notification_state;
+        let Some(id) = state.notifications.get(state.selected).map(|n| n.id) else {
+            return;
+        };
+        match self.read_notifications.toggle(id) {
+            Ok(true) => self.ui_state.status_message = "Marked as read".to_string(),
+            Ok(false) => self.ui_state.status_message = "Marked as unread".to_string(),
+            Err(e) => self.ui_state.error = Some(format!("Failed to save read state: {}", e)),
+        }
+    }
+
+    /// Remember that the selected notification was opened
+    pub fn mark_notification_read(&mut self) {
+        let state = &self.notification_state;
+        let Some(id) = state.notifications.get(state.selected).map(|n| n.id) else {
+            return;
+        };
+        if let Err(e) = self.read_notifications.mark_read(id) {
+            self.ui_state.error = Some(format!("Failed to save read state: {}", e));
+        }
+    }
+
+    pub fn load_favorites(&mut self, client: &V2exClient) {
+        if client.is_offline() {
+            self.load_archived_favorites();
+            return;
+        }
+        self.ui_state.error = None;
+        let client = client.clone();
+        self.loads.start(Slot::Favorites, async move {
+            Loaded::Favorites(client.get_favorite_topics(1).await)
+        });
+    }
+
+    fn finish_favorites(&mut self, result: anyhow::Result<PartialList<Topic>>) {
+        match result {
+            Ok(list) => {
+                self.favorites_state.set_topics(list.items);
+                self.ui_state.status_message = format!(
+                    "Loaded {} favorite topics",
+                    self.favorites_state.topics.len()
+                );
+                self.note_skipped(list.skipped);
+            }
+            Err(e) => {
+                self.load_failed(
+                    &e,
+                    format!("Failed to load favorites: {}", e),
+                    Retry::Favorites,
+                );
+            }
+        }
+    }
+
+    /// Offline stand-in for favorites: the topics under `favorite-archive-dir`
+    fn load_archived_favorites(&mut self) {
+        self.ui_state.error = None;
+        let Some(dir) = self.config.favorite_archive_path() else {
+            self.ui_state.error = Some(
+                "Offline mode: set favorite-archive-dir to read archived favorites".to_string(),
+            );
+            return;
+        };
+
+        match crate::archive::load_all(&dir) {
+            Ok(list) => {
+                self.favorites_state
+                    .set_topics(list.items.iter().map(|a| a.topic.clone()).collect());
+                self.archived_topics = list.items;
+                self.ui_state.status_message = format!(
+                    "Offline: {} archived topics in {}",
+                    self.archived_topics.len(),
+                    dir.display()
+                );
+                self.note_skipped(list.skipped);
+            }
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to read archive: {:#}", e));
+            }
+        }
+    }
+
+    /// Favorite or unfavorite the open topic, or the selected one in Favorites
+    pub fn toggle_favorite(&mut self, client: &V2exClient) {
+        let topic_id = match self.view {
+            View::TopicDetail => self.topic_state.current.as_ref().map(|topic| topic.id),
+            View::Favorites => self
+                .favorites_state
+                .topics
+                .get(self.favorites_state.selected)
+                .map(|topic| topic.id),
+            _ => None,
+        };
+        let Some(topic_id) = topic_id else {
+            self.ui_state.status_message = "No topic selected".to_string();
+            return;
+        };
+
+        let client = client.clone();
+        self.loads.start_action(async move {
+            let result = client.toggle_topic_favorite(topic_id).await;
+            Loaded::Favorite { topic_id, result }
+        });
+    }
+
+    fn finish_favorite(
+        &mut self,
+        client: &V2exClient,
+        topic_id: i64,
+        result: anyhow::Result<bool>,
+    ) {
+        match result {
+            Ok(favorite) => {
+                self.favorites_state.set_favorite(topic_id, favorite);
+                self.ui_state.status_message = if favorite {
+                    format!("Added topic {} to favorites", topic_id)
+                } else {
+                    format!("Removed topic {} from favorites", topic_id)
+                };
+                if favorite {
+                    self.archive_favorite(client, topic_id);
+                }
+            }
+            Err(e) => {
+                self.ui_state.status_message = format!("Failed to update favorite: {}", e);
+            }
+        }
+    }
+
+    /// Thank the selected reply, or the topic when the replies are hidden.
+    /// The count goes up at once and is put back if the thank fails.
+    pub fn thank(&mut self, client: &V2exClient) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            self.ui_state.status_message = "No topic selected".to_string();
+            return;
+        };
+        let topic_id = topic.id;
+        let reply = self
+            .topic_state
+            .show_replies
+            .then(|| {
+                self.topic_state
+                    .replies()
+                    .get(self.topic_state.selected_reply)
+            })
+            .flatten();
+        let (reply_id, author) = match reply {
+            Some(reply) => (
+                Some(reply.id),
+                reply
+                    .member
+                    .as_ref()
+                    .map(|member| member.username.clone())
+                    .unwrap_or_default(),
+            ),
+            None => (None, topic.author_name().to_string()),
+        };
+        let me = self.profile.as_ref().map(|member| member.username.as_str());
+        if me == Some(author.as_str()) {
+            self.ui_state.status_message = format!("Cannot thank your own {}", thanked(reply_id));
+            return;
+        }
+
+        self.topic_state.add_thanks(reply_id, 1);
+        self.ui_state.status_message = format!("Thanking @{}...", author);
+        let client = client.clone();
+        self.loads.start_action(async move {
+            let result = match reply_id {
+                Some(reply_id) => client.thank_reply(topic_id, reply_id).await,
+                None => client.thank_topic(topic_id).await,
+            };
+            Loaded::Thanked {
+                topic_id,
+                reply_id,
+                author,
+                result,
+            }
+        });
+    }
+
+    fn finish_thank(
+        &mut self,
+        topic_id: i64,
+        reply_id: Option<i64>,
+        author: String,
+        result: anyhow::Result<()>,
+    ) {
+        let Err(e) = result else {
+            self.ui_state.status_message = format!("Thanked @{}", author);
+            return;
+        };
+        if self.topic_state.current.as_ref().map(|topic| topic.id) == Some(topic_id) {
+            self.topic_state.add_thanks(reply_id, -1);
+        }
+        self.ui_state.status_message = match e.downcast_ref::<ThankRefused>() {
+            Some(ThankRefused::AlreadyThanked) => {
+                format!("Already thanked this {} by @{}", thanked(reply_id), author)
+            }
+            Some(ThankRefused::OwnPost) => {
+                format!("Cannot thank your own {}", thanked(reply_id))
+            }
+            None => format!("Failed to thank @{}: {}", author, e),
+        };
+    }
+
+    /// Save a just-favorited topic with its replies as Markdown under
+    /// `favorite-archive-dir`, so it survives the topic being deleted. The
+    /// open topic is saved as loaded if all of its replies are.
+    fn archive_favorite(&mut self, client: &V2exClient, topic_id: i64) {
+        let Some(dir) = self.config.favorite_archive_path() else {
+            return;
+        };
+
+        let loaded = self
+            .topic_state
+            .current
+            .as_ref()
+            .filter(|topic| topic.id == topic_id)
+            .filter(|topic| self.topic_state.replies().len() >= topic.replies as usize);
+        if let Some(topic) = loaded {
+            let result = crate::archive::save(&dir, topic, self.topic_state.replies());
+            self.finish_archive(topic_id, result);
+            return;
+        }
+        let client = client.clone();
+        self.loads.start_action(async move {
+            let fetched = async {
+                let topic = client.get_topic(topic_id).await?;
+                let replies = client
+                    .get_all_topic_replies(topic_id, topic.replies as usize)
+                    .await?;
+                Ok((topic, replies.items))
+            }
+            .await;
+            let result =
+                fetched.and_then(|(topic, replies)| crate::archive::save(&dir, &topic, &replies));
+            Loaded::Archived { topic_id, result }
+        });
+    }
+
+    fn finish_archive(&mut self, topic_id: i64, result: anyhow::Result<PathBuf>) {
+        match result {
+            Ok(path) => {
+                self.ui_state.status_message = format!(
+                    "Added topic {} to favorites, archived to {}",
+                    topic_id,
+                    path.display()
+                );
+            }
+            Err(e) => {
+                self.ui_state.status_message = format!(
+                    "Added topic {} to favorites, but archiving failed: {}",
+                    topic_id, e
+                );
+            }
+        }
+    }
+
+    /// Apply `config.lisp` again after it changed on disk: theme, key
+    /// bindings, favorite nodes and pane layout take effect right away
+    pub fn reload_config(&mut self) {
+        match crate::config::reload_config() {
+            Ok(config) => {
+                self.apply_config(config);
+                self.ui_state.status_message = "Configuration reloaded".to_string();
+            }
+            Err(e) => {
+                self.ui_state.status_message = format!("Configuration not reloaded: {:#}", e);
+            }
+        }
+    }
+
+    fn apply_config(&mut self, mut config: Config) {
+        // Chosen with --offline for the whole run; the client can't switch
+        config.offline = self.config.offline;
+        self.ui_state.theme_preset = config.theme;
+        self.ui_state.theme = Theme::with_custom(config.theme, &config.custom_theme);
+        self.ui_state.split = config.split_layout;
+        self.node_state.quick_nodes = config.quick_nodes();
+        self.node_state.original_favorite_nodes = config.favorite_nodes();
+        if !self.node_state.is_completion_mode {
+            self.node_state.favorite_nodes = self.node_state.original_favorite_nodes.clone();
+            self.node_state.selected = 0;
+        }
+        self.config = config;
+    }
+
+    /// Switch to the next theme preset, keeping `set-theme-color` overrides
+    pub fn cycle_theme(&mut self) {
+        let preset = self.ui_state.theme_preset.next();
+        self.ui_state.theme_preset = preset;
+        self.ui_state.theme = Theme::with_custom(preset, &self.config.custom_theme);
+        self.ui_state.status_message = format!("Theme: {}", preset.name());
+    }
+
+    /// Load another member's profile and recent topics, going to the member
+    /// view once they are there when `show`
+    /// Fetch a member with their recent topics and replies. The signed-in
+    /// member comes from the v2 API, others only the v1 API can look up.
+    pub fn load_member(&mut self, client: &V2exClient, username: &str, show: bool) {
+        self.ui_state.error = None;
+        let client = client.clone();
+        let username = username.to_string();
+        let own = self
+            .profile
+            .as_ref()
+            .is_some_and(|profile| profile.username.eq_ignore_ascii_case(&username));
+        self.loads.start(Slot::Member, async move {
+            let member = if own {
+                client.get_member().await
+            } else {
+                client.get_member_by_username(&username).await
+            };
+            let result = match member {
+                Ok(member) => {
+                    let (topics, replies) = tokio::join!(
+                        client.get_member_topics(&username),
+                        client.get_member_replies(&username)
+                    );
+                    Ok(FetchedMember {
+                        member,
+                        topics,
+                        replies,
+                    })
+                }
+                Err(e) => Err(e),
+            };
+            Loaded::Member {
+                username,
+                show,
+                result,
+            }
+        });
+    }
+
+    fn finish_member(
+        &mut self,
+        username: String,
+        show: bool,
+        result: anyhow::Result<FetchedMember>,
+    ) {
+        match result {
+            Ok(fetched) => {
+                if show {
+                    self.navigate_to(View::Member);
+                }
+                self.member_state = MemberState {
+                    member: Some(fetched.member),
+                    showing: self.member_state.showing,
+                    ..Default::default()
+                };
+                let mut missing = Vec::new();
+                let mut skipped = 0;
+                match fetched.topics {
+                    Ok(list) => {
+                        self.member_state.topics = list.items;
+                        skipped += list.skipped;
+                    }
+                    Err(e) => missing.push(format!("topics: {}", e)),
+                }
+                match fetched.replies {
+                    Ok(list) => {
+                        self.member_state.replies = list.items;
+                        skipped += list.skipped;
+                    }
+                    Err(e) => missing.push(format!("replies: {}", e)),
+                }
+                self.ui_state.status_message = if missing.is_empty() {
+                    format!(
+                        "Loaded {} with {} recent topics and {} replies",
+                        username,
+                        self.member_state.topics.len(),
+                        self.member_state.replies.len()
+                    )
+                } else {
+                    format!("Loaded {}, but not their {}", username, missing.join("; "))
+                };
+                self.note_skipped(skipped);
+            }
+            Err(e) => {
+                self.load_failed(
+                    &e,
+                    format!("Failed to load member {}: {}", username, e),
+                    Retry::Member(username),
+                );
+            }
+        }
+    }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum View {
-    TopicList,
-    TopicDetail,
-    Notifications,
-    Profile,
-    Help,
-    NodeSelect,
-    TokenInput,
-    Aggregate,
-}
+    /// Open the profile of the selected reply's author, or the topic author
+    pub fn open_author_profile(&mut self, client: &V2exClient) {
+        let reply_author = self
+            .topic_state
+            .show_replies
+            .then(|| {
+                self.topic_state
+                    .replies()
+                    .get(self.topic_state.selected_reply)
+            })
+            .flatten()
+            .and_then(|reply| reply.member.as_ref());
+        let author = reply_author.or_else(|| {
+            self.topic_state
+                .current
+                .as_ref()
+                .and_then(|topic| topic.member.as_ref())
+        });
+        let Some(username) = author.map(|member| member.username.clone()) else {
+            self.ui_state.status_message = "No author to show".to_string();
+            return;
+        };
 
-#[derive(Debug)]
-pub struct App {
-    pub view: View,
-    pub topic_state: TopicState,
-    pub notification_state: NotificationState,
-    pub profile: Option<Member>,
-    pub node_state: NodeState,
-    pub token_state: TokenState,
-    pub ui_state: UiState,
-    pub aggregate_state: AggregateState,
-    pub terminal_width: usize,
-    pub terminal_height: usize,
-    // History navigation
-    pub view_history: Vec<View>,
-    pub history_position: usize,
-}
+        self.load_member(client, &username, true);
+    }
 
-impl App {
-    pub fn new() -> Self {
-        let initial_view = View::Aggregate;
-        Self {
-            view: initial_view,
-            topic_state: TopicState::default(),
-            notification_state: NotificationState::default(),
-            profile: None,
-            node_state: NodeState::new(),
-            token_state: TokenState::default(),
-            ui_state: UiState::new(),
-            aggregate_state: AggregateState::new(),
-            terminal_width: 80,  // Default width
-            terminal_height: 24, // Default height
-            view_history: vec![initial_view],
-            history_position: 0,
+    pub fn open_member_in_browser(&mut self, client: &V2exClient) {
+        if let Some(ref member) = self.member_state.member {
+            match Browser::open_member(client.site_base(), &member.username) {
+                Ok(result) => {
+                    self.ui_state.status_message = result.to_string();
+                }
+                Err(e) => {
+                    self.ui_state.error = Some(format!("Failed to open browser: {}", e));
+                }
+            }
         }
     }
 
-    // Data loading methods
-    pub async fn load_topics(&mut self, client: &V2exClient, append: bool) {
-        self.ui_state.loading = true;
+    /// Take in a page of notifications found by the background poller
+    pub fn apply_polled_notifications(&mut self, page: Vec<Notification>) {
+        let fresh = self.notification_state.register_polled(&page);
+        if fresh.is_empty() || !self.config.desktop_notifications {
+            return;
+        }
+        let summary = if fresh.len() == 1 {
+            "V2EX: new notification".to_string()
+        } else {
+            format!("V2EX: {} new notifications", fresh.len())
+        };
+        let body = crate::render::plain(&fresh[0].text, 80);
+        crate::notifier::send_desktop_notification(summary, body.trim().to_string());
+    }
+
+    /// Mention items dropped because the API returned malformed data
+    fn note_skipped(&mut self, skipped: usize) {
+        if skipped > 0 {
+            self.ui_state
+                .status_message
+                .push_str(&format!(" ({} malformed skipped)", skipped));
+        }
+    }
+
+    pub fn load_profile(&mut self, client: &V2exClient) {
+        self.start_profile_load(client, AfterProfile::Nothing);
+    }
+
+    /// Find out who the token belongs to at startup, without holding up
+    /// the first frame or replacing the status line
+    pub fn load_startup_profile(&mut self, client: &V2exClient) {
+        self.start_profile_load(client, AfterProfile::Startup);
+    }
+
+    fn start_profile_load(&mut self, client: &V2exClient, then: AfterProfile) {
         self.ui_state.error = None;
+        let client = client.clone();
+        self.loads.start(Slot::Profile, async move {
+            let result = client.get_member().await;
+            Loaded::Profile { then, result }
+        });
+    }
 
-        match client
-            .get_node_topics(&self.node_state.current_node, self.node_state.page)
-            .await
-        {
-            Ok(mut new_topics) => {
-                if append && self.node_state.page > 1 {
-                    self.topic_state.topics.append(&mut new_topics);
-                    self.ui_state.status_message = format!(
-                        "Loaded {} more topics (total: {}) from {}",
-                        new_topics.len(),
-                        self.topic_state.topics.len(),
-                        self.node_state.current_node
-                    );
-                } else {
-                    self.topic_state.topics = new_topics;
-                    self.topic_state.selected = 0;
-                    self.ui_state.status_message = format!(
-                        "Loaded {} topics from {}",
-                        self.topic_state.topics.len(),
-                        self.node_state.current_node
-                    );
+    fn finish_profile(&mut self, then: AfterProfile, result: anyhow::Result<Member>) {
+        match (result, then) {
+            (Ok(member), then) => {
+                match &then {
+                    AfterProfile::SwitchedToken(name) => {
+                        self.ui_state.status_message =
+                            format!("Switched to '{}' as {}", name, member.username);
+                    }
+                    AfterProfile::Startup => {}
+                    _ => self.ui_state.status_message = "Loaded profile".to_string(),
+                }
+                self.profile = Some(member);
+                if then == AfterProfile::CycleReplyFilter {
+                    self.next_reply_filter();
                 }
             }
-            Err(e) => {
-                self.ui_state.error = Some(format!("Failed to load topics: {}", e));
+            // Without the username there is just no filter for mentions
+            (Err(_), AfterProfile::CycleReplyFilter) => self.next_reply_filter(),
+            (Err(e), then) => {
+                self.load_failed(&e, format!("Failed to load profile: {}", e), Retry::Profile);
+                if let AfterProfile::SwitchedToken(name) = then {
+                    self.ui_state.status_message = format!("Switched to '{}'", name);
+                }
             }
         }
+    }
 
-        self.ui_state.loading = false;
+    /// Open the token manager on the token in use and check every token
+    pub fn open_tokens(&mut self, client: &V2exClient) {
+        let store = &self.token_state.store;
+        self.token_state.selected = store
+            .tokens()
+            .iter()
+            .position(|token| store.is_active(&token.name))
+            .unwrap_or(0);
+        self.navigate_to(View::Tokens);
+        self.check_tokens(client);
     }
 
-    pub async fn load_topic_detail(&mut self, client: &V2exClient, topic_id: i64) {
-        self.ui_state.loading = true;
+    /// Ask `/token` about every saved token at once, each with itself
+    pub fn check_tokens(&mut self, client: &V2exClient) {
         self.ui_state.error = None;
+        let clients: Vec<_> = self
+            .token_state
+            .store
+            .tokens()
+            .iter()
+            .map(|token| (token.name.clone(), client.with_token(&token.token)))
+            .collect();
+        self.loads.start(Slot::Tokens, async move {
+            let infos = futures_util::future::join_all(clients.into_iter().map(
+                |(name, client)| async move {
+                    let info = client
+                        .get_token_info()
+                        .await
+                        .map_err(|e| format!("{:#}", e));
+                    (name, info)
+                },
+            ))
+            .await;
+            Loaded::Tokens(infos)
+        });
+    }
 
-        match client.get_topic(topic_id).await {
-            Ok(topic) => {
-                self.topic_state.current = Some(topic);
-                self.topic_state.detect_links(self.terminal_width);
-                self.ui_state.status_message = format!("Loaded topic {}", topic_id);
+    fn finish_tokens(&mut self, infos: Vec<(String, Result<TokenInfo, String>)>) {
+        self.ui_state.status_message = format!("Checked {} tokens", infos.len());
+        self.token_state.info.extend(infos);
+    }
+
+    /// Use the highlighted token from now on, for every request, and load
+    /// the profile of its account
+    pub fn switch_token(&mut self, client: &V2exClient) {
+        let Some(name) = self.token_state.selected_name() else {
+            return;
+        };
+        if self.token_state.store.is_active(&name) {
+            self.ui_state.status_message = format!("Already using '{}'", name);
+            return;
+        }
+        match self.token_state.store.activate(&name) {
+            Ok(token) => {
+                client.set_token(&token);
+                // What belonged to the other account
+                self.profile = None;
+                self.notification_state = NotificationState::default();
+                self.favorites_state = FavoritesState::default();
+                self.start_profile_load(client, AfterProfile::SwitchedToken(name.clone()));
+                self.ui_state.status_message = format!("Switched to '{}'", name);
             }
             Err(e) => {
-                self.ui_state.error = Some(format!("Failed to load topic: {}", e));
+                self.ui_state.error = Some(format!("Failed to switch token: {:#}", e));
+            }
+        }
+    }
+
+    /// Save a token typed into the token manager and highlight it
+    pub fn add_token(&mut self, name: &str, token: &str) {
+        match self.token_state.store.add(name, token) {
+            Ok(()) => {
+                let name = name.trim();
+                self.token_state.selected = self
+                    .token_state
+                    .store
+                    .tokens()
+                    .iter()
+                    .position(|token| token.name == name)
+                    .unwrap_or(0);
+                self.token_state.info.remove(name);
+                self.ui_state.status_message = format!("Added token '{}' (g to check it)", name);
+            }
+            Err(e) => self.ui_state.error = Some(format!("Failed to add token: {:#}", e)),
+        }
+    }
+
+    pub fn remove_selected_token(&mut self) {
+        let Some(name) = self.token_state.selected_name() else {
+            return;
+        };
+        match self.token_state.store.remove(&name) {
+            Ok(()) => {
+                self.token_state.info.remove(&name);
+                let len = self.token_state.store.tokens().len();
+                self.token_state.selected = self.token_state.selected.min(len.saturating_sub(1));
+                self.ui_state.status_message = format!("Removed token '{}'", name);
             }
+            Err(e) => self.ui_state.error = Some(format!("{:#}", e)),
         }
+    }
+
+    pub fn load_aggregate(&mut self, client: &V2exClient) {
+        self.start_aggregate_load(client, false, false);
+    }
+
+    /// Fetch the first aggregate tab at startup. Status messages set while
+    /// it loads, like config errors, are left in place.
+    pub fn load_startup_aggregate(&mut self, client: &V2exClient) {
+        self.start_aggregate_load(client, true, false);
+    }
 
-        self.ui_state.loading = false;
+    fn start_aggregate_load(&mut self, client: &V2exClient, keep_message: bool, quiet: bool) {
+        if !quiet {
+            self.ui_state.error = None;
+        }
+        let client = client.clone();
+        let tab = self.aggregate_state.current_tab.clone();
+        let feed = self.config.feed_url(&tab).map(str::to_string);
+        self.start_load(Slot::Aggregate, quiet, async move {
+            let result = client.get_aggregate_items(&tab, feed.as_deref()).await;
+            Loaded::Aggregate {
+                tab,
+                keep_message,
+                result,
+            }
+        });
     }
 
-    pub async fn load_topic_replies(&mut self, client: &V2exClient, topic_id: i64, append: bool) {
-        self.ui_state.loading = true;
+    /// Show another aggregate tab, from the cache while it is fresh
+    pub fn switch_aggregate_tab(&mut self, client: &V2exClient, tab: &str) {
+        self.aggregate_state.switch_tab(tab);
+        let ttl = Duration::from_secs(self.config.aggregate_cache_ttl);
+        let now = chrono::Utc::now().timestamp();
+        let Some((fetched_at, list)) = self.aggregate_cache.fresh(tab, ttl, now) else {
+            self.load_aggregate(client);
+            return;
+        };
+        let list = list.clone();
+        self.loads.cancel(Slot::Aggregate);
         self.ui_state.error = None;
+        self.show_aggregate(list, fetched_at);
+        self.ui_state.status_message = format!(
+            "Showing {} aggregated topics from {} tab, fetched {}",
+            self.aggregate_state.items.len(),
+            tab,
+            crate::util::format_relative_time(fetched_at)
+        );
+        self.prefetch_aggregate_tabs(client);
+    }
+
+    /// Fetch the tabs likely to be switched to next in the background,
+    /// skipping those still fresh in the cache
+    pub fn prefetch_aggregate_tabs(&mut self, client: &V2exClient) {
+        if self.config.aggregate_cache_ttl == 0 || client.is_offline() {
+            return;
+        }
+        let ttl = Duration::from_secs(self.config.aggregate_cache_ttl);
+        let now = chrono::Utc::now().timestamp();
+        let tabs = crate::prefetch::aggregate_tabs_to_prefetch(
+            &self.aggregate_state.current_tab,
+            &self.config.aggregate_prefetch_tabs,
+            &self.config.tab_key_mappings(),
+        )
+        .into_iter()
+        .filter(|tab| self.aggregate_cache.fresh(tab, ttl, now).is_none())
+        .map(|tab| {
+            let feed = self.config.feed_url(&tab).map(str::to_string);
+            (tab, feed)
+        })
+        .collect();
+        self.aggregate_cache.prefetch(&client.background(), tabs);
+    }
+
+    /// Keep the tabs fetched by `prefetch_aggregate_tabs` for switching to
+    pub fn finish_aggregate_prefetch(&mut self, tabs: AggregateTabs) {
+        let now = chrono::Utc::now().timestamp();
+        for (tab, list) in tabs {
+            self.aggregate_cache.insert(&tab, now, list);
+        }
+    }
+
+    /// Show the aggregate tab fetched by `start_aggregate_load`, if it is
+    /// still the current one, and fetch the tabs likely to come next
+    fn finish_aggregate(
+        &mut self,
+        client: &V2exClient,
+        tab: String,
+        keep_message: bool,
+        result: anyhow::Result<PartialList<RssItem>>,
+    ) {
+        if tab != self.aggregate_state.current_tab {
+            return;
+        }
+        let message = if keep_message {
+            std::mem::take(&mut self.ui_state.status_message)
+        } else {
+            String::new()
+        };
+        match result {
+            Ok(list) => {
+                let now = chrono::Utc::now().timestamp();
+                self.aggregate_cache.insert(&tab, now, list.clone());
+                self.show_aggregate(list, now);
+                self.ui_state.status_message = format!(
+                    "Loaded {} aggregated topics from {} tab",
+                    self.aggregate_state.items.len(),
+                    tab
+                );
+            }
+            // The banner belongs to the view it is about
+            Err(e) if self.view != View::Aggregate => {
+                self.ui_state.status_message = format!("Failed to load aggregated topics: {}", e);
+            }
+            Err(e) => {
+                self.load_failed(
+                    &e,
+                    format!("Failed to load aggregated topics: {}", e),
+                    Retry::Aggregate,
+                );
+            }
+        }
+        if !message.is_empty() {
+            self.ui_state.status_message = message;
+        }
+        self.prefetch_aggregate_tabs(client);
+    }
 
-        if !append {
-            self.topic_state.replies_page = 1;
+    /// Show `list`, fetched at `fetched_at`, as the items of the current
+    /// tab, keeping the same item selected
+    fn show_aggregate(&mut self, list: PartialList<RssItem>, fetched_at: i64) {
+        self.note_skipped(list.skipped);
+        let mut items = list.items;
+        if self.config.hide_read_topics {
+            items.retain(|item| {
+                item.extract_topic_id()
+                    .is_none_or(|id| !self.read_tracker.is_read(id))
+            });
         }
+        // A topic can be listed more than once in a feed; keep the first
+        let mut seen = HashSet::new();
+        items.retain(|item| item.extract_topic_id().is_none_or(|id| seen.insert(id)));
+        self.feed_topics.enrich(&mut items);
+        let state = &mut self.aggregate_state;
+        let selected = state
+            .items
+            .get(state.selected)
+            .map(|item| item.link.clone());
+        state.selected = reselect(&items, selected, state.selected, |item| item.link.clone());
+        state.items = items;
+        state.fetched_at = Some(fetched_at);
+    }
 
-        match client
-            .get_topic_replies(topic_id, self.topic_state.replies_page)
-            .await
+    /// Show why a load failed, reacting to the kind of failure: a rejected
+    /// token opens the token manager to add another, unless nobody asked
+    /// for the load, and a rate limit that says when it resets runs the
+    /// load again then
+    fn load_failed(&mut self, error: &anyhow::Error, message: String, retry: Retry) {
+        let rejected = matches!(V2exError::of(error), Some(V2exError::Unauthorized(_)));
+        if rejected && !self.applying_quietly && self.view != View::Tokens {
+            // Before failing, as leaving the view clears the error
+            self.navigate_to(View::Tokens);
+            self.token_state.start_adding();
+            self.ui_state.status_message =
+                "V2EX rejected the token. Name for the new token: ".to_string();
+        }
+        self.ui_state.fail(message, retry);
+        if let Some(V2exError::RateLimited {
+            retry_after: Some(wait),
+        }) = V2exError::of(error)
         {
-            Ok(replies) => {
-                let replies_len = replies.len();
-                let is_empty = replies.is_empty();
-                if append && self.topic_state.replies_page > 1 {
-                    self.topic_state.replies.extend(replies);
-                    self.ui_state.status_message = format!(
-                        "Loaded {} more replies (total: {})",
-                        replies_len,
-                        self.topic_state.replies.len()
-                    );
+            self.ui_state.retry_at = Some(Instant::now() + *wait);
+        }
+    }
+
+    /// Run the load behind the error banner again
+    pub fn retry_failed(&mut self, client: &V2exClient) {
+        let Some(retry) = self.ui_state.retry().cloned() else {
+            return;
+        };
+        self.ui_state.dismiss_error();
+        match retry {
+            Retry::Topics { append } => self.load_topics(client, append),
+            Retry::Topic(topic_id) => {
+                let current = self.topic_state.current.as_ref().map(|topic| topic.id);
+                if current == Some(topic_id) {
+                    self.refresh_topic(client);
                 } else {
-                    self.topic_state.replies = replies;
-                    self.topic_state.selected_reply = 0;
-                    if is_empty {
-                        self.topic_state.replies_list_state.select(None);
-                    } else {
-                        self.topic_state.replies_list_state.select(Some(0));
-                    }
-                    self.ui_state.status_message = format!("Loaded {} replies", replies_len);
+                    self.open_topic(client, topic_id);
                 }
-                self.topic_state.replies_page += 1;
-                // Update links after loading replies
-                self.topic_state.detect_links(self.terminal_width);
             }
-            Err(e) => {
-                self.ui_state.error = Some(format!("Failed to load replies: {}", e));
+            Retry::Replies { topic_id, append } => {
+                self.load_topic_replies(client, topic_id, append)
             }
+            Retry::Notifications { append: false } => self.load_notifications(client),
+            Retry::Notifications { append: true } => self.load_more_notifications(client),
+            Retry::Favorites => self.load_favorites(client),
+            Retry::Member(username) => self.load_member(client, &username, false),
+            Retry::Profile => self.load_profile(client),
+            Retry::Aggregate => self.load_aggregate(client),
         }
+    }
 
-        self.ui_state.loading = false;
+    /// Periodic refresh of the current view. It happens behind the user's
+    /// back: it waits for loads the user started, the same item stays
+    /// selected even when new ones arrive above it, and `finish_load`
+    /// leaves the status line alone.
+    pub fn auto_refresh(&mut self, client: &V2exClient) {
+        // Skip quietly rather than surfacing an error for a timer tick
+        if !client.can_schedule() {
+            return;
+        }
+        match self.view {
+            View::TopicList => self.start_topics_load(client, false, AfterTopics::Nothing, true),
+            View::Notifications => self.start_notifications_load(client, 1, true),
+            View::Aggregate => self.start_aggregate_load(client, false, true),
+            View::TopicDetail => self.refresh_topic_counters(client),
+            _ => {}
+        }
     }
 
-    pub async fn load_notifications(&mut self, client: &V2exClient) {
-        self.ui_state.loading = true;
-        self.ui_state.error = None;
+    /// Fetch the open topic's reply count and page counters again, leaving
+    /// its content, scroll and replies alone. Whatever can't be fetched
+    /// keeps its last value.
+    pub fn refresh_topic_counters(&mut self, client: &V2exClient) {
+        let Some(topic_id) = self.topic_state.current.as_ref().map(|topic| topic.id) else {
+            return;
+        };
+        let client = client.clone();
+        self.loads.start_quietly(Slot::Topic, async move {
+            let topic = client.get_topic(topic_id).await.ok();
+            let stats = client.get_topic_stats(topic_id).await.ok();
+            Loaded::TopicCounters {
+                topic_id,
+                topic,
+                stats,
+            }
+        });
+    }
+
+    fn finish_topic_counters(
+        &mut self,
+        topic_id: i64,
+        topic: Option<Topic>,
+        stats: Option<TopicStats>,
+    ) {
+        let Some(current) = self.topic_state.current.as_mut() else {
+            return;
+        };
+        if current.id != topic_id {
+            return;
+        }
+        if let Some(topic) = topic {
+            current.replies = topic.replies;
+        }
+        if let Some(fresh) = stats {
+            let mut stats = self.topic_state.stats.take().unwrap_or_default();
+            stats.update(fresh);
+            self.topic_state.set_stats(Some(stats));
+        }
+    }
+
+    fn start_load(
+        &mut self,
+        slot: Slot,
+        quiet: bool,
+        load: impl std::future::Future<Output = Loaded> + Send + 'static,
+    ) {
+        if quiet {
+            self.loads.start_quietly(slot, load);
+        } else {
+            self.loads.start(slot, load);
+        }
+    }
+
+    /// Apply a load that finished. One started by auto-refresh leaves the
+    /// status line alone, and its failure is not reported; the next one
+    /// tries again.
+    pub fn finish_load(&mut self, client: &V2exClient, finished: Finished) {
+        if !finished.quiet {
+            self.apply_loaded(client, finished.loaded);
+            return;
+        }
+        let message = std::mem::take(&mut self.ui_state.status_message);
+        let error = self.ui_state.error.take();
+        let failed = self.ui_state.failed.take();
+        let retry_at = self.ui_state.retry_at.take();
+        self.applying_quietly = true;
+        self.apply_loaded(client, finished.loaded);
+        self.applying_quietly = false;
+        if self.ui_state.error.is_some() {
+            self.ui_state.error = error;
+            self.ui_state.failed = failed;
+            self.ui_state.retry_at = retry_at;
+        }
+        self.ui_state.status_message = message;
+    }
+
+    fn apply_loaded(&mut self, client: &V2exClient, loaded: Loaded) {
+        match loaded {
+            Loaded::Topics {
+                node,
+                page,
+                append,
+                then,
+                result,
+            } => self.finish_topics(client, node, page, append, then, result),
+            Loaded::NodeInfo { name, show, result } => {
+                if let Ok(node) = result {
+                    self.node_state.node_info.insert(name.clone(), node);
+                }
+                if show && name == self.node_state.current_node {
+                    self.show_node_info();
+                }
+            }
+            Loaded::Preview { topic_id, result } => self.finish_preview(topic_id, result),
+            Loaded::Topic {
+                topic_id,
+                how,
+                result,
+            } => self.finish_topic(client, topic_id, how, result),
+            Loaded::Replies {
+                topic_id,
+                page,
+                then,
+                replies,
+            } => self.finish_replies(topic_id, page, then, replies),
+            Loaded::TopicCounters {
+                topic_id,
+                topic,
+                stats,
+            } => self.finish_topic_counters(topic_id, topic, stats),
+            Loaded::Notifications { page, result } => self.finish_notifications(page, result),
+            Loaded::NotificationDeleted { id, result } => {
+                self.finish_notification_delete(id, result)
+            }
+            Loaded::Favorites(result) => self.finish_favorites(result),
+            Loaded::Favorite { topic_id, result } => self.finish_favorite(client, topic_id, result),
+            Loaded::Thanked {
+                topic_id,
+                reply_id,
+                author,
+                result,
+            } => self.finish_thank(topic_id, reply_id, author, result),
+            Loaded::Archived { topic_id, result } => self.finish_archive(topic_id, result),
+            Loaded::Member {
+                username,
+                show,
+                result,
+            } => self.finish_member(username, show, result),
+            Loaded::Profile { then, result } => self.finish_profile(then, result),
+            Loaded::Tokens(infos) => self.finish_tokens(infos),
+            Loaded::Aggregate {
+                tab,
+                keep_message,
+                result,
+            } => self.finish_aggregate(client, tab, keep_message, result),
+            Loaded::Downloaded { shortcut, result } => match result {
+                Ok(path) => {
+                    self.ui_state.status_message =
+                        format!("Saved link {} to {}", shortcut, path.display());
+                }
+                Err(e) => {
+                    self.ui_state.error =
+                        Some(format!("Failed to download link {}: {}", shortcut, e));
+                }
+            },
+        }
+    }
+
+    /// Index of the highlighted item in the current view's list
+    pub fn list_selection(&self) -> Option<usize> {
+        match self.view {
+            View::TopicList => self.topic_state.topic_position(),
+            View::TopicDetail => self.topic_state.reply_position(),
+            View::Notifications => Some(self.notification_state.selected),
+            View::Favorites => Some(self.favorites_state.selected),
+            View::Member => Some(self.member_state.selected),
+            View::History => Some(self.visited_state.selected),
+            View::Bookmarks => Some(self.bookmarks_state.selected),
+            View::Tokens => Some(self.token_state.selected),
+            View::NodeSelect => Some(self.node_state.selected),
+            View::Aggregate => Some(self.aggregate_state.selected),
+            _ => None,
+        }
+    }
+
+    /// Move the highlight of the current view's list to `index`
+    pub fn select_list_item(&mut self, index: usize) {
+        match self.view {
+            View::TopicList => self.topic_state.select_topic_at(index),
+            View::TopicDetail => self.topic_state.select_reply_at(index),
+            View::Notifications => self.notification_state.selected = index,
+            View::Favorites => self.favorites_state.selected = index,
+            View::Member => self.member_state.selected = index,
+            View::History => self.visited_state.selected = index,
+            View::Bookmarks => self.bookmarks_state.selected = index,
+            View::Tokens => self.token_state.selected = index,
+            View::NodeSelect => self.node_state.selected = index,
+            View::Aggregate => self.aggregate_state.selected = index,
+            _ => {}
+        }
+    }
+
+    /// Open the current node in a new tab, to switch it to another node
+    /// while keeping this one a key away
+    pub fn open_tab(&mut self) {
+        self.tabs.open(self.current_tab());
+        self.ui_state.status_message = format!(
+            "Opened tab {} of {} (s or 1-9 to pick its node)",
+            self.tabs.active + 1,
+            self.tabs.tabs.len()
+        );
+    }
+
+    /// Show the tab `delta` steps to the right, wrapping around
+    pub fn switch_tab(&mut self, delta: isize) {
+        match self.tabs.switch(self.current_tab(), delta) {
+            Some(tab) => self.show_tab(tab),
+            None => self.ui_state.status_message = "No other tabs (T opens one)".to_string(),
+        }
+    }
+
+    pub fn close_tab(&mut self) {
+        match self.tabs.close() {
+            Some(tab) => self.show_tab(tab),
+            None => self.ui_state.status_message = "Only one tab is open".to_string(),
+        }
+    }
 
-        match client.get_notifications(1).await {
-            Ok(notifications) => {
-                self.notification_state.notifications = notifications;
-                self.notification_state.selected = 0;
+    /// List `node`'s topics, bringing back the page and selection it had
+    /// when last shown this session; `g` fetches it again
+    pub fn switch_to_node(&mut self, client: &V2exClient, node: &str) {
+        if node == self.node_state.current_node {
+            self.node_state.switch_node(node);
+            self.load_topics(client, false);
+            return;
+        }
+        if !self.topic_state.topics().is_empty() && self.ui_state.error.is_none() {
+            let list = self.current_tab();
+            self.node_lists.insert(list.node.clone(), list);
+        }
+        match self.node_lists.remove(node) {
+            Some(list) => {
+                self.restore_node_list(list);
                 self.ui_state.status_message = format!(
-                    "Loaded {} notifications",
-                    self.notification_state.notifications.len()
+                    "{}: {} topics as last shown (g to refresh)",
+                    node,
+                    self.topic_state.topics().len()
                 );
             }
-            Err(e) => {
-                self.ui_state.error = Some(format!("Failed to load notifications: {}", e));
+            None => {
+                // The last node's topics don't belong under this one
+                self.restore_node_list(NodeTab {
+                    node: node.to_string(),
+                    page: 1,
+                    ..Default::default()
+                });
+                self.load_topics(client, false);
             }
         }
+    }
+
+    /// The topic list as shown now, to stash in the active tab
+    fn current_tab(&self) -> NodeTab {
+        NodeTab {
+            node: self.node_state.current_node.clone(),
+            page: self.node_state.page,
+            topics: self.topic_state.topics().to_vec(),
+            selected: self.topic_state.selected,
+            topics_cached_at: self.topic_state.topics_cached_at,
+            reply_deltas: self.topic_state.reply_deltas.clone(),
+        }
+    }
 
-        self.ui_state.loading = false;
+    fn show_tab(&mut self, tab: NodeTab) {
+        self.restore_node_list(tab);
+        self.ui_state.status_message = format!(
+            "Tab {} of {}: {}",
+            self.tabs.active + 1,
+            self.tabs.tabs.len(),
+            self.node_state.current_node
+        );
     }
 
-    pub async fn load_profile(&mut self, client: &V2exClient) {
-        self.ui_state.loading = true;
+    fn restore_node_list(&mut self, list: NodeTab) {
+        self.node_state.current_node = list.node;
+        self.node_state.page = list.page;
+        self.topic_previews.close();
+        self.topic_state.set_topics(list.topics);
+        self.topic_state.selected = list.selected;
+        self.topic_state.show_selection();
+        self.topic_state.topics_cached_at = list.topics_cached_at;
+        self.topic_state.reply_deltas = list.reply_deltas;
         self.ui_state.error = None;
+    }
 
-        match client.get_member().await {
-            Ok(member) => {
-                self.profile = Some(member);
-                self.ui_state.status_message = "Loaded profile".to_string();
+    /// Toggle read state of the topic under the cursor in a list view
+    pub fn toggle_selected_read(&mut self) {
+        let topic_id = match self.view {
+            View::TopicList => self.topic_state.selected_topic().map(|topic| topic.id),
+            View::Aggregate => self
+                .aggregate_state
+                .items
+                .get(self.aggregate_state.selected)
+                .and_then(|item| item.extract_topic_id()),
+            _ => None,
+        };
+        let Some(topic_id) = topic_id else {
+            self.ui_state.status_message = "No topic selected".to_string();
+            return;
+        };
+
+        match self.read_tracker.toggle(topic_id) {
+            Ok(true) => self.ui_state.status_message = format!("Marked topic {} as read", topic_id),
+            Ok(false) => {
+                self.ui_state.status_message = format!("Marked topic {} as unread", topic_id)
             }
-            Err(e) => {
-                self.ui_state.error = Some(format!("Failed to load profile: {}", e));
+            Err(e) => self.ui_state.error = Some(format!("Failed to save read state: {}", e)),
+        }
+    }
+
+    /// Open the reply composer for the topic currently shown in detail view
+    pub fn open_reply_composer(&mut self) {
+        if let Some(ref topic) = self.topic_state.current {
+            let topic_id = topic.id;
+            if self.reply_input_state.topic_id != Some(topic_id) {
+                self.reply_input_state.start(topic_id);
             }
+            self.reply_input_state.mention_candidates = self.topic_state.participants();
+            self.navigate_to(View::ReplyInput);
+            self.ui_state.status_message =
+                "Composing reply: C-s to send, C-e to open $EDITOR, Esc to cancel".to_string();
+        } else {
+            self.ui_state.status_message = "No topic to reply to".to_string();
+        }
+    }
+
+    /// Post the composer buffer as a reply in the background.
+    ///
+    /// Only one reply per topic may be in flight; repeated submits are
+    /// refused until `finish_reply` sees the first one finish.
+    pub fn submit_reply(&mut self, client: &V2exClient) {
+        let Some(topic_id) = self.reply_input_state.topic_id else {
+            self.ui_state.status_message = "No topic to reply to".to_string();
+            return;
+        };
+
+        if self.reply_input_state.is_empty() {
+            self.ui_state.status_message = "Reply cannot be empty".to_string();
+            return;
         }
 
-        self.ui_state.loading = false;
+        self.reply_input_state.last_error = None;
+        if !self
+            .reply_submissions
+            .begin(topic_id, self.reply_input_state.clone())
+        {
+            self.ui_state.status_message =
+                "Still sending the previous reply, please wait...".to_string();
+            return;
+        }
+
+        let content = self.reply_input_state.content().trim_end().to_string();
+        let client = client.clone();
+        let reply_tx = self.reply_tx.clone();
+        tokio::spawn(async move {
+            let result = client
+                .create_reply(topic_id, &content)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = reply_tx.send(ReplyOutcome {
+                topic_id,
+                content,
+                result,
+            });
+        });
+        self.ui_state.status_message = "Posting reply...".to_string();
     }
 
-    pub async fn load_aggregate(&mut self, client: &V2exClient) {
-        self.ui_state.loading = true;
-        self.ui_state.error = None;
+    /// A reply that finished posting since the last call
+    pub fn next_reply_outcome(&mut self) -> Option<ReplyOutcome> {
+        self.reply_rx.try_recv().ok()
+    }
 
-        match client.get_rss_feed(&self.aggregate_state.current_tab).await {
-            Ok(items) => {
-                self.aggregate_state.items = items;
-                self.aggregate_state.selected = 0;
-                self.ui_state.status_message = format!(
-                    "Loaded {} aggregated topics from {} tab",
-                    self.aggregate_state.items.len(),
-                    self.aggregate_state.current_tab
-                );
-            }
+    /// Show a posted reply in its topic, or bring back the draft if
+    /// posting failed
+    pub fn finish_reply(&mut self, outcome: ReplyOutcome) {
+        let topic_id = outcome.topic_id;
+        let sent_draft = self.reply_submissions.finish(topic_id);
+
+        let mut reply = match outcome.result {
+            Ok(reply) => reply,
             Err(e) => {
-                self.ui_state.error = Some(format!("Failed to load aggregated topics: {}", e));
+                self.restore_failed_draft(topic_id, sent_draft, e);
+                return;
             }
+        };
+
+        let composing_this_topic = self.reply_input_state.topic_id == Some(topic_id);
+        // Keep anything typed after pressing send
+        if composing_this_topic && self.reply_input_state.content().trim_end() == outcome.content {
+            self.reply_input_state.clear();
+        }
+        if composing_this_topic && self.view == View::ReplyInput {
+            self.remove_current_from_history();
+        }
+
+        if self.topic_state.current.as_ref().map(|topic| topic.id) != Some(topic_id) {
+            // A cached copy would now be missing this reply
+            self.topic_cache.remove(topic_id);
+            self.ui_state.status_message = "Reply posted".to_string();
+            return;
+        }
+
+        // Fill in what the API may leave out so the reply renders like the rest
+        if reply.member.is_none() {
+            reply.member = self.profile.clone();
+        }
+        if reply.created == 0 {
+            reply.created = chrono::Utc::now().timestamp();
+        }
+        if reply.content.is_none() && reply.content_rendered.is_none() {
+            reply.content = Some(outcome.content);
         }
 
-        self.ui_state.loading = false;
+        self.topic_state.show_replies = true;
+        let loaded_before = self.topic_state.replies().len();
+        if let Some(floor) = self.topic_state.append_posted_reply(reply) {
+            // Our own reply is not news on the next visit
+            if let Err(e) = self.seen_replies.record(topic_id, floor as i64) {
+                self.ui_state.error = Some(format!("Failed to save seen replies: {}", e));
+            }
+            if self.topic_state.replies().len() > loaded_before {
+                self.topic_state.detect_links();
+                self.ui_state.status_message = format!("Reply posted as #{}", floor);
+            } else {
+                self.ui_state.status_message =
+                    format!("Reply posted as #{} (press + to load it)", floor);
+            }
+        }
     }
 
-    pub async fn switch_aggregate_tab(&mut self, client: &V2exClient, tab: &str) {
-        self.aggregate_state.switch_tab(tab);
-        self.load_aggregate(client).await;
+    /// Put a draft that failed to post back into the composer and reopen it
+    fn restore_failed_draft(
+        &mut self,
+        topic_id: i64,
+        sent_draft: Option<ReplyInputState>,
+        error: String,
+    ) {
+        let composer = &self.reply_input_state;
+        let same_topic = composer.topic_id == Some(topic_id);
+        // Never clobber text the user typed for a different topic meanwhile
+        if composer.is_empty() {
+            if let Some(draft) = sent_draft {
+                self.reply_input_state = draft;
+            }
+        } else if !same_topic {
+            self.ui_state.status_message = format!(
+                "Failed to post reply to topic {}: {} (draft for another topic kept)",
+                topic_id, error
+            );
+            return;
+        }
+
+        self.reply_input_state.last_error = Some(error.clone());
+        let viewing_topic =
+            self.topic_state.current.as_ref().map(|topic| topic.id) == Some(topic_id);
+        if viewing_topic && self.view != View::ReplyInput {
+            self.navigate_to(View::ReplyInput);
+        }
+        self.ui_state.status_message = format!("Failed to post reply: {} (C-s to retry)", error);
     }
 
     // Helper to find current topic index based on previous view
@@ -231,11 +2820,7 @@ impl App {
         let current_topic_id = self.topic_state.current.as_ref()?.id;
 
         // Check history stack to determine the source view
-        let source_view = if self.history_position > 0 {
-            self.view_history.get(self.history_position - 1).copied()
-        } else {
-            None
-        };
+        let source_view = self.history.previous().map(|entry| entry.view);
 
         match source_view {
             Some(View::Aggregate) => {
@@ -263,7 +2848,7 @@ impl App {
     }
 
     // Topic navigation in detail view
-    pub async fn switch_to_next_topic(&mut self, client: &V2exClient) {
+    pub fn switch_to_next_topic(&mut self, client: &V2exClient) {
         if let Some((current_index, source_view)) = self.find_current_topic_index_in_previous_view()
         {
             match source_view {
@@ -281,48 +2866,26 @@ impl App {
                     let next_index = current_index + 1;
                     if let Some(next_item) = self.aggregate_state.items.get(next_index) {
                         if let Some(topic_id) = next_item.extract_topic_id() {
-                            self.topic_state.current = None;
-                            self.topic_state.replies.clear();
-                            self.topic_state.reset_scroll();
-                            self.load_topic_detail(client, topic_id).await;
-                            self.load_topic_replies(client, topic_id, false).await;
+                            self.show_topic(client, topic_id);
                             self.ui_state.status_message =
                                 format!("Switched to next aggregated topic (#{})", next_index + 1);
                         }
                     }
                 }
                 View::TopicList | View::Notifications => {
-                    let topics_len = self.topic_state.topics.len();
+                    let topics_len = self.topic_state.topics().len();
                     if topics_len == 0 {
                         return;
                     }
-                    let at_last = current_index + 1 >= topics_len;
-                    let next_index = if at_last {
-                        // Try to load more topics
-                        let prev_page = self.node_state.page;
-                        self.node_state.page += 1;
-                        let prev_len = self.topic_state.topics.len();
-                        self.load_topics(client, true).await;
-                        if self.topic_state.topics.len() > prev_len {
-                            // New topics loaded, move to next
-                            prev_len
-                        } else {
-                            // No more topics to load, stay at current position and restore page
-                            self.node_state.page = prev_page;
-                            self.ui_state.error = None; // Clear the API error
-                            self.ui_state.status_message = "Already at the last topic".to_string();
-                            return;
-                        }
-                    } else {
-                        current_index + 1
-                    };
-                    if let Some(next_topic) = self.topic_state.topics.get(next_index) {
+                    if current_index + 1 >= topics_len {
+                        // Try to load more topics, and open the first of them
+                        self.load_topics_then(client, true, AfterTopics::OpenNext);
+                        return;
+                    }
+                    let next_index = current_index + 1;
+                    if let Some(next_topic) = self.topic_state.topics().get(next_index) {
                         let topic_id = next_topic.id;
-                        self.topic_state.current = None;
-                        self.topic_state.replies.clear();
-                        self.topic_state.reset_scroll();
-                        self.load_topic_detail(client, topic_id).await;
-                        self.load_topic_replies(client, topic_id, false).await;
+                        self.show_topic(client, topic_id);
                         self.ui_state.status_message =
                             format!("Switched to next topic (#{})", next_index + 1);
                     }
@@ -332,7 +2895,7 @@ impl App {
         }
     }
 
-    pub async fn switch_to_previous_topic(&mut self, client: &V2exClient) {
+    pub fn switch_to_previous_topic(&mut self, client: &V2exClient) {
         if let Some((current_index, source_view)) = self.find_current_topic_index_in_previous_view()
         {
             match source_view {
@@ -350,11 +2913,7 @@ impl App {
                     let prev_index = current_index - 1;
                     if let Some(prev_item) = self.aggregate_state.items.get(prev_index) {
                         if let Some(topic_id) = prev_item.extract_topic_id() {
-                            self.topic_state.current = None;
-                            self.topic_state.replies.clear();
-                            self.topic_state.reset_scroll();
-                            self.load_topic_detail(client, topic_id).await;
-                            self.load_topic_replies(client, topic_id, false).await;
+                            self.show_topic(client, topic_id);
                             self.ui_state.status_message = format!(
                                 "Switched to previous aggregated topic (#{})",
                                 prev_index + 1
@@ -363,7 +2922,7 @@ impl App {
                     }
                 }
                 View::TopicList | View::Notifications => {
-                    let topics_len = self.topic_state.topics.len();
+                    let topics_len = self.topic_state.topics().len();
                     if topics_len == 0 {
                         return;
                     }
@@ -373,13 +2932,9 @@ impl App {
                         return;
                     }
                     let prev_index = current_index - 1;
-                    if let Some(prev_topic) = self.topic_state.topics.get(prev_index) {
+                    if let Some(prev_topic) = self.topic_state.topics().get(prev_index) {
                         let topic_id = prev_topic.id;
-                        self.topic_state.current = None;
-                        self.topic_state.replies.clear();
-                        self.topic_state.reset_scroll();
-                        self.load_topic_detail(client, topic_id).await;
-                        self.load_topic_replies(client, topic_id, false).await;
+                        self.show_topic(client, topic_id);
                         self.ui_state.status_message =
                             format!("Switched to previous topic (#{})", prev_index + 1);
                     }
@@ -390,9 +2945,9 @@ impl App {
     }
 
     // Browser methods
-    pub fn open_current_topic_in_browser(&mut self) {
+    pub fn open_current_topic_in_browser(&mut self, client: &V2exClient) {
         if let Some(ref topic) = self.topic_state.current {
-            match Browser::open_topic(topic.id) {
+            match Browser::open_topic(client.site_base(), topic.id) {
                 Ok(result) => {
                     self.ui_state.status_message = result.to_string();
                 }
@@ -403,14 +2958,14 @@ impl App {
         }
     }
 
-    pub fn open_selected_reply_in_browser(&mut self) {
+    pub fn open_selected_reply_in_browser(&mut self, client: &V2exClient) {
         if let Some(ref topic) = self.topic_state.current {
             if let Some(reply) = self
                 .topic_state
-                .replies
+                .replies()
                 .get(self.topic_state.selected_reply)
             {
-                match Browser::open_topic_reply(topic.id, reply.id) {
+                match Browser::open_topic_reply(client.site_base(), topic.id, reply.id) {
                     Ok(result) => {
                         self.ui_state.status_message = result.to_string();
                     }
@@ -422,10 +2977,31 @@ impl App {
         }
     }
 
+    /// Copy text to the clipboard and remember it in the clipboard history
+    pub fn copy_text(&mut self, label: String, text: String) {
+        match crate::clipboard::copy_to_clipboard(&text) {
+            Ok(()) => {
+                self.ui_state.status_message = format!("Copied {} to clipboard", label);
+                self.clipboard_history.push(label, text);
+            }
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to copy to clipboard: {}", e));
+            }
+        }
+    }
+
+    /// Copy the entry selected in the clipboard history overlay again
+    pub fn recopy_clipboard_entry(&mut self) {
+        if let Some(entry) = self.clipboard_history.selected_entry().cloned() {
+            self.clipboard_history.visible = false;
+            self.copy_text(entry.label, entry.text);
+        }
+    }
+
     pub fn copy_selected_reply_to_clipboard(&mut self) {
         if let Some(reply) = self
             .topic_state
-            .replies
+            .replies()
             .get(self.topic_state.selected_reply)
         {
             let content = reply
@@ -436,17 +3012,16 @@ impl App {
                 .unwrap_or_default();
 
             // Strip HTML tags for plain text
-            let plain_text = html2text::from_read(content.as_bytes(), 80);
-
-            match crate::clipboard::copy_to_clipboard(&plain_text) {
-                Ok(()) => {
-                    self.ui_state.status_message =
-                        format!("Copied reply #{} to clipboard", reply.id);
-                }
-                Err(e) => {
-                    self.ui_state.error = Some(format!("Failed to copy to clipboard: {}", e));
-                }
-            }
+            let plain_text = crate::render::plain(&content, 80).to_string();
+            let label = match reply.member.as_ref() {
+                Some(member) => format!(
+                    "reply #{} by {}",
+                    self.topic_state.selected_reply + 1,
+                    member.username
+                ),
+                None => format!("reply #{}", self.topic_state.selected_reply + 1),
+            };
+            self.copy_text(label, plain_text);
         } else {
             self.ui_state.status_message = "No reply selected".to_string();
         }
@@ -461,22 +3036,213 @@ impl App {
             .unwrap_or_default();
 
         // Strip HTML tags for plain text
-        let plain_text = html2text::from_read(content.as_bytes(), 80);
+        let plain_text = crate::render::plain(&content, 80).to_string();
+        self.copy_text(format!("topic '{}'", topic.title), plain_text);
+    }
 
-        match crate::clipboard::copy_to_clipboard(&plain_text) {
+    /// Read the selected reply, or the topic, aloud with `tts-command`;
+    /// pressing the key again while it speaks stops it
+    pub fn toggle_read_aloud(&mut self) {
+        if self.read_aloud.stop() {
+            self.ui_state.status_message = "Stopped reading aloud".to_string();
+            return;
+        }
+        if self.config.tts_command.trim().is_empty() {
+            self.ui_state.status_message =
+                "Set tts-command in config.lisp to read aloud, e.g. (set! tts-command \"espeak\")"
+                    .to_string();
+            return;
+        }
+        let Some((label, text)) = self.selected_post_text() else {
+            return;
+        };
+        match self.read_aloud.start(&self.config.tts_command, text) {
             Ok(()) => {
-                self.ui_state.status_message =
-                    format!("Copied topic '{}' to clipboard", topic.title);
+                self.ui_state.status_message = format!("Reading {} aloud (R to stop)", label);
             }
             Err(e) => {
-                self.ui_state.error = Some(format!("Failed to copy to clipboard: {}", e));
+                self.ui_state.error = Some(format!("Failed to read aloud: {:#}", e));
+            }
+        }
+    }
+
+    /// Translate the selected reply, or the topic, with `translate-command`
+    /// in the background; `finish_translation` shows the result
+    pub fn translate_selected(&mut self) {
+        if self.config.translate_command.trim().is_empty() {
+            self.ui_state.status_message =
+                "Set translate-command in config.lisp to translate, e.g. (set! translate-command \"trans -b :en\")"
+                    .to_string();
+            return;
+        }
+        let Some((label, text)) = self.selected_post_text() else {
+            return;
+        };
+        self.ui_state.status_message = format!("Translating {}...", label);
+        self.translator
+            .start(&self.config.translate_command, label, text);
+    }
+
+    pub fn finish_translation(&mut self, label: String, result: anyhow::Result<String>) {
+        match result {
+            Ok(text) => {
+                self.ui_state.status_message = format!("Translated {}", label);
+                self.translator.shown = Some(Translation {
+                    label,
+                    text,
+                    scroll: 0,
+                });
+            }
+            Err(e) => {
+                self.ui_state.error = Some(format!("Failed to translate {}: {:#}", label, e));
+            }
+        }
+    }
+
+    /// The selected reply, or the topic when replies are hidden, as a label
+    /// and plain text
+    fn selected_post_text(&self) -> Option<(String, String)> {
+        let (label, html) = self.selected_post_html()?;
+        // Wide enough that lines aren't broken mid-sentence
+        let mut text = html
+            .map(|html| crate::render::plain(html, 1000).to_string())
+            .unwrap_or_default();
+        if !self.reply_is_selected() {
+            let title = self.topic_state.current.as_ref().map(|topic| &topic.title);
+            text = format!("{}\n\n{}", title.cloned().unwrap_or_default(), text);
+        }
+        Some((label, text))
+    }
+
+    /// Whether actions on "the post" in topic detail act on a reply
+    fn reply_is_selected(&self) -> bool {
+        self.topic_state.show_replies
+            && self.topic_state.selected_reply < self.topic_state.replies().len()
+    }
+
+    /// The selected reply, or the topic when replies are hidden, as a label
+    /// and its HTML
+    fn selected_post_html(&self) -> Option<(String, Option<&String>)> {
+        let topic = self.topic_state.current.as_ref()?;
+        let reply = self
+            .topic_state
+            .replies()
+            .get(self.topic_state.selected_reply)
+            .filter(|_| self.topic_state.show_replies);
+        Some(match reply {
+            Some(reply) => (
+                format!("reply #{}", self.topic_state.selected_reply + 1),
+                reply.content_rendered.as_ref().or(reply.content.as_ref()),
+            ),
+            None => (
+                format!("'{}'", topic.title),
+                topic.content_rendered.as_ref().or(topic.content.as_ref()),
+            ),
+        })
+    }
+
+    /// Offer the code blocks of the selected reply, or the topic, to copy.
+    /// A lone block is copied straight away.
+    pub fn open_code_picker(&mut self) {
+        let Some((label, html)) = self.selected_post_html() else {
+            return;
+        };
+        let blocks = html
+            .map(|html| crate::render::html::code_blocks(html))
+            .unwrap_or_default();
+        match blocks.len() {
+            0 => self.ui_state.status_message = format!("No code blocks in {}", label),
+            1 => {
+                let code = blocks.into_iter().next().unwrap_or_default();
+                self.copy_text(format!("code from {}", label), code);
+            }
+            _ => self.code_picker.open(label, blocks),
+        }
+    }
+
+    /// Copy block `index` of the code picker and close it
+    pub fn copy_code_block(&mut self, index: usize) {
+        let Some(code) = self.code_picker.blocks.get(index).cloned() else {
+            return;
+        };
+        self.code_picker.visible = false;
+        let label = format!("code block {} of {}", index + 1, self.code_picker.label);
+        self.copy_text(label, code);
+    }
+
+    /// Show or collapse posts by users on the ignore list for this session
+    pub fn toggle_reveal_blocked(&mut self) {
+        if self.config.ignored_users.is_empty() {
+            self.ui_state.status_message =
+                "No ignored users; add some with (ignore-user \"name\") in config.lisp".to_string();
+            return;
+        }
+        self.ui_state.reveal_blocked = !self.ui_state.reveal_blocked;
+        self.ui_state.status_message = if self.ui_state.reveal_blocked {
+            "Showing posts by ignored users".to_string()
+        } else {
+            "Collapsing posts by ignored users".to_string()
+        };
+    }
+
+    /// Copy the web address of the selected reply, or of the topic
+    pub fn copy_current_url(&mut self, client: &V2exClient) {
+        if self.reply_is_selected() {
+            self.copy_reply_url(client);
+        } else {
+            self.copy_topic_url(client);
+        }
+    }
+
+    /// Copy the web address of the open topic
+    pub fn copy_topic_url(&mut self, client: &V2exClient) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        let label = format!("link to '{}'", topic.title);
+        let url = crate::api::topic_page_url(client.site_base(), topic.id);
+        self.copy_text(label, url);
+    }
+
+    /// Copy the permalink of the selected reply
+    pub fn copy_reply_url(&mut self, client: &V2exClient) {
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return;
+        };
+        let Some(reply) = self
+            .topic_state
+            .replies()
+            .get(self.topic_state.selected_reply)
+            .filter(|_| self.topic_state.show_replies)
+        else {
+            self.ui_state.status_message = "No reply selected".to_string();
+            return;
+        };
+        let label = format!("link to reply #{}", self.topic_state.selected_reply + 1);
+        let url = crate::api::reply_page_url(client.site_base(), topic.id, reply.id);
+        self.copy_text(label, url);
+    }
+
+    pub fn open_selected_topic_in_browser(&mut self, client: &V2exClient) {
+        if let Some(topic) = self.topic_state.selected_topic() {
+            match Browser::open_topic(client.site_base(), topic.id) {
+                Ok(result) => {
+                    self.ui_state.status_message = result.to_string();
+                }
+                Err(e) => {
+                    self.ui_state.error = Some(format!("Failed to open browser: {}", e));
+                }
             }
         }
     }
 
-    pub fn open_selected_topic_in_browser(&mut self) {
-        if let Some(topic) = self.topic_state.topics.get(self.topic_state.selected) {
-            match Browser::open_topic(topic.id) {
+    pub fn open_selected_favorite_in_browser(&mut self, client: &V2exClient) {
+        if let Some(topic) = self
+            .favorites_state
+            .topics
+            .get(self.favorites_state.selected)
+        {
+            match Browser::open_topic(client.site_base(), topic.id) {
                 Ok(result) => {
                     self.ui_state.status_message = result.to_string();
                 }
@@ -487,16 +3253,17 @@ impl App {
         }
     }
 
-    pub fn open_notification_in_browser(&mut self) {
+    pub fn open_notification_in_browser(&mut self, client: &V2exClient) {
         if let Some(notification) = self
             .notification_state
             .notifications
             .get(self.notification_state.selected)
         {
             if let Some(topic_id) = notification.extract_topic_id() {
-                match Browser::open_topic(topic_id) {
+                match Browser::open_topic(client.site_base(), topic_id) {
                     Ok(result) => {
                         self.ui_state.status_message = result.to_string();
+                        self.mark_notification_read();
                     }
                     Err(e) => {
                         self.ui_state.error = Some(format!("Failed to open browser: {}", e));
@@ -526,6 +3293,49 @@ impl App {
         }
     }
 
+    /// Do what was asked with the link picked in link mode, and leave
+    /// link mode
+    pub fn use_chosen_link(&mut self, client: &V2exClient, handler: LinkHandler) {
+        let Some(link) = self.topic_state.link_input_state.chosen.clone() else {
+            return;
+        };
+        self.topic_state.exit_link_selection_mode();
+        match handler {
+            LinkHandler::Browser => match Browser::open_url(&link.url) {
+                Ok(result) => {
+                    self.ui_state.status_message =
+                        format!("Opening link {}: {}", link.shortcut, result);
+                }
+                Err(e) => {
+                    self.ui_state.error =
+                        Some(format!("Failed to open link {}: {}", link.shortcut, e));
+                }
+            },
+            LinkHandler::Copy => self.copy_text(format!("link {}", link.shortcut), link.url),
+            LinkHandler::Download => {
+                let dir = directories::UserDirs::new()
+                    .and_then(|dirs| dirs.download_dir().map(|dir| dir.to_path_buf()))
+                    .unwrap_or_else(|| PathBuf::from("."));
+                let client = client.clone();
+                self.loads.start_action(async move {
+                    let result = client.download(&link.url, &dir).await;
+                    Loaded::Downloaded {
+                        shortcut: link.shortcut,
+                        result,
+                    }
+                });
+            }
+            LinkHandler::Internal => match client.site_link(&link.url) {
+                Some(SiteLink::Topic(topic_id)) => self.open_topic(client, topic_id),
+                Some(SiteLink::Member(username)) => self.load_member(client, &username, true),
+                None => {
+                    self.ui_state.status_message =
+                        format!("Link {} is not a V2EX topic or member", link.shortcut);
+                }
+            },
+        }
+    }
+
     #[allow(dead_code)] // Not currently used, but kept for future use
     pub fn open_detected_link(&mut self, shortcut: usize) {
         if let Some(link) = self.topic_state.get_link_by_shortcut(shortcut) {
@@ -556,229 +3366,720 @@ impl App {
     }
 
     // History navigation methods
-    const MAX_HISTORY_SIZE: usize = 50;
+
+    /// History entry for `view`, tied to the open topic in topic detail
+    fn history_entry(&self, view: View) -> HistoryEntry {
+        let topic_id = match view {
+            View::TopicDetail => self.topic_state.current.as_ref().map(|topic| topic.id),
+            _ => None,
+        };
+        HistoryEntry { view, topic_id }
+    }
 
     /// Navigate to a new view, mutating the history stack
     pub fn navigate_to(&mut self, view: View) {
-        // Truncate forward history
-        if self.history_position + 1 < self.view_history.len() {
-            self.view_history.truncate(self.history_position + 1);
+        let entry = self.history_entry(view);
+        self.history.push(entry);
+        self.view = view;
+        self.ui_state.error = None;
+        self.topic_previews.close();
+        // Focus mode belongs to the topic it was turned on for
+        self.ui_state.focus_mode = false;
+        if view == View::Notifications {
+            self.mark_notifications_seen();
         }
+    }
+
+    /// Continue the history saved by the last session
+    pub fn resume_history(&mut self, saved: ViewHistory) {
+        let current = self.history_entry(self.view);
+        self.history = saved.resume(current);
+    }
 
-        // Push new view
-        self.view_history.push(view);
-        self.history_position = self.view_history.len() - 1;
+    /// Show a view taken from history, bringing back its topic from the
+    /// cache if another one has been opened since. Views whose data is gone,
+    /// e.g. after a restart, are left in `history_reload`.
+    fn enter_history_entry(&mut self, entry: HistoryEntry) {
+        self.view = entry.view;
+        self.ui_state.error = None;
+        self.topic_previews.close();
 
-        // Enforce max history size
-        if self.view_history.len() > Self::MAX_HISTORY_SIZE {
-            let excess = self.view_history.len() - Self::MAX_HISTORY_SIZE;
-            self.view_history.drain(0..excess);
-            self.history_position -= excess;
+        if entry.view == View::Notifications {
+            self.mark_notifications_seen();
+        }
+        let missing = match entry.view {
+            View::Notifications => self.notification_state.notifications.is_empty(),
+            View::Favorites => self.favorites_state.topics.is_empty(),
+            _ => false,
+        };
+        if missing {
+            self.history_reload = Some(entry);
         }
 
-        self.view = view;
-        self.ui_state.error = None;
+        let Some(topic_id) = entry.topic_id else {
+            return;
+        };
+        if self.topic_state.current.as_ref().map(|topic| topic.id) == Some(topic_id) {
+            return;
+        }
+        if let Some(cached) = self.topic_cache.get(topic_id).cloned() {
+            if let Some(snapshot) = self.topic_state.snapshot() {
+                self.topic_cache.insert(snapshot);
+            }
+            self.topic_state.show_replies = false;
+            self.topic_state.restore(cached);
+        } else {
+            self.history_reload = Some(entry);
+        }
+    }
+
+    /// Load the data of a history entry entered by `enter_history_entry`
+    pub fn reload_history_entry(&mut self, client: &V2exClient, entry: HistoryEntry) {
+        if self.view != entry.view {
+            return;
+        }
+        match entry.view {
+            View::Notifications => self.load_notifications(client),
+            View::Favorites => self.load_favorites(client),
+            View::TopicDetail => {
+                if let Some(topic_id) = entry.topic_id {
+                    self.show_topic(client, topic_id);
+                }
+            }
+            _ => {}
+        }
     }
 
     /// Navigate backward in history (l key)
     pub fn history_back(&mut self) -> bool {
-        if self.history_position > 0 {
-            self.history_position -= 1;
-            self.view = self.view_history[self.history_position];
-            self.ui_state.error = None;
-            true
-        } else {
-            false
+        match self.history.back() {
+            Some(entry) => {
+                self.enter_history_entry(entry);
+                true
+            }
+            None => false,
         }
     }
 
     /// Navigate forward in history (r key)
     pub fn history_forward(&mut self) -> bool {
-        if self.history_position + 1 < self.view_history.len() {
-            self.history_position += 1;
-            self.view = self.view_history[self.history_position];
-            self.ui_state.error = None;
-            true
-        } else {
-            false
+        match self.history.forward() {
+            Some(entry) => {
+                self.enter_history_entry(entry);
+                true
+            }
+            None => false,
         }
     }
 
-    /// Remove current view from history and return the view to navigate to (q/Esc)
-    /// Returns None if history is empty or no previous view exists (should exit app)
-    pub fn remove_current_from_history(&mut self) -> Option<View> {
-        if self.view_history.is_empty() {
-            return None;
+    /// Go to the entry selected in the history overlay
+    pub fn jump_to_selected_history(&mut self) {
+        self.history_browser.visible = false;
+        if let Some(entry) = self.history.jump(self.history_browser.selected) {
+            self.enter_history_entry(entry);
         }
+    }
 
-        // If at the first position, there's no previous view to go back to
-        if self.history_position == 0 {
-            return None;
+    /// What the history overlay shows for an entry
+    pub fn history_label(&self, entry: &HistoryEntry) -> String {
+        let name = match entry.view {
+            View::TopicList => "Topic list",
+            View::TopicDetail => "Topic",
+            View::Notifications => "Notifications",
+            View::Profile => "Profile",
+            View::Help => "Help",
+            View::NodeSelect => "Node select",
+            View::TokenInput => "Token",
+            View::Aggregate => "Aggregate",
+            View::ReplyInput => "Reply",
+            View::Favorites => "Favorites",
+            View::Member => "Member",
+            View::History => "Visited topics",
+            View::Bookmarks => "Bookmarks",
+            View::Tokens => "Tokens",
+        };
+        let Some(topic_id) = entry.topic_id else {
+            return name.to_string();
+        };
+        let title = match &self.topic_state.current {
+            Some(topic) if topic.id == topic_id => Some(&topic.title),
+            _ => self
+                .topic_cache
+                .get(topic_id)
+                .map(|cached| &cached.topic.title),
+        };
+        match title {
+            Some(title) => format!("{} #{}: {}", name, topic_id, title),
+            None => format!("{} #{}", name, topic_id),
         }
+    }
 
-        // Remove current view
-        self.view_history.remove(self.history_position);
+    /// Remove current view from history and return the view to navigate to (q/Esc)
+    /// Returns None if history is empty or no previous view exists (should exit app)
+    pub fn remove_current_from_history(&mut self) -> Option<View> {
+        let entry = self.history.remove_current()?;
+        self.enter_history_entry(entry);
+        Some(self.view)
+    }
 
-        if self.view_history.is_empty() {
-            return None;
+    // Rendering
+    /// Draw the open topic, or its selected reply, in focus mode.
+    /// Returns false when there is nothing to focus on.
+    fn render_focus(&mut self, frame: &mut Frame) -> bool {
+        if self.view != View::TopicDetail
+            || self.loads.is_running(Slot::Topic)
+            || self.ui_state.error.is_some()
+        {
+            return false;
         }
+        let Some(topic) = self.topic_state.current.as_ref() else {
+            return false;
+        };
 
-        // Go to previous item in history (left)
-        self.history_position -= 1;
+        let _scope = profiler::scope("TopicDetailView::render_focus");
+        let width = self.config.focus_width.min(u16::MAX as u64) as u16;
+        let view = TopicDetailView::new();
+        let column = match self.topic_state.focused_reply() {
+            Some(reply) => {
+                let author = reply
+                    .member
+                    .as_ref()
+                    .map_or("Unknown", |m| m.username.as_str());
+                let heading = format!(
+                    "#{} {} · {}",
+                    self.topic_state.selected_reply + 1,
+                    author,
+                    crate::util::format_relative_time(reply.created)
+                );
+                let html = reply.content_rendered.as_ref().or(reply.content.as_ref());
+                view.render_focus(
+                    frame,
+                    frame.area(),
+                    &heading,
+                    html.map(String::as_str),
+                    self.topic_state.focus_scroll(),
+                    width,
+                    &self.ui_state.theme,
+                )
+            }
+            None => {
+                let html = topic.content_rendered.as_ref().or(topic.content.as_ref());
+                view.render_focus(
+                    frame,
+                    frame.area(),
+                    &topic.title,
+                    html.map(String::as_str),
+                    self.topic_state.scroll,
+                    width,
+                    &self.ui_state.theme,
+                )
+            }
+        };
+        self.mouse_regions.content = Some(column);
+        true
+    }
 
-        self.view = self.view_history[self.history_position];
-        self.ui_state.error = None;
-        Some(self.view)
+    /// What is being loaded, for the status bar spinner: a load the user
+    /// started, whichever view is shown, or else a page loading ahead. The
+    /// view keeps showing what it had meanwhile.
+    pub fn loading(&self) -> Option<Loading> {
+        self.loads.running().or_else(|| {
+            self.load_ahead.running().map(|page| match page {
+                NextPage::Topics { .. } => Loading::List,
+                NextPage::Replies { .. } => Loading::Replies,
+            })
+        })
     }
 
-    // Rendering
     pub fn render(&mut self, frame: &mut Frame) {
         // Update terminal dimensions
         let terminal_size = frame.area();
-        self.terminal_width = terminal_size.width as usize;
         self.terminal_height = terminal_size.height as usize;
 
+        self.mouse_regions = MouseRegions::default();
+        let theme = &self.ui_state.theme;
+        if theme.canvas != Color::Reset {
+            frame.buffer_mut().set_style(
+                terminal_size,
+                Style::default().bg(theme.canvas).fg(theme.foreground),
+            );
+        }
+        if self.ui_state.focus_mode && self.render_focus(frame) {
+            if self.config.low_bandwidth {
+                let area = frame.area();
+                simplify_for_low_bandwidth(frame.buffer_mut(), area, &self.ui_state.theme);
+            }
+            return;
+        }
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(0), Constraint::Length(1)])
             .split(frame.area());
 
-        match self.view {
-            View::TopicList => {
-                if self.ui_state.loading {
-                    render_loading(frame, chunks[0], &self.ui_state.theme);
-                } else if let Some(ref error) = self.ui_state.error {
-                    render_error(frame, chunks[0], error, &self.ui_state.theme);
-                } else {
-                    let topic_list_view = TopicListView::new();
-                    topic_list_view.render(
-                        frame,
-                        chunks[0],
-                        &self.topic_state.topics,
-                        self.topic_state.selected,
-                        &self.node_state.current_node,
-                        &self.ui_state.theme,
-                    );
-                }
-            }
-            View::TopicDetail => {
-                if self.ui_state.loading {
-                    render_loading(frame, chunks[0], &self.ui_state.theme);
-                } else if let Some(ref error) = self.ui_state.error {
-                    render_error(frame, chunks[0], error, &self.ui_state.theme);
-                } else if let Some(ref topic) = self.topic_state.current {
-                    let topic_detail_view = TopicDetailView::new();
-                    if self.topic_state.show_replies {
-                        let area = chunks[0];
-                        let is_narrow = area.width < 100;
-                        let split_chunks = Layout::default()
-                            .direction(if is_narrow {
-                                Direction::Vertical
-                            } else {
-                                Direction::Horizontal
-                            })
-                            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-                            .split(area);
-                        topic_detail_view.render_split(
-                            frame,
-                            split_chunks[0],
-                            split_chunks[1],
-                            topic,
-                            self.topic_state.scroll,
-                            &self.topic_state.detected_links,
-                            self.topic_state.link_input_state.is_active,
-                            self.topic_state.parsed_content_cache.as_deref(),
-                            &self.topic_state.replies,
-                            &mut self.topic_state.replies_list_state,
-                            &self.ui_state.theme,
-                        );
-                    } else {
-                        topic_detail_view.render(
-                            frame,
-                            chunks[0],
-                            topic,
-                            self.topic_state.scroll,
-                            &self.topic_state.detected_links,
-                            self.topic_state.link_input_state.is_active,
-                            self.topic_state.parsed_content_cache.as_deref(),
-                            &self.ui_state.theme,
-                        );
-                    }
-                }
-            }
-            View::Notifications => {
-                if self.ui_state.loading {
-                    render_loading(frame, chunks[0], &self.ui_state.theme);
-                } else if let Some(ref error) = self.ui_state.error {
-                    render_error(frame, chunks[0], error, &self.ui_state.theme);
-                } else {
-                    let notifications_view = NotificationsView::new();
-                    notifications_view.render(
-                        frame,
-                        chunks[0],
-                        &self.notification_state.notifications,
-                        self.notification_state.selected,
-                        &self.ui_state.theme,
-                    );
-                }
-            }
-            View::Profile => {
-                if self.ui_state.loading {
-                    render_loading(frame, chunks[0], &self.ui_state.theme);
-                } else if let Some(ref error) = self.ui_state.error {
-                    render_error(frame, chunks[0], error, &self.ui_state.theme);
-                } else if let Some(ref profile) = self.profile {
-                    let profile_view = ProfileView::new();
-                    profile_view.render(frame, chunks[0], profile, &self.ui_state.theme);
-                }
+        let component = views::component(self.view);
+        let view_scope = profiler::scope(component.name());
+        let mut area = chunks[0];
+        if let Some(error) = self.ui_state.error.clone() {
+            let bindings = &self.config.key_bindings;
+            let mut keys = Vec::new();
+            if self.ui_state.retry().is_some() {
+                keys.push(key_hint(bindings, self.view, "retry", "retry"));
             }
-            View::Help => {
-                let help_view = HelpView::new();
-                help_view.render(frame, chunks[0], &self.ui_state.theme);
+            keys.push(key_hint(bindings, self.view, "dismiss-error", "dismiss"));
+            area = render_error_banner(
+                frame,
+                area,
+                &error,
+                &keys,
+                self.ui_state.retry_in(),
+                &self.ui_state.theme,
+            );
+        }
+        self.mouse_regions = component.draw(frame, area, self);
+
+        drop(view_scope);
+
+        if self.view == View::TopicList && self.node_state.show_info {
+            if let Some(node) = self.node_state.current_info() {
+                NodeInfoView::new().render(frame, chunks[0], node, &self.ui_state.theme);
             }
-            View::NodeSelect => {
-                let node_select_view = NodeSelectView::new();
-                node_select_view.render(
+        }
+
+        if self.view == View::TopicList {
+            if let Some(topic) = self.topic_previews.shown() {
+                TopicPreviewView.render(
                     frame,
                     chunks[0],
-                    &self.node_state.favorite_nodes,
-                    self.node_state.selected,
-                    &self.node_state.current_node,
-                    &self.node_state.completion_input,
-                    self.node_state.completion_cursor,
-                    self.node_state.is_completion_mode,
+                    topic,
+                    self.config.topic_preview_lines,
                     &self.ui_state.theme,
                 );
             }
-            View::TokenInput => {
-                render_token_input(
-                    frame,
-                    chunks[0],
-                    &self.token_state.input,
-                    self.token_state.cursor,
-                    &self.ui_state.theme,
-                );
+        }
+
+        if let Some(translation) = &self.translator.shown {
+            TranslationView.render(frame, chunks[0], translation, &self.ui_state.theme);
+        }
+
+        if self.clipboard_history.visible {
+            let clipboard_view = ClipboardView::new();
+            clipboard_view.render(
+                frame,
+                chunks[0],
+                &self.clipboard_history,
+                &self.ui_state.theme,
+            );
+        }
+
+        if self.code_picker.visible {
+            CodePickerView.render(frame, chunks[0], &self.code_picker, &self.ui_state.theme);
+        }
+
+        if self.history_browser.visible {
+            let labels: Vec<String> = self
+                .history
+                .entries()
+                .iter()
+                .map(|entry| self.history_label(entry))
+                .collect();
+            HistoryView::new().render(
+                frame,
+                chunks[0],
+                &labels,
+                self.history.position(),
+                self.history_browser.selected,
+                &self.ui_state.theme,
+            );
+        }
+
+        if self.config.low_bandwidth {
+            let _scope = profiler::scope("simplify_for_low_bandwidth");
+            simplify_for_low_bandwidth(frame.buffer_mut(), chunks[0], &self.ui_state.theme);
+        }
+
+        let _status_scope = profiler::scope("render_status_bar");
+        let cached_at = match self.view {
+            View::TopicList => self.topic_state.topics_cached_at,
+            View::TopicDetail | View::ReplyInput => self.topic_state.cached_at,
+            _ => None,
+        };
+        let network = match cached_at {
+            Some(saved_at) => Some(NetworkStatus::Cached {
+                saved_at,
+                offline: self.config.offline,
+            }),
+            None if self.config.offline => Some(NetworkStatus::Offline),
+            None => self.ui_state.api_quota.as_ref().map(NetworkStatus::Quota),
+        };
+        let mut status_message = self.get_status_with_links();
+        if let Some(retry) = &self.ui_state.api_retry {
+            status_message = format!("{} | {}", retry.label(), status_message);
+        }
+        if let Some(report) = profiler::last_report() {
+            status_message = format!("{} | {}", status_message, report.summary());
+        }
+        let page = match self.view {
+            View::TopicDetail | View::ReplyInput => self.topic_state.replies_page,
+            _ => self.node_state.page,
+        };
+        let status_message = format_status(
+            &self.config.status_format,
+            &StatusFields {
+                message: &status_message,
+                view: crate::config::view_name(self.view),
+                node: &self.node_state.current_node,
+                page,
+                unread: self.notification_state.unread_count,
+                time: chrono::Local::now().format("%H:%M").to_string(),
+            },
+        );
+        render_status_bar(
+            frame,
+            chunks[1],
+            &status_message,
+            self.notification_state.unread_count,
+            self.loading(),
+            network,
+            self.config.low_bandwidth,
+            &self.ui_state.theme,
+        );
+    }
+}
+
+/// Replies gained by topics in `current` that were also in `previous`
+fn reply_deltas(previous: &[Topic], current: &[Topic]) -> HashMap<i64, i64> {
+    let before: HashMap<i64, i64> = previous
+        .iter()
+        .map(|topic| (topic.id, topic.replies))
+        .collect();
+    current
+        .iter()
+        .filter_map(|topic| {
+            let gained = topic.replies - before.get(&topic.id)?;
+            (gained > 0).then_some((topic.id, gained))
+        })
+        .collect()
+}
+
+/// What a thank with or without `reply_id` is for, in status messages
+fn thanked(reply_id: Option<i64>) -> &'static str {
+    if reply_id.is_some() {
+        "reply"
+    } else {
+        "topic"
+    }
+}
+
+/// Index in the refreshed `items` of the one whose key was `selected`, or
+/// the previous index when it is gone, kept within the list
+fn reselect<T, K: PartialEq>(
+    items: &[T],
+    selected: Option<K>,
+    previous: usize,
+    key: impl Fn(&T) -> K,
+) -> usize {
+    selected
+        .and_then(|selected| items.iter().position(|item| key(item) == selected))
+        .unwrap_or_else(|| previous.min(items.len().saturating_sub(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reselect_follows_the_item() {
+        let ids = [7, 3, 5];
+        // A new item arrived above the selected one
+        assert_eq!(reselect(&ids, Some(3), 0, |id| *id), 1);
+        assert_eq!(reselect(&ids, Some(9), 2, |id| *id), 2);
+        assert_eq!(reselect(&ids, Some(9), 8, |id| *id), 2);
+        assert_eq!(reselect(&[] as &[i32], None, 4, |id| *id), 0);
+    }
+
+    #[test]
+    fn test_quiet_refresh_keeps_the_place() {
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Config::default());
+        let page = |ids: &[i64]| PartialList::<Notification> {
+            items: ids
+                .iter()
+                .map(|id| serde_json::from_value(serde_json::json!({ "id": id })).unwrap())
+                .collect(),
+            skipped: 0,
+        };
+        let refreshed = |ids: &[i64]| Finished {
+            loaded: Loaded::Notifications {
+                page: 1,
+                result: Ok(page(ids)),
+            },
+            quiet: true,
+        };
+        app.notification_state.notifications = page(&[1, 2, 3, 4]).items;
+        app.notification_state.selected = 2;
+        app.ui_state.status_message = "Reading".to_string();
+
+        // The selected one is gone, so the selection stays where it was
+        app.finish_load(&client, refreshed(&[5, 1, 2, 4]));
+        assert_eq!(app.notification_state.selected, 2);
+        assert_eq!(app.ui_state.status_message, "Reading");
+        // One arriving above moves the selection along with its item
+        app.finish_load(&client, refreshed(&[6, 5, 1, 2, 4]));
+        assert_eq!(app.notification_state.selected, 3);
+    }
+
+    #[test]
+    fn test_rejected_token_redirects_only_when_asked() {
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Config::default());
+        app.view = View::Notifications;
+        let rejected = |quiet| Finished {
+            loaded: Loaded::Notifications {
+                page: 1,
+                result: Err(anyhow::Error::new(V2exError::Unauthorized(None))),
+            },
+            quiet,
+        };
+
+        app.finish_load(&client, rejected(true));
+        assert_eq!(app.view, View::Notifications);
+        assert!(app.ui_state.error.is_none());
+
+        app.finish_load(&client, rejected(false));
+        assert_eq!(app.view, View::Tokens);
+        assert!(app.ui_state.error.is_some());
+    }
+
+    #[test]
+    fn test_aggregate_error_stays_with_its_view() {
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Config::default());
+        let tab = app.aggregate_state.current_tab.clone();
+        let failed = || Finished {
+            loaded: Loaded::Aggregate {
+                tab: tab.clone(),
+                keep_message: false,
+                result: Err(anyhow::anyhow!("timed out")),
+            },
+            quiet: false,
+        };
+
+        app.view = View::Favorites;
+        app.finish_load(&client, failed());
+        assert!(app.ui_state.error.is_none());
+        assert!(app.ui_state.status_message.contains("timed out"));
+
+        app.view = View::Aggregate;
+        app.finish_load(&client, failed());
+        assert!(app.ui_state.error.is_some());
+    }
+
+    #[test]
+    fn test_node_info_pops_up_only_for_its_node() {
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Config::default());
+        let arrived = |name: &str| Finished {
+            loaded: Loaded::NodeInfo {
+                name: name.to_string(),
+                show: true,
+                result: Ok(
+                    serde_json::from_value(serde_json::json!({ "id": 1, "name": name })).unwrap(),
+                ),
+            },
+            quiet: false,
+        };
+        app.node_state.current_node = "python".to_string();
+
+        // Asked for on another node, it is only kept for later
+        app.finish_load(&client, arrived("go"));
+        assert!(!app.node_state.show_info);
+        assert!(app.node_state.node_info.contains_key("go"));
+
+        app.finish_load(&client, arrived("python"));
+        assert!(app.node_state.show_info);
+    }
+
+    #[tokio::test]
+    async fn test_spinner_follows_the_load() {
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Config::default());
+        app.navigate_to(View::TopicList);
+        assert_eq!(app.loading(), None);
+
+        // The list stays on screen while the topic loads
+        app.open_topic(&client, 7);
+        assert_eq!(app.view, View::TopicList);
+        assert_eq!(app.loading(), Some(Loading::Replies));
+
+        // Help shows nothing loaded, but the load goes on
+        app.navigate_to(View::Help);
+        assert_eq!(app.loading(), Some(Loading::Replies));
+        let finished = loop {
+            match app.loads.finished() {
+                Some(finished) => break finished,
+                None => tokio::task::yield_now().await,
             }
-            View::Aggregate => {
-                if self.ui_state.loading {
-                    render_loading(frame, chunks[0], &self.ui_state.theme);
-                } else if let Some(ref error) = self.ui_state.error {
-                    render_error(frame, chunks[0], error, &self.ui_state.theme);
-                } else {
-                    let aggregate_view = AggregateView::new();
-                    aggregate_view.render(
-                        frame,
-                        chunks[0],
-                        &self.aggregate_state.items,
-                        self.aggregate_state.selected,
-                        &self.aggregate_state.current_tab,
-                        &self.ui_state.theme,
-                    );
+        };
+        app.finish_load(&client, finished);
+        assert_eq!(app.loading(), None);
+        assert!(app.ui_state.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failed_thank_takes_the_count_back() {
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Config::default());
+        app.topic_state.current = Some(
+            serde_json::from_value(
+                serde_json::json!({ "id": 1, "member": { "username": "op" }, "replies": 2 }),
+            )
+            .unwrap(),
+        );
+        app.topic_state.set_replies(
+            [(10, "alice", 0), (11, "bob", 2)]
+                .iter()
+                .map(|(id, user, thanks)| {
+                    serde_json::from_value(serde_json::json!({
+                        "id": id,
+                        "member": { "username": user },
+                        "thanks": thanks,
+                    }))
+                    .unwrap()
+                })
+                .collect(),
+        );
+        async fn finish(app: &mut App, client: &V2exClient) {
+            let finished = loop {
+                match app.loads.finished() {
+                    Some(finished) => break finished,
+                    None => tokio::task::yield_now().await,
                 }
+            };
+            app.finish_load(client, finished);
+        }
+
+        // The reply's count goes up before V2EX answers
+        app.topic_state.show_replies = true;
+        app.topic_state.select_reply(1);
+        app.thank(&client);
+        assert_eq!(app.topic_state.replies()[1].thanks, 3);
+        finish(&mut app, &client).await;
+        assert_eq!(app.topic_state.replies()[1].thanks, 2);
+        assert!(
+            app.ui_state
+                .status_message
+                .starts_with("Failed to thank @bob"),
+            "{}",
+            app.ui_state.status_message
+        );
+
+        // With the replies hidden the topic is thanked
+        app.topic_state.show_replies = false;
+        app.thank(&client);
+        assert_eq!(app.topic_state.stats.as_ref().unwrap().thanks, Some(1));
+        finish(&mut app, &client).await;
+        assert_eq!(app.topic_state.stats, None);
+
+        // A refusal is told apart from other failures
+        app.topic_state.add_thanks(Some(11), 1);
+        app.finish_load(
+            &client,
+            Finished {
+                loaded: Loaded::Thanked {
+                    topic_id: 1,
+                    reply_id: Some(11),
+                    author: "bob".to_string(),
+                    result: Err(ThankRefused::AlreadyThanked.into()),
+                },
+                quiet: false,
+            },
+        );
+        assert_eq!(app.topic_state.replies()[1].thanks, 2);
+        assert_eq!(
+            app.ui_state.status_message,
+            "Already thanked this reply by @bob"
+        );
+
+        // Our own posts aren't sent at all
+        app.profile =
+            Some(serde_json::from_value(serde_json::json!({ "username": "op" })).unwrap());
+        app.thank(&client);
+        assert_eq!(app.ui_state.status_message, "Cannot thank your own topic");
+        assert!(app.loads.finished().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_switching_back_to_a_node_keeps_its_list() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Config::default());
+        app.navigate_to(View::TopicList);
+        app.node_state.current_node = "python".to_string();
+        let topics = (1..=30)
+            .map(|id| {
+                serde_json::from_value(serde_json::json!({
+                    "id": id, "title": format!("Topic {}", id), "created": 0, "replies": 0
+                }))
+                .unwrap()
+            })
+            .collect();
+        app.topic_state.set_topics(topics);
+        app.topic_state.selected = 24;
+        let mut terminal = Terminal::new(TestBackend::new(80, 16)).unwrap();
+        let mut list_offset = |app: &mut App| {
+            terminal.draw(|frame| app.render(frame)).unwrap();
+            app.mouse_regions.list.as_ref().unwrap().offset
+        };
+        let offset = list_offset(&mut app);
+        assert!(offset > 0);
+
+        // A node not seen before is fetched
+        app.switch_to_node(&client, "go");
+        assert!(app.loads.is_running(Slot::Topics));
+        while app.loads.is_running(Slot::Topics) {
+            match app.loads.finished() {
+                Some(finished) => app.finish_load(&client, finished),
+                None => tokio::task::yield_now().await,
             }
         }
 
-        let status_message = self.get_status_with_links();
-        render_status_bar(frame, chunks[1], &status_message, &self.ui_state.theme);
+        // Coming back shows the list as it was left, without a fetch
+        app.switch_to_node(&client, "python");
+        assert!(!app.loads.is_running(Slot::Topics));
+        assert_eq!(app.node_state.current_node, "python");
+        assert_eq!(app.topic_state.topics().len(), 30);
+        assert_eq!(app.topic_state.selected, 24);
+        assert_eq!(list_offset(&mut app), offset);
+
+        // g fetches it again
+        crate::keymap::EventHandler::new(&client)
+            .handle_key(
+                &mut app,
+                KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE),
+            )
+            .unwrap();
+        assert!(app.loads.is_running(Slot::Topics));
+    }
+
+    #[test]
+    fn test_preview_closes_when_leaving_the_list() {
+        let mut app = App::new(Config::default());
+        app.navigate_to(View::TopicList);
+        let topic: crate::api::Topic =
+            serde_json::from_str(r#"{"id": 7, "title": "Hello", "created": 0, "replies": 0}"#)
+                .unwrap();
+        app.topic_previews.insert(topic);
+        app.topic_previews.show(7);
+        app.navigate_to(View::Help);
+        assert!(app.topic_previews.shown().is_none());
+
+        // Coming back doesn't bring it back either
+        app.topic_previews.show(7);
+        app.history_back();
+        assert_eq!(app.view, View::TopicList);
+        assert!(app.topic_previews.shown().is_none());
     }
 }
@@ -0,0 +1,104 @@
+//! Favorite topics saved under `favorite-archive-dir`.
+//!
+//! Each topic is written twice: `v2ex-<id>.md` to read with any editor and
+//! `v2ex-<id>.json` that offline mode loads back into the app.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::api::{PartialList, Reply, Topic};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedTopic {
+    pub topic: Topic,
+    pub replies: Vec<Reply>,
+}
+
+/// Archive a topic and its replies, returning the Markdown file's path
+pub fn save(dir: &Path, topic: &Topic, replies: &[Reply]) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let markdown_path = dir.join(format!("v2ex-{}.md", topic.id));
+    std::fs::write(
+        &markdown_path,
+        crate::cli_output::format_markdown(topic, replies),
+    )
+    .with_context(|| format!("Failed to write {}", markdown_path.display()))?;
+
+    let archived = ArchivedTopic {
+        topic: topic.clone(),
+        replies: replies.to_vec(),
+    };
+    let json_path = dir.join(format!("v2ex-{}.json", topic.id));
+    std::fs::write(&json_path, serde_json::to_string(&archived)?)
+        .with_context(|| format!("Failed to write {}", json_path.display()))?;
+    Ok(markdown_path)
+}
+
+/// Every archived topic in `dir`, newest first; a missing directory is an
+/// empty archive and unreadable files are counted as skipped
+pub fn load_all(dir: &Path) -> Result<PartialList<ArchivedTopic>> {
+    let mut list = PartialList {
+        items: Vec::new(),
+        skipped: 0,
+    };
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(list),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+
+    for entry in entries {
+        let path = entry?.path();
+        let is_archive = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("v2ex-") && name.ends_with(".json"));
+        if !is_archive {
+            continue;
+        }
+        match std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+        {
+            Some(archived) => list.items.push(archived),
+            None => list.skipped += 1,
+        }
+    }
+    list.items
+        .sort_by_key(|archived: &ArchivedTopic| std::cmp::Reverse(archived.topic.created));
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_all() {
+        let dir = std::env::temp_dir().join(format!("v2ex-archive-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let older: Topic =
+            serde_json::from_str(r#"{"id": 1, "title": "Older", "created": 100}"#).unwrap();
+        let newer: Topic =
+            serde_json::from_str(r#"{"id": 2, "title": "Newer", "created": 200}"#).unwrap();
+        let reply: Reply =
+            serde_json::from_str(r#"{"id": 9, "created": 150, "content": "+1"}"#).unwrap();
+
+        assert!(load_all(&dir).unwrap().items.is_empty());
+        let path = save(&dir, &older, &[reply]).unwrap();
+        assert!(path.ends_with("v2ex-1.md"));
+        save(&dir, &newer, &[]).unwrap();
+        std::fs::write(dir.join("v2ex-3.json"), "not json").unwrap();
+
+        let list = load_all(&dir).unwrap();
+        let ids: Vec<i64> = list.items.iter().map(|a| a.topic.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(list.items[1].replies.len(), 1);
+        assert_eq!(list.skipped, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
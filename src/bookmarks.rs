@@ -0,0 +1,192 @@
+//! Topics bookmarked in this client, with free-form tags. Kept apart from
+//! V2EX favorites so they need no token and never leave the machine.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::api::Topic;
+use crate::json_store;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: i64,
+    pub title: String,
+    #[serde(default)]
+    pub node: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Unix timestamp (seconds) the topic was bookmarked
+    pub added_at: i64,
+}
+
+/// Bookmarks, newest first, persisted to `~/.local/share/v2ex/bookmarks.json`
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    entries: Vec<Bookmark>,
+    /// Where changes are saved; `None` keeps them in memory only
+    path: Option<PathBuf>,
+}
+
+/// Split what was typed at the tag prompt into distinct tags, dropping a
+/// leading `#` so `#rust` and `rust` are the same tag
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|tag| tag.trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags.iter().any(|seen| seen == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+impl Bookmarks {
+    /// Load bookmarks from disk; a missing file starts an empty store
+    pub fn load() -> Result<Self> {
+        let path = json_store::data_path("bookmarks.json")?;
+        Ok(Self {
+            entries: json_store::load(&path)?.unwrap_or_default(),
+            path: Some(path),
+        })
+    }
+
+    pub fn get(&self, topic_id: i64) -> Option<&Bookmark> {
+        self.entries.iter().find(|bookmark| bookmark.id == topic_id)
+    }
+
+    /// Bookmark `topic`, or replace the tags of an existing bookmark
+    pub fn add(&mut self, topic: &Topic, tags: Vec<String>) -> Result<()> {
+        match self
+            .entries
+            .iter_mut()
+            .find(|bookmark| bookmark.id == topic.id)
+        {
+            Some(bookmark) => {
+                bookmark.title = topic.title.clone();
+                bookmark.tags = tags;
+            }
+            None => self.entries.insert(
+                0,
+                Bookmark {
+                    id: topic.id,
+                    title: topic.title.clone(),
+                    node: topic
+                        .node
+                        .as_ref()
+                        .map(|node| node.name.clone())
+                        .unwrap_or_default(),
+                    tags,
+                    added_at: chrono::Utc::now().timestamp(),
+                },
+            ),
+        }
+        self.save()
+    }
+
+    /// Drop the bookmark for `topic_id`, returning it if there was one
+    pub fn remove(&mut self, topic_id: i64) -> Result<Option<Bookmark>> {
+        let Some(index) = self.entries.iter().position(|b| b.id == topic_id) else {
+            return Ok(None);
+        };
+        let removed = self.entries.remove(index);
+        self.save()?;
+        Ok(Some(removed))
+    }
+
+    /// Every tag in use, sorted
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .entries
+            .iter()
+            .flat_map(|bookmark| bookmark.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Bookmarks carrying `tag`, or all of them without one
+    pub fn filtered(&self, tag: Option<&str>) -> Vec<&Bookmark> {
+        self.entries
+            .iter()
+            .filter(|bookmark| tag.is_none_or(|tag| bookmark.tags.iter().any(|t| t == tag)))
+            .collect()
+    }
+
+    /// A Markdown list of the bookmarks carrying `tag` (or all of them)
+    pub fn to_markdown(&self, tag: Option<&str>) -> String {
+        let mut out = match tag {
+            Some(tag) => format!("# V2EX bookmarks tagged #{}\n\n", tag),
+            None => "# V2EX bookmarks\n\n".to_string(),
+        };
+        for bookmark in self.filtered(tag) {
+            out.push_str(&format!(
+                "- [{}](https://www.v2ex.com/t/{})",
+                bookmark.title.replace('[', "\\[").replace(']', "\\]"),
+                bookmark.id
+            ));
+            if !bookmark.node.is_empty() {
+                out.push_str(&format!(" ({})", bookmark.node));
+            }
+            for tag in &bookmark.tags {
+                out.push_str(&format!(" `#{}`", tag));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        json_store::save(path, &self.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topic(id: i64, title: &str) -> Topic {
+        serde_json::from_value(serde_json::json!({ "id": id, "title": title })).unwrap()
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(parse_tags(" #rust, async rust "), ["rust", "async"]);
+        assert!(parse_tags("  ").is_empty());
+    }
+
+    #[test]
+    fn test_bookmarks_tags_and_filter() {
+        let mut bookmarks = Bookmarks::default();
+        bookmarks
+            .add(&topic(1, "Tokio [tips]"), parse_tags("rust async"))
+            .unwrap();
+        bookmarks
+            .add(&topic(2, "Keyboards"), parse_tags("hardware"))
+            .unwrap();
+        assert_eq!(bookmarks.tags(), ["async", "hardware", "rust"]);
+        assert_eq!(bookmarks.filtered(Some("rust")).len(), 1);
+        assert_eq!(bookmarks.filtered(None).len(), 2);
+
+        // Bookmarking again only changes the tags
+        bookmarks.add(&topic(2, "Keyboards"), Vec::new()).unwrap();
+        assert_eq!(bookmarks.filtered(None).len(), 2);
+        assert!(bookmarks.get(2).unwrap().tags.is_empty());
+
+        let markdown = bookmarks.to_markdown(Some("rust"));
+        assert!(
+            markdown.contains("- [Tokio \\[tips\\]](https://www.v2ex.com/t/1) `#rust` `#async`")
+        );
+        assert!(!markdown.contains("Keyboards"));
+
+        assert!(bookmarks.remove(1).unwrap().is_some());
+        assert!(bookmarks.remove(1).unwrap().is_none());
+    }
+}
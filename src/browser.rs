@@ -1,6 +1,8 @@
 use anyhow::Result;
 use std::fmt;
 
+use crate::api::{reply_page_url, topic_page_url};
+
 /// Browser operation result types
 #[allow(dead_code)] // Error variant not currently used, but kept for completeness
 #[derive(Debug, Clone)]
@@ -57,22 +59,26 @@ impl Browser {
         ))
     }
 
-    /// Open V2EX topic in browser
-    pub fn open_topic(topic_id: i64) -> Result<BrowserResult> {
-        let url = format!("https://www.v2ex.com/t/{}", topic_id);
-        Self::open_url(&url)
+    /// Open V2EX topic in browser, on the `site` the client reads
+    pub fn open_topic(site: &str, topic_id: i64) -> Result<BrowserResult> {
+        Self::open_url(topic_page_url(site, topic_id))
+    }
+
+    /// Open V2EX topic reply in browser, on the `site` the client reads
+    pub fn open_topic_reply(site: &str, topic_id: i64, reply_id: i64) -> Result<BrowserResult> {
+        Self::open_url(reply_page_url(site, topic_id, reply_id))
     }
 
-    /// Open V2EX topic reply in browser
-    pub fn open_topic_reply(topic_id: i64, reply_id: i64) -> Result<BrowserResult> {
-        let url = format!("https://www.v2ex.com/t/{}#r_{}", topic_id, reply_id);
+    /// Open V2EX member page in browser
+    pub fn open_member(site: &str, username: impl AsRef<str>) -> Result<BrowserResult> {
+        let url = format!("{}/member/{}", site, username.as_ref());
         Self::open_url(&url)
     }
 
     /// Open V2EX node in browser
     #[allow(dead_code)] // Node browser opening not currently used in UI, but kept for API completeness
-    pub fn open_node(node_name: impl AsRef<str>) -> Result<BrowserResult> {
-        let url = format!("https://www.v2ex.com/go/{}", node_name.as_ref());
+    pub fn open_node(site: &str, node_name: impl AsRef<str>) -> Result<BrowserResult> {
+        let url = format!("{}/go/{}", site, node_name.as_ref());
         Self::open_url(&url)
     }
 }
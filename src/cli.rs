@@ -9,15 +9,34 @@ use clap::{Parser, Subcommand};
 )]
 pub struct Cli {
     /// Output format (text or json)
-    #[arg(short, long, value_enum, default_value = "text")]
+    #[arg(short, long, value_enum, default_value = "text", global = true)]
     pub output: OutputFormat,
 
+    /// Shorthand for `--output json`
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Make no network requests; the TUI shows archived favorites and cached topics
+    #[arg(long, global = true)]
+    pub offline: bool,
+
     /// Subcommand to execute (if none, starts TUI mode)
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+impl Cli {
+    /// Output format after applying `--json`
+    pub fn output_format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.output
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
 pub enum OutputFormat {
     Text,
     Json,
@@ -26,6 +45,7 @@ pub enum OutputFormat {
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// List topics from a node
+    #[command(visible_alias = "node")]
     List {
         /// Node name (e.g., python, programmer, share)
         #[arg(default_value = "python")]
@@ -41,6 +61,7 @@ pub enum Commands {
     },
 
     /// Show topic details
+    #[command(visible_alias = "topic")]
     Show {
         /// Topic ID
         id: i64,
@@ -64,6 +85,20 @@ pub enum Commands {
         limit: Option<usize>,
     },
 
+    /// Export a topic and all replies as plain text for printing
+    Export {
+        /// Topic ID
+        id: i64,
+
+        /// Line width to wrap at
+        #[arg(short, long, default_value = "80")]
+        width: usize,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        file: Option<std::path::PathBuf>,
+    },
+
     /// Show notifications
     Notifications {
         /// Page number
@@ -91,7 +126,7 @@ pub enum Commands {
 
     /// Show aggregated topics from RSS feeds
     Aggregate {
-        /// Tab name (index, hot, tech, creative, play, apple, jobs, deals, city, qna)
+        /// Tab name (index, hot, latest, tech, creative, play, apple, jobs, deals, city, qna)
         #[arg(default_value = "index")]
         tab: String,
 
@@ -113,9 +148,10 @@ pub fn print_tui_help() {
     println!("Usage: v2ex-tui [OPTIONS] [COMMAND]");
     println!();
     println!("Commands:");
-    println!("  list [NODE]     List topics from a node (default: python)");
-    println!("  show <ID>       Show topic details");
+    println!("  list [NODE]     List topics from a node (default: python), alias: node");
+    println!("  show <ID>       Show topic details, alias: topic");
     println!("  replies <ID>    Show topic replies");
+    println!("  export <ID>     Export topic and replies as plain text");
     println!("  notifications   Show notifications");
     println!("  profile         Show user profile");
     println!("  nodes           List available nodes");
@@ -123,6 +159,8 @@ pub fn print_tui_help() {
     println!();
     println!("Options:");
     println!("  -o, --output <FORMAT>  Output format: text or json [default: text]");
+    println!("      --json             Same as --output json");
+    println!("      --offline          Make no network requests (TUI: archived and cached topics)");
     println!("  -h, --help             Print help");
     println!("  -v, --version          Print version");
     println!();
@@ -185,3 +223,25 @@ pub fn limit_items<T>(items: &[T], limit: Option<usize>) -> &[T] {
         _ => items,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_friendly_aliases() {
+        let cli = Cli::try_parse_from(["v2ex-tui", "node", "rust", "--json"]).unwrap();
+        assert!(matches!(cli.command, Some(Commands::List { ref node, .. }) if node == "rust"));
+        assert_eq!(cli.output_format(), OutputFormat::Json);
+
+        let cli = Cli::try_parse_from(["v2ex-tui", "topic", "123", "-r"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Show {
+                id: 123,
+                replies: true
+            })
+        ));
+        assert_eq!(cli.output_format(), OutputFormat::Text);
+    }
+}
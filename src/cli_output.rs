@@ -65,6 +65,215 @@ pub fn format_reply(reply: &Reply, index: Option<usize>) -> String {
     output
 }
 
+/// Line width of [`format_printable`] output when none is given
+pub const PRINT_WIDTH: usize = 80;
+
+/// How [`format_archive`] writes each part of a topic and its replies
+trait Archive {
+    /// Content as V2EX rendered it, or its raw text when it wasn't
+    fn content(&self, rendered: Option<&String>, raw: Option<&String>) -> String;
+    /// Title and details of the topic, followed by its `content`
+    fn topic(&self, topic: &Topic, content: String) -> String;
+    /// Heading before the first of `count` replies
+    fn replies_heading(&self, _count: usize) -> String {
+        String::new()
+    }
+    /// Reply number `floor` with its `content`
+    fn reply(&self, floor: usize, author: &str, created: i64, content: String) -> String;
+    /// What closes the document
+    fn end(&self) -> String {
+        String::new()
+    }
+}
+
+/// A topic and its replies, each part written by `archive`
+fn format_archive(archive: &impl Archive, topic: &Topic, replies: &[Reply]) -> String {
+    let content = archive.content(topic.content_rendered.as_ref(), topic.content.as_ref());
+    let mut output = archive.topic(topic, content);
+    if !replies.is_empty() {
+        output.push_str(&archive.replies_heading(replies.len()));
+    }
+    for (index, reply) in replies.iter().enumerate() {
+        let author = reply
+            .member
+            .as_ref()
+            .map(|m| m.username.as_str())
+            .unwrap_or("Unknown");
+        let content = archive.content(reply.content_rendered.as_ref(), reply.content.as_ref());
+        output.push_str(&archive.reply(index + 1, author, reply.created, content));
+    }
+    output.push_str(&archive.end());
+    output
+}
+
+/// Wrapped plain text, `width` columns wide
+struct Printable {
+    width: usize,
+}
+
+impl Archive for Printable {
+    fn content(&self, rendered: Option<&String>, raw: Option<&String>) -> String {
+        match (rendered, raw) {
+            (Some(html), _) => crate::render::plain(html, self.width).to_string(),
+            // Wrapped like rendered content, keeping the author's line breaks
+            (None, Some(text)) => {
+                let html = escape_html(text.trim_end()).replace('\n', "<br>");
+                crate::render::plain(&html, self.width).to_string()
+            }
+            (None, None) => String::new(),
+        }
+    }
+
+    fn topic(&self, topic: &Topic, content: String) -> String {
+        let mut output = format!("{}\n", topic.title);
+        output.push_str(&format!(
+            "{} · {} · {} · {} replies\n",
+            topic.node_title(),
+            topic.author_name(),
+            format_timestamp(topic.created),
+            topic.replies
+        ));
+        if !topic.url.is_empty() {
+            output.push_str(&format!("{}\n", topic.url));
+        }
+        output.push_str(&"=".repeat(self.width));
+        output.push_str("\n\n");
+        output.push_str(&content);
+        output
+    }
+
+    fn reply(&self, floor: usize, author: &str, created: i64, content: String) -> String {
+        format!(
+            "\n{}\n#{} {} · {}\n\n{}",
+            "-".repeat(self.width),
+            floor,
+            author,
+            format_timestamp(created),
+            content
+        )
+    }
+}
+
+/// A topic and its replies as wrapped plain text, for printing or mailing
+pub fn format_printable(topic: &Topic, replies: &[Reply], width: usize) -> String {
+    let width = width.max(20);
+    format_archive(&Printable { width }, topic, replies)
+}
+
+/// Markdown, with rendered content converted back from its HTML
+struct Markdown;
+
+impl Archive for Markdown {
+    fn content(&self, rendered: Option<&String>, raw: Option<&String>) -> String {
+        match (rendered, raw) {
+            (Some(html), _) => crate::render::markdown::markdown(html),
+            // Raw content is the Markdown the author wrote
+            (None, Some(text)) => format!("{}\n", text.trim_end()),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn topic(&self, topic: &Topic, content: String) -> String {
+        let escape = |text: &str| crate::render::markdown::escape(text, false);
+        let mut output = format!("# {}\n\n", escape(&topic.title));
+        output.push_str(&format!("- Node: {}\n", escape(topic.node_title())));
+        output.push_str(&format!("- Author: {}\n", escape(topic.author_name())));
+        output.push_str(&format!("- Created: {}\n", format_timestamp(topic.created)));
+        if !topic.url.is_empty() {
+            output.push_str(&format!("- URL: <{}>\n", topic.url));
+        }
+        output.push('\n');
+        output.push_str(&content);
+        output
+    }
+
+    fn replies_heading(&self, count: usize) -> String {
+        format!("\n## Replies ({})\n", count)
+    }
+
+    fn reply(&self, floor: usize, author: &str, created: i64, content: String) -> String {
+        format!(
+            "\n### #{} {} · {}\n\n{}",
+            floor,
+            crate::render::markdown::escape(author, false),
+            format_timestamp(created),
+            content
+        )
+    }
+}
+
+/// A topic and its replies as a Markdown document, for archiving
+pub fn format_markdown(topic: &Topic, replies: &[Reply]) -> String {
+    format_archive(&Markdown, topic, replies)
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A standalone HTML page, keeping the content as V2EX rendered it
+struct Html;
+
+impl Archive for Html {
+    fn content(&self, rendered: Option<&String>, raw: Option<&String>) -> String {
+        match (rendered, raw) {
+            (Some(html), _) => html.clone(),
+            (None, Some(text)) => format!("<pre>{}</pre>", escape_html(text.trim_end())),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn topic(&self, topic: &Topic, content: String) -> String {
+        let title = escape_html(&topic.title);
+        let mut output = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n",
+            title, title
+        );
+        output.push_str(&format!(
+            "<p>{} · {} · {}",
+            escape_html(topic.node_title()),
+            escape_html(topic.author_name()),
+            format_timestamp(topic.created)
+        ));
+        if !topic.url.is_empty() {
+            let url = escape_html(&topic.url);
+            output.push_str(&format!(" · <a href=\"{}\">{}</a>", url, url));
+        }
+        output.push_str("</p>\n<article>\n");
+        output.push_str(&content);
+        output.push_str("\n</article>\n");
+        output
+    }
+
+    fn replies_heading(&self, count: usize) -> String {
+        format!("<h2>Replies ({})</h2>\n", count)
+    }
+
+    fn reply(&self, floor: usize, author: &str, created: i64, content: String) -> String {
+        format!(
+            "<section>\n<h3>#{} {} · {}</h3>\n{}\n</section>\n",
+            floor,
+            escape_html(author),
+            format_timestamp(created),
+            content
+        )
+    }
+
+    fn end(&self) -> String {
+        "</body>\n</html>\n".to_string()
+    }
+}
+
+/// A topic and its replies as a standalone HTML page, keeping the content
+/// as V2EX rendered it
+pub fn format_html(topic: &Topic, replies: &[Reply]) -> String {
+    format_archive(&Html, topic, replies)
+}
+
 /// Format a notification for text output
 pub fn format_notification(notification: &Notification, index: Option<usize>) -> String {
     let idx_str = match index {
@@ -155,9 +364,19 @@ pub fn format_rss_item(item: &RssItem, index: Option<usize>) -> String {
         .map(|id| format!(" (ID: {})", id))
         .unwrap_or_default();
 
+    let node_str = item
+        .node
+        .as_ref()
+        .map(|node| format!("  Node: {}\n", node))
+        .unwrap_or_default();
+    let replies_str = item
+        .replies
+        .map(|replies| format!("  Replies: {}\n", replies))
+        .unwrap_or_default();
+
     format!(
-        "{}{}{}\n  Date: {}\n  URL: {}\n",
-        idx_str, item.title, id_str, item.date, item.link
+        "{}{}{}\n{}  Date: {}\n{}  URL: {}\n",
+        idx_str, item.title, id_str, node_str, item.date, replies_str, item.link
     )
 }
 
@@ -252,3 +471,77 @@ pub fn print_rss_items(items: &[RssItem], limit: Option<usize>) {
         print!("{}", format_rss_item(item, Some(i)));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_printable() {
+        let topic: Topic = serde_json::from_str(
+            r#"{"id": 7, "title": "Hello", "created": 0, "replies": 1,
+                "content_rendered": "<p>A <b>long</b> paragraph that has to be wrapped somewhere before the fortieth column</p>"}"#,
+        )
+        .unwrap();
+        let reply: Reply = serde_json::from_str(
+            r#"{"id": 1, "created": 0, "member": {"id": 2, "username": "bob"}, "content": "+1"}"#,
+        )
+        .unwrap();
+        let raw: Reply = serde_json::from_str(
+            r#"{"id": 2, "created": 0, "content": "first line\na raw reply without any HTML that is longer than forty columns <3"}"#,
+        )
+        .unwrap();
+
+        let text = format_printable(&topic, &[reply, raw], 40);
+        assert!(text.starts_with("Hello\n"));
+        let (_, body) = text.split_once(&"=".repeat(40)).unwrap();
+        assert!(body.lines().all(|line| line.chars().count() <= 40));
+        assert!(!text.contains('\x1b'));
+        assert!(text.contains("#1 bob · 1970-01-01 00:00:00 UTC\n\n+1\n"));
+        assert!(text.contains("\n\nfirst line\na raw reply"));
+        assert!(text.contains("<3"));
+    }
+
+    #[test]
+    fn test_format_markdown() {
+        let topic: Topic = serde_json::from_str(
+            r#"{"id": 7, "title": "Hello", "created": 0, "replies": 1,
+                "url": "https://www.v2ex.com/t/7", "content": "Body"}"#,
+        )
+        .unwrap();
+        let reply: Reply = serde_json::from_str(
+            r#"{"id": 1, "created": 0, "member": {"id": 2, "username": "bob"}, "content": "+1"}"#,
+        )
+        .unwrap();
+
+        let rendered: Reply = serde_json::from_str(
+            r#"{"id": 2, "created": 0, "member": {"id": 3, "username": "a_b"},
+                "content": "**yes**", "content_rendered": "<p><strong>yes</strong>, <a href=\"/t/1\">see</a></p>"}"#,
+        )
+        .unwrap();
+
+        let markdown = format_markdown(&topic, &[reply, rendered]);
+        assert!(markdown.starts_with("# Hello\n\n- Node: Unknown\n"));
+        assert!(markdown.contains("- URL: <https://www.v2ex.com/t/7>\n\nBody\n"));
+        assert!(markdown.contains("## Replies (2)\n\n### #1 bob · 1970-01-01 00:00:00 UTC\n\n+1\n"));
+        assert!(markdown.ends_with(
+            "### #2 a\\_b · 1970-01-01 00:00:00 UTC\n\n**yes**, [see](https://www.v2ex.com/t/1)\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_html() {
+        let topic: Topic = serde_json::from_str(
+            r#"{"id": 7, "title": "<b>Hi</b> & bye", "created": 0, "replies": 1,
+                "content_rendered": "<p>Body</p>"}"#,
+        )
+        .unwrap();
+        let reply: Reply =
+            serde_json::from_str(r#"{"id": 1, "created": 0, "content": "1 < 2"}"#).unwrap();
+
+        let html = format_html(&topic, &[reply]);
+        assert!(html.contains("<title>&lt;b&gt;Hi&lt;/b&gt; &amp; bye</title>"));
+        assert!(html.contains("<article>\n<p>Body</p>\n</article>"));
+        assert!(html.contains("<h3>#1 Unknown · 1970-01-01 00:00:00 UTC</h3>\n<pre>1 &lt; 2</pre>"));
+    }
+}
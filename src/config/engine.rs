@@ -0,0 +1,1142 @@
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::{
+    view_from_name, Config, CountFormat, CustomFeed, RefreshTarget, ThemePreset, TopicColumn,
+};
+use crate::app::View;
+use crate::keymap::{BindingTarget, KeyBindings};
+use crate::ui::SplitLayout;
+use crate::views::aggregate::{AGGREGATE_TABS, AGGREGATE_VIEW_KEYS};
+
+/// A value in the configuration Lisp dialect
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Str(String),
+    Symbol(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+            Value::Bool(_) => "boolean",
+            Value::Int(_) => "integer",
+            Value::Str(_) => "string",
+            Value::Symbol(_) => "symbol",
+            Value::List(_) => "list",
+        }
+    }
+
+    pub fn as_int(&self) -> Result<i64> {
+        match self {
+            Value::Int(n) => Ok(*n),
+            other => bail!("expected integer, got {}", other.type_name()),
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            Value::Nil => Ok(false),
+            other => bail!("expected boolean, got {}", other.type_name()),
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str> {
+        match self {
+            Value::Str(s) => Ok(s),
+            other => bail!("expected string, got {}", other.type_name()),
+        }
+    }
+
+    pub fn as_symbol(&self) -> Result<&str> {
+        match self {
+            Value::Symbol(s) => Ok(s),
+            other => bail!("expected symbol, got {}", other.type_name()),
+        }
+    }
+}
+
+/// Parse config source into top-level forms, each paired with its starting line
+pub fn read(source: &str) -> Result<Vec<(Value, usize)>> {
+    let mut reader = Reader {
+        chars: source.chars().collect(),
+        pos: 0,
+        line: 1,
+    };
+    let mut forms = Vec::new();
+    loop {
+        reader.skip_whitespace();
+        if reader.pos >= reader.chars.len() {
+            break;
+        }
+        let line = reader.line;
+        forms.push((reader.read_value()?, line));
+    }
+    Ok(forms)
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        if ch == '\n' {
+            self.line += 1;
+        }
+        Some(ch)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.peek() {
+            if ch == ';' {
+                while let Some(ch) = self.bump() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            } else if ch.is_whitespace() {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn read_value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+        let line = self.line;
+        match self.peek() {
+            None => bail!("line {}: unexpected end of input", line),
+            Some('(') => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        None => bail!("line {}: unclosed '('", line),
+                        Some(')') => {
+                            self.bump();
+                            break;
+                        }
+                        Some(_) => items.push(self.read_value()?),
+                    }
+                }
+                Ok(Value::List(items))
+            }
+            Some(')') => bail!("line {}: unexpected ')'", line),
+            Some('\'') => {
+                self.bump();
+                let quoted = self.read_value()?;
+                Ok(Value::List(vec![
+                    Value::Symbol("quote".to_string()),
+                    quoted,
+                ]))
+            }
+            Some('"') => {
+                self.bump();
+                let mut s = String::new();
+                loop {
+                    match self.bump() {
+                        None => bail!("line {}: unterminated string", line),
+                        Some('"') => break,
+                        Some('\\') => match self.bump() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(ch) => s.push(ch),
+                            None => bail!("line {}: unterminated string", line),
+                        },
+                        Some(ch) => s.push(ch),
+                    }
+                }
+                Ok(Value::Str(s))
+            }
+            Some(_) => {
+                let mut atom = String::new();
+                while let Some(ch) = self.peek() {
+                    if ch.is_whitespace() || ch == '(' || ch == ')' || ch == ';' {
+                        break;
+                    }
+                    atom.push(ch);
+                    self.bump();
+                }
+                Ok(match atom.as_str() {
+                    "#t" | "t" => Value::Bool(true),
+                    "#f" | "nil" => Value::Bool(false),
+                    _ => match atom.parse::<i64>() {
+                        Ok(n) => Value::Int(n),
+                        Err(_) => Value::Symbol(atom),
+                    },
+                })
+            }
+        }
+    }
+}
+
+/// Forms registered with `on-startup` and `on-topic-open`, kept
+/// unevaluated until the event happens
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    pub startup: Vec<Value>,
+    pub topic_open: Vec<Value>,
+}
+
+/// Actions added with `define-action`, each kept as the forms it runs
+#[derive(Debug, Clone, Default)]
+pub struct ActionRegistry {
+    custom: HashMap<String, Vec<Value>>,
+}
+
+impl ActionRegistry {
+    pub fn get(&self, name: &str) -> Option<&[Value]> {
+        self.custom.get(name).map(Vec::as_slice)
+    }
+
+    /// Names of the defined actions, sorted
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.custom.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    fn define(&mut self, name: String, forms: Vec<Value>) {
+        self.custom.insert(name, forms);
+    }
+}
+
+/// How deeply custom actions may run one another, so an action that ends
+/// up running itself fails instead of looping
+const MAX_ACTION_DEPTH: usize = 16;
+
+/// Something a running hook asked the app to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookEffect {
+    Message(String),
+    /// Name of an action from the `define-key` list
+    Action(String),
+    OpenNode(String),
+}
+
+/// Evaluates config forms against a `Config`
+pub struct Engine {
+    pub config: Config,
+    /// Variables visible to a running hook, such as `topic-id`
+    vars: HashMap<String, Value>,
+    /// Collected while running a hook; `None` while reading the config
+    effects: Option<Vec<HookEffect>>,
+    /// Custom actions currently running inside one another
+    depth: usize,
+}
+
+impl Engine {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            vars: HashMap::new(),
+            effects: None,
+            depth: 0,
+        }
+    }
+
+    /// Evaluate hook `forms` with `vars` bound, returning what they asked
+    /// for in order. Hooks can't change settings but can run `actions`.
+    pub fn run_hook(
+        forms: &[Value],
+        vars: HashMap<String, Value>,
+        actions: &ActionRegistry,
+    ) -> Result<Vec<HookEffect>> {
+        let mut engine = Self {
+            config: Config {
+                actions: actions.clone(),
+                ..Config::default()
+            },
+            vars,
+            effects: Some(Vec::new()),
+            depth: 0,
+        };
+        for form in forms {
+            engine.eval(form)?;
+        }
+        Ok(engine.effects.unwrap_or_default())
+    }
+
+    /// Evaluate a whole config file, stopping at the first error
+    pub fn eval_source(&mut self, source: &str) -> Result<()> {
+        for (form, line) in read(source)? {
+            self.eval(&form)
+                .map_err(|e| anyhow!("Config error at line {}: {}", line, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn eval(&mut self, expr: &Value) -> Result<Value> {
+        match expr {
+            Value::Symbol(name) => self
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("undefined variable '{}'", name)),
+            Value::List(items) if items.is_empty() => Ok(Value::Nil),
+            Value::List(items) => {
+                let head = items[0].as_symbol()?.to_string();
+                let args = &items[1..];
+                match head.as_str() {
+                    "quote" => {
+                        expect_args(&head, args, 1)?;
+                        Ok(args[0].clone())
+                    }
+                    "set!" => {
+                        if self.effects.is_some() {
+                            bail!("settings can't be changed from a hook");
+                        }
+                        expect_args(&head, args, 2)?;
+                        let name = args[0].as_symbol()?.to_string();
+                        let value = self.eval(&args[1])?;
+                        self.set_variable(&name, value)?;
+                        Ok(Value::Nil)
+                    }
+                    "if" => {
+                        if !(2..=3).contains(&args.len()) {
+                            bail!("'if' expects 2 or 3 arguments, got {}", args.len());
+                        }
+                        if self.eval(&args[0])?.as_bool()? {
+                            self.eval(&args[1])
+                        } else {
+                            args.get(2).map_or(Ok(Value::Nil), |form| self.eval(form))
+                        }
+                    }
+                    "on-startup" | "on-topic-open" => {
+                        if self.effects.is_some() {
+                            bail!("'{}' can't be used inside a hook", head);
+                        }
+                        let hook = if head == "on-startup" {
+                            &mut self.config.hooks.startup
+                        } else {
+                            &mut self.config.hooks.topic_open
+                        };
+                        hook.extend(args.iter().cloned());
+                        Ok(Value::Nil)
+                    }
+                    "define-action" => {
+                        if self.effects.is_some() {
+                            bail!("'define-action' can't be used inside a hook");
+                        }
+                        if args.len() < 2 {
+                            bail!("'define-action' expects a name and at least one step");
+                        }
+                        let name = self.eval(&args[0])?.as_symbol()?.to_string();
+                        if crate::keymap::action_keys(&name).is_ok() {
+                            bail!("'{}' is a built-in action", name);
+                        }
+                        let mut forms = Vec::new();
+                        for step in &args[1..] {
+                            match step {
+                                Value::List(items)
+                                    if items.first() == Some(&Value::Symbol("lambda".into())) =>
+                                {
+                                    if items.get(1) != Some(&Value::List(Vec::new())) {
+                                        bail!("action lambdas take no arguments");
+                                    }
+                                    forms.extend(items[2..].iter().cloned());
+                                }
+                                step => {
+                                    let action = self.eval(step)?.as_symbol()?.to_string();
+                                    if self.config.actions.get(&action).is_none() {
+                                        crate::keymap::action_keys(&action)?;
+                                    }
+                                    forms.push(Value::List(vec![
+                                        Value::Symbol("run-action".to_string()),
+                                        Value::List(vec![
+                                            Value::Symbol("quote".to_string()),
+                                            Value::Symbol(action),
+                                        ]),
+                                    ]));
+                                }
+                            }
+                        }
+                        self.config.actions.define(name, forms);
+                        Ok(Value::Nil)
+                    }
+                    _ => {
+                        let values = args
+                            .iter()
+                            .map(|arg| self.eval(arg))
+                            .collect::<Result<Vec<_>>>()?;
+                        self.call_builtin(&head, values)
+                    }
+                }
+            }
+            other => Ok(other.clone()),
+        }
+    }
+
+    fn call_builtin(&mut self, name: &str, args: Vec<Value>) -> Result<Value> {
+        match name {
+            "equal" => {
+                expect_args(name, &args, 2)?;
+                // A quoted name and a string with the same text are equal,
+                // so `(equal topic-node 'qna)` works like the string form
+                let equal = match (&args[0], &args[1]) {
+                    (Value::Str(a) | Value::Symbol(a), Value::Str(b) | Value::Symbol(b)) => a == b,
+                    (a, b) => a == b,
+                };
+                return Ok(Value::Bool(equal));
+            }
+            "concat" => {
+                let mut text = String::new();
+                for arg in &args {
+                    match arg {
+                        Value::Str(s) | Value::Symbol(s) => text.push_str(s),
+                        Value::Int(n) => text.push_str(&n.to_string()),
+                        other => bail!("can't concat {}", other.type_name()),
+                    }
+                }
+                return Ok(Value::Str(text));
+            }
+            "message" | "run-action" | "open-node" => {
+                expect_args(name, &args, 1)?;
+                if self.effects.is_none() {
+                    bail!("'{}' can only be used inside a hook", name);
+                }
+                let effect = match name {
+                    "message" => HookEffect::Message(args[0].as_str()?.to_string()),
+                    "run-action" => {
+                        let action = args[0].as_symbol()?;
+                        if let Some(forms) = self.config.actions.get(action) {
+                            return self.run_custom_action(action, forms.to_vec());
+                        }
+                        crate::keymap::action_keys(action)?;
+                        HookEffect::Action(action.to_string())
+                    }
+                    _ => HookEffect::OpenNode(match &args[0] {
+                        Value::Symbol(node) | Value::Str(node) => node.clone(),
+                        other => bail!("expected node name, got {}", other.type_name()),
+                    }),
+                };
+                if let Some(effects) = self.effects.as_mut() {
+                    effects.push(effect);
+                }
+                return Ok(Value::Nil);
+            }
+            _ if self.effects.is_some() => {
+                bail!("'{}' can't be used inside a hook", name);
+            }
+            _ => {}
+        }
+        match name {
+            "set-refresh-interval" => {
+                expect_args(name, &args, 2)?;
+                let target = RefreshTarget::from_name(args[0].as_symbol()?)?;
+                let seconds = non_negative(&args[1])?;
+                self.config.refresh_intervals.set(target, seconds);
+                Ok(Value::Nil)
+            }
+            "define-key" => {
+                expect_args(name, &args, 3)?;
+                let view = view_from_name(args[0].as_symbol()?)?;
+                let keys = args[1].as_str()?;
+                let target = match &args[2] {
+                    Value::Symbol(action) if self.config.actions.get(action).is_some() => {
+                        BindingTarget::Custom(action.clone())
+                    }
+                    Value::Symbol(action) => BindingTarget::Action(action.clone()),
+                    Value::Str(keys) => BindingTarget::Keys(keys.clone()),
+                    other => bail!(
+                        "expected action symbol or key string, got {}",
+                        other.type_name()
+                    ),
+                };
+                // A feed's key would never reach the feed again
+                let mut probe = KeyBindings::default();
+                probe.define(view, keys, BindingTarget::Keys(keys.to_string()))?;
+                let feed = self
+                    .config
+                    .custom_feeds
+                    .iter()
+                    .find(|feed| probe.binds_key(View::Aggregate, feed.key));
+                if let Some(feed) = feed {
+                    bail!("key '{}' is already used by feed '{}'", feed.key, feed.name);
+                }
+                self.config.key_bindings.define(view, keys, target)?;
+                Ok(Value::Nil)
+            }
+            "set-theme-color" => {
+                expect_args(name, &args, 2)?;
+                self.config
+                    .custom_theme
+                    .set(args[0].as_symbol()?, args[1].as_str()?)?;
+                Ok(Value::Nil)
+            }
+            "offline" => {
+                expect_args(name, &args, 1)?;
+                self.config.offline = args[0].as_bool()?;
+                Ok(Value::Nil)
+            }
+            "set-quick-node-key" => {
+                expect_args(name, &args, 2)?;
+                let mut chars = args[0].as_str()?.chars();
+                let key = match (chars.next(), chars.next()) {
+                    (Some(key), None) => key,
+                    _ => bail!("quick node key must be a single character"),
+                };
+                let node = args[1].as_symbol()?.to_string();
+                self.config.quick_node_keys.retain(|(k, _)| *k != key);
+                self.config.quick_node_keys.push((key, node));
+                Ok(Value::Nil)
+            }
+            "add-feed" => {
+                expect_args(name, &args, 3)?;
+                let mut chars = args[0].as_str()?.chars();
+                let key = match (chars.next(), chars.next()) {
+                    (Some(key), None) => key,
+                    _ => bail!("feed key must be a single character"),
+                };
+                if AGGREGATE_VIEW_KEYS.contains(key)
+                    || self.config.key_bindings.binds_key(View::Aggregate, key)
+                {
+                    bail!("key '{}' is already used by the aggregate view", key);
+                }
+                let tab = match &args[1] {
+                    Value::Symbol(tab) | Value::Str(tab) if !tab.is_empty() => tab.clone(),
+                    other => bail!("expected feed name, got {}", other.type_name()),
+                };
+                if let Some((k, t)) = AGGREGATE_TABS.iter().find(|(k, t)| *k == key || *t == tab) {
+                    bail!("feed '{}' clashes with built-in tab {}:{}", tab, k, t);
+                }
+                let url = args[2].as_str()?.trim().to_string();
+                if url.is_empty() {
+                    bail!("feed '{}' needs a URL", tab);
+                }
+                self.config
+                    .custom_feeds
+                    .retain(|feed| feed.key != key && feed.name != tab);
+                self.config.custom_feeds.push(CustomFeed {
+                    key,
+                    name: tab,
+                    url,
+                });
+                Ok(Value::Nil)
+            }
+            "ignore-user" => {
+                if args.is_empty() {
+                    bail!("ignore-user expects at least one username");
+                }
+                for arg in &args {
+                    self.config.ignored_users.add(arg.as_str()?);
+                }
+                Ok(Value::Nil)
+            }
+            _ => bail!("undefined function '{}'", name),
+        }
+    }
+
+    fn run_custom_action(&mut self, name: &str, forms: Vec<Value>) -> Result<Value> {
+        if self.depth >= MAX_ACTION_DEPTH {
+            bail!("action '{}' keeps running itself", name);
+        }
+        self.depth += 1;
+        let result = forms.iter().try_for_each(|form| self.eval(form).map(drop));
+        self.depth -= 1;
+        result.map(|()| Value::Nil)
+    }
+
+    fn set_variable(&mut self, name: &str, value: Value) -> Result<()> {
+        match name {
+            "auto-refresh-interval" => {
+                self.config.auto_refresh_interval = non_negative(&value)?;
+            }
+            "hide-read-topics" => {
+                self.config.hide_read_topics = value.as_bool()?;
+            }
+            "prefetch-favorite-nodes" => {
+                self.config.prefetch_favorite_nodes = value.as_bool()?;
+            }
+            "load-ahead" => {
+                self.config.load_ahead = non_negative(&value)?;
+            }
+            "wrap-topic-titles" => {
+                self.config.wrap_topic_titles = value.as_bool()?;
+            }
+            "topic-list-columns" => {
+                let Value::List(names) = value else {
+                    bail!("expected list, got {}", value.type_name());
+                };
+                let mut columns = Vec::new();
+                for name in &names {
+                    let column = TopicColumn::from_name(name.as_symbol()?)?;
+                    if columns.contains(&column) {
+                        bail!("column '{}' is listed twice", name.as_symbol()?);
+                    }
+                    columns.push(column);
+                }
+                if !columns.contains(&TopicColumn::Title) {
+                    bail!("topic-list-columns needs the title column");
+                }
+                self.config.topic_list_columns = columns;
+            }
+            "topic-preview-lines" => {
+                self.config.topic_preview_lines = non_negative(&value)?;
+            }
+            "aggregate-resolve-topics" => {
+                self.config.aggregate_resolve_topics = value.as_bool()?;
+            }
+            "aggregate-cache-ttl" => {
+                self.config.aggregate_cache_ttl = non_negative(&value)?;
+            }
+            "aggregate-prefetch-tabs" => {
+                let Value::List(tabs) = value else {
+                    bail!("expected list, got {}", value.type_name());
+                };
+                // Feeds count once `add-feed` has added them
+                let tabs_known = self.config.tab_key_mappings();
+                self.config.aggregate_prefetch_tabs = tabs
+                    .iter()
+                    .map(|tab| match tab {
+                        Value::Symbol(name) | Value::Str(name)
+                            if tabs_known.iter().any(|(_, t)| t == name) =>
+                        {
+                            Ok(name.clone())
+                        }
+                        Value::Symbol(name) | Value::Str(name) => {
+                            bail!("unknown aggregate tab '{}'", name)
+                        }
+                        other => bail!("expected tab name, got {}", other.type_name()),
+                    })
+                    .collect::<Result<_>>()?;
+            }
+            "notification-poll-interval" => {
+                self.config.notification_poll_interval = non_negative(&value)?;
+            }
+            "desktop-notifications" => {
+                self.config.desktop_notifications = value.as_bool()?;
+            }
+            "key-sequence-timeout" => {
+                self.config.key_sequence_timeout = non_negative(&value)?;
+            }
+            "low-bandwidth" => {
+                self.config.low_bandwidth = value.as_bool()?;
+            }
+            "tts-command" => {
+                self.config.tts_command = value.as_str()?.to_string();
+            }
+            "translate-command" => {
+                self.config.translate_command = value.as_str()?.to_string();
+            }
+            "unfurl-links" => {
+                self.config.unfurl_links = value.as_bool()?;
+            }
+            "focus-width" => {
+                self.config.focus_width = non_negative(&value)?;
+            }
+            "theme" => {
+                self.config.theme = ThemePreset::from_name(value.as_symbol()?)?;
+            }
+            "count-format" => {
+                self.config.count_format = CountFormat::from_name(value.as_symbol()?)?;
+            }
+            "split-ratio" => {
+                let percent = non_negative(&value)?;
+                let range = SplitLayout::MIN_PERCENT as u64..=SplitLayout::MAX_PERCENT as u64;
+                if !range.contains(&percent) {
+                    bail!(
+                        "split-ratio must be between {} and {}",
+                        range.start(),
+                        range.end()
+                    );
+                }
+                self.config.split_layout.topic_percent = percent as u16;
+            }
+            "split-swapped" => {
+                self.config.split_layout.swapped = value.as_bool()?;
+            }
+            "split-stacked" => {
+                self.config.split_layout.stacked = value.as_bool()?;
+            }
+            "favorite-nodes" => {
+                let Value::List(nodes) = value else {
+                    bail!("expected list, got {}", value.type_name());
+                };
+                self.config.favorite_nodes = nodes
+                    .iter()
+                    .map(|node| match node {
+                        Value::Symbol(name) | Value::Str(name) => Ok(name.clone()),
+                        other => bail!("expected node name, got {}", other.type_name()),
+                    })
+                    .collect::<Result<_>>()?;
+            }
+            "favorite-archive-dir" => {
+                self.config.favorite_archive_dir = value.as_str()?.to_string();
+            }
+            "export-path" => {
+                self.config.export_path = value.as_str()?.to_string();
+            }
+            "status-format" => {
+                self.config.status_format = value.as_str()?.to_string();
+            }
+            "retry-max-attempts" => {
+                let attempts = non_negative(&value)?;
+                if attempts == 0 {
+                    bail!("retry-max-attempts must be at least 1");
+                }
+                self.config.retry.max_attempts = u32::try_from(attempts).unwrap_or(u32::MAX);
+            }
+            "retry-base-delay" => {
+                self.config.retry.base_delay = Duration::from_millis(non_negative(&value)?);
+            }
+            "retry-max-delay" => {
+                self.config.retry.max_delay = Duration::from_millis(non_negative(&value)?);
+            }
+            "retry-jitter" => {
+                self.config.retry.jitter = value.as_bool()?;
+            }
+            "user-agent" => {
+                let user_agent = value.as_str()?;
+                if reqwest::header::HeaderValue::from_str(user_agent).is_err() {
+                    bail!("user-agent must be a single line of text");
+                }
+                self.config.user_agent = user_agent.to_string();
+            }
+            "request-delay" => {
+                self.config.request_delay = Duration::from_millis(non_negative(&value)?);
+            }
+            "api-base-url" => {
+                self.config.api_base_url = base_url(&value)?;
+            }
+            "site-base-url" => {
+                self.config.site_base_url = base_url(&value)?;
+            }
+            _ => bail!("unknown setting '{}'", name),
+        }
+        Ok(())
+    }
+}
+
+fn expect_args<T>(name: &str, args: &[T], count: usize) -> Result<()> {
+    if args.len() != count {
+        bail!(
+            "'{}' expects {} argument(s), got {}",
+            name,
+            count,
+            args.len()
+        );
+    }
+    Ok(())
+}
+
+fn non_negative(value: &Value) -> Result<u64> {
+    let n = value.as_int()?;
+    u64::try_from(n).map_err(|_| anyhow!("expected a non-negative number, got {}", n))
+}
+
+/// An http(s) address for a mirror, or "" for the default
+fn base_url(value: &Value) -> Result<String> {
+    let url = value.as_str()?.trim();
+    if !url.is_empty() && !url.starts_with("https://") && !url.starts_with("http://") {
+        bail!("expected an http:// or https:// address, got \"{}\"", url);
+    }
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_forms_with_comments_and_lines() {
+        let forms = read("; comment\n(set! a 1)\n\n'(b \"c\" #t)").unwrap();
+        assert_eq!(forms.len(), 2);
+        assert_eq!(forms[0].1, 2);
+        assert_eq!(forms[1].1, 4);
+        assert_eq!(
+            forms[1].0,
+            Value::List(vec![
+                Value::Symbol("quote".to_string()),
+                Value::List(vec![
+                    Value::Symbol("b".to_string()),
+                    Value::Str("c".to_string()),
+                    Value::Bool(true),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_read_unclosed_list() {
+        assert!(read("(set! a").is_err());
+    }
+
+    #[test]
+    fn test_eval_refresh_settings() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source(
+                "(set! auto-refresh-interval 300)\n(set-refresh-interval 'notifications 60)\n(set-refresh-interval 'topic-detail 120)",
+            )
+            .unwrap();
+        assert_eq!(engine.config.auto_refresh_interval, 300);
+        assert_eq!(engine.config.refresh_intervals.notifications, Some(60));
+        assert_eq!(engine.config.refresh_intervals.topic_list, None);
+        assert_eq!(engine.config.refresh_intervals.topic_detail, Some(120));
+    }
+
+    #[test]
+    fn test_eval_boolean_setting() {
+        let mut engine = Engine::new(Config::default());
+        engine.eval_source("(set! hide-read-topics #t)").unwrap();
+        assert!(engine.config.hide_read_topics);
+        assert!(engine.eval_source("(set! hide-read-topics 1)").is_err());
+    }
+
+    #[test]
+    fn test_eval_string_setting() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(set! tts-command \"espeak -s 160\")")
+            .unwrap();
+        assert_eq!(engine.config.tts_command, "espeak -s 160");
+        assert!(engine.eval_source("(set! tts-command #t)").is_err());
+    }
+
+    #[test]
+    fn test_eval_retry_settings() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source(
+                "(set! retry-max-attempts 5)\n(set! retry-base-delay 200)\n(set! retry-jitter #f)",
+            )
+            .unwrap();
+        assert_eq!(engine.config.retry.max_attempts, 5);
+        assert_eq!(engine.config.retry.base_delay, Duration::from_millis(200));
+        assert!(!engine.config.retry.jitter);
+        assert!(engine.eval_source("(set! retry-max-attempts 0)").is_err());
+    }
+
+    #[test]
+    fn test_eval_request_etiquette() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(set! user-agent \"mirror-reader/1.0 (admin@example.org)\")\n(set! request-delay 750)")
+            .unwrap();
+        assert_eq!(
+            engine.config.user_agent,
+            "mirror-reader/1.0 (admin@example.org)"
+        );
+        assert_eq!(engine.config.request_delay, Duration::from_millis(750));
+        assert!(engine
+            .eval_source("(set! user-agent \"two\nlines\")")
+            .is_err());
+        assert!(engine.eval_source("(set! request-delay -1)").is_err());
+    }
+
+    #[test]
+    fn test_eval_base_urls() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source(
+                "(set! api-base-url \"https://mirror.example.org/api/\")\n(set! site-base-url \"http://localhost:8080\")",
+            )
+            .unwrap();
+        assert_eq!(
+            engine.config.api_base_url,
+            "https://mirror.example.org/api/"
+        );
+        assert_eq!(engine.config.site_base_url, "http://localhost:8080");
+        assert!(engine
+            .eval_source("(set! api-base-url \"mirror.example.org\")")
+            .is_err());
+    }
+
+    #[test]
+    fn test_eval_theme() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(set! theme 'solarized)\n(set-theme-color 'accent \"#b58900\")")
+            .unwrap();
+        assert_eq!(engine.config.theme, ThemePreset::Solarized);
+        assert!(engine.config.custom_theme.accent.is_some());
+        assert!(engine.eval_source("(set! theme \"dark\")").is_err());
+        assert!(engine
+            .eval_source("(set-theme-color 'accent \"teal\")")
+            .is_err());
+    }
+
+    #[test]
+    fn test_eval_offline() {
+        let mut engine = Engine::new(Config::default());
+        engine.eval_source("(offline t)").unwrap();
+        assert!(engine.config.offline);
+        assert!(engine.eval_source("(offline)").is_err());
+    }
+
+    #[test]
+    fn test_eval_split_layout() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(set! split-ratio 55)\n(set! split-swapped #t)")
+            .unwrap();
+        assert_eq!(engine.config.split_layout.topic_percent, 55);
+        assert!(engine.config.split_layout.swapped);
+        assert!(!engine.config.split_layout.stacked);
+        assert!(engine.eval_source("(set! split-ratio 90)").is_err());
+    }
+
+    #[test]
+    fn test_eval_favorite_nodes() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(set! favorite-nodes '(qna \"apple\"))\n(set-quick-node-key \"0\" 'rust)")
+            .unwrap();
+        assert_eq!(engine.config.favorite_nodes, ["qna", "apple"]);
+        assert_eq!(engine.config.quick_node_keys, [('0', "rust".to_string())]);
+        assert!(engine.eval_source("(set! favorite-nodes 'qna)").is_err());
+        assert!(engine
+            .eval_source("(set-quick-node-key \"10\" 'rust)")
+            .is_err());
+    }
+
+    #[test]
+    fn test_eval_aggregate_prefetch_tabs() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(set! aggregate-prefetch-tabs '(hot \"tech\"))")
+            .unwrap();
+        assert_eq!(engine.config.aggregate_prefetch_tabs, ["hot", "tech"]);
+        assert!(engine
+            .eval_source("(set! aggregate-prefetch-tabs '(python))")
+            .is_err());
+    }
+
+    #[test]
+    fn test_eval_topic_list_columns() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(set! topic-list-columns '(unread title author last-touched))")
+            .unwrap();
+        assert_eq!(
+            engine.config.topic_columns(),
+            [
+                TopicColumn::Unread,
+                TopicColumn::Title,
+                TopicColumn::Author,
+                TopicColumn::LastTouched
+            ]
+        );
+        assert!(engine
+            .eval_source("(set! topic-list-columns '(node replies))")
+            .is_err());
+        assert!(engine
+            .eval_source("(set! topic-list-columns '(title title))")
+            .is_err());
+        assert!(engine
+            .eval_source("(set! topic-list-columns '(title votes))")
+            .is_err());
+    }
+
+    #[test]
+    fn test_eval_add_feed() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source(
+                "(add-feed \"w\" 'rust \"/feed/rust.xml\")\n\
+                 (add-feed \"W\" \"planet\" \"https://example.com/atom.xml\")\n\
+                 (add-feed \"R\" 'rust \"/feed/rust.xml\")\n\
+                 (set! aggregate-prefetch-tabs '(rust))",
+            )
+            .unwrap();
+        let tabs: Vec<_> = engine
+            .config
+            .custom_feeds
+            .iter()
+            .map(|feed| (feed.key, feed.name.as_str()))
+            .collect();
+        assert_eq!(tabs, [('W', "planet"), ('R', "rust")]);
+        assert_eq!(engine.config.aggregate_prefetch_tabs, ["rust"]);
+        // Keys of the view and names of built-in tabs are taken
+        assert!(engine
+            .eval_source("(add-feed \"n\" 'x \"/x.xml\")")
+            .is_err());
+        assert!(engine
+            .eval_source("(add-feed \"t\" 'x \"/x.xml\")")
+            .is_err());
+        assert!(engine
+            .eval_source("(add-feed \"w\" 'hot \"/x.xml\")")
+            .is_err());
+        assert!(engine.eval_source("(add-feed \"w\" 'x \"\")").is_err());
+        // So are keys every view shares and those bound with define-key,
+        // whichever comes first
+        assert!(engine
+            .eval_source("(add-feed \"K\" 'x \"/x.xml\")")
+            .is_err());
+        engine
+            .eval_source("(define-key 'aggregate \"j\" 'next)")
+            .unwrap();
+        assert!(engine
+            .eval_source("(add-feed \"j\" 'x \"/x.xml\")")
+            .is_err());
+        assert!(engine
+            .eval_source("(define-key 'global \"R q\" 'quit)")
+            .is_err());
+        engine
+            .eval_source("(define-key 'topic-list \"R\" 'next)")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_eval_ignore_user() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(ignore-user \"Spammer\" \"troll\")")
+            .unwrap();
+        assert!(engine.config.ignored_users.contains("spammer"));
+        assert!(engine.config.ignored_users.contains("Troll"));
+        assert!(!engine.config.ignored_users.contains("alice"));
+        assert!(engine.eval_source("(ignore-user)").is_err());
+    }
+
+    #[test]
+    fn test_eval_define_key() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(define-key 'topic-detail \"C-x r\" 'compose-reply)\n(define-key 'global \"j\" \"n\")")
+            .unwrap();
+        let keys = crate::keymap::parse_key_sequence("C-x r").unwrap();
+        assert_eq!(
+            engine
+                .config
+                .key_bindings
+                .lookup(View::TopicDetail, false, &keys),
+            crate::keymap::BindingMatch::Bound(crate::keymap::Action::Keys(
+                crate::keymap::parse_key_sequence("c").unwrap()
+            ))
+        );
+        assert!(engine
+            .eval_source("(define-key 'nowhere \"j\" 'next)")
+            .is_err());
+        assert!(engine.eval_source("(define-key 'global \"j\" 1)").is_err());
+    }
+
+    #[test]
+    fn test_hooks_run_later() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source(
+                "(on-startup (open-node 'rust))\n(on-topic-open (if (equal topic-node \"qna\") (message (concat \"Q&A \" topic-id)) (run-action 'toggle-replies)))",
+            )
+            .unwrap();
+        let hooks = &engine.config.hooks;
+        assert_eq!(
+            Engine::run_hook(&hooks.startup, HashMap::new(), &engine.config.actions).unwrap(),
+            [HookEffect::OpenNode("rust".to_string())]
+        );
+
+        let vars = |node: &str| {
+            HashMap::from([
+                ("topic-id".to_string(), Value::Int(42)),
+                ("topic-node".to_string(), Value::Str(node.to_string())),
+            ])
+        };
+        assert_eq!(
+            Engine::run_hook(&hooks.topic_open, vars("qna"), &engine.config.actions).unwrap(),
+            [HookEffect::Message("Q&A 42".to_string())]
+        );
+        assert_eq!(
+            Engine::run_hook(&hooks.topic_open, vars("rust"), &engine.config.actions).unwrap(),
+            [HookEffect::Action("toggle-replies".to_string())]
+        );
+
+        let forms: Vec<Value> =
+            read("(if (equal topic-node 'qna) (message \"yes\") (message \"no\"))")
+                .unwrap()
+                .into_iter()
+                .map(|(form, _)| form)
+                .collect();
+        assert_eq!(
+            Engine::run_hook(&forms, vars("qna"), &engine.config.actions).unwrap(),
+            [HookEffect::Message("yes".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_hook_errors() {
+        let mut engine = Engine::new(Config::default());
+        assert!(engine.eval_source("(message \"hi\")").is_err());
+        let run = |source: &str| {
+            let forms: Vec<Value> = read(source).unwrap().into_iter().map(|(f, _)| f).collect();
+            Engine::run_hook(&forms, HashMap::new(), &ActionRegistry::default())
+        };
+        assert!(run("(set! focus-width 60)").is_err());
+        assert!(run("(offline t)").is_err());
+        assert!(run("(run-action 'no-such-action)").is_err());
+        assert!(run("(message topic-title)").is_err());
+    }
+
+    #[test]
+    fn test_define_action() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source(
+                "(define-action 'open-first 'first 'open)\n(define-action 'greet (lambda () (message \"hi\")) 'open-first)\n(define-key 'topic-list \"C-c g\" 'greet)",
+            )
+            .unwrap();
+        let actions = &engine.config.actions;
+        let forms = actions.get("greet").unwrap();
+        assert_eq!(
+            Engine::run_hook(forms, HashMap::new(), actions).unwrap(),
+            [
+                HookEffect::Message("hi".to_string()),
+                HookEffect::Action("first".to_string()),
+                HookEffect::Action("open".to_string()),
+            ]
+        );
+        let keys = crate::keymap::parse_key_sequence("C-c g").unwrap();
+        assert_eq!(
+            engine
+                .config
+                .key_bindings
+                .lookup(View::TopicList, false, &keys),
+            crate::keymap::BindingMatch::Bound(crate::keymap::Action::Custom("greet".to_string()))
+        );
+
+        assert!(engine.eval_source("(define-action 'next 'open)").is_err());
+        assert!(engine.eval_source("(define-action 'x 'no-such)").is_err());
+        assert!(engine
+            .eval_source("(define-action 'x (lambda (a) (message a)))")
+            .is_err());
+    }
+
+    #[test]
+    fn test_custom_action_running_itself_fails() {
+        let mut engine = Engine::new(Config::default());
+        engine
+            .eval_source("(define-action 'again (lambda () (run-action 'again)))")
+            .unwrap();
+        let actions = &engine.config.actions;
+        let error =
+            Engine::run_hook(actions.get("again").unwrap(), HashMap::new(), actions).unwrap_err();
+        assert!(error.to_string().contains("keeps running itself"));
+    }
+
+    #[test]
+    fn test_eval_reports_line_of_error() {
+        let mut engine = Engine::new(Config::default());
+        let err = engine
+            .eval_source("(set! auto-refresh-interval 10)\n(set! nex-topic 1)")
+            .unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::engine::Engine;
+use super::Config;
+
+/// Default configuration written on first run
+pub const DEFAULT_CONFIG: &str = include_str!("../../config/default.lisp");
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(crate::api::V2exClient::config_dir()?.join("config.lisp"))
+}
+
+/// Load the user configuration, creating it from the defaults on first run.
+///
+/// Never fails: on error the defaults are returned together with a message
+/// suitable for the status bar.
+pub fn load_config() -> (Config, Option<String>) {
+    match try_load_config() {
+        Ok(result) => result,
+        Err(e) => (Config::default(), Some(format!("{:#}", e))),
+    }
+}
+
+/// Evaluate the config file again after it was edited. Unlike
+/// [`load_config`] errors are returned, so the settings in use can be kept.
+pub fn reload_config() -> Result<Config> {
+    eval_config_file(&config_path()?)
+}
+
+fn try_load_config() -> Result<(Config, Option<String>)> {
+    let path = config_path()?;
+    let mut message = None;
+
+    if !path.exists() {
+        std::fs::write(&path, DEFAULT_CONFIG)
+            .with_context(|| format!("Failed to write default config to {:?}", path))?;
+        message = Some(format!(
+            "Created default configuration at {}",
+            path.display()
+        ));
+    }
+
+    Ok((eval_config_file(&path)?, message))
+}
+
+fn eval_config_file(path: &Path) -> Result<Config> {
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config from {:?}", path))?;
+    let mut engine = Engine::new(Config::default());
+    engine.eval_source(&source)?;
+    Ok(engine.config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_evaluates() {
+        let mut engine = Engine::new(Config::default());
+        engine.eval_source(DEFAULT_CONFIG).unwrap();
+    }
+
+    #[test]
+    fn test_example_configs_evaluate() {
+        for source in [
+            include_str!("../../docs/examples/basic-config.lisp"),
+            include_str!("../../docs/examples/advanced-config.lisp"),
+        ] {
+            let mut engine = Engine::new(Config::default());
+            engine.eval_source(source).unwrap();
+        }
+    }
+}
@@ -0,0 +1,683 @@
+//! User configuration loaded from `~/.config/v2ex/config.lisp`
+
+pub mod engine;
+pub mod loader;
+pub mod watcher;
+
+use anyhow::{bail, Result};
+use ratatui::style::Color;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::app::View;
+use crate::keymap::KeyBindings;
+use crate::retry::RetryPolicy;
+use crate::views::aggregate::AGGREGATE_TABS;
+
+pub use loader::{load_config, reload_config};
+
+/// Views that can be refreshed periodically
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefreshTarget {
+    Notifications,
+    TopicList,
+    Aggregate,
+    /// The open topic's reply count and page counters
+    TopicDetail,
+}
+
+impl RefreshTarget {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "notifications" => Ok(Self::Notifications),
+            "topic-list" => Ok(Self::TopicList),
+            "aggregate" => Ok(Self::Aggregate),
+            "topic-detail" => Ok(Self::TopicDetail),
+            _ => bail!(
+                "unknown refresh target '{}' (expected notifications, topic-list, aggregate or topic-detail)",
+                name
+            ),
+        }
+    }
+
+    pub fn from_view(view: View) -> Option<Self> {
+        match view {
+            View::Notifications => Some(Self::Notifications),
+            View::TopicList => Some(Self::TopicList),
+            View::Aggregate => Some(Self::Aggregate),
+            View::TopicDetail => Some(Self::TopicDetail),
+            _ => None,
+        }
+    }
+}
+
+/// Name of `view` in `define-key` scopes and `{view}` of `status-format`
+pub fn view_name(view: View) -> &'static str {
+    match view {
+        View::TopicList => "topic-list",
+        View::TopicDetail => "topic-detail",
+        View::Notifications => "notifications",
+        View::Profile => "profile",
+        View::Help => "help",
+        View::NodeSelect => "node-select",
+        View::TokenInput => "token-input",
+        View::Aggregate => "aggregate",
+        View::ReplyInput => "reply-input",
+        View::Favorites => "favorites",
+        View::Member => "member",
+        View::History => "history",
+        View::Bookmarks => "bookmarks",
+        View::Tokens => "tokens",
+    }
+}
+
+/// View named by a `define-key` scope; `None` for `global`
+pub fn view_from_name(name: &str) -> Result<Option<View>> {
+    Ok(Some(match name {
+        "global" => return Ok(None),
+        "topic-list" => View::TopicList,
+        "topic-detail" => View::TopicDetail,
+        "notifications" => View::Notifications,
+        "profile" => View::Profile,
+        "help" => View::Help,
+        "node-select" => View::NodeSelect,
+        "aggregate" => View::Aggregate,
+        "reply-input" => View::ReplyInput,
+        "favorites" => View::Favorites,
+        "member" => View::Member,
+        "history" => View::History,
+        "bookmarks" => View::Bookmarks,
+        "tokens" => View::Tokens,
+        _ => bail!("unknown keymap '{}'", name),
+    }))
+}
+
+/// Per-view overrides of `auto_refresh_interval`, in seconds (0 disables)
+#[derive(Debug, Clone, Default)]
+pub struct RefreshIntervals {
+    pub notifications: Option<u64>,
+    pub topic_list: Option<u64>,
+    pub aggregate: Option<u64>,
+    pub topic_detail: Option<u64>,
+}
+
+impl RefreshIntervals {
+    pub fn set(&mut self, target: RefreshTarget, seconds: u64) {
+        match target {
+            RefreshTarget::Notifications => self.notifications = Some(seconds),
+            RefreshTarget::TopicList => self.topic_list = Some(seconds),
+            RefreshTarget::Aggregate => self.aggregate = Some(seconds),
+            RefreshTarget::TopicDetail => self.topic_detail = Some(seconds),
+        }
+    }
+
+    pub fn get(&self, target: RefreshTarget) -> Option<u64> {
+        match target {
+            RefreshTarget::Notifications => self.notifications,
+            RefreshTarget::TopicList => self.topic_list,
+            RefreshTarget::Aggregate => self.aggregate,
+            RefreshTarget::TopicDetail => self.topic_detail,
+        }
+    }
+}
+
+/// Members whose topics and replies are collapsed, matched case-insensitively
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreList(HashSet<String>);
+
+impl IgnoreList {
+    pub fn add(&mut self, username: &str) {
+        self.0.insert(username.to_lowercase());
+    }
+
+    pub fn contains(&self, username: &str) -> bool {
+        self.0.contains(&username.to_lowercase())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Virtual node listing the newest topics of all favorite nodes together,
+/// on key 0 unless `set-quick-node-key` rebinds it
+pub const ALL_FAVORITES_NODE: &str = "*favorites*";
+
+/// Nodes in the topic list header when `favorite-nodes` is not set
+pub const DEFAULT_FAVORITE_NODES: [&str; 9] = [
+    "python",
+    "programmer",
+    "share",
+    "create",
+    "jobs",
+    "go",
+    "rust",
+    "javascript",
+    "linux",
+];
+
+/// Built-in colour schemes selected with `(set! theme '...)`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    Solarized,
+    Gruvbox,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 4] = [Self::Dark, Self::Light, Self::Solarized, Self::Gruvbox];
+
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "dark" => Ok(Self::Dark),
+            "light" => Ok(Self::Light),
+            "solarized" => Ok(Self::Solarized),
+            "gruvbox" => Ok(Self::Gruvbox),
+            _ => bail!(
+                "unknown theme '{}' (expected dark, light, solarized or gruvbox)",
+                name
+            ),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::Solarized => "solarized",
+            Self::Gruvbox => "gruvbox",
+        }
+    }
+
+    /// The preset after this one, wrapping around
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// How large counts are shortened, set with `(set! count-format '...)`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CountFormat {
+    /// Thousands and millions: `1.2k`, `3.4M`
+    #[default]
+    Western,
+    /// 万 and 亿: `1.2万`, `3.4亿`
+    Chinese,
+}
+
+impl CountFormat {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "western" => Ok(Self::Western),
+            "chinese" => Ok(Self::Chinese),
+            _ => bail!(
+                "unknown count-format '{}' (expected western or chinese)",
+                name
+            ),
+        }
+    }
+}
+
+/// A column of the topic list, set in order with
+/// `(set! topic-list-columns '...)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TopicColumn {
+    /// `▲` / `△` for threads replied to in the last hour / today
+    Heat,
+    /// `●` for topics not opened yet
+    Unread,
+    Node,
+    /// The title and the `+N` new replies badge, taking the width left over
+    Title,
+    Author,
+    Replies,
+    /// When the topic was posted
+    Created,
+    /// When the topic was last replied to
+    LastTouched,
+}
+
+impl TopicColumn {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "heat" => Ok(Self::Heat),
+            "unread" => Ok(Self::Unread),
+            "node" => Ok(Self::Node),
+            "title" => Ok(Self::Title),
+            "author" => Ok(Self::Author),
+            "replies" => Ok(Self::Replies),
+            "created" => Ok(Self::Created),
+            "last-touched" => Ok(Self::LastTouched),
+            _ => bail!(
+                "unknown topic list column '{}' (expected heat, unread, node, title, \
+                 author, replies, created or last-touched)",
+                name
+            ),
+        }
+    }
+}
+
+/// Topic list columns when `topic-list-columns` is not set
+pub const DEFAULT_TOPIC_COLUMNS: [TopicColumn; 5] = [
+    TopicColumn::Heat,
+    TopicColumn::Node,
+    TopicColumn::Title,
+    TopicColumn::Replies,
+    TopicColumn::Created,
+];
+
+/// Theme colours overridden with `set-theme-color`, applied on top of
+/// whichever preset is active
+#[derive(Debug, Clone, Default)]
+pub struct CustomTheme {
+    pub primary: Option<Color>,
+    pub secondary: Option<Color>,
+    pub accent: Option<Color>,
+    pub background: Option<Color>,
+    pub foreground: Option<Color>,
+    pub muted: Option<Color>,
+    pub error: Option<Color>,
+    pub canvas: Option<Color>,
+    pub op_badge: Option<Color>,
+    pub thanks: Option<Color>,
+    pub selection: Option<Color>,
+    pub selection_text: Option<Color>,
+}
+
+impl CustomTheme {
+    /// Override one colour, given as `#rrggbb` or a name such as `light-blue`
+    pub fn set(&mut self, slot: &str, color: &str) -> Result<()> {
+        let color = crate::ui::parse_color(color)?;
+        let target = match slot {
+            "primary" => &mut self.primary,
+            "secondary" => &mut self.secondary,
+            "accent" => &mut self.accent,
+            "background" => &mut self.background,
+            "foreground" => &mut self.foreground,
+            "muted" => &mut self.muted,
+            "error" => &mut self.error,
+            "canvas" => &mut self.canvas,
+            "op-badge" => &mut self.op_badge,
+            "thanks" => &mut self.thanks,
+            "selection" => &mut self.selection,
+            "selection-text" => &mut self.selection_text,
+            _ => bail!(
+                "unknown theme color '{}' (expected primary, secondary, accent, background, foreground, muted, error, canvas, op-badge, thanks, selection or selection-text)",
+                slot
+            ),
+        };
+        *target = Some(color);
+        Ok(())
+    }
+}
+
+/// A feed added with `add-feed`, shown as an extra aggregate tab
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomFeed {
+    pub key: char,
+    pub name: String,
+    /// Absolute URL, or a path such as `/feed/tab/creative.xml` on the site
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Default auto-refresh interval in seconds (0 = off)
+    pub auto_refresh_interval: u64,
+    pub refresh_intervals: RefreshIntervals,
+    /// Drop topics already opened when loading topic lists
+    pub hide_read_topics: bool,
+    /// Wrap long topic titles onto a second line instead of cutting them off
+    pub wrap_topic_titles: bool,
+    /// Columns set with `topic-list-columns` (empty = `DEFAULT_TOPIC_COLUMNS`)
+    pub topic_list_columns: Vec<TopicColumn>,
+    /// Lines of content the SPC preview in the topic list shows
+    pub topic_preview_lines: u64,
+    /// Load page 1 of the quick-switch nodes in the background at startup
+    pub prefetch_favorite_nodes: bool,
+    /// Fetch the next page of topics or replies once the selection is this
+    /// many items from the end (0 = only when n reaches the end)
+    pub load_ahead: u64,
+    /// Seconds a fetched aggregate tab is shown again instead of fetching
+    /// it (0 = always fetch)
+    pub aggregate_cache_ttl: u64,
+    /// Aggregate tabs fetched in the background along with the one shown
+    /// (empty = the tabs next to it)
+    pub aggregate_prefetch_tabs: Vec<String>,
+    /// Look up the topics of RSS items on screen for their nodes and last
+    /// reply times
+    pub aggregate_resolve_topics: bool,
+    /// Check for new notifications every N seconds in the background (0 = off)
+    pub notification_poll_interval: u64,
+    /// Pop up a desktop notification when the poller finds new notifications
+    pub desktop_notifications: bool,
+    /// Bindings added with `define-key`
+    pub key_bindings: KeyBindings,
+    /// Milliseconds to wait for the next key of a sequence (0 = forever)
+    pub key_sequence_timeout: u64,
+    /// Plain ASCII, uncoloured output and throttled redraws for slow links
+    pub low_bandwidth: bool,
+    /// Shell command reading text from stdin aloud (empty = disabled)
+    pub tts_command: String,
+    /// Shell command that prints a translation of the text on its stdin
+    pub translate_command: String,
+    /// Look up titles of GitHub, YouTube and Twitter links to show in link mode
+    pub unfurl_links: bool,
+    /// Maximum text width in focus mode (0 = full width)
+    pub focus_width: u64,
+    /// Users added with `ignore-user`
+    pub ignored_users: IgnoreList,
+    /// Save a Markdown copy of topics when favoriting them here (empty = off)
+    pub favorite_archive_dir: String,
+    /// Where `export-topic` writes, with `{id}`, `{node}` and `{title}`
+    /// filled in; `.html` exports HTML, anything else Markdown
+    pub export_path: String,
+    /// Status bar text, with `{message}`, `{view}`, `{node}`, `{page}`,
+    /// `{unread}` and `{time}` filled in (empty = just the message)
+    pub status_format: String,
+    /// How reply, click and topic counts are shortened
+    pub count_format: CountFormat,
+    /// Colour scheme at startup; C-t cycles through the others
+    pub theme: ThemePreset,
+    /// Colours set with `set-theme-color`
+    pub custom_theme: CustomTheme,
+    /// Never touch the network; only archived favorites and cached topics can be read
+    pub offline: bool,
+    /// Retries of API requests that failed with network or server errors
+    pub retry: RetryPolicy,
+    /// User-Agent sent with every request (empty = the built-in one)
+    pub user_agent: String,
+    /// Least time between any two requests (zero = no pacing)
+    pub request_delay: Duration,
+    /// Where the API is served, for mirrors (empty = www.v2ex.com)
+    pub api_base_url: String,
+    /// Where the RSS feeds and topic pages are served (empty = www.v2ex.com)
+    pub site_base_url: String,
+    /// Topic detail pane sizes and arrangement at startup
+    pub split_layout: crate::ui::SplitLayout,
+    /// Node names set with `favorite-nodes` (empty = `DEFAULT_FAVORITE_NODES`)
+    pub favorite_nodes: Vec<String>,
+    /// Keys added with `set-quick-node-key`, on top of 1-9 for the favorites
+    pub quick_node_keys: Vec<(char, String)>,
+    /// Feeds added with `add-feed`, after the built-in aggregate tabs
+    pub custom_feeds: Vec<CustomFeed>,
+    /// Forms added with `on-startup` and `on-topic-open`
+    pub hooks: engine::Hooks,
+    /// Actions added with `define-action`
+    pub actions: engine::ActionRegistry,
+}
+
+impl Config {
+    /// Effective auto-refresh interval for a view, if refreshing is enabled
+    pub fn refresh_interval_for(&self, view: View) -> Option<Duration> {
+        let target = RefreshTarget::from_view(view)?;
+        let seconds = self
+            .refresh_intervals
+            .get(target)
+            .unwrap_or(self.auto_refresh_interval);
+        (seconds > 0).then(|| Duration::from_secs(seconds))
+    }
+
+    /// Users to collapse in lists, unless `reveal` shows them for now
+    pub fn blocked_users(&self, reveal: bool) -> Option<&IgnoreList> {
+        (!reveal && !self.ignored_users.is_empty()).then_some(&self.ignored_users)
+    }
+
+    /// Favorite nodes as `(name, title)`, titles taken from the node list
+    pub fn favorite_nodes(&self) -> Vec<(String, String)> {
+        let names: Vec<&str> = if self.favorite_nodes.is_empty() {
+            DEFAULT_FAVORITE_NODES.to_vec()
+        } else {
+            self.favorite_nodes.iter().map(String::as_str).collect()
+        };
+        names
+            .into_iter()
+            .map(|name| {
+                let title = crate::nodes::get_all_nodes()
+                    .iter()
+                    .find(|(node, _)| node == name)
+                    .map_or(name, |(_, title)| title.as_str());
+                (name.to_string(), title.to_string())
+            })
+            .collect()
+    }
+
+    /// Keys switching straight to a node in the topic list: 1-9 for the
+    /// first nine favorites and 0 for all of them, then `set-quick-node-key`
+    /// bindings, which win
+    pub fn quick_nodes(&self) -> Vec<(char, String)> {
+        let mut quick: Vec<(char, String)> = ('1'..='9')
+            .zip(self.favorite_nodes())
+            .map(|(key, (name, _))| (key, name))
+            .collect();
+        quick.push(('0', ALL_FAVORITES_NODE.to_string()));
+        for (key, node) in &self.quick_node_keys {
+            match quick.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = node.clone(),
+                None => quick.push((*key, node.clone())),
+            }
+        }
+        quick
+    }
+
+    /// Columns of each topic list row, left to right
+    pub fn topic_columns(&self) -> &[TopicColumn] {
+        if self.topic_list_columns.is_empty() {
+            &DEFAULT_TOPIC_COLUMNS
+        } else {
+            &self.topic_list_columns
+        }
+    }
+
+    /// Aggregate tabs as `(key, tab)`, in header order: the built-in ones,
+    /// then those added with `add-feed`
+    pub fn tab_key_mappings(&self) -> Vec<(char, String)> {
+        AGGREGATE_TABS
+            .iter()
+            .map(|(key, tab)| (*key, tab.to_string()))
+            .chain(
+                self.custom_feeds
+                    .iter()
+                    .map(|feed| (feed.key, feed.name.clone())),
+            )
+            .collect()
+    }
+
+    /// Feed URL of `tab`, if it was added with `add-feed`
+    pub fn feed_url(&self, tab: &str) -> Option<&str> {
+        self.custom_feeds
+            .iter()
+            .find(|feed| feed.name == tab)
+            .map(|feed| feed.url.as_str())
+    }
+
+    /// Directory for favorite archives with `~/` expanded, if archiving is on
+    pub fn favorite_archive_path(&self) -> Option<PathBuf> {
+        let dir = self.favorite_archive_dir.trim();
+        if dir.is_empty() {
+            return None;
+        }
+        expand_home(dir)
+    }
+
+    /// File `export-topic` writes `topic` to, from the `export-path`
+    /// template (`v2ex-{id}.md` when unset)
+    pub fn export_path_for(&self, topic: &crate::api::Topic) -> Option<PathBuf> {
+        let template = match self.export_path.trim() {
+            "" => DEFAULT_EXPORT_PATH,
+            template => template,
+        };
+        // Keep substituted values from adding directories or odd characters
+        let clean = |value: &str| -> String {
+            value
+                .trim()
+                .chars()
+                .map(|c| match c {
+                    '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                    c if c.is_control() => '_',
+                    c => c,
+                })
+                .collect()
+        };
+        let node = topic.node.as_ref().map_or("", |node| node.name.as_str());
+        let path = template
+            .replace("{id}", &topic.id.to_string())
+            .replace("{node}", &clean(node))
+            .replace("{title}", &clean(&topic.title));
+        expand_home(&path)
+    }
+}
+
+/// `export-path` when none is configured: the current directory
+pub const DEFAULT_EXPORT_PATH: &str = "v2ex-{id}.md";
+
+/// `path` with a leading `~/` replaced by the home directory
+fn expand_home(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(rest) => directories::BaseDirs::new().map(|base| base.home_dir().join(rest)),
+        None => Some(PathBuf::from(path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quick_nodes() {
+        let mut config = Config::default();
+        assert_eq!(config.favorite_nodes().len(), 9);
+        assert_eq!(config.quick_nodes()[0], ('1', "python".to_string()));
+
+        config.favorite_nodes = vec!["qna".to_string(), "no-such-node".to_string()];
+        config.quick_node_keys = vec![('2', "apple".to_string()), ('0', "rust".to_string())];
+        assert_eq!(
+            config.favorite_nodes(),
+            vec![
+                ("qna".to_string(), "问与答".to_string()),
+                ("no-such-node".to_string(), "no-such-node".to_string()),
+            ]
+        );
+        assert_eq!(
+            config.quick_nodes(),
+            vec![
+                ('1', "qna".to_string()),
+                ('2', "apple".to_string()),
+                ('0', "rust".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tab_key_mappings() {
+        let mut config = Config::default();
+        assert_eq!(config.tab_key_mappings().len(), AGGREGATE_TABS.len());
+        config.custom_feeds.push(CustomFeed {
+            key: 'w',
+            name: "planet".to_string(),
+            url: "https://example.com/atom.xml".to_string(),
+        });
+        let tabs = config.tab_key_mappings();
+        assert_eq!(tabs[0], ('t', "tech".to_string()));
+        assert_eq!(tabs.last(), Some(&('w', "planet".to_string())));
+        assert_eq!(
+            config.feed_url("planet"),
+            Some("https://example.com/atom.xml")
+        );
+        assert_eq!(config.feed_url("tech"), None);
+    }
+
+    #[test]
+    fn test_refresh_interval_override() {
+        let mut config = Config {
+            auto_refresh_interval: 300,
+            ..Default::default()
+        };
+        config
+            .refresh_intervals
+            .set(RefreshTarget::Notifications, 60);
+        config.refresh_intervals.set(RefreshTarget::Aggregate, 0);
+
+        assert_eq!(
+            config.refresh_interval_for(View::Notifications),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            config.refresh_interval_for(View::TopicList),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(config.refresh_interval_for(View::Aggregate), None);
+        assert_eq!(config.refresh_interval_for(View::Help), None);
+    }
+
+    #[test]
+    fn test_favorite_archive_path() {
+        let mut config = Config::default();
+        assert_eq!(config.favorite_archive_path(), None);
+
+        config.favorite_archive_dir = "/tmp/v2ex".to_string();
+        assert_eq!(
+            config.favorite_archive_path(),
+            Some(PathBuf::from("/tmp/v2ex"))
+        );
+
+        config.favorite_archive_dir = "~/v2ex-archive".to_string();
+        let path = config.favorite_archive_path().unwrap();
+        assert!(path.is_absolute());
+        assert!(path.ends_with("v2ex-archive"));
+    }
+
+    #[test]
+    fn test_view_name_round_trips() {
+        for view in [View::TopicList, View::TopicDetail, View::Bookmarks] {
+            assert_eq!(view_from_name(view_name(view)).unwrap(), Some(view));
+        }
+    }
+
+    #[test]
+    fn test_export_path_for() {
+        let topic: crate::api::Topic = serde_json::from_value(serde_json::json!({
+            "id": 42,
+            "title": "A/B: testing?",
+            "node": { "id": 1, "name": "qna" },
+        }))
+        .unwrap();
+        let mut config = Config::default();
+        assert_eq!(
+            config.export_path_for(&topic),
+            Some(PathBuf::from("v2ex-42.md"))
+        );
+
+        config.export_path = "/tmp/{node}/{id}-{title}.html".to_string();
+        assert_eq!(
+            config.export_path_for(&topic),
+            Some(PathBuf::from("/tmp/qna/42-A_B_ testing_.html"))
+        );
+    }
+
+    #[test]
+    fn test_theme_preset_cycle() {
+        assert_eq!(
+            ThemePreset::from_name("gruvbox").unwrap(),
+            ThemePreset::Gruvbox
+        );
+        assert!(ThemePreset::from_name("neon").is_err());
+        assert_eq!(ThemePreset::Dark.next(), ThemePreset::Light);
+        assert_eq!(ThemePreset::Gruvbox.next(), ThemePreset::Dark);
+    }
+
+    #[test]
+    fn test_custom_theme_set() {
+        let mut custom = CustomTheme::default();
+        custom.set("primary", "#fe8019").unwrap();
+        custom.set("muted", "dark-gray").unwrap();
+        assert_eq!(custom.primary, Some(Color::Rgb(0xfe, 0x80, 0x19)));
+        assert_eq!(custom.muted, Some(Color::DarkGray));
+        assert!(custom.set("border", "red").is_err());
+        assert!(custom.set("primary", "#12345").is_err());
+    }
+}
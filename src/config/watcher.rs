@@ -0,0 +1,105 @@
+//! Noticing edits to `config.lisp` while the app runs, so they can be
+//! applied without a restart.
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// Whether `event` created or changed the file at `path`; other files in
+/// its directory are ignored
+fn edits(event: &notify::Event, path: &Path) -> bool {
+    let edited = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+    edited
+        && event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name() == path.file_name())
+}
+
+/// Watches the config file; dropping it stops watching
+pub struct ConfigWatcher {
+    _watcher: notify::RecommendedWatcher,
+    changes: mpsc::UnboundedReceiver<()>,
+}
+
+impl std::fmt::Debug for ConfigWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigWatcher").finish_non_exhaustive()
+    }
+}
+
+impl ConfigWatcher {
+    /// Watch the directory holding `path` rather than the file itself, since
+    /// editors often save by writing a new file and renaming it over the old
+    pub fn new(path: PathBuf) -> Result<Self> {
+        let dir = path
+            .parent()
+            .context("Config file has no parent directory")?
+            .to_path_buf();
+        let (tx, changes) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if event.is_ok_and(|event| edits(&event, &path)) {
+                    let _ = tx.send(());
+                }
+            })
+            .context("Failed to start watching the config file")?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {:?}", dir))?;
+        Ok(Self {
+            _watcher: watcher,
+            changes,
+        })
+    }
+
+    /// Whether the file changed since the last call. A save often arrives
+    /// as several events; they are all taken at once.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        while self.changes.try_recv().is_ok() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, ModifyKind, RemoveKind};
+
+    fn event(kind: EventKind, path: &str) -> notify::Event {
+        notify::Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_only_edits_of_the_config_file_count() {
+        let path = Path::new("/home/user/.config/v2ex/config.lisp");
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+        assert!(edits(
+            &event(modify, "/home/user/.config/v2ex/config.lisp"),
+            path
+        ));
+        // Saved through a temp file renamed over the old one
+        assert!(edits(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/home/user/.config/v2ex/config.lisp"
+            ),
+            path
+        ));
+        assert!(!edits(
+            &event(modify, "/home/user/.config/v2ex/other.lisp"),
+            path
+        ));
+        assert!(!edits(
+            &event(
+                EventKind::Remove(RemoveKind::File),
+                "/home/user/.config/v2ex/config.lisp"
+            ),
+            path
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+//! Topic lists and topics saved under `~/.cache/v2ex` as they are loaded,
+//! and served instead when the network is unreachable or in offline mode.
+//! Titles found by link unfurling are kept there too.
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::api::{Reply, Topic};
+use crate::archive::ArchivedTopic;
+use crate::json_store;
+
+/// A cached value and when it was saved
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cached<T> {
+    /// Unix timestamp (seconds)
+    pub saved_at: i64,
+    pub value: T,
+}
+
+#[derive(Debug, Default)]
+pub struct DiskCache {
+    /// Where entries live; `None` disables the cache
+    dir: Option<PathBuf>,
+}
+
+impl DiskCache {
+    pub fn open() -> Result<Self> {
+        let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
+        Ok(Self {
+            dir: Some(base_dirs.cache_dir().join("v2ex")),
+        })
+    }
+
+    /// First page of a node's topic list
+    pub fn save_topics(&self, node: &str, topics: &[Topic]) -> Result<()> {
+        self.write(&format!("nodes/{}.json", file_stem(node)), &topics)
+    }
+
+    pub fn load_topics(&self, node: &str) -> Option<Cached<Vec<Topic>>> {
+        self.read(&format!("nodes/{}.json", file_stem(node)))
+    }
+
+    /// A topic with the replies loaded so far
+    pub fn save_topic(&self, topic: &Topic, replies: &[Reply]) -> Result<()> {
+        self.write(
+            &format!("topics/{}.json", topic.id),
+            &ArchivedTopic {
+                topic: topic.clone(),
+                replies: replies.to_vec(),
+            },
+        )
+    }
+
+    pub fn load_topic(&self, topic_id: i64) -> Option<Cached<ArchivedTopic>> {
+        self.read(&format!("topics/{}.json", topic_id))
+    }
+
+    /// Titles found by link unfurling, by URL
+    pub fn save_link_titles(&self, titles: &HashMap<String, String>) -> Result<()> {
+        self.write("link-titles.json", titles)
+    }
+
+    pub fn load_link_titles(&self) -> Option<Cached<HashMap<String, String>>> {
+        self.read("link-titles.json")
+    }
+
+    fn write<T: Serialize>(&self, name: &str, value: &T) -> Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let cached = Cached {
+            saved_at: chrono::Utc::now().timestamp(),
+            value,
+        };
+        json_store::save(&dir.join(name), &cached)
+    }
+
+    /// A missing or unreadable entry is simply not cached
+    fn read<T: DeserializeOwned>(&self, name: &str) -> Option<Cached<T>> {
+        json_store::load(&self.dir.as_ref()?.join(name)).ok()?
+    }
+}
+
+/// Node names are plain identifiers, but keep anything else out of the path
+fn file_stem(node: &str) -> String {
+    node.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("v2ex-cache-test-{}", std::process::id()));
+        let cache = DiskCache {
+            dir: Some(dir.clone()),
+        };
+        let topic: Topic =
+            serde_json::from_str(r#"{"id": 7, "title": "Cached", "created": 100}"#).unwrap();
+
+        assert!(cache.load_topics("python").is_none());
+        cache
+            .save_topics("python", std::slice::from_ref(&topic))
+            .unwrap();
+        cache.save_topic(&topic, &[]).unwrap();
+
+        let topics = cache.load_topics("python").unwrap();
+        assert_eq!(topics.value[0].title, "Cached");
+        assert!(topics.saved_at > 0);
+        assert_eq!(cache.load_topic(7).unwrap().value.topic.id, 7);
+        assert!(cache.load_topic(8).is_none());
+        assert_eq!(file_stem("../etc"), "___etc");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
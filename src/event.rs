@@ -0,0 +1,381 @@
+//! The main loop as messages. Input, timers and the results of background
+//! work all arrive as a [`Msg`], and [`Runtime::update`] is the one place
+//! they change the [`App`], so the loop itself only waits, draws and hands
+//! messages over.
+
+use anyhow::Result;
+use crossterm::event::{Event, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
+use std::time::Instant;
+
+use crate::api::{Notification, Topic, V2exClient};
+use crate::app::{App, ReplyOutcome, View};
+use crate::keymap::EventHandler;
+use crate::loads::Finished;
+use crate::prefetch::{AggregateTabs, LoadedPage, NextPage};
+use crate::retry::RetryNotice;
+use crate::scheduler::RateLimit;
+use crate::state::HistoryEntry;
+
+/// Something that happened, for [`Runtime::update`] to apply
+#[derive(Debug)]
+pub enum Msg {
+    Key(KeyEvent),
+    /// A click or wheel turn
+    Mouse(MouseEvent),
+    /// The terminal changed size
+    Resize,
+    /// A turn of the loop, which may expire an unfinished key sequence
+    Tick,
+    /// Config hooks queued actions to run
+    QueuedActions,
+    /// `config.lisp` was edited
+    ConfigChanged,
+    /// A load the user started, or auto-refresh, finished
+    Loaded(Box<Finished>),
+    /// Aggregate tabs fetched ahead of switching to them arrived
+    AggregatePrefetched(AggregateTabs),
+    /// A reply finished posting
+    ReplyPosted(Box<ReplyOutcome>),
+    /// The poller fetched the first page of notifications
+    NotificationsPolled(Vec<Notification>),
+    /// The startup prefetch fetched a node's first page
+    Prefetched(String, Vec<Topic>),
+    /// The next page of the topic or reply list arrived ahead of time
+    LoadedAhead(NextPage, anyhow::Result<LoadedPage>),
+    /// The API budget changed
+    RateLimit(Option<RateLimit>),
+    /// A request is waiting to be retried, or stopped waiting
+    Retry(Option<RetryNotice>),
+    /// The auto-refresh interval of the current view passed
+    RefreshDue,
+    /// The rate limit a failed load hit has reset
+    RetryDue,
+    /// The history browser went to an entry that must be loaded again
+    ReloadHistory(HistoryEntry),
+    /// The reply editor asked for `$EDITOR`
+    EditReply,
+    /// `translate-command` finished, with what was translated
+    Translated(String, anyhow::Result<String>),
+    /// Link unfurling looked up the title of a link
+    LinkTitle(String, anyhow::Result<String>),
+    /// The topic of an aggregate feed item was looked up
+    FeedTopic(Box<Topic>),
+}
+
+impl Msg {
+    /// The message for a terminal event, if it matters
+    pub fn from_event(event: Event) -> Option<Self> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some(Msg::Key(key)),
+            // Pointer motion alone changes nothing on screen
+            Event::Mouse(mouse) if mouse.kind != MouseEventKind::Moved => Some(Msg::Mouse(mouse)),
+            Event::Resize(_, _) => Some(Msg::Resize),
+            _ => None,
+        }
+    }
+}
+
+/// What the loop does after a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Nothing on screen changed
+    Idle,
+    Redraw,
+    /// Suspend the TUI to edit the reply draft in `$EDITOR`
+    EditReply,
+    Quit,
+}
+
+fn quit_or_redraw(quit: bool) -> Flow {
+    if quit {
+        Flow::Quit
+    } else {
+        Flow::Redraw
+    }
+}
+
+/// The app, with the key handling and timers messages go through
+pub struct Runtime<'a> {
+    pub app: App,
+    events: EventHandler<'a>,
+    client: &'a V2exClient,
+    /// Auto-refresh yields to requests the user is waiting for
+    background: V2exClient,
+    last_refresh: Instant,
+    last_view: View,
+}
+
+impl<'a> Runtime<'a> {
+    pub fn new(app: App, client: &'a V2exClient) -> Self {
+        Self {
+            last_view: app.view,
+            app,
+            events: EventHandler::new(client),
+            client,
+            background: client.background(),
+            last_refresh: Instant::now(),
+        }
+    }
+
+    /// Messages from background work and timers that are ready now
+    pub fn pending(&mut self) -> Vec<Msg> {
+        let app = &mut self.app;
+        let mut messages = Vec::new();
+        if !app.queued_actions.is_empty() && !self.events.has_pending_keys() {
+            messages.push(Msg::QueuedActions);
+        }
+        if app
+            .config_watcher
+            .as_mut()
+            .is_some_and(|watcher| watcher.changed())
+        {
+            messages.push(Msg::ConfigChanged);
+        }
+        while let Some(finished) = app.loads.finished() {
+            messages.push(Msg::Loaded(Box::new(finished)));
+        }
+        if let Some(tabs) = app.aggregate_cache.prefetched() {
+            messages.push(Msg::AggregatePrefetched(tabs));
+        }
+        while let Some(outcome) = app.next_reply_outcome() {
+            messages.push(Msg::ReplyPosted(Box::new(outcome)));
+        }
+        if let Some(updates) = app.notification_updates.as_mut() {
+            while let Ok(page) = updates.try_recv() {
+                messages.push(Msg::NotificationsPolled(page));
+            }
+        }
+        if let Some((page, result)) = app.load_ahead.finished() {
+            messages.push(Msg::LoadedAhead(page, result));
+        }
+        if let Some(updates) = app.prefetch_updates.as_mut() {
+            while let Ok((node, topics)) = updates.try_recv() {
+                messages.push(Msg::Prefetched(node, topics));
+            }
+        }
+
+        let quota = self.client.rate_limit();
+        if quota != app.ui_state.api_quota {
+            messages.push(Msg::RateLimit(quota));
+        }
+        // A pending retry counts down, so it is shown again every turn
+        let retry = self.client.retry_notice();
+        if retry.is_some() || retry != app.ui_state.api_retry {
+            messages.push(Msg::Retry(retry));
+        }
+
+        // Restart the refresh timer whenever the view changes
+        if app.view != self.last_view {
+            self.last_view = app.view;
+            self.last_refresh = Instant::now();
+        }
+        if app
+            .config
+            .refresh_interval_for(app.view)
+            .is_some_and(|interval| self.last_refresh.elapsed() >= interval)
+        {
+            messages.push(Msg::RefreshDue);
+        }
+        if app.ui_state.retry_due() {
+            messages.push(Msg::RetryDue);
+        }
+
+        if let Some(entry) = app.history_reload.take() {
+            messages.push(Msg::ReloadHistory(entry));
+        }
+        if let Some((label, result)) = app.translator.finished() {
+            messages.push(Msg::Translated(label, result));
+        }
+        while let Some((url, result)) = app.link_titles.finished() {
+            messages.push(Msg::LinkTitle(url, result));
+        }
+        // An item whose lookup failed stays as the feed had it
+        while let Some(result) = app.feed_topics.finished() {
+            if let Ok(topic) = result {
+                messages.push(Msg::FeedTopic(Box::new(topic)));
+            }
+        }
+        if app.reply_input_state.external_edit_requested {
+            messages.push(Msg::EditReply);
+        }
+        messages
+    }
+
+    /// Apply `msg` to the app
+    pub fn update(&mut self, msg: Msg) -> Result<Flow> {
+        let app = &mut self.app;
+        Ok(match msg {
+            Msg::Key(key) => {
+                let quit = self.events.handle_key(app, key)?;
+                app.start_load_ahead(&self.background);
+                app.start_link_titles(&self.background);
+                app.start_feed_topics(&self.background);
+                quit_or_redraw(quit)
+            }
+            Msg::Mouse(mouse) => {
+                let quit = self.events.handle_mouse(app, mouse)?;
+                app.start_load_ahead(&self.background);
+                app.start_link_titles(&self.background);
+                app.start_feed_topics(&self.background);
+                quit_or_redraw(quit)
+            }
+            Msg::Resize => Flow::Redraw,
+            Msg::Tick => match self.events.expire_pending_keys(app)? {
+                Some(quit) => quit_or_redraw(quit),
+                // Keep the loading spinner turning and the retry counting down
+                None if app.loading().is_some() && !app.config.low_bandwidth => Flow::Redraw,
+                None if app.ui_state.retry_in().is_some() => Flow::Redraw,
+                None => Flow::Idle,
+            },
+            Msg::QueuedActions => quit_or_redraw(self.events.run_queued_actions(app)?),
+            Msg::ConfigChanged => {
+                app.reload_config();
+                Flow::Redraw
+            }
+            Msg::Loaded(finished) => {
+                app.finish_load(self.client, *finished);
+                app.start_feed_topics(&self.background);
+                Flow::Redraw
+            }
+            // Kept until the tab is switched to, so nothing shows yet
+            Msg::AggregatePrefetched(tabs) => {
+                app.finish_aggregate_prefetch(tabs);
+                Flow::Idle
+            }
+            Msg::ReplyPosted(outcome) => {
+                app.finish_reply(*outcome);
+                Flow::Redraw
+            }
+            Msg::NotificationsPolled(page) => {
+                app.apply_polled_notifications(page);
+                Flow::Redraw
+            }
+            // Kept until the node is opened, so nothing shows yet
+            Msg::Prefetched(node, topics) => {
+                app.prefetched_topics.insert(node, topics);
+                Flow::Idle
+            }
+            Msg::LoadedAhead(page, result) => {
+                app.finish_load_ahead(page, result);
+                Flow::Redraw
+            }
+            Msg::RateLimit(quota) => {
+                app.ui_state.api_quota = quota;
+                Flow::Redraw
+            }
+            Msg::Retry(notice) => {
+                app.ui_state.api_retry = notice;
+                Flow::Redraw
+            }
+            Msg::RefreshDue => {
+                app.auto_refresh(&self.background);
+                self.last_refresh = Instant::now();
+                Flow::Redraw
+            }
+            Msg::RetryDue => {
+                app.retry_failed(self.client);
+                Flow::Redraw
+            }
+            Msg::ReloadHistory(entry) => {
+                app.reload_history_entry(self.client, entry);
+                Flow::Redraw
+            }
+            Msg::EditReply => Flow::EditReply,
+            Msg::Translated(label, result) => {
+                app.finish_translation(label, result);
+                Flow::Redraw
+            }
+            Msg::FeedTopic(topic) => {
+                app.finish_feed_topic(*topic);
+                Flow::Redraw
+            }
+            // Only link mode shows titles
+            Msg::LinkTitle(url, result) => {
+                let shown = app.topic_state.link_input_state.is_active;
+                app.finish_link_title(url, result);
+                if shown {
+                    Flow::Redraw
+                } else {
+                    Flow::Idle
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers, MouseEvent};
+
+    #[test]
+    fn test_runtime_applies_messages() {
+        let client = V2exClient::new(String::new());
+        let mut runtime = Runtime::new(App::new(Default::default()), &client);
+        assert!(runtime.pending().is_empty());
+
+        let key = KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE);
+        let flow = runtime.update(Msg::Key(key)).unwrap();
+        assert_eq!(flow, Flow::Redraw);
+        assert_eq!(runtime.app.view, View::Help);
+        assert_eq!(runtime.update(Msg::Tick).unwrap(), Flow::Idle);
+
+        // Hooks queue actions, which arrive as a message of their own
+        runtime.app.queued_actions.push("quit".to_string());
+        let pending = runtime.pending();
+        assert!(matches!(pending[..], [Msg::QueuedActions]));
+        for msg in pending {
+            runtime.update(msg).unwrap();
+        }
+        assert!(runtime.app.queued_actions.is_empty());
+
+        let flow = runtime
+            .update(Msg::Prefetched("rust".to_string(), Vec::new()))
+            .unwrap();
+        assert_eq!(flow, Flow::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_key_starts_load_that_arrives_as_message() {
+        let client = V2exClient::new(String::new());
+        let mut runtime = Runtime::new(App::new(Default::default()), &client);
+
+        // The key returns before the favorites are fetched
+        let key = KeyEvent::new(KeyCode::Char('b'), KeyModifiers::NONE);
+        assert_eq!(runtime.update(Msg::Key(key)).unwrap(), Flow::Redraw);
+        assert_eq!(runtime.app.view, View::Favorites);
+        assert!(runtime.app.ui_state.error.is_none());
+        assert_eq!(runtime.update(Msg::Tick).unwrap(), Flow::Redraw);
+
+        let finished = loop {
+            let pending = runtime.pending();
+            if let Some(msg) = pending
+                .into_iter()
+                .find(|msg| matches!(msg, Msg::Loaded(_)))
+            {
+                break msg;
+            }
+            tokio::task::yield_now().await;
+        };
+        assert_eq!(runtime.update(finished).unwrap(), Flow::Redraw);
+        // Without a website session the view says what is missing
+        let error = runtime.app.ui_state.error.as_deref().unwrap();
+        assert!(error.contains("cookie.txt"));
+        assert_eq!(runtime.app.loading(), None);
+    }
+
+    #[test]
+    fn test_msg_from_event() {
+        let moved = MouseEvent {
+            kind: MouseEventKind::Moved,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        };
+        assert!(Msg::from_event(Event::Mouse(moved)).is_none());
+        assert!(matches!(
+            Msg::from_event(Event::Resize(80, 24)),
+            Some(Msg::Resize)
+        ));
+    }
+}
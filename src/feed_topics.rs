@@ -0,0 +1,133 @@
+//! Topics of the aggregate feed items on screen, looked up one by one in
+//! the v1 API for their nodes and last reply times when
+//! `aggregate-resolve-topics` is on. Each topic is looked up once a
+//! session, however often its tab is fetched again.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use tokio::sync::mpsc;
+
+use crate::api::{RssItem, Topic, V2exClient};
+
+/// Topics looked up so far and the lookups still running
+#[derive(Debug)]
+pub struct FeedTopics {
+    topics: HashMap<i64, Topic>,
+    /// Topics looked up this session, so failures aren't tried again
+    asked: HashSet<i64>,
+    tx: mpsc::UnboundedSender<Result<Topic>>,
+    rx: mpsc::UnboundedReceiver<Result<Topic>>,
+}
+
+impl Default for FeedTopics {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            topics: HashMap::new(),
+            asked: HashSet::new(),
+            tx,
+            rx,
+        }
+    }
+}
+
+impl FeedTopics {
+    /// Look up the topic of each of `items` not asked about yet, without
+    /// waiting
+    pub fn start(&mut self, client: &V2exClient, items: &[RssItem]) {
+        for topic_id in items.iter().filter_map(RssItem::extract_topic_id) {
+            if !self.asked.insert(topic_id) {
+                continue;
+            }
+            let client = client.clone();
+            let tx = self.tx.clone();
+            tokio::spawn(async move {
+                let _ = tx.send(client.get_topic_v1(topic_id).await);
+            });
+        }
+    }
+
+    /// The next lookup that finished, if any
+    pub fn finished(&mut self) -> Option<Result<Topic>> {
+        self.rx.try_recv().ok()
+    }
+
+    pub fn insert(&mut self, topic: Topic) {
+        self.topics.insert(topic.id, topic);
+    }
+
+    /// Fill in `items` from the topics looked up so far
+    pub fn enrich(&self, items: &mut [RssItem]) {
+        for item in items {
+            if let Some(topic) = item.extract_topic_id().and_then(|id| self.topics.get(&id)) {
+                item.enrich(topic);
+            }
+        }
+    }
+}
+
+/// Rows of a list of `len` items shown `height` rows at a time, from
+/// `offset` when last drawn, scrolled as little as needed to show `selected`
+pub fn visible_rows(offset: usize, height: usize, selected: usize, len: usize) -> Range<usize> {
+    let start = if selected < offset {
+        selected
+    } else if selected >= offset + height {
+        selected + 1 - height
+    } else {
+        offset
+    };
+    start.min(len)..(start + height).min(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(link: &str, replies: Option<i64>) -> RssItem {
+        RssItem {
+            title: String::new(),
+            link: link.to_string(),
+            date: String::new(),
+            author: None,
+            timestamp: None,
+            node: None,
+            replies,
+            last_touched: None,
+        }
+    }
+
+    #[test]
+    fn test_enrich_from_topics_looked_up() {
+        let mut topics = FeedTopics::default();
+        topics.insert(
+            serde_json::from_value(serde_json::json!({
+                "id": 42,
+                "replies": 3,
+                "last_touched": 1700000600,
+                "node": {"id": 1, "name": "rust", "title": "Rust"},
+            }))
+            .unwrap(),
+        );
+        let mut items = [
+            item("https://www.v2ex.com/t/42#reply5", Some(5)),
+            item("https://www.v2ex.com/t/43#reply1", Some(1)),
+        ];
+        topics.enrich(&mut items);
+
+        assert_eq!(items[0].node.as_deref(), Some("rust"));
+        assert_eq!(items[0].last_touched, Some(1700000600));
+        // The feed fetched since has seen more replies than the lookup
+        assert_eq!(items[0].replies, Some(5));
+        assert_eq!(items[1].node, None);
+    }
+
+    #[test]
+    fn test_visible_rows() {
+        assert_eq!(visible_rows(0, 10, 3, 50), 0..10);
+        assert_eq!(visible_rows(0, 10, 12, 50), 3..13);
+        assert_eq!(visible_rows(20, 10, 5, 50), 5..15);
+        assert_eq!(visible_rows(45, 10, 47, 50), 45..50);
+        assert_eq!(visible_rows(0, 10, 0, 0), 0..0);
+    }
+}
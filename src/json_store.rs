@@ -0,0 +1,109 @@
+//! JSON files that keep what the app remembers between sessions: read
+//! topics, bookmarks, prompt history and the like under
+//! `~/.local/share/v2ex`, and the disk cache and saved tokens elsewhere
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Where the file `name` is kept
+pub fn data_path(name: &str) -> Result<PathBuf> {
+    let base_dirs = BaseDirs::new().context("Failed to get base directories")?;
+    Ok(base_dirs.data_dir().join("v2ex").join(name))
+}
+
+/// The value saved at `path`, or `None` if nothing was saved yet
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let value =
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))?;
+    Ok(Some(value))
+}
+
+/// Save `value` at `path`, creating its directory if needed.
+///
+/// The JSON goes to a temporary file next to `path` that is then renamed
+/// over it, so a crash or a full disk leaves the old contents in place.
+pub fn save<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    let name = path
+        .file_name()
+        .with_context(|| format!("No file name in {:?}", path))?;
+    let temp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    let written = write_json(&temp, value).and_then(|()| {
+        std::fs::rename(&temp, path).with_context(|| format!("Failed to replace {:?}", path))
+    });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    written
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    let file =
+        std::fs::File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    let mut writer = std::io::BufWriter::new(file);
+    serde_json::to_writer(&mut writer, value)
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    let file = writer
+        .into_inner()
+        .map_err(|e| e.into_error())
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    file.sync_all()
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("v2ex-store-{}", std::process::id()))
+            .join("ids.json");
+        assert_eq!(load::<Vec<i64>>(&path).unwrap(), None);
+
+        save(&path, &[3, 1, 2]).unwrap();
+        assert_eq!(load::<Vec<i64>>(&path).unwrap(), Some(vec![3, 1, 2]));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load::<Vec<i64>>(&path).is_err());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    /// Fails part-way through, after some of the JSON was written
+    struct Unwritable;
+
+    impl Serialize for Unwritable {
+        fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("disk full"))
+        }
+    }
+
+    #[test]
+    fn test_failed_save_keeps_old_contents() {
+        let dir = std::env::temp_dir().join(format!("v2ex-store-fail-{}", std::process::id()));
+        let path = dir.join("ids.json");
+        save(&path, &[1, 2]).unwrap();
+
+        assert!(save(&path, &(3, Unwritable)).is_err());
+        assert_eq!(load::<Vec<i64>>(&path).unwrap(), Some(vec![1, 2]));
+        // The half-written temporary file is cleaned up
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
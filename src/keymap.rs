@@ -1,17 +1,473 @@
-use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+use std::time::{Duration, Instant};
 
 use crate::api::V2exClient;
 use crate::app::{App, View};
+use crate::loads::AfterTopics;
+use crate::minibuffer::{Minibuffer, MinibufferEvent};
+use crate::state::{LinkHandler, ReplyJump, ReplySort, SearchPattern, TopicState};
+use crate::views::{self, Handled};
 
 /// Trait for key mappings
 pub trait KeyMap {
-    fn handle_key(
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool>;
+
+    /// Sequences of more than one key this keymap handles, in key notation.
+    /// They are typed like user bindings, echoing the keys so far.
+    fn sequences(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Handle one of [`Self::sequences`], typed in full
+    fn handle_sequence(
         &self,
-        app: &mut App,
-        key: KeyEvent,
-        client: &V2exClient,
-    ) -> impl std::future::Future<Output = Result<bool>>;
+        _app: &mut App,
+        _keys: &[KeyEvent],
+        _client: &V2exClient,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// What the keys this keymap handles do, for the help screen
+    fn help(&self) -> Vec<HelpSection> {
+        Vec::new()
+    }
+}
+
+/// Built-in actions that can be bound from the config, with the key that
+/// triggers them in the default keymaps
+pub const ACTIONS: &[(&str, &str)] = &[
+    ("quit", "q"),
+    ("exit", "C-c"),
+    ("help", "?"),
+    ("history-back", "l"),
+    ("history-forward", "r"),
+    ("next", "n"),
+    ("previous", "p"),
+    ("scroll-down", "SPC"),
+    ("page-down", "C-v"),
+    ("page-up", "M-v"),
+    ("first", "<"),
+    ("last", ">"),
+    ("open", "RET"),
+    ("refresh", "g"),
+    ("retry", "r"),
+    ("dismiss-error", "ESC"),
+    ("notifications", "m"),
+    ("profile", "u"),
+    ("aggregate", "a"),
+    ("favorites", "b"),
+    ("select-node", "s"),
+    ("open-in-browser", "o"),
+    ("toggle-read", "x"),
+    ("preview-topic", "SPC"),
+    ("node-info", "i"),
+    ("new-tab", "T"),
+    ("next-tab", "]"),
+    ("previous-tab", "["),
+    ("close-tab", "X"),
+    ("load-more", "+"),
+    ("toggle-replies", "t"),
+    ("link-mode", "f"),
+    ("copy", "w"),
+    ("copy-url", "W"),
+    ("copy-topic-url", "Y"),
+    ("copy-reply-url", "y"),
+    ("copy-code", "C"),
+    ("export-text", "E"),
+    ("export-topic", "D"),
+    ("clipboard-history", "C-y"),
+    ("history-browser", "H"),
+    ("visited-topics", "V"),
+    ("bookmark", "M"),
+    ("bookmarks", "'"),
+    ("tokens", "K"),
+    ("cycle-theme", "C-t"),
+    ("read-aloud", "R"),
+    ("translate", "L"),
+    ("focus-mode", "z"),
+    ("reveal-blocked", "B"),
+    ("compose-reply", "c"),
+    ("toggle-favorite", "F"),
+    ("view-author", "U"),
+    ("next-topic", "N"),
+    ("previous-topic", "P"),
+    ("jump-to-quote", "j"),
+    ("jump-back", "J"),
+    ("goto-reply", "G"),
+    ("jump-to-first-unread", "."),
+    ("cycle-reply-sort", "S"),
+    ("cycle-reply-filter", "O"),
+    ("search-replies", "C-s"),
+    ("cycle-topic-sort", "S"),
+    ("filter-topics", "/"),
+    ("thank", "T"),
+    ("shrink-topic-pane", "C-w <"),
+    ("grow-topic-pane", "C-w >"),
+    ("swap-panes", "C-w x"),
+    ("toggle-stacked-panes", "C-w s"),
+    ("reset-panes", "C-w ="),
+    ("send-reply", "C-s"),
+    ("edit-reply-externally", "C-e"),
+    ("next-line", "C-n"),
+    ("previous-line", "C-p"),
+    ("forward-char", "C-f"),
+    ("backward-char", "C-b"),
+    ("beginning-of-line", "C-a"),
+    ("delete-backward-char", "DEL"),
+    ("previous-input", "<up>"),
+    ("complete", "TAB"),
+    ("cancel", "ESC"),
+    ("toggle-profiling", "<f12>"),
+];
+
+/// Actions listed in the empty node completion prompt, shown with their
+/// keys by [`prompt_hints`]
+pub const NODE_COMPLETION_HINTS: &[(&str, &str)] = &[
+    ("open", "open"),
+    ("next-line", "next"),
+    ("previous-line", "previous"),
+    ("delete-backward-char", "delete"),
+    ("previous-input", "history"),
+    ("complete", "complete"),
+    ("cancel", "close"),
+];
+
+/// Actions listed in the empty visited topics search prompt
+pub const VISITED_TOPICS_HINTS: &[(&str, &str)] = &[
+    ("open", "open"),
+    ("next-line", "next"),
+    ("previous-line", "previous"),
+    ("cancel", "close"),
+];
+
+/// Actions listed in the empty reply editor
+pub const REPLY_INPUT_HINTS: &[(&str, &str)] = &[
+    ("send-reply", "send"),
+    ("edit-reply-externally", "edit in $EDITOR"),
+    ("beginning-of-line", "line start"),
+    ("backward-char", "back"),
+    ("forward-char", "forward"),
+    ("previous-line", "up"),
+    ("next-line", "down"),
+    ("cancel", "keep draft"),
+];
+
+/// A group of keys on the help screen: one keymap, or a mode of one
+pub struct HelpSection {
+    pub title: &'static str,
+    /// `define-key` scope whose bindings are listed too; `None` for global
+    pub view: Option<View>,
+    /// Action names from [`ACTIONS`] (shown with their default key) or key
+    /// notation, each with what it does
+    pub keys: &'static [(&'static str, &'static str)],
+}
+
+/// The keys of a hint or help entry, which names either an action from
+/// [`ACTIONS`] or the keys themselves
+pub fn entry_keys(entry: &str) -> &str {
+    ACTIONS
+        .iter()
+        .find(|(name, _)| *name == entry)
+        .map_or(entry, |(_, keys)| keys)
+}
+
+/// Describe `hints` (see [`entry_keys`]) as `"KEY desc  KEY desc"`, showing
+/// keys the user bound in `view` to the same built-in key alongside the
+/// default
+pub fn prompt_hints(bindings: &KeyBindings, view: View, hints: &[(&str, &str)]) -> String {
+    hints
+        .iter()
+        .map(|(entry, description)| key_hint(bindings, view, entry, description))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Describe one hint (see [`prompt_hints`]) as `"KEY desc"`
+pub fn key_hint(bindings: &KeyBindings, view: View, entry: &str, description: &str) -> String {
+    let notation = entry_keys(entry);
+    let mut keys = vec![notation.to_string()];
+    if let Ok(target) = parse_key_sequence(notation) {
+        keys.extend(
+            bindings
+                .keys_for(Some(view), &target)
+                .iter()
+                .map(|keys| format_key_sequence(keys)),
+        );
+    }
+    format!("{} {}", keys.join("/"), description)
+}
+
+/// Parse Emacs-style key notation such as `"C-x C-s"`, `"M-v"` or `"SPC"`.
+///
+/// Each space-separated key takes `C-` (Control), `M-` (Alt) and `S-`
+/// (Shift) prefixes followed by a single character or one of `SPC`, `RET`,
+/// `TAB`, `ESC`, `DEL`, `<up>`, `<down>`, `<left>`, `<right>`, `<home>`,
+/// `<end>`, `<prior>`, `<next>`, `<insert>`, `<delete>`, `<backtab>` and
+/// `<f1>`..`<f12>`.
+pub fn parse_key_sequence(notation: &str) -> Result<Vec<KeyEvent>> {
+    let keys = notation
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Result<Vec<_>>>()?;
+    if keys.is_empty() {
+        bail!("empty key sequence");
+    }
+    Ok(keys)
+}
+
+fn parse_key(token: &str) -> Result<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = token;
+    // A lone modifier letter such as "C-" followed by "-" is the key "-"
+    while rest.len() > 2 {
+        let modifier = match rest.get(..2) {
+            Some("C-") => KeyModifiers::CONTROL,
+            Some("M-") => KeyModifiers::ALT,
+            Some("S-") => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        modifiers |= modifier;
+        rest = &rest[2..];
+    }
+
+    let code = match rest {
+        "SPC" => KeyCode::Char(' '),
+        "RET" => KeyCode::Enter,
+        "TAB" => KeyCode::Tab,
+        "ESC" => KeyCode::Esc,
+        "DEL" => KeyCode::Backspace,
+        "<up>" => KeyCode::Up,
+        "<down>" => KeyCode::Down,
+        "<left>" => KeyCode::Left,
+        "<right>" => KeyCode::Right,
+        "<home>" => KeyCode::Home,
+        "<end>" => KeyCode::End,
+        "<prior>" => KeyCode::PageUp,
+        "<next>" => KeyCode::PageDown,
+        "<insert>" => KeyCode::Insert,
+        "<delete>" => KeyCode::Delete,
+        "<backtab>" => KeyCode::BackTab,
+        _ => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => KeyCode::Char(ch),
+                _ => {
+                    let n = rest
+                        .strip_prefix("<f")
+                        .and_then(|r| r.strip_suffix('>'))
+                        .and_then(|n| n.parse::<u8>().ok())
+                        .filter(|n| (1..=12).contains(n))
+                        .ok_or_else(|| anyhow!("unknown key '{}' in '{}'", rest, token))?;
+                    KeyCode::F(n)
+                }
+            }
+        }
+    };
+    Ok(match code {
+        KeyCode::Char(ch) if modifiers.contains(KeyModifiers::SHIFT) => KeyEvent::new(
+            KeyCode::Char(ch.to_ascii_uppercase()),
+            modifiers - KeyModifiers::SHIFT,
+        ),
+        _ => KeyEvent::new(code, modifiers),
+    })
+}
+
+/// Render keys back in the notation accepted by [`parse_key_sequence`]
+pub fn format_key_sequence(keys: &[KeyEvent]) -> String {
+    keys.iter()
+        .map(|key| {
+            let mut out = String::new();
+            if key.modifiers.contains(KeyModifiers::CONTROL) {
+                out.push_str("C-");
+            }
+            if key.modifiers.contains(KeyModifiers::ALT) {
+                out.push_str("M-");
+            }
+            match key.code {
+                KeyCode::Char(' ') => out.push_str("SPC"),
+                KeyCode::Char(ch) => out.push(ch),
+                KeyCode::Enter => out.push_str("RET"),
+                KeyCode::Tab => out.push_str("TAB"),
+                KeyCode::Esc => out.push_str("ESC"),
+                KeyCode::Backspace => out.push_str("DEL"),
+                KeyCode::F(n) => out.push_str(&format!("<f{}>", n)),
+                other => out.push_str(&format!("<{:?}>", other).to_lowercase()),
+            }
+            out
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Compare keys ignoring Shift on characters, which terminals report
+/// inconsistently (`F` may or may not carry SHIFT)
+fn same_key(a: &KeyEvent, b: &KeyEvent) -> bool {
+    let significant = |key: &KeyEvent| {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if !matches!(key.code, KeyCode::Char(_)) {
+            modifiers |= key.modifiers & KeyModifiers::SHIFT;
+        }
+        (key.code, modifiers)
+    };
+    significant(a) == significant(b)
+}
+
+/// Whether two key sequences are the same, as [`same_key`] compares keys
+pub fn same_keys(a: &[KeyEvent], b: &[KeyEvent]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_key(a, b))
+}
+
+/// Whether the current view is taking text, so letters are input
+fn is_typing(app: &App) -> bool {
+    matches!(
+        app.view,
+        View::ReplyInput | View::TokenInput | View::History
+    ) || (app.view == View::NodeSelect && app.node_state.is_completion_mode)
+        || (app.view == View::TopicDetail && app.topic_state.jump_input.is_some())
+        || (app.view == View::TopicList && app.topic_state.filter_input.is_some())
+        || (app.view == View::TopicDetail && app.topic_state.search_input.is_some())
+        || (app.view == View::TopicDetail && app.topic_state.tag_input.is_some())
+        || (app.view == View::Tokens && app.token_state.adding.is_some())
+}
+
+/// C-g, which aborts a partial key sequence as in Emacs
+fn is_cancel_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('g') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// A user key binding from `define-key`
+#[derive(Debug, Clone)]
+pub struct KeyBinding {
+    /// View the binding applies to; `None` binds it everywhere except
+    /// while typing text
+    pub view: Option<View>,
+    pub keys: Vec<KeyEvent>,
+    /// What runs when `keys` is pressed
+    pub target: Action,
+}
+
+/// What a user key binding runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Built-in keys replayed through the current view's keymap
+    Keys(Vec<KeyEvent>),
+    /// An action from `define-action`, by name
+    Custom(String),
+}
+
+/// Outcome of matching the keys pressed so far against user bindings
+#[derive(Debug, PartialEq)]
+pub enum BindingMatch {
+    Bound(Action),
+    Prefix,
+    Unbound,
+}
+
+/// The default keys of the action called `name`
+pub fn action_keys(name: &str) -> Result<Vec<KeyEvent>> {
+    let (_, default) = ACTIONS
+        .iter()
+        .find(|(action, _)| *action == name)
+        .ok_or_else(|| anyhow!("unknown action '{}'", name))?;
+    parse_key_sequence(default)
+}
+
+/// User key bindings, later definitions taking precedence
+#[derive(Debug, Clone, Default)]
+pub struct KeyBindings {
+    bindings: Vec<KeyBinding>,
+}
+
+impl KeyBindings {
+    /// Bind `keys` in `view` (or globally) to an action name or key notation
+    pub fn define(&mut self, view: Option<View>, keys: &str, target: BindingTarget) -> Result<()> {
+        let keys = parse_key_sequence(keys)?;
+        let target = match target {
+            BindingTarget::Action(name) => Action::Keys(action_keys(&name)?),
+            BindingTarget::Custom(name) => Action::Custom(name),
+            BindingTarget::Keys(notation) => Action::Keys(parse_key_sequence(&notation)?),
+        };
+        self.bindings.push(KeyBinding { view, keys, target });
+        Ok(())
+    }
+
+    /// Whether a binding in `view`, or a global one, starts with `key`
+    /// pressed on its own
+    pub fn binds_key(&self, view: View, key: char) -> bool {
+        let pressed = [KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE)];
+        self.lookup(view, false, &pressed) != BindingMatch::Unbound
+    }
+
+    /// Match pressed keys, preferring bindings for `view` over global ones.
+    /// Global bindings are skipped while `typing` so they don't eat text.
+    pub fn lookup(&self, view: View, typing: bool, pressed: &[KeyEvent]) -> BindingMatch {
+        let applicable = |binding: &&KeyBinding| match binding.view {
+            Some(v) => v == view,
+            None => !typing,
+        };
+        let starts_with = |binding: &KeyBinding| {
+            binding.keys.len() >= pressed.len()
+                && binding
+                    .keys
+                    .iter()
+                    .zip(pressed)
+                    .all(|(a, b)| same_key(a, b))
+        };
+
+        let mut candidates: Vec<&KeyBinding> = self
+            .bindings
+            .iter()
+            .filter(applicable)
+            .filter(|binding| starts_with(binding))
+            .collect();
+        // View bindings first, then most recently defined
+        candidates.reverse();
+        candidates.sort_by_key(|binding| binding.view.is_none());
+
+        if let Some(exact) = candidates
+            .iter()
+            .find(|binding| binding.keys.len() == pressed.len())
+        {
+            return BindingMatch::Bound(exact.target.clone());
+        }
+        if candidates.is_empty() {
+            BindingMatch::Unbound
+        } else {
+            BindingMatch::Prefix
+        }
+    }
+
+    /// Keys bound in `view` itself (or globally for `None`) that replay
+    /// exactly `target`. Only that one scope is searched, since global
+    /// bindings don't apply while typing.
+    pub fn keys_for(&self, view: Option<View>, target: &[KeyEvent]) -> Vec<Vec<KeyEvent>> {
+        self.bindings
+            .iter()
+            .filter(|binding| binding.view == view)
+            .filter(|binding| match &binding.target {
+                Action::Keys(keys) => same_keys(keys, target),
+                Action::Custom(_) => false,
+            })
+            .map(|binding| binding.keys.clone())
+            .collect()
+    }
+
+    /// Every binding, in the order defined
+    pub fn iter(&self) -> impl Iterator<Item = &KeyBinding> {
+        self.bindings.iter()
+    }
+}
+
+/// What a `define-key` binding runs
+pub enum BindingTarget {
+    /// A built-in action
+    Action(String),
+    /// An action from `define-action`
+    Custom(String),
+    Keys(String),
 }
 
 /// Link selection mode key mapping
@@ -22,7 +478,7 @@ impl LinkSelectionKeyMap {
         Self
     }
 
-    async fn handle_link_mode_char(&self, app: &mut App, ch: char) -> Result<bool> {
+    fn handle_link_mode_char(&self, app: &mut App, ch: char, client: &V2exClient) -> Result<bool> {
         if !app.topic_state.link_input_state.is_active {
             return Ok(false);
         }
@@ -55,18 +511,10 @@ impl LinkSelectionKeyMap {
             .find(|link| link.shortcut.len() == input.len());
 
         if let Some(link) = exact_match {
-            // Exact match found - open the link
-            match crate::browser::Browser::open_url(&link.url) {
-                Ok(result) => {
-                    app.ui_state.status_message =
-                        format!("Opening link {}: {}", link.shortcut, result);
-                }
-                Err(e) => {
-                    app.ui_state.error =
-                        Some(format!("Failed to open link {}: {}", link.shortcut, e));
-                }
-            }
-            app.topic_state.exit_link_selection_mode();
+            // Exact match found - ask what to do with the link
+            let link = (*link).clone();
+            app.ui_state.status_message = link_handler_prompt(&link.shortcut, &link.url, client);
+            app.topic_state.link_input_state.chosen = Some(link);
         } else if !matches.is_empty() {
             // Multiple prefix matches - show feedback
             let shortcuts: Vec<&str> = matches.iter().map(|l| l.shortcut.as_str()).collect();
@@ -86,7 +534,51 @@ impl LinkSelectionKeyMap {
 }
 
 impl KeyMap for LinkSelectionKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, _client: &V2exClient) -> Result<bool> {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Link Selection Mode",
+            view: Some(View::TopicDetail),
+            keys: &[
+                ("a o e u i d h t n s", "Type the letters of a link's label"),
+                (
+                    "RET",
+                    "Then open V2EX topics and members here, other links in the browser",
+                ),
+                ("o", "Then open the link in the browser"),
+                ("w", "Then copy the link"),
+                ("d", "Then download it to the Downloads folder"),
+                ("ESC", "Cancel (also q, C-g)"),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        if let Some(link) = &app.topic_state.link_input_state.chosen {
+            let handler = match key.code {
+                // V2EX pages open here unless the browser is asked for
+                KeyCode::Enter if client.site_link(&link.url).is_some() => {
+                    Some(LinkHandler::Internal)
+                }
+                KeyCode::Enter | KeyCode::Char('o') => Some(LinkHandler::Browser),
+                KeyCode::Char('w') => Some(LinkHandler::Copy),
+                KeyCode::Char('d') => Some(LinkHandler::Download),
+                KeyCode::Char('q') | KeyCode::Esc => None,
+                KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => None,
+                _ => {
+                    app.ui_state.status_message =
+                        link_handler_prompt(&link.shortcut, &link.url, client);
+                    return Ok(false);
+                }
+            };
+            match handler {
+                Some(handler) => app.use_chosen_link(client, handler),
+                None => {
+                    app.topic_state.exit_link_selection_mode();
+                    app.ui_state.status_message = "Link selection cancelled".to_string();
+                }
+            }
+            return Ok(false);
+        }
         match key.code {
             KeyCode::Char('q') => {
                 app.topic_state.exit_link_selection_mode();
@@ -103,16 +595,16 @@ impl KeyMap for LinkSelectionKeyMap {
                 Ok(false)
             }
             // Home row keys for link selection
-            KeyCode::Char('a') => self.handle_link_mode_char(app, 'a').await,
-            KeyCode::Char('o') => self.handle_link_mode_char(app, 'o').await,
-            KeyCode::Char('e') => self.handle_link_mode_char(app, 'e').await,
-            KeyCode::Char('u') => self.handle_link_mode_char(app, 'u').await,
-            KeyCode::Char('i') => self.handle_link_mode_char(app, 'i').await,
-            KeyCode::Char('d') => self.handle_link_mode_char(app, 'd').await,
-            KeyCode::Char('h') => self.handle_link_mode_char(app, 'h').await,
-            KeyCode::Char('t') => self.handle_link_mode_char(app, 't').await,
-            KeyCode::Char('n') => self.handle_link_mode_char(app, 'n').await,
-            KeyCode::Char('s') => self.handle_link_mode_char(app, 's').await,
+            KeyCode::Char('a') => self.handle_link_mode_char(app, 'a', client),
+            KeyCode::Char('o') => self.handle_link_mode_char(app, 'o', client),
+            KeyCode::Char('e') => self.handle_link_mode_char(app, 'e', client),
+            KeyCode::Char('u') => self.handle_link_mode_char(app, 'u', client),
+            KeyCode::Char('i') => self.handle_link_mode_char(app, 'i', client),
+            KeyCode::Char('d') => self.handle_link_mode_char(app, 'd', client),
+            KeyCode::Char('h') => self.handle_link_mode_char(app, 'h', client),
+            KeyCode::Char('t') => self.handle_link_mode_char(app, 't', client),
+            KeyCode::Char('n') => self.handle_link_mode_char(app, 'n', client),
+            KeyCode::Char('s') => self.handle_link_mode_char(app, 's', client),
             KeyCode::Char(ch) => {
                 // Any other character key is invalid in link selection mode
                 app.ui_state.status_message = format!(
@@ -130,17 +622,347 @@ impl KeyMap for LinkSelectionKeyMap {
     }
 }
 
+/// The choices offered once a link's label has been typed
+fn link_handler_prompt(shortcut: &str, url: &str, client: &V2exClient) -> String {
+    let open = if client.site_link(url).is_some() {
+        "RET open here, o browser"
+    } else {
+        "RET/o browser"
+    };
+    format!("Link {}: {}, w copy, d download, q cancel", shortcut, open)
+}
+
+/// Clipboard history overlay key mapping
+pub struct ClipboardKeyMap;
+
+impl ClipboardKeyMap {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl KeyMap for ClipboardKeyMap {
+    fn handle_key(&self, app: &mut App, key: KeyEvent, _client: &V2exClient) -> Result<bool> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => Ok(true),
+            KeyCode::Char('n') if ctrl => {
+                app.clipboard_history.next();
+                Ok(false)
+            }
+            KeyCode::Char('p') if ctrl => {
+                app.clipboard_history.previous();
+                Ok(false)
+            }
+            KeyCode::Char('g') | KeyCode::Char('y') if ctrl => {
+                app.clipboard_history.visible = false;
+                Ok(false)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                app.clipboard_history.visible = false;
+                Ok(false)
+            }
+            KeyCode::Char('n') | KeyCode::Down => {
+                app.clipboard_history.next();
+                Ok(false)
+            }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.clipboard_history.previous();
+                Ok(false)
+            }
+            KeyCode::Enter | KeyCode::Char('w') => {
+                app.recopy_clipboard_entry();
+                Ok(false)
+            }
+            KeyCode::Char('d') => {
+                app.clipboard_history.remove_selected();
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Code block picker key mapping
+pub struct CodePickerKeyMap;
+
+impl CodePickerKeyMap {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl KeyMap for CodePickerKeyMap {
+    fn handle_key(&self, app: &mut App, key: KeyEvent, _client: &V2exClient) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Ok(true),
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.code_picker.visible = false;
+                Ok(false)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                app.code_picker.visible = false;
+                Ok(false)
+            }
+            KeyCode::Char('n') | KeyCode::Down => {
+                app.code_picker.next();
+                Ok(false)
+            }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.code_picker.previous();
+                Ok(false)
+            }
+            KeyCode::Enter | KeyCode::Char('w') => {
+                app.copy_code_block(app.code_picker.selected);
+                Ok(false)
+            }
+            KeyCode::Char(digit @ '1'..='9') => {
+                app.copy_code_block(digit as usize - '1' as usize);
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Translation popup key mapping; keys that don't scroll close it
+pub struct TranslationKeyMap;
+
+impl TranslationKeyMap {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl KeyMap for TranslationKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Translation",
+            view: Some(View::TopicDetail),
+            keys: &[
+                ("n", "Scroll down (also SPC, Down)"),
+                ("p", "Scroll up (also Up)"),
+                ("ESC", "Close (any other key too)"),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, _client: &V2exClient) -> Result<bool> {
+        let Some(translation) = app.translator.shown.as_mut() else {
+            return Ok(false);
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Ok(true),
+            KeyCode::Char('n') | KeyCode::Char(' ') | KeyCode::Down => {
+                translation.scroll_down();
+                Ok(false)
+            }
+            KeyCode::Char('p') | KeyCode::Up => {
+                translation.scroll_up();
+                Ok(false)
+            }
+            _ => {
+                app.translator.shown = None;
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// View history overlay key mapping
+pub struct HistoryKeyMap;
+
+impl HistoryKeyMap {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl KeyMap for HistoryKeyMap {
+    fn handle_key(&self, app: &mut App, key: KeyEvent, _client: &V2exClient) -> Result<bool> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => Ok(true),
+            // The list shows the newest entry at the top
+            KeyCode::Char('n') if ctrl => {
+                app.history_browser.older();
+                Ok(false)
+            }
+            KeyCode::Char('p') if ctrl => {
+                app.history_browser.newer(&app.history);
+                Ok(false)
+            }
+            KeyCode::Char('g') if ctrl => {
+                app.history_browser.visible = false;
+                Ok(false)
+            }
+            KeyCode::Char('q') | KeyCode::Char('H') | KeyCode::Esc => {
+                app.history_browser.visible = false;
+                Ok(false)
+            }
+            KeyCode::Char('n') | KeyCode::Down => {
+                app.history_browser.older();
+                Ok(false)
+            }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.history_browser.newer(&app.history);
+                Ok(false)
+            }
+            KeyCode::Enter => {
+                app.jump_to_selected_history();
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Error banner key mapping, ahead of the view's own while an error is shown
+pub struct ErrorBannerKeyMap;
+
+impl ErrorBannerKeyMap {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether the banner takes `key` from the view below it
+    fn takes(&self, app: &App, key: &KeyEvent) -> bool {
+        if app.ui_state.error.is_none() || !key.modifiers.is_empty() || is_typing(app) {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('r') => app.ui_state.retry().is_some(),
+            KeyCode::Esc => true,
+            _ => false,
+        }
+    }
+}
+
+impl KeyMap for ErrorBannerKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Error Banner",
+            view: None,
+            keys: &[
+                ("retry", "Retry the load that failed"),
+                ("dismiss-error", "Dismiss the error"),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('r') => app.retry_failed(client),
+            KeyCode::Esc => app.ui_state.dismiss_error(),
+            _ => {}
+        }
+        Ok(false)
+    }
+}
+
 /// Topic list view key mapping
 pub struct TopicListKeyMap;
 
 impl TopicListKeyMap {
-    pub fn new() -> Self {
-        Self
+    /// Edit the `/` prompt, narrowing the list as it is typed; RET keeps
+    /// the filter, ESC or C-g clears it
+    fn handle_filter_input(&self, app: &mut App, key: KeyEvent) -> Result<bool> {
+        let Some(mut prompt) = app.topic_state.filter_input.take() else {
+            return Ok(false);
+        };
+        match prompt.handle_key(key) {
+            MinibufferEvent::Cancel => {
+                app.topic_state.set_topic_filter("");
+                app.ui_state.status_message = "Filter cleared".to_string();
+                return Ok(false);
+            }
+            MinibufferEvent::Submit(input) => {
+                if let Err(e) = app.prompt_history.remember(&prompt) {
+                    app.ui_state.error = Some(format!("Failed to save prompt history: {}", e));
+                }
+                app.topic_state.set_topic_filter(&input);
+                app.ui_state.status_message = if input.is_empty() {
+                    "Filter cleared".to_string()
+                } else {
+                    format!(
+                        "{} topics matching '{}'",
+                        app.topic_state.topic_order().len(),
+                        input
+                    )
+                };
+                return Ok(false);
+            }
+            MinibufferEvent::Edited => app.topic_state.set_topic_filter(prompt.input()),
+            _ => {}
+        }
+        app.ui_state.status_message = prompt.text();
+        app.topic_state.filter_input = Some(prompt);
+        Ok(false)
     }
 }
 
 impl KeyMap for TopicListKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Topic List",
+            view: Some(View::TopicList),
+            keys: &[
+                ("select-node", "Select a node from the menu"),
+                ("1-9", "Switch to a quick node, as listed in the header"),
+                ("0", "Newest topics of all favorite nodes together"),
+                ("t", "Open the selected topic (also l)"),
+                ("load-more", "Load more topics"),
+                ("toggle-read", "Toggle read/unread (unread is bold)"),
+                ("preview-topic", "Preview the topic; n/p move it, RET opens"),
+                (
+                    "cycle-topic-sort",
+                    "Sort by last reply, newest, replies or as listed",
+                ),
+                (
+                    "filter-topics",
+                    "Filter titles by text or regex (ESC clears)",
+                ),
+                ("node-info", "Node details"),
+                ("reveal-blocked", "Reveal/collapse topics by ignored users"),
+                ("new-tab", "Open the node in a new tab"),
+                ("next-tab", "Next tab"),
+                ("previous-tab", "Previous tab"),
+                ("close-tab", "Close the tab"),
+                ("+N", "Replies since you last opened the topic"),
+                (
+                    "▲ / △",
+                    "Replied to in the last hour (or busy today) / today",
+                ),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        // Any key closes the node details popup
+        if app.node_state.show_info {
+            app.node_state.show_info = false;
+            return Ok(false);
+        }
+        // The preview follows n/p and gives way to RET; other keys close it
+        if app.topic_previews.shown().is_some() {
+            match key.code {
+                KeyCode::Char('n') | KeyCode::Down => {
+                    app.topic_state.next_topic();
+                    app.preview_selected_topic(client);
+                    return Ok(false);
+                }
+                KeyCode::Char('p') | KeyCode::Up => {
+                    app.topic_state.previous_topic();
+                    app.preview_selected_topic(client);
+                    return Ok(false);
+                }
+                KeyCode::Enter => app.topic_previews.close(),
+                _ => {
+                    app.topic_previews.close();
+                    return Ok(false);
+                }
+            }
+        }
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Exit app immediately
@@ -168,22 +990,10 @@ impl KeyMap for TopicListKeyMap {
                 Ok(false)
             }
             KeyCode::Char('n') => {
-                let at_last = app.topic_state.selected + 1 >= app.topic_state.topics.len();
-                if at_last && !app.topic_state.topics.is_empty() {
-                    // At the end, try to load more topics
-                    let prev_page = app.node_state.page;
-                    app.node_state.page += 1;
-                    let prev_len = app.topic_state.topics.len();
-                    app.load_topics(client, true).await;
-                    if app.topic_state.topics.len() > prev_len {
-                        // New topics loaded, move to next
-                        app.topic_state.selected = prev_len;
-                    } else {
-                        // No more topics to load, stay at current position and restore page
-                        app.node_state.page = prev_page;
-                        app.ui_state.error = None; // Clear the API error
-                        app.ui_state.status_message = "Already at the last topic".to_string();
-                    }
+                let at_last = app.topic_state.at_last_topic();
+                if at_last && !app.topic_state.topics().is_empty() {
+                    // At the end, try to load more topics and move onto them
+                    app.load_topics_then(client, true, AfterTopics::SelectNext);
                 } else {
                     app.topic_state.next_topic();
                 }
@@ -194,12 +1004,11 @@ impl KeyMap for TopicListKeyMap {
                 Ok(false)
             }
             KeyCode::Char(' ') => {
-                // SPC: Scroll down (same as n/Down)
-                app.topic_state.next_topic();
+                app.preview_selected_topic(client);
                 Ok(false)
             }
             KeyCode::Char('p') => {
-                if app.topic_state.selected == 0 {
+                if app.topic_state.topic_position().unwrap_or(0) == 0 {
                     app.ui_state.status_message = "Already at the first topic".to_string();
                 } else {
                     app.topic_state.previous_topic();
@@ -211,141 +1020,131 @@ impl KeyMap for TopicListKeyMap {
                 Ok(false)
             }
             KeyCode::Enter => {
-                if let Some(topic) = app.topic_state.topics.get(app.topic_state.selected) {
+                if let Some(topic) = app.topic_state.selected_topic() {
                     let topic_id = topic.id;
-                    app.topic_state.show_replies = false;
-                    app.load_topic_detail(client, topic_id).await;
-                    app.load_topic_replies(client, topic_id, false).await;
-                    app.navigate_to(View::TopicDetail);
+                    app.open_topic(client, topic_id);
                 }
                 Ok(false)
             }
             KeyCode::Char('g') => {
-                app.load_topics(client, false).await;
+                app.load_topics(client, false);
                 Ok(false)
             }
             KeyCode::Char('a') => {
-                app.load_aggregate(client).await;
+                app.load_aggregate(client);
                 app.navigate_to(View::Aggregate);
                 Ok(false)
             }
             KeyCode::Char('m') => {
-                app.load_notifications(client).await;
+                app.load_notifications(client);
                 app.navigate_to(View::Notifications);
                 Ok(false)
             }
             KeyCode::Char('u') => {
-                app.load_profile(client).await;
+                app.load_profile(client);
                 app.navigate_to(View::Profile);
                 Ok(false)
             }
             KeyCode::Char('s') => {
-                app.node_state.completion_input.clear();
-                app.node_state.completion_cursor = 0;
-                app.node_state.is_completion_mode = true;
+                app.node_state.start_completion();
                 app.node_state.update_suggestions();
                 app.navigate_to(View::NodeSelect);
                 Ok(false)
             }
             KeyCode::Char('t') => {
-                if let Some(topic) = app.topic_state.topics.get(app.topic_state.selected) {
+                if let Some(topic) = app.topic_state.selected_topic() {
                     let topic_id = topic.id;
-                    app.topic_state.show_replies = false;
-                    app.load_topic_detail(client, topic_id).await;
-                    app.load_topic_replies(client, topic_id, false).await;
-                    app.navigate_to(View::TopicDetail);
+                    app.open_topic(client, topic_id);
                 }
                 Ok(false)
             }
             KeyCode::Char('o') => {
-                app.open_selected_topic_in_browser();
+                app.open_selected_topic_in_browser(client);
+                Ok(false)
+            }
+            KeyCode::Char('B') => {
+                app.toggle_reveal_blocked();
+                Ok(false)
+            }
+            KeyCode::Char('x') => {
+                app.toggle_selected_read();
+                Ok(false)
+            }
+            KeyCode::Char('T') => {
+                app.open_tab();
+                Ok(false)
+            }
+            KeyCode::Char(']') => {
+                app.switch_tab(1);
+                Ok(false)
+            }
+            KeyCode::Char('[') => {
+                app.switch_tab(-1);
+                Ok(false)
+            }
+            KeyCode::Char('X') => {
+                app.close_tab();
+                Ok(false)
+            }
+            KeyCode::Char('i') => {
+                app.load_node_info(client, true);
                 Ok(false)
             }
             KeyCode::PageDown => {
-                app.node_state.page += 1;
-                app.load_topics(client, true).await;
+                app.load_topics(client, true);
                 Ok(false)
             }
             KeyCode::PageUp => {
                 if app.node_state.page > 1 {
                     app.node_state.page -= 1;
-                    app.load_topics(client, false).await;
+                    app.load_topics(client, false);
                 }
                 Ok(false)
             }
             // Emacs-style page navigation
             KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // C-v: Page down (move down 5 topics)
-                let len = app.topic_state.topics.len();
-                if len > 0 {
-                    app.topic_state.selected = (app.topic_state.selected + 5).min(len - 1);
-                }
+                app.topic_state.move_topic_selection(5);
                 Ok(false)
             }
             KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::ALT) => {
                 // M-v: Page up (move up 5 topics)
-                if app.topic_state.selected >= 5 {
-                    app.topic_state.selected -= 5;
-                } else {
-                    app.topic_state.selected = 0;
-                }
+                app.topic_state.move_topic_selection(-5);
                 Ok(false)
             }
             KeyCode::Char('+') => {
-                app.node_state.page += 1;
-                app.load_topics(client, true).await;
+                app.load_topics(client, true);
                 Ok(false)
             }
             KeyCode::Char('<') => {
-                app.topic_state.selected = 0;
+                app.topic_state.select_topic_at(0);
                 Ok(false)
             }
             KeyCode::Char('>') => {
-                if !app.topic_state.topics.is_empty() {
-                    app.topic_state.selected = app.topic_state.topics.len() - 1;
-                }
+                app.topic_state.select_topic_at(usize::MAX);
                 Ok(false)
             }
-            KeyCode::Char(ch) => {
-                // Handle number keys for quick node switching
-                match ch {
-                    '1' => {
-                        app.node_state.switch_node("python");
-                        app.load_topics(client, false).await;
-                    }
-                    '2' => {
-                        app.node_state.switch_node("programmer");
-                        app.load_topics(client, false).await;
-                    }
-                    '3' => {
-                        app.node_state.switch_node("share");
-                        app.load_topics(client, false).await;
-                    }
-                    '4' => {
-                        app.node_state.switch_node("create");
-                        app.load_topics(client, false).await;
-                    }
-                    '5' => {
-                        app.node_state.switch_node("jobs");
-                        app.load_topics(client, false).await;
-                    }
-                    '6' => {
-                        app.node_state.switch_node("go");
-                        app.load_topics(client, false).await;
-                    }
-                    '7' => {
-                        app.node_state.switch_node("rust");
-                        app.load_topics(client, false).await;
-                    }
-                    '8' => {
-                        app.node_state.switch_node("javascript");
-                        app.load_topics(client, false).await;
-                    }
-                    '9' => {
-                        app.node_state.switch_node("linux");
-                        app.load_topics(client, false).await;
-                    }
-                    _ => {}
+            KeyCode::Char('S') => {
+                app.topic_state.cycle_topic_sort();
+                app.ui_state.status_message =
+                    format!("Topics sorted {}", app.topic_state.topic_sort.label());
+                Ok(false)
+            }
+            KeyCode::Char('/') => {
+                let mut prompt = filter_prompt();
+                app.prompt_history.restore(&mut prompt);
+                let pattern = app.topic_state.topic_filter.as_ref();
+                for ch in pattern.map_or("", |filter| filter.pattern.as_str()).chars() {
+                    prompt.insert_char(ch);
+                }
+                app.ui_state.status_message = prompt.text();
+                app.topic_state.filter_input = Some(prompt);
+                Ok(false)
+            }
+            KeyCode::Char(ch) => {
+                // Quick node switching with the keys shown in the header
+                if let Some(node) = app.node_state.quick_node(ch).map(str::to_string) {
+                    app.switch_to_node(client, &node);
                 }
                 Ok(false)
             }
@@ -354,22 +1153,302 @@ impl KeyMap for TopicListKeyMap {
     }
 }
 
+fn search_prompt() -> Minibuffer {
+    Minibuffer::new("Search replies (text or regex): ").with_history("search")
+}
+
+/// `Match 2 of 5` for the selected reply, or how many replies match
+fn match_status(state: &TopicState) -> String {
+    let matches = state.reply_matches();
+    match matches.iter().position(|&i| i == state.selected_reply) {
+        Some(position) => format!("Match {} of {}", position + 1, matches.len()),
+        None if matches.is_empty() => "No matches".to_string(),
+        None => format!("{} matching replies", matches.len()),
+    }
+}
+
+fn filter_prompt() -> Minibuffer {
+    Minibuffer::new("Filter titles (text or regex): ").with_history("filter")
+}
+
 /// Topic detail view key mapping
 pub struct TopicDetailKeyMap;
 
 impl TopicDetailKeyMap {
-    pub fn new() -> Self {
-        Self
+    /// Edit the `G` prompt; RET jumps, ESC or C-g closes it
+    fn handle_jump_input(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        let Some(mut prompt) = app.topic_state.jump_input.take() else {
+            return Ok(false);
+        };
+        match prompt.handle_key(key) {
+            MinibufferEvent::Cancel => {
+                app.ui_state.status_message = "Jump cancelled".to_string();
+                return Ok(false);
+            }
+            MinibufferEvent::Submit(input) => {
+                if let Err(e) = app.prompt_history.remember(&prompt) {
+                    app.ui_state.error = Some(format!("Failed to save prompt history: {}", e));
+                }
+                if let Some(jump) = ReplyJump::parse(&input) {
+                    app.jump_to_reply(client, jump.floor());
+                }
+                return Ok(false);
+            }
+            MinibufferEvent::Invalid(message) => {
+                app.ui_state.status_message = message;
+                app.topic_state.jump_input = Some(prompt);
+                return Ok(false);
+            }
+            _ => {}
+        }
+        app.ui_state.status_message = prompt.text();
+        app.topic_state.jump_input = Some(prompt);
+        Ok(false)
+    }
+
+    /// Edit the `C-s` prompt, moving to the first matching reply as it is
+    /// typed; C-s moves to the next one, RET keeps the search and ESC or
+    /// C-g goes back to where it started
+    fn handle_search_input(&self, app: &mut App, key: KeyEvent) -> Result<bool> {
+        let Some((mut prompt, origin)) = app.topic_state.search_input.take() else {
+            return Ok(false);
+        };
+        if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            app.topic_state.select_reply_match(true, false);
+        } else {
+            match prompt.handle_key(key) {
+                MinibufferEvent::Cancel => {
+                    app.topic_state.reply_search = None;
+                    app.topic_state.select_reply(origin);
+                    app.ui_state.status_message = "Search cancelled".to_string();
+                    return Ok(false);
+                }
+                MinibufferEvent::Submit(input) => {
+                    if let Err(e) = app.prompt_history.remember(&prompt) {
+                        app.ui_state.error = Some(format!("Failed to save prompt history: {}", e));
+                    }
+                    app.topic_state.reply_search = SearchPattern::new(&input);
+                    app.ui_state.status_message = if app.topic_state.reply_search.is_none() {
+                        "Search cleared".to_string()
+                    } else if app.topic_state.reply_matches().is_empty() {
+                        app.topic_state.reply_search = None;
+                        format!("No replies match '{}'", input)
+                    } else {
+                        format!(
+                            "{} · n/N next/previous, ESC clears",
+                            match_status(&app.topic_state)
+                        )
+                    };
+                    return Ok(false);
+                }
+                MinibufferEvent::Edited => {
+                    app.topic_state.reply_search = SearchPattern::new(prompt.input());
+                    app.topic_state.select_reply(origin);
+                    app.topic_state.select_reply_match(true, true);
+                }
+                _ => {}
+            }
+        }
+        app.ui_state.status_message = match app.topic_state.reply_search {
+            Some(_) => format!("{}  [{}]", prompt.text(), match_status(&app.topic_state)),
+            None => prompt.text(),
+        };
+        app.topic_state.search_input = Some((prompt, origin));
+        Ok(false)
+    }
+
+    /// Edit the `M` prompt; RET bookmarks, ESC or C-g closes it
+    fn handle_tag_input(&self, app: &mut App, key: KeyEvent) -> Result<bool> {
+        let Some(mut prompt) = app.topic_state.tag_input.take() else {
+            return Ok(false);
+        };
+        match prompt.handle_key(key) {
+            MinibufferEvent::Cancel => {
+                app.ui_state.status_message = "Bookmark cancelled".to_string();
+            }
+            MinibufferEvent::Submit(input) => {
+                if let Err(e) = app.prompt_history.remember(&prompt) {
+                    app.ui_state.error = Some(format!("Failed to save prompt history: {}", e));
+                }
+                app.bookmark_topic(&input);
+            }
+            _ => {
+                app.ui_state.status_message = prompt.text();
+                app.topic_state.tag_input = Some(prompt);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// The `M` prompt for a bookmark's tags, filled with the current ones
+fn tag_prompt(tags: &[String]) -> Minibuffer {
+    let mut prompt = Minibuffer::new("Bookmark with tags (space separated): ").with_history("tags");
+    for ch in tags.join(" ").chars() {
+        prompt.insert_char(ch);
     }
+    prompt
+}
+
+/// The key after C-w in topic detail: resize, swap or stack the panes
+fn handle_window_command(app: &mut App, key: KeyEvent) {
+    let split = &mut app.ui_state.split;
+    app.ui_state.status_message = match key.code {
+        KeyCode::Char('<') => {
+            split.resize(false);
+            format!("Topic pane {}%", split.topic_percent)
+        }
+        KeyCode::Char('>') => {
+            split.resize(true);
+            format!("Topic pane {}%", split.topic_percent)
+        }
+        KeyCode::Char('x') => {
+            split.swapped = !split.swapped;
+            "Swapped panes".to_string()
+        }
+        KeyCode::Char('s') => {
+            split.stacked = !split.stacked;
+            if split.stacked {
+                "Panes stacked".to_string()
+            } else {
+                "Panes side by side on wide terminals".to_string()
+            }
+        }
+        KeyCode::Char('=') => {
+            *split = app.config.split_layout;
+            "Pane layout reset".to_string()
+        }
+        _ => return,
+    };
+}
+
+/// The `G` prompt: a floor such as `123` or `#123`, or a page such as `p5`
+fn jump_prompt() -> Minibuffer {
+    Minibuffer::new("Go to reply # (or pN for page N): ")
+        .with_history("jump")
+        .accepting(|c| c.is_ascii_digit() || matches!(c, '#' | 'p' | 'P'))
+        .validated_by(|input| match ReplyJump::parse(input) {
+            Some(_) => Ok(()),
+            None => Err("Enter a reply number (e.g. 123) or a page (e.g. p5)".to_string()),
+        })
 }
 
 impl KeyMap for TopicDetailKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Topic Detail",
+            view: Some(View::TopicDetail),
+            keys: &[
+                ("toggle-replies", "Show/hide replies"),
+                ("load-more", "Load more replies"),
+                ("next-topic", "Next topic in the list"),
+                ("previous-topic", "Previous topic in the list"),
+                ("open-in-browser", "Open the topic or reply in the browser"),
+                ("link-mode", "Pick a link by its label"),
+                ("1-9", "Open a link by number"),
+                ("copy", "Copy the selected reply"),
+                ("copy-url", "Copy the link to the reply or topic"),
+                ("copy-topic-url", "Copy the link to the topic"),
+                ("copy-reply-url", "Copy the permalink of the reply"),
+                (
+                    "copy-code",
+                    "Copy a code block of the reply or topic (numbered picker)",
+                ),
+                ("export-text", "Export with all replies as text"),
+                ("export-topic", "Export with all replies to export-path"),
+                ("read-aloud", "Read aloud with tts-command (again stops)"),
+                ("translate", "Translate with translate-command"),
+                ("focus-mode", "Focus mode: only the text, focus-width wide"),
+                ("reveal-blocked", "Reveal/collapse replies by ignored users"),
+                ("compose-reply", "Compose a reply"),
+                (
+                    "thank",
+                    "Thank the reply (the topic when replies are hidden)",
+                ),
+                ("toggle-favorite", "Favorite/unfavorite the topic"),
+                ("bookmark", "Bookmark locally, with optional tags"),
+                ("view-author", "Profile of the reply's or topic's author"),
+                ("jump-to-quote", "Jump to the reply quoted by @user #N"),
+                ("jump-back", "Jump back"),
+                ("goto-reply", "Go to reply number (123) or page (p5)"),
+                (
+                    "search-replies",
+                    "Search replies and authors; n/N next/previous match, ESC clears",
+                ),
+                ("jump-to-first-unread", "First reply since your last visit"),
+                ("cycle-reply-sort", "Sort replies by floor / by thanks"),
+                (
+                    "cycle-reply-filter",
+                    "All replies / OP only / mentioning me",
+                ),
+                ("shrink-topic-pane", "Shrink the topic pane"),
+                ("grow-topic-pane", "Grow the topic pane"),
+                ("swap-panes", "Swap the topic and replies panes"),
+                ("toggle-stacked-panes", "Stack the panes / side by side"),
+                ("reset-panes", "Reset the pane layout"),
+            ],
+        }]
+    }
+
+    fn sequences(&self) -> &'static [&'static str] {
+        &["C-w <", "C-w >", "C-w x", "C-w s", "C-w ="]
+    }
+
+    fn handle_sequence(
+        &self,
+        app: &mut App,
+        keys: &[KeyEvent],
+        _client: &V2exClient,
+    ) -> Result<bool> {
+        if let [_, key] = keys {
+            handle_window_command(app, *key);
+        }
+        Ok(false)
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        // While a search is shown, n and N move between its matches and ESC
+        // ends it
+        if app.topic_state.reply_search.is_some() && !key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            let forward = match key.code {
+                KeyCode::Char('n') => Some(true),
+                KeyCode::Char('N') => Some(false),
+                KeyCode::Esc => {
+                    app.topic_state.reply_search = None;
+                    app.ui_state.status_message = "Search cleared".to_string();
+                    return Ok(false);
+                }
+                _ => None,
+            };
+            if let Some(forward) = forward {
+                app.topic_state.show_replies = true;
+                app.topic_state.select_reply_match(forward, false);
+                app.ui_state.status_message = match_status(&app.topic_state);
+                return Ok(false);
+            }
+        }
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Exit app immediately
                 return Ok(true);
             }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if app.topic_state.replies().is_empty() {
+                    app.ui_state.status_message = "No replies to search".to_string();
+                } else {
+                    let mut prompt = search_prompt();
+                    app.prompt_history.restore(&mut prompt);
+                    app.topic_state.show_replies = true;
+                    app.ui_state.status_message = prompt.text();
+                    app.topic_state.search_input = Some((prompt, app.topic_state.selected_reply));
+                }
+                Ok(false)
+            }
+            KeyCode::Esc if app.ui_state.focus_mode => {
+                app.ui_state.focus_mode = false;
+                Ok(false)
+            }
             KeyCode::Char('q') | KeyCode::Esc => {
                 // Remove current view from history, exit if empty
                 if app.remove_current_from_history().is_none() {
@@ -392,76 +1471,18 @@ impl KeyMap for TopicDetailKeyMap {
                 Ok(false)
             }
             KeyCode::Char('n') => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    let at_last =
-                        app.topic_state.selected_reply + 1 >= app.topic_state.replies.len();
-                    let has_more = app.topic_state.replies.len()
-                        < app
-                            .topic_state
-                            .current
-                            .as_ref()
-                            .map(|t| t.replies as usize)
-                            .unwrap_or(0);
-                    if at_last && has_more {
-                        // At the end with more replies available, load more
-                        if let Some(ref topic) = app.topic_state.current {
-                            let prev_len = app.topic_state.replies.len();
-                            app.load_topic_replies(client, topic.id, true).await;
-                            if app.topic_state.replies.len() > prev_len {
-                                // New replies loaded, move to next
-                                app.topic_state.selected_reply = prev_len;
-                                app.topic_state.replies_list_state.select(Some(prev_len));
-                                app.topic_state.detect_links(app.terminal_width);
-                            } else {
-                                // No more replies to load, stay at current position
-                                app.ui_state.status_message =
-                                    "Already at the last reply".to_string();
-                            }
-                        }
-                    } else if at_last && !has_more {
-                        // At the last reply and no more to load
-                        app.ui_state.status_message = "Already at the last reply".to_string();
-                    } else {
-                        app.topic_state.next_reply(app.terminal_width);
-                    }
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    // At the end with more replies available, load more first
+                    app.next_reply_or_load(client);
                 } else {
                     app.topic_state.scroll_down();
                 }
                 Ok(false)
             }
             KeyCode::Down => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    let at_last =
-                        app.topic_state.selected_reply + 1 >= app.topic_state.replies.len();
-                    let has_more = app.topic_state.replies.len()
-                        < app
-                            .topic_state
-                            .current
-                            .as_ref()
-                            .map(|t| t.replies as usize)
-                            .unwrap_or(0);
-                    if at_last && has_more {
-                        // At the end with more replies available, load more
-                        if let Some(ref topic) = app.topic_state.current {
-                            let prev_len = app.topic_state.replies.len();
-                            app.load_topic_replies(client, topic.id, true).await;
-                            if app.topic_state.replies.len() > prev_len {
-                                // New replies loaded, move to next
-                                app.topic_state.selected_reply = prev_len;
-                                app.topic_state.replies_list_state.select(Some(prev_len));
-                                app.topic_state.detect_links(app.terminal_width);
-                            } else {
-                                // No more replies to load, stay at current position
-                                app.ui_state.status_message =
-                                    "Already at the last reply".to_string();
-                            }
-                        }
-                    } else if at_last && !has_more {
-                        // At the last reply and no more to load
-                        app.ui_state.status_message = "Already at the last reply".to_string();
-                    } else {
-                        app.topic_state.next_reply(app.terminal_width);
-                    }
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    // At the end with more replies available, load more first
+                    app.next_reply_or_load(client);
                 } else {
                     app.topic_state.scroll_down();
                 }
@@ -469,49 +1490,20 @@ impl KeyMap for TopicDetailKeyMap {
             }
             KeyCode::Char(' ') => {
                 // SPC: Scroll down (same as n/Down)
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    let at_last =
-                        app.topic_state.selected_reply + 1 >= app.topic_state.replies.len();
-                    let has_more = app.topic_state.replies.len()
-                        < app
-                            .topic_state
-                            .current
-                            .as_ref()
-                            .map(|t| t.replies as usize)
-                            .unwrap_or(0);
-                    if at_last && has_more {
-                        // At the end with more replies available, load more
-                        if let Some(ref topic) = app.topic_state.current {
-                            let prev_len = app.topic_state.replies.len();
-                            app.load_topic_replies(client, topic.id, true).await;
-                            if app.topic_state.replies.len() > prev_len {
-                                // New replies loaded, move to next
-                                app.topic_state.selected_reply = prev_len;
-                                app.topic_state.replies_list_state.select(Some(prev_len));
-                                app.topic_state.detect_links(app.terminal_width);
-                            } else {
-                                // No more replies to load, stay at current position
-                                app.ui_state.status_message =
-                                    "Already at the last reply".to_string();
-                            }
-                        }
-                    } else if at_last && !has_more {
-                        // At the last reply and no more to load
-                        app.ui_state.status_message = "Already at the last reply".to_string();
-                    } else {
-                        app.topic_state.next_reply(app.terminal_width);
-                    }
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    // At the end with more replies available, load more first
+                    app.next_reply_or_load(client);
                 } else {
                     app.topic_state.scroll_down();
                 }
                 Ok(false)
             }
             KeyCode::Char('p') => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    if app.topic_state.selected_reply == 0 {
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    if !app.topic_state.has_previous_reply() {
                         app.ui_state.status_message = "Already at the first reply".to_string();
                     } else {
-                        app.topic_state.previous_reply(app.terminal_width);
+                        app.topic_state.previous_reply();
                     }
                 } else {
                     app.topic_state.scroll_up();
@@ -519,11 +1511,11 @@ impl KeyMap for TopicDetailKeyMap {
                 Ok(false)
             }
             KeyCode::Up => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    if app.topic_state.selected_reply == 0 {
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    if !app.topic_state.has_previous_reply() {
                         app.ui_state.status_message = "Already at the first reply".to_string();
                     } else {
-                        app.topic_state.previous_reply(app.terminal_width);
+                        app.topic_state.previous_reply();
                     }
                 } else {
                     app.topic_state.scroll_up();
@@ -534,90 +1526,199 @@ impl KeyMap for TopicDetailKeyMap {
                 // Already in topic detail, no-op
                 Ok(false)
             }
+            KeyCode::Char('c') => {
+                app.open_reply_composer();
+                Ok(false)
+            }
+            KeyCode::Char('W') => {
+                app.copy_current_url(client);
+                Ok(false)
+            }
+            KeyCode::Char('Y') => {
+                app.copy_topic_url(client);
+                Ok(false)
+            }
+            KeyCode::Char('y') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.copy_reply_url(client);
+                Ok(false)
+            }
+            KeyCode::Char('C') => {
+                app.open_code_picker();
+                Ok(false)
+            }
+            KeyCode::Char('R') => {
+                app.toggle_read_aloud();
+                Ok(false)
+            }
+            KeyCode::Char('L') => {
+                app.translate_selected();
+                Ok(false)
+            }
+            KeyCode::Char('z') => {
+                app.ui_state.focus_mode = !app.ui_state.focus_mode;
+                Ok(false)
+            }
+            KeyCode::Char('B') => {
+                app.toggle_reveal_blocked();
+                Ok(false)
+            }
+            // Only the start of the C-w window commands, so it doesn't copy
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => Ok(false),
             KeyCode::Char('w') => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
                     app.copy_selected_reply_to_clipboard();
                 } else if let Some(topic) = app.topic_state.current.clone() {
                     app.copy_topic_content_to_clipboard(&topic);
                 }
                 Ok(false)
             }
+            KeyCode::Char('U') => {
+                app.open_author_profile(client);
+                Ok(false)
+            }
+            KeyCode::Char('F') => {
+                app.toggle_favorite(client);
+                Ok(false)
+            }
+            KeyCode::Char('T') => {
+                app.thank(client);
+                Ok(false)
+            }
+            KeyCode::Char('E') => {
+                app.export_topic_text(client);
+                Ok(false)
+            }
             KeyCode::Char('f') => {
-                app.topic_state
-                    .enter_link_selection_mode(app.terminal_width);
+                app.topic_state.enter_link_selection_mode();
                 app.ui_state.status_message =
                     "Link mode: press a/o/e/u/i/d/h/t/n/s (home row), Esc/Ctrl+g to cancel"
                         .to_string();
                 Ok(false)
             }
             KeyCode::Char('g') => {
-                if let Some(ref topic) = app.topic_state.current {
-                    let topic_id = topic.id;
-                    app.load_topic_detail(client, topic_id).await;
-                    app.load_topic_replies(client, topic_id, false).await;
-                }
+                app.refresh_topic(client);
                 Ok(false)
             }
             KeyCode::Char('a') => {
-                app.load_aggregate(client).await;
+                app.load_aggregate(client);
                 app.navigate_to(View::Aggregate);
                 Ok(false)
             }
             KeyCode::Char('m') => {
-                app.load_notifications(client).await;
+                app.load_notifications(client);
                 app.navigate_to(View::Notifications);
                 Ok(false)
             }
             KeyCode::Char('u') => {
-                app.load_profile(client).await;
+                app.load_profile(client);
                 app.navigate_to(View::Profile);
                 Ok(false)
             }
             KeyCode::Char('s') => {
-                app.node_state.completion_input.clear();
-                app.node_state.completion_cursor = 0;
-                app.node_state.is_completion_mode = true;
+                app.node_state.start_completion();
                 app.node_state.update_suggestions();
                 app.navigate_to(View::NodeSelect);
                 Ok(false)
             }
             KeyCode::Char('t') => {
-                app.topic_state.show_replies = !app.topic_state.show_replies;
-                app.topic_state.reset_scroll();
+                app.topic_state.toggle_replies();
+                Ok(false)
+            }
+            KeyCode::Char('.') => {
+                app.jump_to_first_unread(client);
+                Ok(false)
+            }
+            KeyCode::Char('S') => {
+                app.topic_state.cycle_reply_sort();
+                app.topic_state.show_replies = true;
+                app.ui_state.status_message = match app.topic_state.reply_sort {
+                    ReplySort::Time => "Replies in floor order".to_string(),
+                    ReplySort::Thanks => "Replies sorted by thanks".to_string(),
+                };
+                Ok(false)
+            }
+            KeyCode::Char('O') => {
+                app.cycle_reply_filter(client);
+                Ok(false)
+            }
+            KeyCode::Char('D') => {
+                app.export_topic(client);
+                Ok(false)
+            }
+            KeyCode::Char('M') => {
+                if let Some(topic) = app.topic_state.current.as_ref() {
+                    let tags = app
+                        .bookmarks
+                        .get(topic.id)
+                        .map(|bookmark| bookmark.tags.clone())
+                        .unwrap_or_default();
+                    let prompt = tag_prompt(&tags);
+                    app.ui_state.status_message = prompt.text();
+                    app.topic_state.tag_input = Some(prompt);
+                }
+                Ok(false)
+            }
+            KeyCode::Char('G') => {
+                if app.topic_state.current.is_some() {
+                    let mut prompt = jump_prompt();
+                    app.prompt_history.restore(&mut prompt);
+                    app.ui_state.status_message = prompt.text();
+                    app.topic_state.jump_input = Some(prompt);
+                }
+                Ok(false)
+            }
+            KeyCode::Char('j') => {
+                if !app.topic_state.show_replies {
+                    app.ui_state.status_message = "Press t to show replies first".to_string();
+                } else if let Some(index) = app.topic_state.jump_to_quoted_reply() {
+                    app.ui_state.status_message =
+                        format!("Jumped to quoted reply #{} (J to go back)", index + 1);
+                } else {
+                    app.ui_state.status_message =
+                        "This reply doesn't quote an earlier reply".to_string();
+                }
+                Ok(false)
+            }
+            KeyCode::Char('J') => {
+                if let Some(index) = app.topic_state.jump_back_reply() {
+                    app.ui_state.status_message = format!("Back to reply #{}", index + 1);
+                } else {
+                    app.ui_state.status_message = "No quote jump to return from".to_string();
+                }
                 Ok(false)
             }
             KeyCode::Char('o') => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    app.open_selected_reply_in_browser();
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    app.open_selected_reply_in_browser(client);
                 } else {
-                    app.open_current_topic_in_browser();
+                    app.open_current_topic_in_browser(client);
                 }
                 Ok(false)
             }
             KeyCode::Char('N') => {
-                app.switch_to_next_topic(client).await;
+                app.switch_to_next_topic(client);
                 Ok(false)
             }
             KeyCode::Char('P') => {
-                app.switch_to_previous_topic(client).await;
+                app.switch_to_previous_topic(client);
                 Ok(false)
             }
             KeyCode::PageDown => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    app.topic_state.selected_reply =
-                        (app.topic_state.selected_reply + 5).min(app.topic_state.replies.len() - 1);
+                if app.ui_state.focus_mode && app.topic_state.focused_reply().is_some() {
+                    // The list is hidden, so page through the reply itself
+                    app.topic_state.scroll_focus(15);
+                } else if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    app.topic_state.move_reply_selection(5);
                 } else {
                     app.topic_state.scroll += 15;
                 }
                 Ok(false)
             }
             KeyCode::PageUp => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    if app.topic_state.selected_reply >= 5 {
-                        app.topic_state.selected_reply -= 5;
-                    } else {
-                        app.topic_state.selected_reply = 0;
-                    }
+                if app.ui_state.focus_mode && app.topic_state.focused_reply().is_some() {
+                    app.topic_state.scroll_focus(-15);
+                } else if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    app.topic_state.move_reply_selection(-5);
                 } else if app.topic_state.scroll >= 15 {
                     app.topic_state.scroll -= 15;
                 } else {
@@ -628,9 +1729,11 @@ impl KeyMap for TopicDetailKeyMap {
             // Emacs-style page navigation
             KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // C-v: Page down (scroll down faster)
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    app.topic_state.selected_reply =
-                        (app.topic_state.selected_reply + 5).min(app.topic_state.replies.len() - 1);
+                if app.ui_state.focus_mode && app.topic_state.focused_reply().is_some() {
+                    // The list is hidden, so page through the reply itself
+                    app.topic_state.scroll_focus(15);
+                } else if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    app.topic_state.move_reply_selection(5);
                 } else {
                     app.topic_state.scroll += 15;
                 }
@@ -638,12 +1741,10 @@ impl KeyMap for TopicDetailKeyMap {
             }
             KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::ALT) => {
                 // M-v: Page up (scroll up faster)
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    if app.topic_state.selected_reply >= 5 {
-                        app.topic_state.selected_reply -= 5;
-                    } else {
-                        app.topic_state.selected_reply = 0;
-                    }
+                if app.ui_state.focus_mode && app.topic_state.focused_reply().is_some() {
+                    app.topic_state.scroll_focus(-15);
+                } else if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    app.topic_state.move_reply_selection(-5);
                 } else if app.topic_state.scroll >= 15 {
                     app.topic_state.scroll -= 15;
                 } else {
@@ -654,10 +1755,10 @@ impl KeyMap for TopicDetailKeyMap {
             KeyCode::Char('+') => {
                 if app.topic_state.show_replies {
                     if let Some(ref topic) = app.topic_state.current {
-                        let loaded_replies = app.topic_state.replies.len();
+                        let loaded_replies = app.topic_state.replies().len();
                         let total_replies = topic.replies as usize;
                         if loaded_replies < total_replies {
-                            app.load_topic_replies(client, topic.id, true).await;
+                            app.load_topic_replies(client, topic.id, true);
                         } else {
                             app.ui_state.status_message = "No more replies to load".to_string();
                         }
@@ -666,18 +1767,14 @@ impl KeyMap for TopicDetailKeyMap {
                 Ok(false)
             }
             KeyCode::Char('<') => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    app.topic_state.selected_reply = 0;
-                    app.topic_state.replies_list_state.select(Some(0));
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    app.topic_state.select_reply_at(0);
                 }
                 Ok(false)
             }
             KeyCode::Char('>') => {
-                if app.topic_state.show_replies && !app.topic_state.replies.is_empty() {
-                    app.topic_state.selected_reply = app.topic_state.replies.len() - 1;
-                    app.topic_state
-                        .replies_list_state
-                        .select(Some(app.topic_state.selected_reply));
+                if app.topic_state.show_replies && !app.topic_state.replies().is_empty() {
+                    app.topic_state.select_reply_at(usize::MAX);
                 }
                 Ok(false)
             }
@@ -689,14 +1786,25 @@ impl KeyMap for TopicDetailKeyMap {
 /// Notifications view key mapping
 pub struct NotificationsKeyMap;
 
-impl NotificationsKeyMap {
-    pub fn new() -> Self {
-        Self
+impl KeyMap for NotificationsKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Notifications",
+            view: Some(View::Notifications),
+            keys: &[
+                ("load-more", "Load more notifications (also PageDown)"),
+                ("toggle-read", "Toggle read/unread (unread is bold)"),
+                ("d d", "Delete the notification"),
+                ("open-in-browser", "Open in the browser"),
+            ],
+        }]
     }
-}
 
-impl KeyMap for NotificationsKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        // Deleting takes `d` twice in a row
+        if key.code != KeyCode::Char('d') {
+            app.notification_state.delete_pending = None;
+        }
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Exit app immediately
@@ -752,12 +1860,10 @@ impl KeyMap for NotificationsKeyMap {
                 {
                     let topic_id = notification.extract_topic_id();
                     let reply_id = notification.extract_reply_id();
+                    app.mark_notification_read();
 
                     if let Some(topic_id) = topic_id {
-                        app.topic_state.show_replies = false;
-                        app.load_topic_detail(client, topic_id).await;
-                        app.load_topic_replies(client, topic_id, false).await;
-                        app.navigate_to(View::TopicDetail);
+                        app.open_topic(client, topic_id);
 
                         if let Some(reply_id) = reply_id {
                             app.ui_state.status_message =
@@ -773,34 +1879,44 @@ impl KeyMap for NotificationsKeyMap {
                 Ok(false)
             }
             KeyCode::Char('g') => {
-                app.load_notifications(client).await;
+                app.load_notifications(client);
                 Ok(false)
             }
             KeyCode::Char('a') => {
-                app.load_aggregate(client).await;
+                app.load_aggregate(client);
                 app.navigate_to(View::Aggregate);
                 Ok(false)
             }
             KeyCode::Char('m') => {
                 // Already in notifications, refresh
-                app.load_notifications(client).await;
+                app.load_notifications(client);
                 Ok(false)
             }
             KeyCode::Char('u') => {
-                app.load_profile(client).await;
+                app.load_profile(client);
                 app.navigate_to(View::Profile);
                 Ok(false)
             }
             KeyCode::Char('s') => {
-                app.node_state.completion_input.clear();
-                app.node_state.completion_cursor = 0;
-                app.node_state.is_completion_mode = true;
+                app.node_state.start_completion();
                 app.node_state.update_suggestions();
                 app.navigate_to(View::NodeSelect);
                 Ok(false)
             }
             KeyCode::Char('o') => {
-                app.open_notification_in_browser();
+                app.open_notification_in_browser(client);
+                Ok(false)
+            }
+            KeyCode::Char('x') => {
+                app.toggle_notification_read();
+                Ok(false)
+            }
+            KeyCode::Char('d') => {
+                app.delete_selected_notification(client);
+                Ok(false)
+            }
+            KeyCode::Char('+') | KeyCode::PageDown => {
+                app.load_more_notifications(client);
                 Ok(false)
             }
             KeyCode::Char('<') => {
@@ -841,14 +1957,16 @@ impl KeyMap for NotificationsKeyMap {
 /// Profile view key mapping
 pub struct ProfileKeyMap;
 
-impl ProfileKeyMap {
-    pub fn new() -> Self {
-        Self
+impl KeyMap for ProfileKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Profile",
+            view: Some(View::Profile),
+            keys: &[("refresh", "Reload your profile (also u)")],
+        }]
     }
-}
 
-impl KeyMap for ProfileKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Exit app immediately
@@ -876,28 +1994,26 @@ impl KeyMap for ProfileKeyMap {
                 Ok(false)
             }
             KeyCode::Char('g') => {
-                app.load_profile(client).await;
+                app.load_profile(client);
                 Ok(false)
             }
             KeyCode::Char('a') => {
-                app.load_aggregate(client).await;
+                app.load_aggregate(client);
                 app.navigate_to(View::Aggregate);
                 Ok(false)
             }
             KeyCode::Char('m') => {
-                app.load_notifications(client).await;
+                app.load_notifications(client);
                 app.navigate_to(View::Notifications);
                 Ok(false)
             }
             KeyCode::Char('u') => {
                 // Already in profile, refresh
-                app.load_profile(client).await;
+                app.load_profile(client);
                 Ok(false)
             }
             KeyCode::Char('s') => {
-                app.node_state.completion_input.clear();
-                app.node_state.completion_cursor = 0;
-                app.node_state.is_completion_mode = true;
+                app.node_state.start_completion();
                 app.node_state.update_suggestions();
                 app.navigate_to(View::NodeSelect);
                 Ok(false)
@@ -910,14 +2026,8 @@ impl KeyMap for ProfileKeyMap {
 /// Help view key mapping
 pub struct HelpKeyMap;
 
-impl HelpKeyMap {
-    pub fn new() -> Self {
-        Self
-    }
-}
-
 impl KeyMap for HelpKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, _client: &V2exClient) -> Result<bool> {
+    fn handle_key(&self, app: &mut App, key: KeyEvent, _client: &V2exClient) -> Result<bool> {
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Exit app immediately
@@ -953,30 +2063,86 @@ impl KeyMap for HelpKeyMap {
 pub struct NodeSelectKeyMap;
 
 impl NodeSelectKeyMap {
-    pub fn new() -> Self {
-        Self
+    /// Keys while typing a node name; C-n/C-p move through the matches
+    fn handle_completion_key(
+        &self,
+        app: &mut App,
+        key: KeyEvent,
+        client: &V2exClient,
+    ) -> Result<bool> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => return Ok(true),
+            KeyCode::Char('n') if ctrl => {
+                app.node_state.next_node();
+                return Ok(false);
+            }
+            KeyCode::Char('p') if ctrl => {
+                app.node_state.previous_node();
+                return Ok(false);
+            }
+            _ => {}
+        }
+        match app.node_state.prompt.handle_key(key) {
+            MinibufferEvent::Edited => app.node_state.update_suggestions(),
+            MinibufferEvent::Submit(_) => {
+                if let Err(e) = app.prompt_history.remember(&app.node_state.prompt) {
+                    app.ui_state.error = Some(format!("Failed to save prompt history: {}", e));
+                }
+                self.open_selected_node(app, client);
+            }
+            MinibufferEvent::Cancel => {
+                // Remove current view from history, exit if empty
+                if app.remove_current_from_history().is_none() {
+                    return Ok(true);
+                }
+            }
+            MinibufferEvent::Invalid(message) => app.ui_state.status_message = message,
+            MinibufferEvent::Ignored if key.code == KeyCode::Tab => {
+                app.node_state.toggle_completion_mode();
+            }
+            MinibufferEvent::Moved | MinibufferEvent::Ignored => {}
+        }
+        Ok(false)
+    }
+
+    fn open_selected_node(&self, app: &mut App, client: &V2exClient) {
+        let node = app.node_state.selected_node();
+        app.node_state.reset_selection();
+        match node {
+            Some(node) => app.switch_to_node(client, &node),
+            None => app.load_topics(client, false),
+        }
+        app.navigate_to(View::TopicList);
     }
 }
 
 impl KeyMap for NodeSelectKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![
+            HelpSection {
+                title: "Node Selection",
+                view: Some(View::NodeSelect),
+                keys: &[("TAB", "Type a node name instead")],
+            },
+            HelpSection {
+                title: "Node Name Input",
+                view: Some(View::NodeSelect),
+                keys: NODE_COMPLETION_HINTS,
+            },
+        ]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        if app.node_state.is_completion_mode {
+            return self.handle_completion_key(app, key, client);
+        }
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Exit app immediately
                 return Ok(true);
             }
-            KeyCode::Char('q') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('q');
-                } else {
-                    // Remove current view from history, exit if empty
-                    if app.remove_current_from_history().is_none() {
-                        return Ok(true);
-                    }
-                }
-                Ok(false)
-            }
-            KeyCode::Esc => {
+            KeyCode::Char('q') | KeyCode::Esc => {
                 // Remove current view from history, exit if empty
                 if app.remove_current_from_history().is_none() {
                     return Ok(true);
@@ -984,174 +2150,333 @@ impl KeyMap for NodeSelectKeyMap {
                 Ok(false)
             }
             KeyCode::Char('?') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('?');
-                } else {
-                    app.navigate_to(View::Help);
-                }
+                app.navigate_to(View::Help);
                 Ok(false)
             }
             KeyCode::Char('l') | KeyCode::Left => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('l');
-                } else {
-                    // History back
-                    app.history_back();
-                }
+                app.history_back();
                 Ok(false)
             }
             KeyCode::Char('r') | KeyCode::Right => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('r');
-                } else {
-                    // History forward
-                    app.history_forward();
-                }
+                app.history_forward();
                 Ok(false)
             }
             KeyCode::Enter => {
-                if app.node_state.is_completion_mode {
-                    // In completion mode, Enter selects the node
-                    app.node_state.select_current_node();
-                    app.node_state.reset_selection();
-                    app.load_topics(client, false).await;
-                    app.navigate_to(View::TopicList);
-                } else {
-                    // Not in completion mode, Enter also selects
-                    app.node_state.select_current_node();
-                    app.node_state.reset_selection();
-                    app.load_topics(client, false).await;
-                    app.navigate_to(View::TopicList);
-                }
+                self.open_selected_node(app, client);
                 Ok(false)
             }
-            KeyCode::Char('n') => {
-                if key.modifiers.contains(KeyModifiers::CONTROL) {
-                    app.node_state.next_node();
-                } else if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('n');
-                } else {
-                    app.node_state.next_node();
-                }
+            KeyCode::Char('n') | KeyCode::Down | KeyCode::Char(' ') => {
+                app.node_state.next_node();
                 Ok(false)
             }
-            KeyCode::Down => {
-                if !app.node_state.is_completion_mode {
-                    app.node_state.next_node();
-                }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.node_state.previous_node();
                 Ok(false)
             }
-            KeyCode::Char(' ') => {
-                // SPC: Scroll down (same as n/Down)
-                if !app.node_state.is_completion_mode {
-                    app.node_state.next_node();
-                }
+            KeyCode::Char('a') => {
+                app.load_aggregate(client);
+                app.navigate_to(View::Aggregate);
                 Ok(false)
             }
-            KeyCode::Char('p') => {
-                if key.modifiers.contains(KeyModifiers::CONTROL) {
-                    app.node_state.previous_node();
-                } else if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('p');
-                } else {
-                    app.node_state.previous_node();
-                }
+            KeyCode::Char('m') => {
+                app.load_notifications(client);
+                app.navigate_to(View::Notifications);
                 Ok(false)
             }
-            KeyCode::Up => {
-                if !app.node_state.is_completion_mode {
-                    app.node_state.previous_node();
-                }
+            KeyCode::Char('u') => {
+                app.load_profile(client);
+                app.navigate_to(View::Profile);
                 Ok(false)
             }
-            KeyCode::Char('g') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('g');
-                }
+            KeyCode::Char('s') | KeyCode::Tab => {
+                app.node_state.toggle_completion_mode();
                 Ok(false)
             }
-            KeyCode::Char('a') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('a');
-                } else {
-                    app.load_aggregate(client).await;
-                    app.navigate_to(View::Aggregate);
+            // Emacs-style page navigation
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // C-v: Page down (move down 5 nodes)
+                for _ in 0..5 {
+                    app.node_state.next_node();
                 }
                 Ok(false)
             }
-            KeyCode::Char('m') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('m');
-                } else {
-                    app.load_notifications(client).await;
-                    app.navigate_to(View::Notifications);
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::ALT) => {
+                // M-v: Page up (move up 5 nodes)
+                for _ in 0..5 {
+                    app.node_state.previous_node();
                 }
                 Ok(false)
             }
-            KeyCode::Char('u') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('u');
-                } else {
-                    app.load_profile(client).await;
-                    app.navigate_to(View::Profile);
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Visited topics key mapping; typing searches, C-n/C-p move
+pub struct VisitedTopicsKeyMap;
+
+impl KeyMap for VisitedTopicsKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Visited Topics",
+            view: Some(View::History),
+            keys: VISITED_TOPICS_HINTS,
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => return Ok(true),
+            KeyCode::Char('n') if ctrl => {
+                app.visited_state.next();
+                return Ok(false);
+            }
+            KeyCode::Char('p') if ctrl => {
+                app.visited_state.previous();
+                return Ok(false);
+            }
+            KeyCode::Down => {
+                app.visited_state.next();
+                return Ok(false);
+            }
+            KeyCode::Up => {
+                app.visited_state.previous();
+                return Ok(false);
+            }
+            _ => {}
+        }
+        match app.visited_state.prompt.handle_key(key) {
+            MinibufferEvent::Edited => app
+                .visited_state
+                .update_matches(app.visited_topics.entries()),
+            MinibufferEvent::Submit(_) => {
+                let topic_id = app
+                    .visited_state
+                    .selected_entry()
+                    .map(|i| app.visited_topics.entries()[i].id);
+                match topic_id {
+                    Some(topic_id) => app.open_topic(client, topic_id),
+                    None => app.ui_state.status_message = "No matching topic".to_string(),
+                }
+            }
+            MinibufferEvent::Cancel => {
+                // Remove current view from history, exit if empty
+                if app.remove_current_from_history().is_none() {
+                    return Ok(true);
                 }
-                Ok(false)
             }
-            KeyCode::Char('s') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('s');
-                } else {
-                    app.node_state.toggle_completion_mode();
+            MinibufferEvent::Invalid(message) => app.ui_state.status_message = message,
+            MinibufferEvent::Moved | MinibufferEvent::Ignored => {}
+        }
+        Ok(false)
+    }
+}
+
+/// Bookmarks view key mapping
+pub struct BookmarksKeyMap;
+
+impl KeyMap for BookmarksKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Bookmarks",
+            view: Some(View::Bookmarks),
+            keys: &[
+                ("t", "Filter by the next tag (then all again)"),
+                ("d", "Delete the bookmark"),
+                ("E", "Export the shown bookmarks as Markdown"),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        let len = app.visible_bookmarks().len();
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Exit app immediately
+                Ok(true)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                // Remove current view from history, exit if empty
+                if app.remove_current_from_history().is_none() {
+                    return Ok(true);
                 }
                 Ok(false)
             }
-            KeyCode::Tab => {
-                app.node_state.toggle_completion_mode();
+            KeyCode::Char('?') => {
+                app.navigate_to(View::Help);
                 Ok(false)
             }
-            KeyCode::Char('t') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('t');
-                }
+            KeyCode::Char('l') | KeyCode::Left => {
+                app.history_back();
                 Ok(false)
             }
-            KeyCode::Char('o') => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char('o');
-                }
+            KeyCode::Char('r') | KeyCode::Right => {
+                app.history_forward();
                 Ok(false)
             }
-            // Emacs-style page navigation
-            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // C-v: Page down (move down 5 nodes)
-                if !app.node_state.is_completion_mode {
-                    for _ in 0..5 {
-                        app.node_state.next_node();
-                    }
+            KeyCode::Char('n') | KeyCode::Down | KeyCode::Char(' ') => {
+                if app.bookmarks_state.selected + 1 < len {
+                    app.bookmarks_state.selected += 1;
                 }
                 Ok(false)
             }
-            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // M-v: Page up (move up 5 nodes)
-                if !app.node_state.is_completion_mode {
-                    for _ in 0..5 {
-                        app.node_state.previous_node();
-                    }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.bookmarks_state.selected = app.bookmarks_state.selected.saturating_sub(1);
+                Ok(false)
+            }
+            KeyCode::Char('<') => {
+                app.bookmarks_state.selected = 0;
+                Ok(false)
+            }
+            KeyCode::Char('>') => {
+                app.bookmarks_state.selected = len.saturating_sub(1);
+                Ok(false)
+            }
+            KeyCode::Enter => {
+                if let Some(topic_id) = app.selected_bookmark_id() {
+                    app.open_topic(client, topic_id);
                 }
                 Ok(false)
             }
-            KeyCode::Backspace => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.delete_char();
+            KeyCode::Char('t') => {
+                let tags = app.bookmarks.tags();
+                app.bookmarks_state.cycle_tag(&tags);
+                Ok(false)
+            }
+            KeyCode::Char('d') => {
+                app.remove_selected_bookmark();
+                Ok(false)
+            }
+            KeyCode::Char('E') => {
+                app.export_bookmarks();
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Token manager key mapping
+pub struct TokensKeyMap;
+
+impl TokensKeyMap {
+    /// Keys for the add prompt: the name, then the token itself, which is
+    /// not echoed
+    fn handle_add_input(&self, app: &mut App, key: KeyEvent) -> Result<bool> {
+        let Some((name, mut prompt)) = app.token_state.adding.take() else {
+            return Ok(false);
+        };
+        match (prompt.handle_key(key), name) {
+            (MinibufferEvent::Cancel, _) => {
+                app.ui_state.status_message = "Token not added".to_string();
+            }
+            (MinibufferEvent::Submit(input), None) => {
+                let name = input.trim().to_string();
+                if name.is_empty() {
+                    app.ui_state.status_message = "Token not added".to_string();
+                } else {
+                    app.ui_state.status_message = format!("Token for '{}': ", name);
+                    let prompt = Minibuffer::new(&format!("Token for '{}': ", name));
+                    app.token_state.adding = Some((Some(name), prompt));
+                }
+            }
+            (MinibufferEvent::Submit(token), Some(name)) => app.add_token(&name, &token),
+            (_, None) => {
+                app.ui_state.status_message = prompt.text();
+                app.token_state.adding = Some((None, prompt));
+            }
+            (_, Some(name)) => {
+                app.ui_state.status_message = format!(
+                    "Token for '{}': {}",
+                    name,
+                    "*".repeat(prompt.input().chars().count())
+                );
+                app.token_state.adding = Some((Some(name), prompt));
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl KeyMap for TokensKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Tokens",
+            view: Some(View::Tokens),
+            keys: &[
+                ("RET", "Switch to the token's account"),
+                ("a", "Add a token (name, then token)"),
+                ("d", "Remove the token"),
+                ("g", "Check scope and expiry again"),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        if app.token_state.adding.is_some() {
+            return self.handle_add_input(app, key);
+        }
+        let len = app.token_state.store.tokens().len();
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Exit app immediately
+                Ok(true)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                // Remove current view from history, exit if empty
+                if app.remove_current_from_history().is_none() {
+                    return Ok(true);
                 }
                 Ok(false)
             }
-            KeyCode::Char(ch) => {
-                if app.node_state.is_completion_mode {
-                    app.node_state.insert_char(ch);
+            KeyCode::Char('?') => {
+                app.navigate_to(View::Help);
+                Ok(false)
+            }
+            KeyCode::Char('l') | KeyCode::Left => {
+                app.history_back();
+                Ok(false)
+            }
+            KeyCode::Char('r') | KeyCode::Right => {
+                app.history_forward();
+                Ok(false)
+            }
+            KeyCode::Char('n') | KeyCode::Down => {
+                if app.token_state.selected + 1 < len {
+                    app.token_state.selected += 1;
                 }
                 Ok(false)
             }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.token_state.selected = app.token_state.selected.saturating_sub(1);
+                Ok(false)
+            }
+            KeyCode::Char('<') => {
+                app.token_state.selected = 0;
+                Ok(false)
+            }
+            KeyCode::Char('>') => {
+                app.token_state.selected = len.saturating_sub(1);
+                Ok(false)
+            }
+            KeyCode::Enter => {
+                app.switch_token(client);
+                Ok(false)
+            }
+            KeyCode::Char('a') => {
+                app.token_state.start_adding();
+                app.ui_state.status_message = "Name for the new token: ".to_string();
+                Ok(false)
+            }
+            KeyCode::Char('d') => {
+                app.remove_selected_token();
+                Ok(false)
+            }
+            KeyCode::Char('g') => {
+                app.check_tokens(client);
+                Ok(false)
+            }
             _ => Ok(false),
         }
     }
@@ -1160,14 +2485,23 @@ impl KeyMap for NodeSelectKeyMap {
 /// Aggregate view key mapping
 pub struct AggregateKeyMap;
 
-impl AggregateKeyMap {
-    pub fn new() -> Self {
-        Self
+impl KeyMap for AggregateKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Aggregated Topics",
+            view: Some(View::Aggregate),
+            keys: &[
+                ("t c k", "Tech / creative / play tab"),
+                ("a j d", "Apple / jobs / deals tab"),
+                ("y z i", "City / qna / index tab"),
+                ("h e", "Site-wide hot / latest topics"),
+                ("open-in-browser", "Open in the browser"),
+                ("toggle-read", "Toggle read/unread"),
+            ],
+        }]
     }
-}
 
-impl KeyMap for AggregateKeyMap {
-    async fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Exit app immediately
@@ -1245,10 +2579,7 @@ impl KeyMap for AggregateKeyMap {
                     let topic_id = item.extract_topic_id();
 
                     if let Some(topic_id) = topic_id {
-                        app.topic_state.show_replies = false;
-                        app.load_topic_detail(client, topic_id).await;
-                        app.load_topic_replies(client, topic_id, false).await;
-                        app.navigate_to(View::TopicDetail);
+                        app.open_topic(client, topic_id);
                         app.ui_state.status_message =
                             format!("Loading topic {} from RSS", topic_id);
                     } else {
@@ -1260,41 +2591,33 @@ impl KeyMap for AggregateKeyMap {
                 Ok(false)
             }
             KeyCode::Char('g') => {
-                app.load_aggregate(client).await;
-                Ok(false)
-            }
-            KeyCode::Char('a') => {
-                // Switch to apple tab
-                app.switch_aggregate_tab(client, "apple").await;
+                app.load_aggregate(client);
                 Ok(false)
             }
             KeyCode::Char('m') => {
-                app.load_notifications(client).await;
+                app.load_notifications(client);
                 app.navigate_to(View::Notifications);
                 Ok(false)
             }
             KeyCode::Char('u') => {
-                app.load_profile(client).await;
+                app.load_profile(client);
                 app.navigate_to(View::Profile);
                 Ok(false)
             }
             KeyCode::Char('s') => {
-                app.node_state.completion_input.clear();
-                app.node_state.completion_cursor = 0;
-                app.node_state.is_completion_mode = true;
+                app.node_state.start_completion();
                 app.node_state.update_suggestions();
                 app.navigate_to(View::NodeSelect);
                 Ok(false)
             }
-            KeyCode::Char('t') => {
-                // Switch to tech tab
-                app.switch_aggregate_tab(client, "tech").await;
-                Ok(false)
-            }
             KeyCode::Char('o') => {
                 app.open_selected_aggregate_in_browser();
                 Ok(false)
             }
+            KeyCode::Char('x') => {
+                app.toggle_selected_read();
+                Ok(false)
+            }
             // Emacs-style page navigation
             KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // C-v: Page down (move down 5 items)
@@ -1324,82 +2647,1074 @@ impl KeyMap for AggregateKeyMap {
                 Ok(false)
             }
             KeyCode::Char(ch) => {
-                // Handle tab switching
-                let tab = match ch {
-                    'c' => Some("creative"),
-                    'k' => Some("play"),
-                    'j' => Some("jobs"),
-                    'd' => Some("deals"),
-                    'y' => Some("city"),
-                    'z' => Some("qna"),
-                    'i' => Some("index"),
-                    _ => None,
-                };
-                if let Some(tab) = tab {
-                    app.switch_aggregate_tab(client, tab).await;
+                // Tab switching, built-in tabs and `add-feed` ones alike
+                let tab = app
+                    .config
+                    .tab_key_mappings()
+                    .into_iter()
+                    .find(|(key, _)| *key == ch);
+                if let Some((_, tab)) = tab {
+                    app.switch_aggregate_tab(client, &tab);
+                }
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Favorites view key mapping
+pub struct FavoritesKeyMap;
+
+impl KeyMap for FavoritesKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Favorites",
+            view: Some(View::Favorites),
+            keys: &[
+                ("t", "Open the topic"),
+                ("toggle-favorite", "Remove from favorites"),
+                ("open-in-browser", "Open in the browser"),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Exit app immediately
+                Ok(true)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                // Remove current view from history, exit if empty
+                if app.remove_current_from_history().is_none() {
+                    return Ok(true);
+                }
+                Ok(false)
+            }
+            KeyCode::Char('?') => {
+                app.navigate_to(View::Help);
+                Ok(false)
+            }
+            KeyCode::Char('l') | KeyCode::Left => {
+                // History back
+                app.history_back();
+                Ok(false)
+            }
+            KeyCode::Char('r') | KeyCode::Right => {
+                // History forward
+                app.history_forward();
+                Ok(false)
+            }
+            KeyCode::Char('n') | KeyCode::Down | KeyCode::Char(' ') => {
+                app.favorites_state.next();
+                Ok(false)
+            }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.favorites_state.previous();
+                Ok(false)
+            }
+            KeyCode::Enter | KeyCode::Char('t') => {
+                if let Some(topic) = app.favorites_state.topics.get(app.favorites_state.selected) {
+                    let topic_id = topic.id;
+                    app.open_topic(client, topic_id);
+                }
+                Ok(false)
+            }
+            KeyCode::Char('F') => {
+                app.toggle_favorite(client);
+                Ok(false)
+            }
+            KeyCode::Char('o') => {
+                app.open_selected_favorite_in_browser(client);
+                Ok(false)
+            }
+            KeyCode::Char('g') => {
+                app.load_favorites(client);
+                Ok(false)
+            }
+            KeyCode::Char('a') => {
+                app.load_aggregate(client);
+                app.navigate_to(View::Aggregate);
+                Ok(false)
+            }
+            KeyCode::Char('m') => {
+                app.load_notifications(client);
+                app.navigate_to(View::Notifications);
+                Ok(false)
+            }
+            KeyCode::Char('u') => {
+                app.load_profile(client);
+                app.navigate_to(View::Profile);
+                Ok(false)
+            }
+            KeyCode::Char('<') => {
+                app.favorites_state.selected = 0;
+                Ok(false)
+            }
+            KeyCode::Char('>') => {
+                if !app.favorites_state.topics.is_empty() {
+                    app.favorites_state.selected = app.favorites_state.topics.len() - 1;
+                }
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Member profile view key mapping
+pub struct MemberKeyMap;
+
+impl KeyMap for MemberKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Member",
+            view: Some(View::Member),
+            keys: &[
+                ("t", "Open the topic, or the one replied to"),
+                ("TAB", "Switch between recent topics and replies"),
+                ("open-in-browser", "Open the member page in the browser"),
+            ],
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Exit app immediately
+                Ok(true)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                // Remove current view from history, exit if empty
+                if app.remove_current_from_history().is_none() {
+                    return Ok(true);
                 }
                 Ok(false)
             }
+            KeyCode::Char('?') => {
+                app.navigate_to(View::Help);
+                Ok(false)
+            }
+            KeyCode::Char('l') | KeyCode::Left => {
+                // History back
+                app.history_back();
+                Ok(false)
+            }
+            KeyCode::Char('r') | KeyCode::Right => {
+                // History forward
+                app.history_forward();
+                Ok(false)
+            }
+            KeyCode::Char('n') | KeyCode::Down | KeyCode::Char(' ') => {
+                app.member_state.next();
+                Ok(false)
+            }
+            KeyCode::Char('p') | KeyCode::Up => {
+                app.member_state.previous();
+                Ok(false)
+            }
+            KeyCode::Enter | KeyCode::Char('t') => {
+                if let Some(topic_id) = app.member_state.selected_topic_id() {
+                    app.open_topic(client, topic_id);
+                }
+                Ok(false)
+            }
+            KeyCode::Tab => {
+                app.member_state.toggle_list();
+                Ok(false)
+            }
+            KeyCode::Char('g') => {
+                if let Some(username) = app.member_state.member.as_ref().map(|m| m.username.clone())
+                {
+                    app.load_member(client, &username, false);
+                }
+                Ok(false)
+            }
+            KeyCode::Char('o') => {
+                app.open_member_in_browser(client);
+                Ok(false)
+            }
+            KeyCode::Char('<') => {
+                app.member_state.selected = 0;
+                Ok(false)
+            }
+            KeyCode::Char('>') => {
+                app.member_state.selected = app.member_state.shown_len().saturating_sub(1);
+                Ok(false)
+            }
             _ => Ok(false),
         }
     }
 }
 
+/// Reply composer key mapping
+pub struct ReplyInputKeyMap;
+
+impl ReplyInputKeyMap {
+    /// Keys of the @mention popup; false when the key is for the editor
+    fn handle_mention_key(&self, app: &mut App, key: KeyEvent) -> bool {
+        if app.reply_input_state.mention.is_none() {
+            return false;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Tab | KeyCode::Enter => app.reply_input_state.accept_mention(),
+            KeyCode::Down => app.reply_input_state.next_mention(),
+            KeyCode::Char('n') if ctrl => app.reply_input_state.next_mention(),
+            KeyCode::Up => app.reply_input_state.previous_mention(),
+            KeyCode::Char('p') if ctrl => app.reply_input_state.previous_mention(),
+            KeyCode::Esc => app.reply_input_state.mention = None,
+            KeyCode::Char('g') if ctrl => app.reply_input_state.mention = None,
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl KeyMap for ReplyInputKeyMap {
+    fn help(&self) -> Vec<HelpSection> {
+        vec![HelpSection {
+            title: "Reply Editor",
+            view: Some(View::ReplyInput),
+            keys: REPLY_INPUT_HINTS,
+        }]
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent, client: &V2exClient) -> Result<bool> {
+        if self.handle_mention_key(app, key) {
+            return Ok(false);
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let result = match key.code {
+            KeyCode::Char('c') if ctrl => {
+                // Exit app immediately
+                Ok(true)
+            }
+            KeyCode::Esc => {
+                // Keep the draft so reopening the composer resumes it
+                app.remove_current_from_history();
+                app.ui_state.status_message = "Reply draft kept".to_string();
+                Ok(false)
+            }
+            KeyCode::Char('g') if ctrl => {
+                app.remove_current_from_history();
+                app.ui_state.status_message = "Reply draft kept".to_string();
+                Ok(false)
+            }
+            KeyCode::Char('s') if ctrl => {
+                app.submit_reply(client);
+                Ok(false)
+            }
+            KeyCode::Char('e') if ctrl => {
+                // Handled by the event loop, which owns the terminal
+                app.reply_input_state.external_edit_requested = true;
+                Ok(false)
+            }
+            KeyCode::Char('a') if ctrl => {
+                app.reply_input_state.move_line_start();
+                Ok(false)
+            }
+            KeyCode::Char('b') if ctrl => {
+                app.reply_input_state.move_cursor_left();
+                Ok(false)
+            }
+            KeyCode::Char('f') if ctrl => {
+                app.reply_input_state.move_cursor_right();
+                Ok(false)
+            }
+            KeyCode::Char('p') if ctrl => {
+                app.reply_input_state.move_cursor_up();
+                Ok(false)
+            }
+            KeyCode::Char('n') if ctrl => {
+                app.reply_input_state.move_cursor_down();
+                Ok(false)
+            }
+            KeyCode::Enter => {
+                app.reply_input_state.insert_newline();
+                Ok(false)
+            }
+            KeyCode::Backspace => {
+                app.reply_input_state.delete_char();
+                Ok(false)
+            }
+            KeyCode::Left => {
+                app.reply_input_state.move_cursor_left();
+                Ok(false)
+            }
+            KeyCode::Right => {
+                app.reply_input_state.move_cursor_right();
+                Ok(false)
+            }
+            KeyCode::Up => {
+                app.reply_input_state.move_cursor_up();
+                Ok(false)
+            }
+            KeyCode::Down => {
+                app.reply_input_state.move_cursor_down();
+                Ok(false)
+            }
+            KeyCode::Home => {
+                app.reply_input_state.move_line_start();
+                Ok(false)
+            }
+            KeyCode::End => {
+                app.reply_input_state.move_line_end();
+                Ok(false)
+            }
+            KeyCode::Char(ch) if !ctrl => {
+                app.reply_input_state.insert_char(ch);
+                Ok(false)
+            }
+            _ => Ok(false),
+        };
+        app.reply_input_state.update_mention();
+        result
+    }
+}
+
 /// Main event handler that routes to appropriate key map
 pub struct EventHandler<'a> {
     client: &'a V2exClient,
     link_map: LinkSelectionKeyMap,
-    topic_list_map: TopicListKeyMap,
-    topic_detail_map: TopicDetailKeyMap,
-    notifications_map: NotificationsKeyMap,
-    profile_map: ProfileKeyMap,
-    help_map: HelpKeyMap,
-    node_select_map: NodeSelectKeyMap,
-    aggregate_map: AggregateKeyMap,
+    clipboard_map: ClipboardKeyMap,
+    code_picker_map: CodePickerKeyMap,
+    history_map: HistoryKeyMap,
+    translation_map: TranslationKeyMap,
+    error_banner_map: ErrorBannerKeyMap,
+    /// Keys of a multi-key binding typed so far
+    pending_keys: Vec<KeyEvent>,
+    /// When the last key of `pending_keys` was pressed
+    pending_since: Option<Instant>,
 }
 
 impl<'a> EventHandler<'a> {
+    /// Keys every view handles the same way, in its own keymap or before it
+    const SHARED_KEYS: HelpSection = HelpSection {
+        title: "Global",
+        view: None,
+        keys: &[
+            ("next", "Move down (also Down)"),
+            ("previous", "Move up (also Up)"),
+            (
+                "history-back",
+                "History back, to the previous view (also Left)",
+            ),
+            ("history-forward", "History forward (also Right)"),
+            (
+                "scroll-down",
+                "Scroll down (previews the topic in the topic list)",
+            ),
+            ("page-down", "Page down (also PageDown)"),
+            ("page-up", "Page up (also PageUp)"),
+            ("first", "Go to the first item"),
+            ("last", "Go to the last item"),
+            ("open", "Open the selected item"),
+            ("refresh", "Refresh the current view"),
+            ("notifications", "Notifications"),
+            ("profile", "Your profile"),
+            ("aggregate", "Aggregated topics (RSS feeds)"),
+            ("favorites", "Favorite topics"),
+            ("bookmarks", "Local bookmarks"),
+            ("tokens", "Saved tokens, to switch accounts"),
+            ("visited-topics", "Visited topics (type to search)"),
+            ("history-browser", "View history"),
+            ("clipboard-history", "Clipboard history"),
+            ("help", "Show this help"),
+            ("cycle-theme", "Cycle the colour theme"),
+            ("toggle-profiling", "Show render timings"),
+            (
+                "quit",
+                "Quit / remove the current view from history (also ESC)",
+            ),
+            ("exit", "Exit immediately"),
+        ],
+    };
+
+    /// What the mouse does in [`Self::handle_mouse`]
+    const MOUSE_KEYS: HelpSection = HelpSection {
+        title: "Mouse",
+        view: None,
+        keys: &[
+            ("Click", "Select a topic, reply, notification or node"),
+            ("Click again", "Open the selected item (like RET)"),
+            ("Wheel", "Move through lists, scroll topic content"),
+            (
+                "Click a tab",
+                "Switch the node or aggregate tab in the header",
+            ),
+        ],
+    };
+
+    /// The help screen, in the order it is shown: the keys every view
+    /// shares, then what each keymap lists for itself
+    pub fn help_sections() -> Vec<HelpSection> {
+        let mut sections = vec![Self::SHARED_KEYS];
+        sections.extend(ErrorBannerKeyMap.help());
+        sections.push(Self::MOUSE_KEYS);
+        let keymaps: [&dyn KeyMap; 8] = [
+            &TopicListKeyMap,
+            &TopicDetailKeyMap,
+            &LinkSelectionKeyMap,
+            &TranslationKeyMap,
+            &ReplyInputKeyMap,
+            &AggregateKeyMap,
+            &NodeSelectKeyMap,
+            &NotificationsKeyMap,
+        ];
+        sections.extend(keymaps.iter().flat_map(|keymap| keymap.help()));
+        sections.push(HelpSection {
+            title: "Prompts",
+            view: None,
+            keys: Minibuffer::KEYS,
+        });
+        let keymaps: [&dyn KeyMap; 6] = [
+            &ProfileKeyMap,
+            &FavoritesKeyMap,
+            &BookmarksKeyMap,
+            &VisitedTopicsKeyMap,
+            &MemberKeyMap,
+            &TokensKeyMap,
+        ];
+        sections.extend(keymaps.iter().flat_map(|keymap| keymap.help()));
+        sections
+    }
+
     pub fn new(client: &'a V2exClient) -> Self {
         Self {
             client,
             link_map: LinkSelectionKeyMap::new(),
-            topic_list_map: TopicListKeyMap::new(),
-            topic_detail_map: TopicDetailKeyMap::new(),
-            notifications_map: NotificationsKeyMap::new(),
-            profile_map: ProfileKeyMap::new(),
-            help_map: HelpKeyMap::new(),
-            node_select_map: NodeSelectKeyMap::new(),
-            aggregate_map: AggregateKeyMap::new(),
+            clipboard_map: ClipboardKeyMap::new(),
+            code_picker_map: CodePickerKeyMap::new(),
+            history_map: HistoryKeyMap::new(),
+            translation_map: TranslationKeyMap::new(),
+            error_banner_map: ErrorBannerKeyMap::new(),
+            pending_keys: Vec::new(),
+            pending_since: None,
+        }
+    }
+
+    /// Handle a key press, resolving user bindings from the config first
+    pub fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> Result<bool> {
+        // Link selection and the overlays read raw keys
+        if app.topic_state.link_input_state.is_active
+            || app.clipboard_history.visible
+            || app.code_picker.visible
+            || app.history_browser.visible
+            || app.translator.shown.is_some()
+        {
+            self.pending_keys.clear();
+            return self.dispatch_key(app, key);
+        }
+
+        if !self.pending_keys.is_empty() && is_cancel_key(&key) {
+            self.cancel_pending_keys(app, "Quit");
+            return Ok(false);
+        }
+
+        self.pending_keys.push(key);
+        self.pending_since = Some(Instant::now());
+        let typing = is_typing(app);
+        let found = match app
+            .config
+            .key_bindings
+            .lookup(app.view, typing, &self.pending_keys)
+        {
+            BindingMatch::Unbound if !typing => self.sequence_match(app, &self.pending_keys),
+            found => found,
+        };
+        match found {
+            BindingMatch::Bound(action) => {
+                self.pending_keys.clear();
+                self.pending_since = None;
+                self.run_action(app, &action)
+            }
+            BindingMatch::Prefix => {
+                app.ui_state.status_message =
+                    format!("{} -", format_key_sequence(&self.pending_keys));
+                Ok(false)
+            }
+            BindingMatch::Unbound => {
+                self.pending_since = None;
+                let pressed = std::mem::take(&mut self.pending_keys);
+                if pressed.len() == 1 {
+                    self.dispatch_key(app, key)
+                } else {
+                    app.ui_state.status_message =
+                        format!("{} is undefined", format_key_sequence(&pressed));
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    /// Give up on a partial key sequence after `key-sequence-timeout`.
+    ///
+    /// A single pending key then runs its built-in action, so a prefix bound
+    /// by the user doesn't permanently hide what that key did before.
+    /// Returns `Ok(None)` while nothing expired, otherwise whether to quit.
+    pub fn expire_pending_keys(&mut self, app: &mut App) -> Result<Option<bool>> {
+        let timeout = app.config.key_sequence_timeout;
+        let expired = timeout > 0
+            && self
+                .pending_since
+                .is_some_and(|since| since.elapsed() >= Duration::from_millis(timeout));
+        if !expired {
+            return Ok(None);
+        }
+
+        let sequence_started = self.sequence_match(app, &self.pending_keys) == BindingMatch::Prefix;
+        if let [key] = self.pending_keys[..] {
+            if sequence_started {
+                self.cancel_pending_keys(
+                    app,
+                    &format!("{} - timed out", format_key_sequence(&[key])),
+                );
+                return Ok(Some(false));
+            }
+            self.pending_keys.clear();
+            self.pending_since = None;
+            return self.dispatch_key(app, key).map(Some);
+        }
+        let echo = format_key_sequence(&self.pending_keys);
+        self.cancel_pending_keys(app, &format!("{} - timed out", echo));
+        Ok(Some(false))
+    }
+
+    /// Whether the user is part way through a key sequence
+    pub fn has_pending_keys(&self) -> bool {
+        !self.pending_keys.is_empty()
+    }
+
+    /// Press the default keys of the actions queued by config hooks,
+    /// returning whether one of them quit. They wait while a key sequence
+    /// is unfinished, so they don't land in the middle of it.
+    pub fn run_queued_actions(&mut self, app: &mut App) -> Result<bool> {
+        if self.has_pending_keys() {
+            return Ok(false);
         }
+        for name in std::mem::take(&mut app.queued_actions) {
+            if self.run_action(app, &Action::Keys(action_keys(&name)?))? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
     }
 
-    pub async fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> Result<bool> {
+    /// How `pressed` matches the key sequences the keymap of the current
+    /// view handles itself
+    fn sequence_match(&self, app: &App, pressed: &[KeyEvent]) -> BindingMatch {
+        let sequences = views::component(app.view)
+            .keymap()
+            .map_or(&[][..], |map| map.sequences());
+        let mut found = BindingMatch::Unbound;
+        for keys in sequences
+            .iter()
+            .filter_map(|notation| parse_key_sequence(notation).ok())
+        {
+            if same_keys(&keys, pressed) {
+                return BindingMatch::Bound(Action::Keys(keys));
+            }
+            if keys.len() > pressed.len() && same_keys(&keys[..pressed.len()], pressed) {
+                found = BindingMatch::Prefix;
+            }
+        }
+        found
+    }
+
+    /// Run a bound action in the current view, pressing its keys one at a
+    /// time when the view's component passes it back. Returns whether to
+    /// quit.
+    fn run_action(&mut self, app: &mut App, action: &Action) -> Result<bool> {
+        match views::component(app.view).handle_action(action, app, self.client)? {
+            Handled::Done => self.run_queued_actions(app),
+            Handled::Quit => Ok(true),
+            Handled::Pass => {
+                let Action::Keys(keys) = action else {
+                    return Ok(false);
+                };
+                for key in keys {
+                    if self.dispatch_key(app, *key)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn cancel_pending_keys(&mut self, app: &mut App, message: &str) {
+        self.pending_keys.clear();
+        self.pending_since = None;
+        app.ui_state.status_message = message.to_string();
+    }
+
+    /// Run a key through the built-in keymap of the current view
+    fn dispatch_key(&mut self, app: &mut App, key: KeyEvent) -> Result<bool> {
+        // Hidden developer toggle, available in every view
+        if key.code == KeyCode::F(12) {
+            app.ui_state.status_message = if crate::profiler::toggle() {
+                "Frame profiling on".to_string()
+            } else {
+                "Frame profiling off".to_string()
+            };
+            return Ok(false);
+        }
+
         // Check link selection mode first - it has highest priority
         if app.topic_state.link_input_state.is_active {
-            return self.link_map.handle_key(app, key, self.client).await;
+            return self.link_map.handle_key(app, key, self.client);
+        }
+
+        if app.clipboard_history.visible {
+            return self.clipboard_map.handle_key(app, key, self.client);
+        }
+
+        if app.code_picker.visible {
+            return self.code_picker_map.handle_key(app, key, self.client);
+        }
+
+        if app.history_browser.visible {
+            return self.history_map.handle_key(app, key, self.client);
+        }
+
+        if app.translator.shown.is_some() {
+            return self.translation_map.handle_key(app, key, self.client);
+        }
+
+        if app.view == View::TopicDetail && app.topic_state.tag_input.is_some() {
+            return TopicDetailKeyMap.handle_tag_input(app, key);
+        }
+
+        if app.view == View::TopicDetail && app.topic_state.jump_input.is_some() {
+            return TopicDetailKeyMap.handle_jump_input(app, key, self.client);
+        }
+
+        if app.view == View::TopicDetail && app.topic_state.search_input.is_some() {
+            return TopicDetailKeyMap.handle_search_input(app, key);
         }
 
-        // Route to appropriate key map based on current view
-        match app.view {
-            View::TopicList => self.topic_list_map.handle_key(app, key, self.client).await,
-            View::TopicDetail => {
-                self.topic_detail_map
-                    .handle_key(app, key, self.client)
-                    .await
+        if app.view == View::TopicList && app.topic_state.filter_input.is_some() {
+            return TopicListKeyMap.handle_filter_input(app, key);
+        }
+
+        if self.error_banner_map.takes(app, &key) {
+            return self.error_banner_map.handle_key(app, key, self.client);
+        }
+
+        // Recent copies, from any view that isn't taking text
+        if key.code == KeyCode::Char('y')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && !is_typing(app)
+        {
+            app.clipboard_history.visible = true;
+            app.clipboard_history.selected = 0;
+            return Ok(false);
+        }
+
+        // Every visited view, to jump to one directly
+        if key.code == KeyCode::Char('H') && !is_typing(app) {
+            app.history_browser.open(&app.history);
+            return Ok(false);
+        }
+
+        // Favorites from any view, reloaded when already there. The view is
+        // shown first so a failed load leaves its error on it.
+        if key.code == KeyCode::Char('b') && key.modifiers.is_empty() && !is_typing(app) {
+            if app.view != View::Favorites {
+                app.navigate_to(View::Favorites);
             }
-            View::Notifications => {
-                self.notifications_map
-                    .handle_key(app, key, self.client)
-                    .await
+            app.load_favorites(self.client);
+            return Ok(false);
+        }
+
+        if key.code == KeyCode::Char('\'') && !is_typing(app) {
+            app.bookmarks_state.selected = 0;
+            app.navigate_to(View::Bookmarks);
+            return Ok(false);
+        }
+
+        // Saved tokens, to switch accounts
+        if key.code == KeyCode::Char('K') && !is_typing(app) && app.view != View::Tokens {
+            app.open_tokens(self.client);
+            return Ok(false);
+        }
+
+        // Every topic opened before, to search and reopen one
+        if key.code == KeyCode::Char('V') && !is_typing(app) {
+            app.open_visited_topics();
+            return Ok(false);
+        }
+
+        if key.code == KeyCode::Char('t')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && !is_typing(app)
+        {
+            app.cycle_theme();
+            return Ok(false);
+        }
+
+        // Route to the keymap of the current view
+        match views::component(app.view).keymap() {
+            Some(map) => map.handle_key(app, key, self.client),
+            None => Ok(false),
+        }
+    }
+
+    /// Click to select (or open an already selected item) and scroll with
+    /// the wheel, replaying the equivalent key where one exists
+    pub fn handle_mouse(&mut self, app: &mut App, mouse: MouseEvent) -> Result<bool> {
+        if app.topic_state.link_input_state.is_active
+            || app.clipboard_history.visible
+            || app.code_picker.visible
+            || app.history_browser.visible
+        {
+            return Ok(false);
+        }
+        let (column, row) = (mouse.column, mouse.row);
+
+        match mouse.kind {
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                let down = mouse.kind == MouseEventKind::ScrollDown;
+                if app.view == View::TopicDetail && app.mouse_regions.in_content(column, row) {
+                    if app.ui_state.focus_mode && app.topic_state.focused_reply().is_some() {
+                        app.topic_state.scroll_focus(if down { 3 } else { -3 });
+                    } else if down {
+                        app.topic_state.scroll_down();
+                    } else {
+                        app.topic_state.scroll_up();
+                    }
+                    return Ok(false);
+                }
+                // Topic detail splits the screen, so only scroll replies under the pointer
+                let over_list = app.mouse_regions.list.as_ref().is_some_and(|list| {
+                    app.view != View::TopicDetail || list.contains(column, row)
+                });
+                if !over_list {
+                    return Ok(false);
+                }
+                let code = if down { KeyCode::Down } else { KeyCode::Up };
+                self.dispatch_key(app, KeyEvent::from(code))
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let header_key = app
+                    .mouse_regions
+                    .header
+                    .as_ref()
+                    .and_then(|header| header.key_at(column, row));
+                if let Some(key) = header_key {
+                    return self.handle_key(app, KeyEvent::from(KeyCode::Char(key)));
+                }
+
+                let Some(index) = app
+                    .mouse_regions
+                    .list
+                    .as_ref()
+                    .and_then(|list| list.item_at(column, row))
+                else {
+                    return Ok(false);
+                };
+                if app.list_selection() == Some(index) {
+                    self.dispatch_key(app, KeyEvent::from(KeyCode::Enter))
+                } else {
+                    app.select_list_item(index);
+                    Ok(false)
+                }
             }
-            View::Profile => self.profile_map.handle_key(app, key, self.client).await,
-            View::Help => self.help_map.handle_key(app, key, self.client).await,
-            View::NodeSelect => self.node_select_map.handle_key(app, key, self.client).await,
-            View::Aggregate => self.aggregate_map.handle_key(app, key, self.client).await,
-            View::TokenInput => {
-                // Token input is handled separately in main.rs
-                Ok(false)
+            _ => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_sequence() {
+        assert_eq!(
+            parse_key_sequence("C-x C-s").unwrap(),
+            vec![
+                KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL),
+                KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            ]
+        );
+        assert_eq!(
+            parse_key_sequence("M-v SPC <next> S-a C--").unwrap(),
+            vec![
+                KeyEvent::new(KeyCode::Char('v'), KeyModifiers::ALT),
+                KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Char('A'), KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Char('-'), KeyModifiers::CONTROL),
+            ]
+        );
+        assert_eq!(
+            format_key_sequence(&parse_key_sequence("C-x RET <f5>").unwrap()),
+            "C-x RET <f5>"
+        );
+        assert!(parse_key_sequence("").is_err());
+        assert!(parse_key_sequence("C-foo").is_err());
+        assert!(parse_key_sequence("<f13>").is_err());
+    }
+
+    #[test]
+    fn test_pending_sequence_echo_cancel_and_timeout() {
+        let key = |notation| parse_key_sequence(notation).unwrap()[0];
+        let client = V2exClient::new(String::new());
+        let mut config = crate::config::Config {
+            key_sequence_timeout: 1,
+            ..Default::default()
+        };
+        config
+            .key_bindings
+            .define(None, "C-x r t", BindingTarget::Keys("?".into()))
+            .unwrap();
+        let mut app = App::new(config);
+        let mut handler = EventHandler::new(&client);
+
+        handler.handle_key(&mut app, key("C-x")).unwrap();
+        assert_eq!(app.ui_state.status_message, "C-x -");
+        handler.handle_key(&mut app, key("C-g")).unwrap();
+        assert_eq!(app.ui_state.status_message, "Quit");
+        assert_eq!(handler.expire_pending_keys(&mut app).unwrap(), None);
+
+        // Two keys into the sequence, so nothing is replayed on timeout
+        handler.handle_key(&mut app, key("C-x")).unwrap();
+        handler.handle_key(&mut app, key("r")).unwrap();
+        assert_eq!(app.ui_state.status_message, "C-x r -");
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(handler.expire_pending_keys(&mut app).unwrap(), Some(false));
+        assert_eq!(app.ui_state.status_message, "C-x r - timed out");
+    }
+
+    #[test]
+    fn test_custom_action_binding() {
+        let key = |notation| parse_key_sequence(notation).unwrap()[0];
+        let client = V2exClient::new(String::new());
+        let mut engine = crate::config::engine::Engine::new(Default::default());
+        engine
+            .eval_source(
+                "(define-action 'greet (lambda () (message \"hi\")) 'help)\n(define-key 'global \"C-c h\" 'greet)",
+            )
+            .unwrap();
+        let mut app = App::new(engine.config);
+        let mut handler = EventHandler::new(&client);
+
+        handler.handle_key(&mut app, key("C-c")).unwrap();
+        handler.handle_key(&mut app, key("h")).unwrap();
+        assert_eq!(app.view, View::Help);
+        assert!(app.queued_actions.is_empty());
+
+        // Actions queued while a sequence is unfinished wait for it
+        handler.handle_key(&mut app, key("C-c")).unwrap();
+        app.queued_actions.push("quit".to_string());
+        assert!(!handler.run_queued_actions(&mut app).unwrap());
+        assert_eq!(app.queued_actions, ["quit"]);
+        handler.handle_key(&mut app, key("C-g")).unwrap();
+        handler.run_queued_actions(&mut app).unwrap();
+        assert!(app.queued_actions.is_empty());
+    }
+
+    #[test]
+    fn test_window_commands_are_key_sequences() {
+        let key = |notation| parse_key_sequence(notation).unwrap()[0];
+        let client = V2exClient::new(String::new());
+        let mut config = crate::config::Config::default();
+        config
+            .key_bindings
+            .define(
+                Some(View::TopicDetail),
+                "C-c >",
+                BindingTarget::Action("grow-topic-pane".into()),
+            )
+            .unwrap();
+        let mut app = App::new(config);
+        app.view = View::TopicDetail;
+        let mut handler = EventHandler::new(&client);
+        let start = app.ui_state.split.topic_percent;
+
+        handler.handle_key(&mut app, key("C-w")).unwrap();
+        assert_eq!(app.ui_state.status_message, "C-w -");
+        handler.handle_key(&mut app, key(">")).unwrap();
+        assert!(app.ui_state.split.topic_percent > start);
+
+        // Bound to other keys or run from a hook, the sequence stays whole
+        handler.handle_key(&mut app, key("C-c")).unwrap();
+        handler.handle_key(&mut app, key(">")).unwrap();
+        app.queued_actions.push("shrink-topic-pane".to_string());
+        handler.run_queued_actions(&mut app).unwrap();
+        assert!(app.ui_state.split.topic_percent > start);
+        app.queued_actions.push("reset-panes".to_string());
+        handler.run_queued_actions(&mut app).unwrap();
+        assert_eq!(app.ui_state.split.topic_percent, start);
+
+        handler.handle_key(&mut app, key("C-w")).unwrap();
+        handler.handle_key(&mut app, key("q")).unwrap();
+        assert_eq!(app.ui_state.status_message, "C-w q is undefined");
+        assert_eq!(app.view, View::TopicDetail);
+    }
+
+    #[test]
+    fn test_error_banner_keys_rebindable() {
+        let key = |notation| parse_key_sequence(notation).unwrap()[0];
+        let client = V2exClient::new(String::new());
+        let mut config = crate::config::Config::default();
+        config
+            .key_bindings
+            .define(None, "C-c d", BindingTarget::Action("dismiss-error".into()))
+            .unwrap();
+        let mut app = App::new(config);
+        let mut handler = EventHandler::new(&client);
+
+        app.ui_state
+            .fail("Failed".to_string(), crate::state::Retry::Profile);
+        handler.handle_key(&mut app, key("C-c")).unwrap();
+        handler.handle_key(&mut app, key("d")).unwrap();
+        assert_eq!(app.ui_state.error, None);
+    }
+
+    #[tokio::test]
+    async fn test_favorites_from_any_view() {
+        let key = |notation| parse_key_sequence(notation).unwrap()[0];
+        let client = V2exClient::new(String::new());
+        let mut app = App::new(Default::default());
+        app.view = View::Help;
+        let mut handler = EventHandler::new(&client);
+
+        // Without a website session the view says what is missing
+        handler.handle_key(&mut app, key("b")).unwrap();
+        assert_eq!(app.view, View::Favorites);
+        let finished = loop {
+            match app.loads.finished() {
+                Some(finished) => break finished,
+                None => tokio::task::yield_now().await,
+            }
+        };
+        app.finish_load(&client, finished);
+        assert!(app.ui_state.error.as_ref().unwrap().contains("cookie.txt"));
+        handler.handle_key(&mut app, key("b")).unwrap();
+        assert_eq!(app.view, View::Favorites);
+    }
+
+    #[tokio::test]
+    async fn test_notification_delete_asks_first() {
+        let key = |notation| parse_key_sequence(notation).unwrap()[0];
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = App::new(Default::default());
+        app.view = View::Notifications;
+        app.notification_state.notifications =
+            vec![serde_json::from_value(serde_json::json!({ "id": 7 })).unwrap()];
+        let mut handler = EventHandler::new(&client);
+
+        // Anything else in between starts over
+        handler.handle_key(&mut app, key("d")).unwrap();
+        handler.handle_key(&mut app, key("x")).unwrap();
+        handler.handle_key(&mut app, key("d")).unwrap();
+        assert!(app.ui_state.status_message.contains("again"));
+        assert!(app.loads.finished().is_none());
+
+        handler.handle_key(&mut app, key("d")).unwrap();
+        let finished = loop {
+            match app.loads.finished() {
+                Some(finished) => break finished,
+                None => tokio::task::yield_now().await,
+            }
+        };
+        app.finish_load(&client, finished);
+        // Offline nothing can be deleted, so it stays
+        assert_eq!(app.notification_state.notifications.len(), 1);
+        assert!(app.ui_state.error.is_some());
+    }
+
+    #[test]
+    fn test_key_bindings_lookup() {
+        let key = |notation| parse_key_sequence(notation).unwrap();
+        let mut bindings = KeyBindings::default();
+        bindings
+            .define(None, "C-x C-s", BindingTarget::Action("send-reply".into()))
+            .unwrap();
+        bindings
+            .define(
+                Some(View::TopicList),
+                "j",
+                BindingTarget::Action("next".into()),
+            )
+            .unwrap();
+        bindings
+            .define(None, "j", BindingTarget::Keys("p".into()))
+            .unwrap();
+        assert!(bindings
+            .define(None, "k", BindingTarget::Action("no-such-action".into()))
+            .is_err());
+
+        // The view binding beats the later global one
+        assert_eq!(
+            bindings.lookup(View::TopicList, false, &key("j")),
+            BindingMatch::Bound(Action::Keys(key("n")))
+        );
+        assert_eq!(
+            bindings.lookup(View::Aggregate, false, &key("j")),
+            BindingMatch::Bound(Action::Keys(key("p")))
+        );
+        assert_eq!(
+            bindings.lookup(View::Aggregate, false, &key("C-x")),
+            BindingMatch::Prefix
+        );
+        assert_eq!(
+            bindings.lookup(View::Aggregate, false, &key("C-x C-s")),
+            BindingMatch::Bound(Action::Keys(key("C-s")))
+        );
+        // Global bindings stay out of the way while typing
+        assert_eq!(
+            bindings.lookup(View::ReplyInput, true, &key("j")),
+            BindingMatch::Unbound
+        );
+    }
+
+    #[test]
+    fn test_help_names_actions_or_keys() {
+        // Ranges and markers drawn in the lists, not keys to press
+        let shown = ["1-9", "+N", "▲ / △"];
+        for section in EventHandler::help_sections() {
+            if section.title == "Mouse" {
+                continue;
+            }
+            for (entry, _) in section.keys {
+                assert!(
+                    shown.contains(entry) || parse_key_sequence(entry_keys(entry)).is_ok(),
+                    "{} in {} is neither an action nor a key",
+                    entry,
+                    section.title
+                );
             }
         }
     }
+
+    #[test]
+    fn test_prompt_hints_include_user_bindings() {
+        let mut bindings = KeyBindings::default();
+        bindings
+            .define(
+                Some(View::ReplyInput),
+                "C-c C-c",
+                BindingTarget::Action("send-reply".into()),
+            )
+            .unwrap();
+        bindings
+            .define(None, "C-x s", BindingTarget::Keys("C-s".into()))
+            .unwrap();
+
+        let hints = [("send-reply", "send"), ("cancel", "keep draft")];
+        assert_eq!(
+            prompt_hints(&bindings, View::ReplyInput, &hints),
+            "C-s/C-c C-c send  ESC keep draft"
+        );
+        assert_eq!(
+            prompt_hints(&bindings, View::NodeSelect, &hints),
+            "C-s send  ESC keep draft"
+        );
+    }
 }
@@ -0,0 +1,489 @@
+//! Loads the user is waiting for. Each runs as a task of its own and comes
+//! back to the main loop as a [`Loaded`], so a key only starts a load and
+//! the screen keeps drawing, with the spinner turning, until it arrives.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+
+use crate::api::{
+    Member, MemberReply, Node, Notification, PartialList, Reply, RssItem, TokenInfo, Topic,
+    TopicStats, V2exClient,
+};
+use crate::ui::Loading;
+
+/// What a load fills in. Starting a load for a slot supersedes the one
+/// still running for it, whose result is then dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slot {
+    Topics,
+    NodeInfo,
+    Preview,
+    Topic,
+    Replies,
+    Notifications,
+    Favorites,
+    Member,
+    Profile,
+    Tokens,
+    Aggregate,
+}
+
+impl Slot {
+    /// How the status bar spinner shows it
+    fn loading(self) -> Loading {
+        match self {
+            Slot::Topic | Slot::Replies => Loading::Replies,
+            _ => Loading::List,
+        }
+    }
+}
+
+/// What to do once a page of topics arrived
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfterTopics {
+    Nothing,
+    /// Move the selection onto the first new topic, for `n` at the end
+    SelectNext,
+    /// Open the first new topic, for `N` in topic detail
+    OpenNext,
+}
+
+/// Why a topic is being fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicLoad {
+    /// To open it in topic detail
+    Open,
+    /// To show it in topic detail, which is already on screen
+    Show,
+    /// To fetch the open topic again, with as many replies as it had
+    Refresh,
+}
+
+/// What to do once pages of replies arrived
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfterReplies {
+    Nothing,
+    /// Move the selection onto the first new reply, for `n` at the end
+    SelectNext,
+    /// Select reply number `floor`
+    Jump(usize),
+    /// Save the topic as plain text
+    ExportText,
+    /// Save the topic to `export-path`
+    Export,
+}
+
+/// What to do once the profile arrived
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AfterProfile {
+    Nothing,
+    /// Keep the status line, which has the startup messages
+    Startup,
+    /// Report the switch to token `name`
+    SwitchedToken(String),
+    /// Move on to the next reply filter, which needs the username
+    CycleReplyFilter,
+}
+
+/// A topic with its counters and first pages of replies
+#[derive(Debug)]
+pub struct FetchedTopic {
+    pub topic: Topic,
+    /// Counters from the topic page, when it could be read
+    pub stats: Option<TopicStats>,
+    pub replies: FetchedReplies,
+}
+
+/// A member with what they posted lately, each part fetched on its own
+#[derive(Debug)]
+pub struct FetchedMember {
+    pub member: Member,
+    pub topics: Result<PartialList<Topic>>,
+    pub replies: Result<PartialList<MemberReply>>,
+}
+
+/// Consecutive pages of a topic's replies
+#[derive(Debug)]
+pub struct FetchedReplies {
+    pub items: Vec<Reply>,
+    pub skipped: usize,
+    /// Pages fetched, the last one possibly empty
+    pub pages: i32,
+    /// Why fetching stopped early; the replies before it are kept
+    pub error: Option<anyhow::Error>,
+}
+
+/// A load that finished, with what it was for
+#[derive(Debug)]
+pub enum Loaded {
+    Topics {
+        node: String,
+        page: i32,
+        append: bool,
+        then: AfterTopics,
+        /// The topics, and the favorite nodes that failed when all of them
+        /// were fetched
+        result: Result<(PartialList<Topic>, Vec<String>)>,
+    },
+    NodeInfo {
+        name: String,
+        /// Open the node details popup once it is there
+        show: bool,
+        result: Result<Node>,
+    },
+    Preview {
+        topic_id: i64,
+        result: Result<Topic>,
+    },
+    Topic {
+        topic_id: i64,
+        how: TopicLoad,
+        result: Result<FetchedTopic>,
+    },
+    Replies {
+        topic_id: i64,
+        /// The first page fetched
+        page: i32,
+        then: AfterReplies,
+        replies: FetchedReplies,
+    },
+    /// The open topic's reply count and counters, for auto-refresh
+    TopicCounters {
+        topic_id: i64,
+        topic: Option<Topic>,
+        stats: Option<TopicStats>,
+    },
+    Notifications {
+        page: i32,
+        result: Result<PartialList<Notification>>,
+    },
+    NotificationDeleted {
+        id: i64,
+        result: Result<()>,
+    },
+    Favorites(Result<PartialList<Topic>>),
+    /// A topic was favorited or unfavorited; true when it now is one
+    Favorite {
+        topic_id: i64,
+        result: Result<bool>,
+    },
+    /// A topic, or one of its replies when `reply_id` is set, was thanked
+    Thanked {
+        topic_id: i64,
+        reply_id: Option<i64>,
+        author: String,
+        result: Result<()>,
+    },
+    /// A favorited topic was saved under `favorite-archive-dir`
+    Archived {
+        topic_id: i64,
+        result: Result<PathBuf>,
+    },
+    Member {
+        username: String,
+        /// Go to the member view once it is there
+        show: bool,
+        result: Result<FetchedMember>,
+    },
+    Profile {
+        then: AfterProfile,
+        result: Result<Member>,
+    },
+    /// What `/token` said about each saved token, by name
+    Tokens(Vec<(String, Result<TokenInfo, String>)>),
+    Aggregate {
+        tab: String,
+        /// Leave a status message set while it loaded in place
+        keep_message: bool,
+        result: Result<PartialList<RssItem>>,
+    },
+    /// A link downloaded in link mode
+    Downloaded {
+        shortcut: String,
+        result: Result<PathBuf>,
+    },
+}
+
+/// A load that finished and is still wanted
+#[derive(Debug)]
+pub struct Finished {
+    pub loaded: Loaded,
+    /// Started by auto-refresh rather than by the user
+    pub quiet: bool,
+}
+
+#[derive(Debug)]
+struct Running {
+    slot: Option<Slot>,
+    quiet: bool,
+    task: AbortHandle,
+}
+
+/// The loads running now
+#[derive(Debug)]
+pub struct Loads {
+    tx: mpsc::UnboundedSender<(u64, Loaded)>,
+    rx: mpsc::UnboundedReceiver<(u64, Loaded)>,
+    next_id: u64,
+    running: HashMap<u64, Running>,
+}
+
+impl Default for Loads {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            tx,
+            rx,
+            next_id: 0,
+            running: HashMap::new(),
+        }
+    }
+}
+
+impl Loads {
+    /// Run `load` for `slot`, superseding the load running for it
+    pub fn start(&mut self, slot: Slot, load: impl Future<Output = Loaded> + Send + 'static) {
+        self.cancel(slot);
+        self.spawn(Some(slot), false, load);
+    }
+
+    /// Run `load` for `slot` behind the user's back, unless the slot is
+    /// already loading
+    pub fn start_quietly(
+        &mut self,
+        slot: Slot,
+        load: impl Future<Output = Loaded> + Send + 'static,
+    ) {
+        if !self.is_running(slot) {
+            self.spawn(Some(slot), true, load);
+        }
+    }
+
+    /// Run `load`, which changes something rather than filling a slot, so
+    /// nothing supersedes it
+    pub fn start_action(&mut self, load: impl Future<Output = Loaded> + Send + 'static) {
+        self.spawn(None, false, load);
+    }
+
+    fn spawn(
+        &mut self,
+        slot: Option<Slot>,
+        quiet: bool,
+        load: impl Future<Output = Loaded> + Send + 'static,
+    ) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let tx = self.tx.clone();
+        let task = tokio::spawn(async move {
+            let _ = tx.send((id, load.await));
+        })
+        .abort_handle();
+        self.running.insert(id, Running { slot, quiet, task });
+    }
+
+    /// Stop the load running for `slot`, if any
+    pub fn cancel(&mut self, slot: Slot) {
+        self.running.retain(|_, running| {
+            let superseded = running.slot == Some(slot);
+            if superseded {
+                running.task.abort();
+            }
+            !superseded
+        });
+    }
+
+    pub fn is_running(&self, slot: Slot) -> bool {
+        self.running
+            .values()
+            .any(|running| running.slot == Some(slot))
+    }
+
+    /// The next load that finished, skipping superseded ones
+    pub fn finished(&mut self) -> Option<Finished> {
+        while let Ok((id, loaded)) = self.rx.try_recv() {
+            if let Some(running) = self.running.remove(&id) {
+                return Some(Finished {
+                    loaded,
+                    quiet: running.quiet,
+                });
+            }
+        }
+        None
+    }
+
+    /// What the user is waiting for, for the status bar spinner; a topic
+    /// or its replies before anything else
+    pub fn running(&self) -> Option<Loading> {
+        let mut shown = self
+            .running
+            .values()
+            .filter(|running| !running.quiet)
+            .map(|running| running.slot.map_or(Loading::List, Slot::loading));
+        let first = shown.next()?;
+        Some(if shown.any(|loading| loading == Loading::Replies) {
+            Loading::Replies
+        } else {
+            first
+        })
+    }
+}
+
+/// Reply pages of a topic from `page` on, until `wanted` replies have come
+/// or a page comes back empty or fails
+pub async fn fetch_replies(
+    client: &V2exClient,
+    topic_id: i64,
+    mut page: i32,
+    wanted: usize,
+) -> FetchedReplies {
+    let mut fetched = FetchedReplies {
+        items: Vec::new(),
+        skipped: 0,
+        pages: 0,
+        error: None,
+    };
+    loop {
+        match client.get_topic_replies(topic_id, page).await {
+            Ok(list) => {
+                fetched.pages += 1;
+                let empty = list.items.is_empty() && list.skipped == 0;
+                fetched.items.extend(list.items);
+                fetched.skipped += list.skipped;
+                if empty || fetched.items.len() >= wanted {
+                    return fetched;
+                }
+                page += 1;
+            }
+            Err(e) => {
+                fetched.error = Some(e);
+                return fetched;
+            }
+        }
+    }
+}
+
+/// A topic, its counters and enough reply pages for `wanted` replies.
+/// Counters are optional extras, left out if the page can't be read.
+pub async fn fetch_topic(
+    client: &V2exClient,
+    topic_id: i64,
+    wanted: usize,
+) -> Result<FetchedTopic> {
+    let topic = client.get_topic(topic_id).await?;
+    let stats = client.get_topic_stats(topic_id).await.ok();
+    let replies = fetch_replies(client, topic_id, 1, wanted.max(1)).await;
+    Ok(FetchedTopic {
+        topic,
+        stats,
+        replies,
+    })
+}
+
+/// Page `page` of every one of `nodes`, fetched concurrently and merged
+/// newest first, with the nodes that failed. Only when all of them fail is
+/// it an error.
+pub async fn fetch_all_favorites(
+    client: &V2exClient,
+    nodes: Vec<(String, String)>,
+    page: i32,
+) -> Result<(PartialList<Topic>, Vec<String>)> {
+    let mut requests = tokio::task::JoinSet::new();
+    for (name, title) in nodes {
+        let client = client.clone();
+        requests.spawn(async move {
+            let result = client.get_node_topics(&name, page).await;
+            (name, title, result)
+        });
+    }
+
+    let mut pages = Vec::new();
+    let mut skipped = 0;
+    let mut failed = Vec::new();
+    let mut first_error = None;
+    while let Some(joined) = requests.join_next().await {
+        let (name, title, result) = joined?;
+        match result {
+            Ok(list) => {
+                skipped += list.skipped;
+                pages.push((name, title, list.items));
+            }
+            Err(e) => {
+                failed.push(name);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if pages.is_empty() {
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+    }
+    failed.sort();
+    let list = PartialList {
+        items: crate::state::merge_node_topics(pages),
+        skipped,
+    };
+    Ok((list, failed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn next_finished(loads: &mut Loads) -> Finished {
+        loop {
+            if let Some(finished) = loads.finished() {
+                return finished;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_newer_load_supersedes() {
+        let mut loads = Loads::default();
+        assert_eq!(loads.running(), None);
+        loads.start(Slot::Favorites, async {
+            Loaded::Favorites(Err(anyhow::anyhow!("superseded")))
+        });
+        loads.start(Slot::Favorites, async {
+            Loaded::Favorites(Ok(PartialList {
+                items: Vec::new(),
+                skipped: 0,
+            }))
+        });
+        assert_eq!(loads.running(), Some(Loading::List));
+
+        let finished = next_finished(&mut loads).await;
+        assert!(matches!(finished.loaded, Loaded::Favorites(Ok(_))));
+        assert!(!finished.quiet);
+        assert_eq!(loads.running(), None);
+        assert!(loads.finished().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quiet_load_yields_to_the_user() {
+        let mut loads = Loads::default();
+        let profile = || async {
+            Loaded::Profile {
+                then: AfterProfile::Nothing,
+                result: Err(anyhow::anyhow!("offline")),
+            }
+        };
+        loads.start_quietly(Slot::Profile, profile());
+        // Auto-refresh doesn't turn the spinner on
+        assert_eq!(loads.running(), None);
+        loads.start_quietly(Slot::Profile, profile());
+        assert!(next_finished(&mut loads).await.quiet);
+        assert!(!loads.is_running(Slot::Profile));
+
+        loads.start(Slot::Topic, profile());
+        assert_eq!(loads.running(), Some(Loading::Replies));
+        loads.cancel(Slot::Topic);
+        assert_eq!(loads.running(), None);
+    }
+}
@@ -1,28 +1,52 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::event::{Event, KeyCode, KeyEventKind};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 mod api;
 mod app;
+mod archive;
+mod bookmarks;
 mod browser;
 mod cli;
 mod cli_output;
 mod clipboard;
+mod config;
+mod disk_cache;
+mod event;
+mod feed_topics;
+mod json_store;
 mod keymap;
+mod loads;
+mod minibuffer;
+mod mouse;
 mod nodes;
+mod notifier;
+mod prefetch;
+mod profiler;
+mod read_tracker;
+mod render;
+mod retry;
+mod scheduler;
 mod state;
 mod terminal;
+mod tokens;
+mod translate;
+mod tts;
 mod ui;
+mod unfurl;
 mod util;
 mod views;
 
 use api::V2exClient;
 use app::{App, View};
 use cli::{Cli, Commands, OutputFormat};
-use keymap::EventHandler;
+use event::{Flow, Msg, Runtime};
+use minibuffer::MinibufferEvent;
 use terminal::TerminalManager;
 
 async fn run_token_input(terminal: &mut TerminalManager) -> Result<Option<String>> {
-    let mut app = App::new();
+    let mut app = App::new(config::Config::default());
     app.view = View::TokenInput;
     app.ui_state.status_message = "Enter your V2EX token".to_string();
 
@@ -31,44 +55,22 @@ async fn run_token_input(terminal: &mut TerminalManager) -> Result<Option<String
 
         if let Event::Key(key) = crossterm::event::read()? {
             if key.kind == KeyEventKind::Press {
-                match key.code {
-                    KeyCode::Char('c')
-                        if key
-                            .modifiers
-                            .contains(crossterm::event::KeyModifiers::CONTROL) =>
-                    {
-                        return Ok(None);
-                    }
-                    KeyCode::Esc => {
-                        return Ok(None);
-                    }
-                    KeyCode::Enter => {
-                        if !app.token_state.input.trim().is_empty() {
-                            match app.token_state.save() {
-                                Ok(_) => {
-                                    return Ok(Some(app.token_state.input.trim().to_string()));
-                                }
-                                Err(e) => {
-                                    app.ui_state.status_message =
-                                        format!("Error saving token: {}", e);
-                                }
-                            }
-                        } else {
-                            app.ui_state.status_message = "Token cannot be empty".to_string();
+                if key.code == KeyCode::Char('c')
+                    && key
+                        .modifiers
+                        .contains(crossterm::event::KeyModifiers::CONTROL)
+                {
+                    return Ok(None);
+                }
+                match app.token_state.prompt.handle_key(key) {
+                    MinibufferEvent::Cancel => return Ok(None),
+                    MinibufferEvent::Submit(token) => match app.token_state.save() {
+                        Ok(_) => return Ok(Some(token.trim().to_string())),
+                        Err(e) => {
+                            app.ui_state.status_message = format!("Error saving token: {}", e);
                         }
-                    }
-                    KeyCode::Char(ch) => {
-                        app.token_state.insert_char(ch);
-                    }
-                    KeyCode::Backspace => {
-                        app.token_state.delete_char();
-                    }
-                    KeyCode::Left => {
-                        app.token_state.move_cursor_left();
-                    }
-                    KeyCode::Right => {
-                        app.token_state.move_cursor_right();
-                    }
+                    },
+                    MinibufferEvent::Invalid(message) => app.ui_state.status_message = message,
                     _ => {}
                 }
             }
@@ -76,19 +78,86 @@ async fn run_token_input(terminal: &mut TerminalManager) -> Result<Option<String
     }
 }
 
-async fn run_app(terminal: &mut TerminalManager, client: V2exClient) -> Result<()> {
-    let mut app = App::new();
-    let mut event_handler = EventHandler::new(&client);
-
-    // Load initial aggregated topics
-    app.load_aggregate(&client).await;
+/// Write `content` to a new file in the temp directory for the editor.
+/// The name is unpredictable and claimed with `create_new`, so another
+/// user can't plant a file or symlink there first, and only the owner may
+/// read the draft.
+fn create_draft_file(content: &str) -> Result<std::path::PathBuf> {
+    let dir = std::env::temp_dir();
+    for _ in 0..16 {
+        let path = dir.join(format!(
+            "v2ex-reply-{}-{:016x}.md",
+            std::process::id(),
+            retry::jitter_seed()
+        ));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(mut file) => {
+                std::io::Write::write_all(&mut file, content.as_bytes())
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create {}", path.display()))
+            }
+        }
+    }
+    anyhow::bail!("No free name for the reply draft in {}", dir.display())
+}
 
-    loop {
-        terminal.terminal().draw(|frame| app.render(frame))?;
+/// Suspend the TUI, edit the reply draft in $EDITOR and post the result
+fn run_external_editor(
+    terminal: &mut TerminalManager,
+    app: &mut App,
+    client: &V2exClient,
+) -> Result<()> {
+    app.reply_input_state.external_edit_requested = false;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let path = match create_draft_file(&app.reply_input_state.content()) {
+        Ok(path) => path,
+        Err(e) => {
+            app.ui_state.status_message = format!("{:#}", e);
+            return Ok(());
+        }
+    };
 
-        if let Event::Key(key) = crossterm::event::read()? {
-            if key.kind == KeyEventKind::Press && event_handler.handle_key(&mut app, key).await? {
-                break;
+    terminal.suspend()?;
+    // $EDITOR may carry arguments (e.g. "code --wait"), so run it through the shell
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status();
+    terminal.resume()?;
+
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    match (status, edited) {
+        (Err(e), _) => {
+            app.ui_state.status_message = format!("Failed to run editor '{}': {}", editor, e);
+        }
+        (Ok(status), _) if !status.success() => {
+            app.ui_state.status_message =
+                format!("Editor exited with {}, reply not posted", status);
+        }
+        (Ok(_), Err(e)) => {
+            app.ui_state.status_message = format!("Failed to read edited reply: {}", e);
+        }
+        (Ok(_), Ok(content)) => {
+            app.reply_input_state.set_content(content.trim_end());
+            if app.reply_input_state.is_empty() {
+                app.ui_state.status_message = "Empty reply, nothing posted".to_string();
+            } else {
+                app.submit_reply(client);
             }
         }
     }
@@ -96,14 +165,224 @@ async fn run_app(terminal: &mut TerminalManager, client: V2exClient) -> Result<(
     Ok(())
 }
 
+/// How long to wait for input before checking timers
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Repaint at least this often so relative timestamps stay current
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_secs(60);
+/// Minimum gap between frames in low-bandwidth mode; input arriving in
+/// between is applied and sent as one frame
+const LOW_BANDWIDTH_FRAME_INTERVAL: Duration = Duration::from_millis(200);
+
+async fn run_app(
+    terminal: &mut TerminalManager,
+    client: V2exClient,
+    config: config::Config,
+    config_message: Option<String>,
+) -> Result<()> {
+    let mut app = App::new(config);
+    if app.config.prefetch_favorite_nodes && !client.is_offline() {
+        let nodes = app
+            .node_state
+            .quick_nodes
+            .iter()
+            .map(|(_, node)| node.clone())
+            .filter(|node| {
+                *node != app.node_state.current_node && node != config::ALL_FAVORITES_NODE
+            })
+            .collect();
+        app.prefetch_updates = Some(prefetch::spawn_node_prefetch(client.background(), nodes));
+    }
+    // Without a watcher edits just need a restart, so failures are ignored
+    app.config_watcher = config::loader::config_path()
+        .and_then(config::watcher::ConfigWatcher::new)
+        .ok();
+    if app.config.notification_poll_interval > 0 && !client.is_offline() {
+        app.notification_updates = Some(notifier::spawn_notification_poller(
+            client.background(),
+            Duration::from_secs(app.config.notification_poll_interval),
+        ));
+    }
+
+    let read_tracker_error = match read_tracker::ReadTracker::load() {
+        Ok(tracker) => {
+            app.read_tracker = tracker;
+            None
+        }
+        Err(e) => Some(format!("Read state not loaded: {:#}", e)),
+    };
+    let read_notifications_error = match read_tracker::ReadTracker::load_notifications() {
+        Ok(tracker) => {
+            app.read_notifications = tracker;
+            None
+        }
+        Err(e) => Some(format!("Read notifications not loaded: {:#}", e)),
+    };
+    let seen_notifications_error = match read_tracker::SeenNotifications::load() {
+        Ok(seen) => {
+            app.notification_state.last_seen_id = seen.newest();
+            app.seen_notifications = seen;
+            None
+        }
+        Err(e) => Some(format!("Seen notifications not loaded: {:#}", e)),
+    };
+    let seen_replies_error = match read_tracker::SeenReplies::load() {
+        Ok(seen) => {
+            app.seen_replies = seen;
+            None
+        }
+        Err(e) => Some(format!("Seen replies not loaded: {:#}", e)),
+    };
+    let visited_topics_error = match read_tracker::VisitedTopics::load() {
+        Ok(visited) => {
+            app.visited_topics = visited;
+            None
+        }
+        Err(e) => Some(format!("Visited topics not loaded: {:#}", e)),
+    };
+    let bookmarks_error = match bookmarks::Bookmarks::load() {
+        Ok(bookmarks) => {
+            app.bookmarks = bookmarks;
+            None
+        }
+        Err(e) => Some(format!("Bookmarks not loaded: {:#}", e)),
+    };
+    let tokens_error = match tokens::TokenStore::load(&client.token()) {
+        Ok(store) => {
+            app.token_state.store = store;
+            None
+        }
+        Err(e) => Some(format!("Saved tokens not loaded: {:#}", e)),
+    };
+    let prompt_history_error = match minibuffer::PromptHistory::load() {
+        Ok(history) => {
+            history.restore(&mut app.node_state.prompt);
+            app.prompt_history = history;
+            None
+        }
+        Err(e) => Some(format!("Prompt history not loaded: {:#}", e)),
+    };
+
+    // Without a cache directory there is just nothing to fall back on
+    if let Ok(cache) = disk_cache::DiskCache::open() {
+        app.disk_cache = cache;
+    }
+    if let Some(saved) = app.disk_cache.load_link_titles() {
+        app.link_titles = unfurl::LinkTitles::new(saved.value);
+    }
+
+    let history_error = match state::ViewHistory::load() {
+        Ok(Some(saved)) => {
+            app.resume_history(saved);
+            None
+        }
+        Ok(None) => None,
+        Err(e) => Some(format!("View history not restored: {:#}", e)),
+    };
+
+    // Load initial aggregated topics, or what can be read without a network.
+    // The feed and profile arrive in the background so the first frame
+    // isn't held up; a rejected token opens the token manager then.
+    if client.is_offline() {
+        app.load_favorites(&client);
+    } else {
+        app.load_startup_aggregate(&client);
+        app.load_startup_profile(&client);
+    }
+    if let Some(message) = config_message
+        .or(read_tracker_error)
+        .or(read_notifications_error)
+        .or(seen_notifications_error)
+        .or(seen_replies_error)
+        .or(visited_topics_error)
+        .or(bookmarks_error)
+        .or(tokens_error)
+        .or(prompt_history_error)
+        .or(history_error)
+    {
+        app.ui_state.status_message = message;
+    }
+    app.run_startup_hooks(&client);
+
+    let mut runtime = Runtime::new(app, &client);
+    // Only draw when something may have changed since the last frame
+    let mut dirty = true;
+    let mut last_draw = Instant::now();
+    let frame_interval = if runtime.app.config.low_bandwidth {
+        LOW_BANDWIDTH_FRAME_INTERVAL
+    } else {
+        Duration::ZERO
+    };
+
+    'frames: loop {
+        let since_draw = last_draw.elapsed();
+        if (dirty && since_draw >= frame_interval) || since_draw >= IDLE_REDRAW_INTERVAL {
+            let frame_start = Instant::now();
+            terminal
+                .terminal()
+                .draw(|frame| runtime.app.render(frame))?;
+            profiler::finish_frame(frame_start.elapsed());
+            dirty = false;
+            last_draw = Instant::now();
+        }
+
+        // Wake up in time to draw a frame that is being held back
+        let poll_timeout = if dirty {
+            frame_interval.saturating_sub(last_draw.elapsed())
+        } else {
+            EVENT_POLL_INTERVAL
+        };
+        let mut messages = VecDeque::new();
+        if crossterm::event::poll(poll_timeout)? {
+            messages.extend(Msg::from_event(crossterm::event::read()?));
+        }
+        messages.push_back(Msg::Tick);
+
+        // Background work is looked at after the input, so what a key
+        // started is picked up in the same turn
+        let mut collected = false;
+        loop {
+            let msg = match messages.pop_front() {
+                Some(msg) => msg,
+                None if !collected => {
+                    collected = true;
+                    messages.extend(runtime.pending());
+                    continue;
+                }
+                None => break,
+            };
+            match runtime.update(msg)? {
+                Flow::Idle => {}
+                Flow::Redraw => dirty = true,
+                Flow::EditReply => {
+                    run_external_editor(terminal, &mut runtime.app, &client)?;
+                    dirty = true;
+                }
+                Flow::Quit => break 'frames,
+            }
+        }
+    }
+
+    runtime.app.save_link_titles();
+    runtime.app.history.save()
+}
+
+/// Warn on stderr about malformed items so JSON output stays clean
+fn report_skipped<T>(list: api::PartialList<T>) -> Vec<T> {
+    if list.skipped > 0 {
+        eprintln!("Warning: skipped {} malformed item(s)", list.skipped);
+    }
+    list.items
+}
+
 async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
     use cli_output::*;
 
+    let output = cli.output_format();
     match cli.command.unwrap() {
         Commands::List { node, page, limit } => {
-            let topics = client.get_node_topics(&node, page).await?;
+            let topics = report_skipped(client.get_node_topics(&node, page).await?);
 
-            match cli.output {
+            match output {
                 OutputFormat::Json => {
                     cli::print_json(&topics)?;
                 }
@@ -119,9 +398,9 @@ async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
             let topic = client.get_topic(id).await?;
 
             if replies {
-                let replies_data = client.get_topic_replies(id, 1).await?;
+                let replies_data = report_skipped(client.get_topic_replies(id, 1).await?);
 
-                match cli.output {
+                match output {
                     OutputFormat::Json => {
                         #[derive(serde::Serialize)]
                         struct TopicWithReplies {
@@ -141,7 +420,7 @@ async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
                     }
                 }
             } else {
-                match cli.output {
+                match output {
                     OutputFormat::Json => {
                         cli::print_json(&topic)?;
                     }
@@ -153,9 +432,9 @@ async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
         }
 
         Commands::Replies { id, page, limit } => {
-            let replies = client.get_topic_replies(id, page).await?;
+            let replies = report_skipped(client.get_topic_replies(id, page).await?);
 
-            match cli.output {
+            match output {
                 OutputFormat::Json => {
                     cli::print_json(&replies)?;
                 }
@@ -167,10 +446,29 @@ async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
             }
         }
 
+        Commands::Export { id, width, file } => {
+            let topic = client.get_topic(id).await?;
+            let replies = report_skipped(
+                client
+                    .get_all_topic_replies(id, topic.replies as usize)
+                    .await?,
+            );
+
+            let text = format_printable(&topic, &replies, width);
+            match file {
+                Some(path) => {
+                    std::fs::write(&path, text)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    eprintln!("Saved topic {} to {}", id, path.display());
+                }
+                None => print!("{}", text),
+            }
+        }
+
         Commands::Notifications { page, limit } => {
-            let notifications = client.get_notifications(page).await?;
+            let notifications = report_skipped(client.get_notifications(page).await?);
 
-            match cli.output {
+            match output {
                 OutputFormat::Json => {
                     cli::print_json(&notifications)?;
                 }
@@ -185,7 +483,7 @@ async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
         Commands::Profile => {
             let member = client.get_member().await?;
 
-            match cli.output {
+            match output {
                 OutputFormat::Json => {
                     cli::print_json(&member)?;
                 }
@@ -199,7 +497,7 @@ async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
         Commands::Nodes { filter, limit } => {
             let nodes = find_nodes(filter.as_deref(), limit);
 
-            match cli.output {
+            match output {
                 OutputFormat::Json => {
                     cli::print_json(&nodes)?;
                 }
@@ -216,9 +514,9 @@ async fn run_cli(client: &V2exClient, cli: Cli) -> Result<()> {
         }
 
         Commands::Aggregate { tab, limit } => {
-            let items = client.get_rss_feed(&tab).await?;
+            let items = client.get_aggregate_items(&tab, None).await?.items;
 
-            match cli.output {
+            match output {
                 OutputFormat::Json => {
                     cli::print_json(&items)?;
                 }
@@ -241,9 +539,19 @@ async fn main() -> Result<()> {
     // Check if we should run in TUI mode (no subcommand)
     let is_tui_mode = cli.command.is_none();
 
+    // The TUI config is read first since it can turn on offline mode
+    let (mut config, config_message) = if is_tui_mode {
+        config::load_config()
+    } else {
+        Default::default()
+    };
+    config.offline |= cli.offline;
+
     // Try to load token
     let token = match V2exClient::load_token() {
         Ok(t) => t,
+        // Nothing is sent offline, so there is no need to ask for one
+        Err(_) if config.offline => String::new(),
         Err(_) if is_tui_mode => {
             // Token not found, setup terminal and show token input view
             let mut manager = TerminalManager::new()?;
@@ -266,26 +574,31 @@ async fn main() -> Result<()> {
         }
     };
 
-    let client = V2exClient::new(token.clone());
-
-    // Test API connection
-    match client.get_member().await {
-        Ok(member) => {
-            if is_tui_mode {
-                println!("Connected to V2EX as: {}", member.username);
-            }
-        }
-        Err(e) => {
-            eprintln!("Error: Failed to connect to V2EX API: {}", e);
-            eprintln!("The token appears to be invalid. Please check ~/.config/v2ex/token.txt");
-            std::process::exit(1);
-        }
+    let mut client = V2exClient::new(token.clone())
+        .with_retry(config.retry)
+        .with_request_delay(config.request_delay);
+    if !config.user_agent.is_empty() {
+        client = client.with_user_agent(&config.user_agent);
     }
+    if !config.api_base_url.is_empty() {
+        client = client.with_api_base(&config.api_base_url);
+    }
+    if !config.site_base_url.is_empty() {
+        client = client.with_site_base(&config.site_base_url);
+    }
+    if let Some(cookie) = V2exClient::load_cookie() {
+        client = client.with_cookie(&cookie);
+    }
+    let client = if config.offline {
+        client.offline()
+    } else {
+        client
+    };
 
     if is_tui_mode {
         // Start TUI mode
         let mut manager = TerminalManager::new()?;
-        let result = run_app(&mut manager, client).await;
+        let result = run_app(&mut manager, client, config, config_message).await;
         manager.shutdown()?;
         result
     } else {
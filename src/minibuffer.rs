@@ -0,0 +1,567 @@
+//! One-line prompt shared by node completion, token entry and the reply
+//! jump: Emacs-style editing, input history, TAB completion and validation
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::json_store;
+use crate::ui::Theme;
+use crate::util::width::{byte_offset, grapheme_count, split_at_cursor};
+
+/// What a key did to the prompt
+#[derive(Debug, PartialEq, Eq)]
+pub enum MinibufferEvent {
+    /// The input changed
+    Edited,
+    /// Only the cursor moved
+    Moved,
+    /// RET with valid input, now added to the history
+    Submit(String),
+    /// RET with input the validator rejected, and why; the prompt stays open
+    Invalid(String),
+    /// ESC or C-g
+    Cancel,
+    /// Not an editing key, left to the view
+    Ignored,
+}
+
+#[derive(Debug, Clone)]
+pub struct Minibuffer {
+    prompt: String,
+    /// Name the history is saved under in [`PromptHistory`]; `None` keeps
+    /// it to this session (the token prompt)
+    history_key: Option<&'static str>,
+    input: String,
+    /// Cursor position in grapheme clusters
+    cursor: usize,
+    /// Submitted inputs, oldest first
+    history: Vec<String>,
+    /// Entry of `history` shown while browsing it with Up/Down
+    history_index: Option<usize>,
+    /// What was typed before browsing the history, back after its end
+    draft: String,
+    /// Characters the prompt takes; others are ignored
+    accepts: fn(char) -> bool,
+    /// Checked on RET, giving the message shown when input is rejected
+    validate: fn(&str) -> Result<(), String>,
+    /// Candidates TAB completes the input to
+    complete: Option<fn(&str) -> Vec<String>>,
+}
+
+impl Default for Minibuffer {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Minibuffer {
+    /// Editing keys every prompt takes, for the help screen
+    pub const KEYS: &'static [(&'static str, &'static str)] = &[
+        ("beginning-of-line", "Start of the input (also Home)"),
+        ("C-e", "End of the input (also End)"),
+        ("backward-char", "Move left (also Left)"),
+        ("forward-char", "Move right (also Right)"),
+        ("C-d", "Delete forward (also Delete)"),
+        ("delete-backward-char", "Delete backward"),
+        ("C-k", "Delete to the end"),
+        ("C-u", "Delete to the start"),
+        ("previous-input", "Previous input (also M-p)"),
+        ("M-n", "Next input (also Down)"),
+        ("complete", "Complete, where the prompt offers completions"),
+        ("RET", "Submit"),
+        ("cancel", "Cancel (also C-g)"),
+    ];
+
+    pub fn new(prompt: &str) -> Self {
+        Self {
+            prompt: prompt.to_string(),
+            history_key: None,
+            input: String::new(),
+            cursor: 0,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+            accepts: |_| true,
+            validate: |_| Ok(()),
+            complete: None,
+        }
+    }
+
+    pub fn accepting(mut self, accepts: fn(char) -> bool) -> Self {
+        self.accepts = accepts;
+        self
+    }
+
+    pub fn validated_by(mut self, validate: fn(&str) -> Result<(), String>) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    pub fn with_history(mut self, key: &'static str) -> Self {
+        self.history_key = Some(key);
+        self
+    }
+
+    pub fn completed_by(mut self, complete: fn(&str) -> Vec<String>) -> Self {
+        self.complete = Some(complete);
+        self
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    #[allow(dead_code)] // Rendering goes through `line`
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    /// Prompt and input as plain text, for the status bar
+    pub fn text(&self) -> String {
+        format!("{}{}", self.prompt, self.input)
+    }
+
+    /// Empty the input for a new prompt, keeping the history
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.cursor = 0;
+        self.history_index = None;
+        self.draft.clear();
+    }
+
+    pub fn insert_char(&mut self, ch: char) {
+        let at = byte_offset(&self.input, self.cursor);
+        self.input.insert(at, ch);
+        // A combining mark or joiner extends the grapheme before it
+        self.cursor = grapheme_count(&self.input[..at + ch.len_utf8()]);
+    }
+
+    /// Delete before the cursor, returning whether anything was deleted
+    pub fn delete_backward(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        self.delete_forward()
+    }
+
+    /// Delete under the cursor, returning whether anything was deleted
+    pub fn delete_forward(&mut self) -> bool {
+        if self.cursor >= grapheme_count(&self.input) {
+            return false;
+        }
+        let start = byte_offset(&self.input, self.cursor);
+        let end = byte_offset(&self.input, self.cursor + 1);
+        self.input.drain(start..end);
+        true
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(grapheme_count(&self.input));
+    }
+
+    pub fn move_start(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = grapheme_count(&self.input);
+    }
+
+    /// C-k
+    pub fn kill_to_end(&mut self) {
+        let at = byte_offset(&self.input, self.cursor);
+        self.input.truncate(at);
+    }
+
+    /// C-u
+    pub fn kill_to_start(&mut self) {
+        let at = byte_offset(&self.input, self.cursor);
+        self.input.drain(..at);
+        self.cursor = 0;
+    }
+
+    /// Show the previous history entry, returning false at the oldest
+    pub fn history_previous(&mut self) -> bool {
+        let index = match self.history_index {
+            Some(0) => return false,
+            Some(index) => index - 1,
+            None if self.history.is_empty() => return false,
+            None => {
+                self.draft = self.input.clone();
+                self.history.len() - 1
+            }
+        };
+        self.history_index = Some(index);
+        self.set_input(self.history[index].clone());
+        true
+    }
+
+    /// Show the next history entry, or the draft after the newest
+    pub fn history_next(&mut self) -> bool {
+        let Some(index) = self.history_index else {
+            return false;
+        };
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.set_input(self.history[index + 1].clone());
+        } else {
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.draft);
+            self.set_input(draft);
+        }
+        true
+    }
+
+    /// Extend the input to the longest prefix shared by all completion
+    /// candidates, returning whether it grew
+    pub fn complete(&mut self) -> bool {
+        let Some(complete) = self.complete else {
+            return false;
+        };
+        let candidates = complete(&self.input);
+        let Some(prefix) = common_prefix(&candidates) else {
+            return false;
+        };
+        if prefix.len() <= self.input.len() || !prefix.starts_with(&self.input) {
+            return false;
+        }
+        self.set_input(prefix);
+        true
+    }
+
+    /// Validate the input and, if it passes, add it to the history
+    pub fn submit(&mut self) -> MinibufferEvent {
+        if let Err(message) = (self.validate)(&self.input) {
+            return MinibufferEvent::Invalid(message);
+        }
+        let input = self.input.clone();
+        if !input.trim().is_empty() {
+            // A repeated input moves to the end instead of piling up
+            self.history.retain(|entry| *entry != input);
+            self.history.push(input.clone());
+        }
+        self.history_index = None;
+        self.draft.clear();
+        MinibufferEvent::Submit(input)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> MinibufferEvent {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let edited = |changed: bool| {
+            if changed {
+                MinibufferEvent::Edited
+            } else {
+                MinibufferEvent::Moved
+            }
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(),
+            KeyCode::Esc => MinibufferEvent::Cancel,
+            KeyCode::Char('g') if ctrl => MinibufferEvent::Cancel,
+            KeyCode::Backspace => edited(self.delete_backward()),
+            KeyCode::Delete => edited(self.delete_forward()),
+            KeyCode::Char('d') if ctrl => edited(self.delete_forward()),
+            KeyCode::Char('k') if ctrl => {
+                self.kill_to_end();
+                MinibufferEvent::Edited
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.kill_to_start();
+                MinibufferEvent::Edited
+            }
+            KeyCode::Left => {
+                self.move_left();
+                MinibufferEvent::Moved
+            }
+            KeyCode::Char('b') if ctrl => {
+                self.move_left();
+                MinibufferEvent::Moved
+            }
+            KeyCode::Right => {
+                self.move_right();
+                MinibufferEvent::Moved
+            }
+            KeyCode::Char('f') if ctrl => {
+                self.move_right();
+                MinibufferEvent::Moved
+            }
+            KeyCode::Home => {
+                self.move_start();
+                MinibufferEvent::Moved
+            }
+            KeyCode::Char('a') if ctrl => {
+                self.move_start();
+                MinibufferEvent::Moved
+            }
+            KeyCode::End => {
+                self.move_end();
+                MinibufferEvent::Moved
+            }
+            KeyCode::Char('e') if ctrl => {
+                self.move_end();
+                MinibufferEvent::Moved
+            }
+            KeyCode::Up => edited(self.history_previous()),
+            KeyCode::Char('p') if alt => edited(self.history_previous()),
+            KeyCode::Down => edited(self.history_next()),
+            KeyCode::Char('n') if alt => edited(self.history_next()),
+            KeyCode::Tab if self.complete() => MinibufferEvent::Edited,
+            KeyCode::Char(ch) if !ctrl && !alt && (self.accepts)(ch) => {
+                self.insert_char(ch);
+                MinibufferEvent::Edited
+            }
+            _ => MinibufferEvent::Ignored,
+        }
+    }
+
+    /// Prompt and input with the cursor drawn as a block
+    pub fn line(&self, theme: &Theme) -> Line<'static> {
+        let cursor_style = Style::default().bg(theme.primary).fg(theme.background);
+        let text_style = Style::default().fg(theme.foreground);
+        let (before, at, after) = split_at_cursor(&self.input, self.cursor);
+
+        let mut spans = vec![
+            Span::styled(self.prompt.clone(), Style::default().fg(theme.primary)),
+            Span::styled(before.to_string(), text_style),
+        ];
+        match at {
+            Some(at) => {
+                spans.push(Span::styled(at.to_string(), cursor_style));
+                spans.push(Span::styled(after.to_string(), text_style));
+            }
+            None => spans.push(Span::styled(" ", cursor_style)),
+        }
+        Line::from(spans)
+    }
+
+    fn set_input(&mut self, input: String) {
+        self.input = input;
+        self.cursor = grapheme_count(&self.input);
+    }
+}
+
+/// Submitted input of every prompt with a history key, persisted to
+/// `~/.local/share/v2ex/prompt_history.json` like a shell history
+#[derive(Debug, Default)]
+pub struct PromptHistory {
+    entries: HashMap<String, Vec<String>>,
+    /// Where changes are saved; `None` keeps the history in memory only
+    path: Option<PathBuf>,
+}
+
+impl PromptHistory {
+    /// Entries kept per prompt, oldest dropped first
+    const CAPACITY: usize = 100;
+
+    /// Load the history from disk; a missing file starts empty
+    pub fn load() -> Result<Self> {
+        let path = json_store::data_path("prompt_history.json")?;
+        Ok(Self {
+            entries: json_store::load(&path)?.unwrap_or_default(),
+            path: Some(path),
+        })
+    }
+
+    /// Give `minibuffer` the saved history of its prompt
+    pub fn restore(&self, minibuffer: &mut Minibuffer) {
+        if let Some(saved) = minibuffer.history_key.and_then(|key| self.entries.get(key)) {
+            minibuffer.history = saved.clone();
+        }
+    }
+
+    /// Save the history of `minibuffer`'s prompt after a submission
+    pub fn remember(&mut self, minibuffer: &Minibuffer) -> Result<()> {
+        let Some(key) = minibuffer.history_key else {
+            return Ok(());
+        };
+        let skip = minibuffer.history.len().saturating_sub(Self::CAPACITY);
+        let entries = minibuffer.history[skip..].to_vec();
+        if self.entries.get(key) == Some(&entries) {
+            return Ok(());
+        }
+        self.entries.insert(key.to_string(), entries);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        json_store::save(path, &self.entries)
+    }
+}
+
+/// Longest prefix, on character boundaries, shared by all `candidates`
+fn common_prefix(candidates: &[String]) -> Option<String> {
+    let (first, rest) = candidates.split_first()?;
+    let mut len = first.len();
+    for candidate in rest {
+        len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .map(|((i, a), _)| i + a.len_utf8())
+            .last()
+            .unwrap_or(0)
+            .min(len);
+    }
+    Some(first[..len].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(minibuffer: &mut Minibuffer, keys: &str) -> MinibufferEvent {
+        let mut last = MinibufferEvent::Ignored;
+        for key in crate::keymap::parse_key_sequence(keys).unwrap() {
+            last = minibuffer.handle_key(key);
+        }
+        last
+    }
+
+    #[test]
+    fn test_editing_keys() {
+        let mut minibuffer = Minibuffer::new("> ");
+        press(&mut minibuffer, "h e l o");
+        press(&mut minibuffer, "<left> l");
+        assert_eq!(minibuffer.input(), "hello");
+        assert_eq!(minibuffer.cursor(), 4);
+
+        press(&mut minibuffer, "C-a C-d");
+        assert_eq!(minibuffer.input(), "ello");
+        press(&mut minibuffer, "C-f C-k");
+        assert_eq!(minibuffer.input(), "e");
+        press(&mut minibuffer, "C-e 中 文 DEL");
+        assert_eq!(minibuffer.input(), "e中");
+        press(&mut minibuffer, "C-u");
+        assert!(minibuffer.is_empty());
+        assert_eq!(press(&mut minibuffer, "C-g"), MinibufferEvent::Cancel);
+    }
+
+    #[test]
+    fn test_cursor_moves_over_whole_emoji() {
+        let mut minibuffer = Minibuffer::new("> ");
+        for ch in "a👍\u{1F3FD}b".chars() {
+            minibuffer.insert_char(ch);
+        }
+        assert_eq!(minibuffer.cursor(), 3);
+        press(&mut minibuffer, "<left> <left>");
+        assert_eq!(minibuffer.cursor(), 1);
+        press(&mut minibuffer, "C-d");
+        assert_eq!(minibuffer.input(), "ab");
+    }
+
+    #[test]
+    fn test_validation_and_history() {
+        let mut minibuffer = Minibuffer::new("# ")
+            .accepting(|c| c.is_ascii_digit())
+            .validated_by(|input| {
+                if input.is_empty() {
+                    Err("Enter a number".to_string())
+                } else {
+                    Ok(())
+                }
+            });
+        assert_eq!(
+            press(&mut minibuffer, "RET"),
+            MinibufferEvent::Invalid("Enter a number".to_string())
+        );
+        assert_eq!(press(&mut minibuffer, "x"), MinibufferEvent::Ignored);
+        assert_eq!(
+            press(&mut minibuffer, "1 2 RET"),
+            MinibufferEvent::Submit("12".to_string())
+        );
+        minibuffer.clear();
+        press(&mut minibuffer, "3 4 RET");
+        minibuffer.clear();
+
+        press(&mut minibuffer, "5");
+        press(&mut minibuffer, "<up>");
+        assert_eq!(minibuffer.input(), "34");
+        press(&mut minibuffer, "<up>");
+        assert_eq!(minibuffer.input(), "12");
+        assert_eq!(press(&mut minibuffer, "<up>"), MinibufferEvent::Moved);
+        press(&mut minibuffer, "<down> <down>");
+        assert_eq!(minibuffer.input(), "5");
+    }
+
+    #[test]
+    fn test_completion() {
+        let mut minibuffer = Minibuffer::new("").completed_by(|input| {
+            ["python", "programmer", "share"]
+                .iter()
+                .filter(|name| name.starts_with(input))
+                .map(|name| name.to_string())
+                .collect()
+        });
+        assert_eq!(press(&mut minibuffer, "p TAB"), MinibufferEvent::Ignored);
+        assert_eq!(press(&mut minibuffer, "y TAB"), MinibufferEvent::Edited);
+        assert_eq!(minibuffer.input(), "python");
+        assert_eq!(
+            common_prefix(&["中文".into(), "中国".into()]).unwrap(),
+            "中"
+        );
+        assert_eq!(common_prefix(&[]), None);
+    }
+
+    #[test]
+    fn test_prompt_history_persists_per_prompt() {
+        let path =
+            std::env::temp_dir().join(format!("v2ex-prompt-history-{}.json", std::process::id()));
+        let mut store = PromptHistory {
+            entries: HashMap::new(),
+            path: Some(path.clone()),
+        };
+        let mut node = Minibuffer::new("> ").with_history("node");
+        let mut secret = Minibuffer::new("");
+        for input in ["rust", "go", "rust"] {
+            node.clear();
+            press(
+                &mut node,
+                &input
+                    .chars()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+            node.submit();
+            store.remember(&node).unwrap();
+        }
+        press(&mut secret, "x");
+        secret.submit();
+        store.remember(&secret).unwrap();
+
+        let saved: HashMap<String, Vec<String>> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            saved,
+            HashMap::from([(
+                "node".to_string(),
+                vec!["go".to_string(), "rust".to_string()]
+            )])
+        );
+
+        let mut reopened = Minibuffer::new("> ").with_history("node");
+        store.restore(&mut reopened);
+        press(&mut reopened, "<up> <up>");
+        assert_eq!(reopened.input(), "go");
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use ratatui::layout::{Position, Rect};
+use unicode_width::UnicodeWidthStr;
+
+/// A list drawn in the last frame and the rows its items occupy
+#[derive(Debug, Clone, Default)]
+pub struct ListRegion {
+    /// Inner area of the list, excluding borders
+    pub area: Rect,
+    /// Index of the first visible item
+    pub offset: usize,
+    /// Height in rows of every item
+    pub heights: Vec<u16>,
+}
+
+impl ListRegion {
+    /// Region for a bordered list of single-line items scrolled so that
+    /// item `offset` is at the top
+    pub fn bordered(area: Rect, offset: usize, len: usize) -> Self {
+        Self::bordered_with_heights(area, offset, vec![1; len])
+    }
+
+    pub fn bordered_with_heights(area: Rect, offset: usize, heights: Vec<u16>) -> Self {
+        let area = Rect {
+            x: area.x.saturating_add(1),
+            y: area.y.saturating_add(1),
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(2),
+        };
+        Self {
+            area,
+            offset,
+            heights,
+        }
+    }
+
+    pub fn contains(&self, column: u16, row: u16) -> bool {
+        self.area.contains(Position::new(column, row))
+    }
+
+    /// Index of the item drawn at the given screen cell
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.contains(column, row) {
+            return None;
+        }
+        let target = row - self.area.y;
+        let mut top = 0u16;
+        for (index, height) in self.heights.iter().enumerate().skip(self.offset) {
+            top = top.saturating_add(*height);
+            if target < top {
+                return Some(index);
+            }
+        }
+        None
+    }
+}
+
+/// Header entries that stand in for a key press when clicked
+#[derive(Debug, Clone, Default)]
+pub struct HeaderRegion {
+    pub row: u16,
+    /// `(start column, end column exclusive, key)` of each entry
+    pub entries: Vec<(u16, u16, char)>,
+}
+
+impl HeaderRegion {
+    /// Lay out a `<prefix><key>:<label> <key>:<label> ...` header line
+    pub fn new(area: Rect, prefix: &str, entries: &[(char, &str)]) -> Self {
+        let mut x = area.x.saturating_add(prefix.width() as u16);
+        let entries = entries
+            .iter()
+            .map(|(key, label)| {
+                // "<key>:<label>" followed by a separating space
+                let width = (format!("{}:{}", key, label).width()) as u16;
+                let entry = (x, x.saturating_add(width), *key);
+                x = x.saturating_add(width + 1);
+                entry
+            })
+            .collect();
+        Self {
+            row: area.y,
+            entries,
+        }
+    }
+
+    pub fn key_at(&self, column: u16, row: u16) -> Option<char> {
+        if row != self.row {
+            return None;
+        }
+        self.entries
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&column))
+            .map(|(_, _, key)| *key)
+    }
+}
+
+/// Clickable and scrollable regions of the last rendered frame
+#[derive(Debug, Clone, Default)]
+pub struct MouseRegions {
+    pub list: Option<ListRegion>,
+    pub header: Option<HeaderRegion>,
+    /// Scrollable topic content in detail view
+    pub content: Option<Rect>,
+}
+
+impl MouseRegions {
+    pub fn with_list(list: ListRegion) -> Self {
+        Self {
+            list: Some(list),
+            ..Default::default()
+        }
+    }
+
+    pub fn in_content(&self, column: u16, row: u16) -> bool {
+        self.content
+            .is_some_and(|area| area.contains(Position::new(column, row)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_item_at() {
+        let list = ListRegion::bordered_with_heights(Rect::new(0, 0, 20, 10), 1, vec![3, 2, 4]);
+        // Border rows and columns are not part of any item
+        assert_eq!(list.item_at(0, 1), None);
+        assert_eq!(list.item_at(5, 0), None);
+        // Item 0 is scrolled out, so item 1 starts at the first inner row
+        assert_eq!(list.item_at(5, 1), Some(1));
+        assert_eq!(list.item_at(5, 2), Some(1));
+        assert_eq!(list.item_at(5, 3), Some(2));
+        assert_eq!(list.item_at(5, 6), Some(2));
+        assert_eq!(list.item_at(5, 7), None);
+    }
+
+    #[test]
+    fn test_header_key_at() {
+        let header = HeaderRegion::new(
+            Rect::new(0, 2, 80, 1),
+            "Nodes: ",
+            &[('1', "python"), ('2', "go")],
+        );
+        assert_eq!(header.key_at(7, 2), Some('1'));
+        assert_eq!(header.key_at(14, 2), Some('1'));
+        // The separating space belongs to neither entry
+        assert_eq!(header.key_at(15, 2), None);
+        assert_eq!(header.key_at(16, 2), Some('2'));
+        assert_eq!(header.key_at(16, 3), None);
+    }
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::api::{Notification, V2exClient};
+
+/// Poll the first page of notifications every `interval` in the background.
+///
+/// Each successful fetch is sent to the returned receiver; the task stops
+/// once the receiver is dropped. Polls are skipped while the rate limit
+/// scheduler is holding back background work.
+pub fn spawn_notification_poller(
+    client: V2exClient,
+    interval: Duration,
+) -> mpsc::UnboundedReceiver<Vec<Notification>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if tx.is_closed() {
+                break;
+            }
+            if !client.can_schedule() {
+                continue;
+            }
+            if let Ok(list) = client.get_notifications(1).await {
+                if tx.send(list.items).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Show a desktop notification off the UI thread, ignoring failures
+/// (e.g. no notification daemon running)
+pub fn send_desktop_notification(summary: String, body: String) {
+    tokio::task::spawn_blocking(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("v2ex-tui")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
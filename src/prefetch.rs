@@ -0,0 +1,302 @@
+//! Loading pages in the background before they are asked for: the first
+//! page of the favorite nodes after startup, so switching to them with 1-9
+//! needs no request, the next page of a list as the selection nears its
+//! end, and the aggregate tabs next to the one shown.
+
+use anyhow::Result;
+use futures_util::future::join_all;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::api::{PartialList, Reply, RssItem, Topic, V2exClient};
+
+/// Pause between nodes, on top of the scheduler's own throttling
+const PREFETCH_SPACING: Duration = Duration::from_secs(2);
+/// How long a prefetched page is shown instead of fetching it again
+const PREFETCH_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Fetch page 1 of each of `nodes`, one at a time, sending each page to the
+/// returned receiver. Stops early when the rate limit budget runs low or
+/// the receiver is dropped.
+pub fn spawn_node_prefetch(
+    client: V2exClient,
+    nodes: Vec<String>,
+) -> mpsc::UnboundedReceiver<(String, Vec<Topic>)> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        for node in nodes {
+            tokio::time::sleep(PREFETCH_SPACING).await;
+            if tx.is_closed() || !client.can_schedule() {
+                break;
+            }
+            if let Ok(list) = client.get_node_topics(&node, 1).await {
+                if tx.send((node, list.items)).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Prefetched first pages waiting to be shown, by node name
+#[derive(Debug, Default)]
+pub struct PrefetchedTopics {
+    pages: HashMap<String, (Instant, Vec<Topic>)>,
+}
+
+impl PrefetchedTopics {
+    pub fn insert(&mut self, node: String, topics: Vec<Topic>) {
+        self.pages.insert(node, (Instant::now(), topics));
+    }
+
+    /// The page for `node` if it is still fresh. It is handed out once, so
+    /// refreshing afterwards goes to the network.
+    pub fn take(&mut self, node: &str) -> Option<Vec<Topic>> {
+        let (fetched, topics) = self.pages.remove(node)?;
+        (fetched.elapsed() < PREFETCH_TTL).then_some(topics)
+    }
+}
+
+/// The page after the last one loaded of a list
+#[derive(Debug, Clone, PartialEq)]
+pub enum NextPage {
+    Topics { node: String, page: i32 },
+    Replies { topic_id: i64, page: i32 },
+}
+
+#[derive(Debug)]
+pub enum LoadedPage {
+    Topics(PartialList<Topic>),
+    Replies(PartialList<Reply>),
+}
+
+/// Whether `position` is within `distance` items of the end of a list of
+/// `len`; a distance of 0 never is
+pub fn near_end(position: usize, len: usize, distance: usize) -> bool {
+    distance > 0 && len > 0 && position + distance >= len
+}
+
+/// The next page being fetched before the selection reaches the end
+#[derive(Debug, Default)]
+pub struct LoadAhead {
+    running: Option<(NextPage, oneshot::Receiver<Result<LoadedPage>>)>,
+    /// A page that came back empty or failed, so it isn't fetched ahead
+    /// again on every key
+    skipped: Option<NextPage>,
+}
+
+impl LoadAhead {
+    /// Fetch `page` without waiting for it, unless a page is already being
+    /// fetched or `page` was skipped
+    pub fn start(&mut self, client: &V2exClient, page: NextPage) {
+        if self.running.is_some() || self.skipped.as_ref() == Some(&page) {
+            return;
+        }
+        let (tx, rx) = oneshot::channel();
+        let client = client.clone();
+        let wanted = page.clone();
+        tokio::spawn(async move {
+            let result = match wanted {
+                NextPage::Topics { node, page } => client
+                    .get_node_topics(&node, page)
+                    .await
+                    .map(LoadedPage::Topics),
+                NextPage::Replies { topic_id, page } => client
+                    .get_topic_replies(topic_id, page)
+                    .await
+                    .map(LoadedPage::Replies),
+            };
+            let _ = tx.send(result);
+        });
+        self.running = Some((page, rx));
+    }
+
+    /// The page being fetched, if any
+    pub fn running(&self) -> Option<&NextPage> {
+        self.running.as_ref().map(|(page, _)| page)
+    }
+
+    /// The page fetched by `start`, once it arrived
+    pub fn finished(&mut self) -> Option<(NextPage, Result<LoadedPage>)> {
+        let (_, rx) = self.running.as_mut()?;
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return None,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Err(anyhow::anyhow!("loading was interrupted"))
+            }
+        };
+        let (page, _) = self.running.take()?;
+        Some((page, result))
+    }
+
+    /// Leave `page` to be loaded at the end of the list as usual
+    pub fn skip(&mut self, page: NextPage) {
+        self.skipped = Some(page);
+    }
+}
+
+/// Aggregate tabs fetched together, by name
+pub type AggregateTabs = Vec<(String, PartialList<RssItem>)>;
+
+/// Aggregate tabs fetched so far with when (Unix seconds), so switching
+/// back to one soon after needs no request
+#[derive(Debug, Default)]
+pub struct AggregateCache {
+    tabs: HashMap<String, (i64, PartialList<RssItem>)>,
+    prefetching: Option<oneshot::Receiver<AggregateTabs>>,
+}
+
+impl AggregateCache {
+    pub fn insert(&mut self, tab: &str, fetched_at: i64, list: PartialList<RssItem>) {
+        self.tabs.insert(tab.to_string(), (fetched_at, list));
+    }
+
+    /// The items of `tab` and when they were fetched, if that was less
+    /// than `ttl` before `now`
+    pub fn fresh(
+        &self,
+        tab: &str,
+        ttl: Duration,
+        now: i64,
+    ) -> Option<(i64, &PartialList<RssItem>)> {
+        let (fetched_at, list) = self.tabs.get(tab)?;
+        let age = now.saturating_sub(*fetched_at).max(0) as u64;
+        (age < ttl.as_secs()).then_some((*fetched_at, list))
+    }
+
+    /// Fetch `tabs`, paired with their `add-feed` URLs, all at once without
+    /// waiting for them, unless a prefetch is already running
+    pub fn prefetch(&mut self, client: &V2exClient, tabs: Vec<(String, Option<String>)>) {
+        if self.prefetching.is_some() || tabs.is_empty() {
+            return;
+        }
+        let (tx, rx) = oneshot::channel();
+        let client = client.clone();
+        tokio::spawn(async move {
+            let fetches = tabs
+                .iter()
+                .map(|(tab, feed)| client.get_aggregate_items(tab, feed.as_deref()));
+            let results = join_all(fetches).await;
+            // A tab that failed is fetched when it is switched to, as before
+            let loaded = tabs
+                .into_iter()
+                .zip(results)
+                .filter_map(|((tab, _), result)| Some((tab, result.ok()?)))
+                .collect();
+            let _ = tx.send(loaded);
+        });
+        self.prefetching = Some(rx);
+    }
+
+    /// The tabs fetched by `prefetch`, once they all arrived
+    pub fn prefetched(&mut self) -> Option<AggregateTabs> {
+        let loaded = match self.prefetching.as_mut()?.try_recv() {
+            Ok(loaded) => loaded,
+            Err(oneshot::error::TryRecvError::Empty) => return None,
+            Err(oneshot::error::TryRecvError::Closed) => Vec::new(),
+        };
+        self.prefetching = None;
+        Some(loaded)
+    }
+}
+
+/// Tabs to fetch along with `tab`: the `configured` ones, or else the tabs
+/// on either side of it in the header, `tabs` as `tab_key_mappings` lists them
+pub fn aggregate_tabs_to_prefetch(
+    tab: &str,
+    configured: &[String],
+    tabs: &[(char, String)],
+) -> Vec<String> {
+    if !configured.is_empty() {
+        return configured.iter().filter(|t| *t != tab).cloned().collect();
+    }
+    let Some(index) = tabs.iter().position(|(_, t)| t == tab) else {
+        return Vec::new();
+    };
+    let len = tabs.len();
+    [(index + len - 1) % len, (index + 1) % len]
+        .into_iter()
+        .map(|i| tabs[i].1.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::aggregate::AGGREGATE_TABS;
+
+    #[test]
+    fn test_prefetched_topics_taken_once() {
+        let mut prefetched = PrefetchedTopics::default();
+        prefetched.insert("rust".to_string(), Vec::new());
+        assert!(prefetched.take("go").is_none());
+        assert!(prefetched.take("rust").is_some());
+        assert!(prefetched.take("rust").is_none());
+
+        prefetched.pages.insert(
+            "go".to_string(),
+            (Instant::now() - PREFETCH_TTL, Vec::new()),
+        );
+        assert!(prefetched.take("go").is_none());
+    }
+
+    #[test]
+    fn test_aggregate_cache_fresh() {
+        let mut cache = AggregateCache::default();
+        let ttl = Duration::from_secs(300);
+        let list = PartialList {
+            items: Vec::new(),
+            skipped: 0,
+        };
+        cache.insert("tech", 1000, list);
+        assert_eq!(cache.fresh("tech", ttl, 1299).map(|(at, _)| at), Some(1000));
+        assert!(cache.fresh("tech", ttl, 1300).is_none());
+        assert!(cache.fresh("tech", Duration::ZERO, 1000).is_none());
+        assert!(cache.fresh("play", ttl, 1000).is_none());
+    }
+
+    #[test]
+    fn test_aggregate_tabs_to_prefetch() {
+        let mut tabs: Vec<(char, String)> = AGGREGATE_TABS
+            .iter()
+            .map(|(key, tab)| (*key, tab.to_string()))
+            .collect();
+        let first = AGGREGATE_TABS[0].1;
+        let (second, last) = (
+            AGGREGATE_TABS[1].1,
+            AGGREGATE_TABS[AGGREGATE_TABS.len() - 1].1,
+        );
+        assert_eq!(
+            aggregate_tabs_to_prefetch(first, &[], &tabs),
+            [last, second]
+        );
+        let configured = ["hot".to_string(), "tech".to_string()];
+        assert_eq!(
+            aggregate_tabs_to_prefetch("tech", &configured, &tabs),
+            ["hot"]
+        );
+        assert!(aggregate_tabs_to_prefetch("nope", &[], &tabs).is_empty());
+
+        // Added feeds come after the built-in tabs and wrap around to them
+        tabs.push(('w', "planet".to_string()));
+        assert_eq!(
+            aggregate_tabs_to_prefetch(first, &[], &tabs),
+            ["planet", second]
+        );
+        assert_eq!(
+            aggregate_tabs_to_prefetch("planet", &[], &tabs),
+            [last, first]
+        );
+    }
+
+    #[test]
+    fn test_near_end() {
+        assert!(near_end(15, 20, 5));
+        assert!(!near_end(14, 20, 5));
+        assert!(!near_end(19, 20, 0));
+        assert!(!near_end(0, 0, 5));
+    }
+}
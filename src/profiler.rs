@@ -0,0 +1,149 @@
+//! Opt-in frame timing for finding slow render paths.
+//!
+//! Render code wraps interesting sections in [`scope`]; while profiling is
+//! off a scope is a single atomic load. Time is attributed to the innermost
+//! scope, so an outer scope only reports what its children did not spend.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static STATE: RefCell<ProfilerState> = RefCell::new(ProfilerState::default());
+}
+
+#[derive(Debug, Default)]
+struct ProfilerState {
+    /// Time spent in children of each open scope, innermost last
+    open: Vec<Duration>,
+    /// Self time per scope name in the frame being drawn
+    current: Vec<(&'static str, Duration)>,
+    last: Option<FrameReport>,
+}
+
+/// Timing of the last completed frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameReport {
+    pub total: Duration,
+    pub slowest: Option<(&'static str, Duration)>,
+}
+
+impl FrameReport {
+    pub fn summary(&self) -> String {
+        let mut summary = format!("frame {:.1}ms", millis(self.total));
+        if let Some((name, time)) = self.slowest {
+            summary.push_str(&format!(", slowest {} {:.1}ms", name, millis(time)));
+        }
+        summary
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Flip profiling on or off, returning the new state
+pub fn toggle() -> bool {
+    let enabled = !ENABLED.fetch_xor(true, Ordering::Relaxed);
+    STATE.with(|state| *state.borrow_mut() = ProfilerState::default());
+    enabled
+}
+
+/// Time a section until the returned guard is dropped
+pub fn scope(name: &'static str) -> Scope {
+    if !is_enabled() {
+        return Scope { name, start: None };
+    }
+    STATE.with(|state| state.borrow_mut().open.push(Duration::ZERO));
+    Scope {
+        name,
+        start: Some(Instant::now()),
+    }
+}
+
+pub struct Scope {
+    name: &'static str,
+    start: Option<Instant>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let children = state.open.pop().unwrap_or_default();
+            if let Some(parent) = state.open.last_mut() {
+                *parent += elapsed;
+            }
+            let self_time = elapsed.saturating_sub(children);
+            match state
+                .current
+                .iter_mut()
+                .find(|(name, _)| *name == self.name)
+            {
+                Some((_, total)) => *total += self_time,
+                None => state.current.push((self.name, self_time)),
+            }
+        });
+    }
+}
+
+/// Close the frame that took `total` to draw and keep its report
+pub fn finish_frame(total: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let slowest = state.current.drain(..).max_by_key(|(_, time)| *time);
+        state.open.clear();
+        state.last = Some(FrameReport { total, slowest });
+    });
+}
+
+/// Report of the last frame, while profiling is on
+pub fn last_report() -> Option<FrameReport> {
+    if !is_enabled() {
+        return None;
+    }
+    STATE.with(|state| state.borrow().last.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_report_self_time() {
+        // Profiling state is per thread, but ENABLED is global; restore it
+        let was_enabled = is_enabled();
+        if !was_enabled {
+            toggle();
+        }
+        {
+            let _outer = scope("outer");
+            std::thread::sleep(Duration::from_millis(2));
+            {
+                let _inner = scope("inner");
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        }
+        finish_frame(Duration::from_millis(30));
+        let report = last_report().unwrap();
+        assert_eq!(report.total, Duration::from_millis(30));
+        assert_eq!(report.slowest.map(|(name, _)| name), Some("inner"));
+        assert!(report.summary().starts_with("frame 30.0ms, slowest inner"));
+        if !was_enabled {
+            toggle();
+        }
+    }
+}
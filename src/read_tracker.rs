@@ -0,0 +1,295 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::json_store;
+
+/// Topic IDs the user has opened, persisted to `~/.local/share/v2ex/read.json`.
+/// Read notifications are tracked the same way in `read_notifications.json`.
+#[derive(Debug, Default)]
+pub struct ReadTracker {
+    read: HashSet<i64>,
+    /// Where changes are saved; `None` keeps the set in memory only
+    path: Option<PathBuf>,
+}
+
+impl ReadTracker {
+    /// Load the read set from disk; a missing file starts an empty set
+    pub fn load() -> Result<Self> {
+        Self::load_from(json_store::data_path("read.json")?)
+    }
+
+    /// Load the set of read notifications
+    pub fn load_notifications() -> Result<Self> {
+        Self::load_from(json_store::data_path("read_notifications.json")?)
+    }
+
+    fn load_from(path: PathBuf) -> Result<Self> {
+        Ok(Self {
+            read: json_store::load(&path)?.unwrap_or_default(),
+            path: Some(path),
+        })
+    }
+
+    pub fn is_read(&self, topic_id: i64) -> bool {
+        self.read.contains(&topic_id)
+    }
+
+    pub fn mark_read(&mut self, topic_id: i64) -> Result<()> {
+        if self.read.insert(topic_id) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Flip the read state of a topic, returning whether it is now read
+    pub fn toggle(&mut self, topic_id: i64) -> Result<bool> {
+        let now_read = if self.read.remove(&topic_id) {
+            false
+        } else {
+            self.read.insert(topic_id);
+            true
+        };
+        self.save()?;
+        Ok(now_read)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut ids: Vec<i64> = self.read.iter().copied().collect();
+        ids.sort_unstable();
+        json_store::save(path, &ids)
+    }
+}
+
+/// Newest notification shown in the notifications view, persisted to
+/// `~/.local/share/v2ex/seen_notifications.json` so the unread badge also
+/// counts what arrived while the app was closed
+#[derive(Debug, Default)]
+pub struct SeenNotifications {
+    newest: Option<i64>,
+    /// Where changes are saved; `None` keeps the ID in memory only
+    path: Option<PathBuf>,
+}
+
+impl SeenNotifications {
+    /// Load the ID from disk; a missing file means nothing seen yet
+    pub fn load() -> Result<Self> {
+        let path = json_store::data_path("seen_notifications.json")?;
+        Ok(Self {
+            newest: json_store::load(&path)?.flatten(),
+            path: Some(path),
+        })
+    }
+
+    pub fn newest(&self) -> Option<i64> {
+        self.newest
+    }
+
+    /// Remember that notifications up to `id` were seen
+    pub fn record(&mut self, id: i64) -> Result<()> {
+        if self.newest.is_some_and(|newest| newest >= id) {
+            return Ok(());
+        }
+        self.newest = Some(id);
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        json_store::save(path, &self.newest)
+    }
+}
+
+/// Reply counts of topics as of their last visit, persisted to
+/// `~/.local/share/v2ex/seen_replies.json`
+#[derive(Debug, Default)]
+pub struct SeenReplies {
+    counts: HashMap<i64, i64>,
+    /// Where changes are saved; `None` keeps the counts in memory only
+    path: Option<PathBuf>,
+}
+
+impl SeenReplies {
+    /// Load the counts from disk; a missing file starts with none
+    pub fn load() -> Result<Self> {
+        let path = json_store::data_path("seen_replies.json")?;
+        Ok(Self {
+            counts: json_store::load(&path)?.unwrap_or_default(),
+            path: Some(path),
+        })
+    }
+
+    /// Remember that `topic_id` had `count` replies when seen, returning
+    /// the count from the visit before. Counts never go down, so replies
+    /// deleted in between don't hide new ones next time.
+    pub fn record(&mut self, topic_id: i64, count: i64) -> Result<Option<i64>> {
+        let previous = self.counts.get(&topic_id).copied();
+        if previous.is_none_or(|seen| seen < count) {
+            self.counts.insert(topic_id, count);
+            self.save()?;
+        }
+        Ok(previous)
+    }
+
+    /// Replies added since the last visit to a topic that now has `count`;
+    /// `None` for topics never opened or with nothing new
+    pub fn new_replies(&self, topic_id: i64, count: i64) -> Option<i64> {
+        let seen = self.counts.get(&topic_id)?;
+        (count > *seen).then(|| count - seen)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        json_store::save(path, &self.counts)
+    }
+}
+
+/// Most topics kept in `VisitedTopics`; the oldest are dropped first
+const MAX_VISITED_TOPICS: usize = 1000;
+
+/// A topic as it was when last opened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisitedTopic {
+    pub id: i64,
+    pub title: String,
+    #[serde(default)]
+    pub node: String,
+    /// Unix timestamp (seconds) of the last visit
+    pub visited_at: i64,
+}
+
+/// Every topic opened, newest first, persisted to
+/// `~/.local/share/v2ex/visited_topics.json`
+#[derive(Debug, Default)]
+pub struct VisitedTopics {
+    entries: Vec<VisitedTopic>,
+    /// Where changes are saved; `None` keeps the list in memory only
+    path: Option<PathBuf>,
+}
+
+impl VisitedTopics {
+    /// Load the list from disk; a missing file starts an empty one
+    pub fn load() -> Result<Self> {
+        let path = json_store::data_path("visited_topics.json")?;
+        Ok(Self {
+            entries: json_store::load(&path)?.unwrap_or_default(),
+            path: Some(path),
+        })
+    }
+
+    pub fn entries(&self) -> &[VisitedTopic] {
+        &self.entries
+    }
+
+    /// Put `topic` at the top, replacing its earlier visit
+    pub fn record(&mut self, topic: &crate::api::Topic) -> Result<()> {
+        self.entries.retain(|entry| entry.id != topic.id);
+        self.entries.insert(
+            0,
+            VisitedTopic {
+                id: topic.id,
+                title: topic.title.clone(),
+                node: topic
+                    .node
+                    .as_ref()
+                    .map(|node| node.name.clone())
+                    .unwrap_or_default(),
+                visited_at: chrono::Utc::now().timestamp(),
+            },
+        );
+        self.entries.truncate(MAX_VISITED_TOPICS);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        json_store::save(path, &self.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_and_persist() {
+        let path = std::env::temp_dir().join(format!("v2ex-read-{}.json", std::process::id()));
+        let mut tracker = ReadTracker {
+            read: HashSet::new(),
+            path: Some(path.clone()),
+        };
+
+        tracker.mark_read(42).unwrap();
+        assert!(tracker.is_read(42));
+        assert!(tracker.toggle(7).unwrap());
+        assert!(!tracker.toggle(42).unwrap());
+
+        let saved: HashSet<i64> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved, HashSet::from([7]));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_seen_replies_record() {
+        let path = std::env::temp_dir().join(format!("v2ex-seen-{}.json", std::process::id()));
+        let mut seen = SeenReplies {
+            counts: HashMap::new(),
+            path: Some(path.clone()),
+        };
+
+        assert_eq!(seen.record(42, 10).unwrap(), None);
+        assert_eq!(seen.record(42, 15).unwrap(), Some(10));
+        assert_eq!(seen.record(42, 12).unwrap(), Some(15));
+        assert_eq!(seen.record(42, 15).unwrap(), Some(15));
+        assert_eq!(seen.new_replies(42, 27), Some(12));
+        assert_eq!(seen.new_replies(42, 15), None);
+        assert_eq!(seen.new_replies(7, 3), None);
+
+        let saved: HashMap<i64, i64> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved, HashMap::from([(42, 15)]));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_seen_notifications_record() {
+        let path = std::env::temp_dir().join(format!(
+            "v2ex-seen-notifications-{}.json",
+            std::process::id()
+        ));
+        let mut seen = SeenNotifications {
+            newest: None,
+            path: Some(path.clone()),
+        };
+
+        seen.record(12).unwrap();
+        seen.record(9).unwrap();
+        assert_eq!(seen.newest(), Some(12));
+        let saved: Option<i64> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved, Some(12));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_visited_topics_record() {
+        let mut visited = VisitedTopics::default();
+        let topic = |id: i64, title: &str| -> crate::api::Topic {
+            serde_json::from_value(serde_json::json!({ "id": id, "title": title })).unwrap()
+        };
+        visited.record(&topic(1, "First")).unwrap();
+        visited.record(&topic(2, "Second")).unwrap();
+        visited.record(&topic(1, "First, renamed")).unwrap();
+
+        let ids: Vec<i64> = visited.entries().iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, [1, 2]);
+        assert_eq!(visited.entries()[0].title, "First, renamed");
+    }
+}
@@ -0,0 +1,1056 @@
+//! Render the HTML of topics and replies as styled lines.
+//!
+//! html2text runs table cells together and loses the structure of nested
+//! lists, so this handles the subset V2EX's Markdown produces itself:
+//! tables become aligned columns, list items keep their markers and
+//! nesting, quotes get a bar per level, and headings, code and links are
+//! styled. Lines are wrapped to the given width so indentation survives.
+//!
+//! Links are collected while rendering, so link mode numbers exactly the
+//! links that are shown, whatever the width.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::util::width::{grapheme_width, graphemes, split_at_width, text_width};
+
+use crate::ui::Theme;
+
+/// Separator between table columns
+const COLUMN_GAP: &str = " │ ";
+
+/// Lines of rendered HTML and where its links go
+#[derive(Debug, Default)]
+pub struct Rendered {
+    pub lines: Vec<Line<'static>>,
+    /// Absolute URLs of links, images and addresses in the text, in the
+    /// order they appear
+    pub links: Vec<String>,
+}
+
+/// Lay out `html` in lines at most `width` cells wide, putting `[label]`
+/// before the n-th link for each of `labels`, as link mode shows them
+pub fn render(html: &str, width: usize, theme: &Theme, labels: &[String]) -> Rendered {
+    let mut renderer = Renderer::new(width.max(1), theme, labels);
+    for token in tokenize(html) {
+        renderer.token(token);
+    }
+    renderer.finish()
+}
+
+/// The text of each `<pre>` block in `html`, in order, for copying code
+pub fn code_blocks(html: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut block = String::new();
+    for token in tokenize(html) {
+        match token {
+            Token::Open { name, .. } if name == "pre" => depth += 1,
+            Token::Close(name) if name == "pre" && depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    let code = std::mem::take(&mut block);
+                    // A newline right after <pre> is not part of the content
+                    let code = code.strip_prefix('\n').unwrap_or(&code).trim_end();
+                    if !code.trim().is_empty() {
+                        blocks.push(code.to_string());
+                    }
+                }
+            }
+            Token::Open { name, .. } if name == "br" && depth > 0 => block.push('\n'),
+            Token::Text(text) if depth > 0 => block.push_str(&text),
+            _ => {}
+        }
+    }
+    blocks
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    Open {
+        name: String,
+        attrs: Vec<(String, String)>,
+    },
+    Close(String),
+    Text(String),
+}
+
+pub(super) fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+pub(super) fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..start])));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim().to_ascii_lowercase()));
+        } else if !tag.starts_with(['!', '?']) {
+            let tag = tag.trim_end_matches('/');
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            let name = tag[..name_end].to_ascii_lowercase();
+            if !name.is_empty() {
+                let attrs = parse_attrs(&tag[name_end..]);
+                tokens.push(Token::Open { name, attrs });
+            }
+        }
+    }
+    tokens
+}
+
+fn parse_attrs(mut rest: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        if key_end == 0 {
+            break;
+        }
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let inner = &after[1..];
+                        let close = inner.find(quote).unwrap_or(inner.len());
+                        (&inner[..close], inner.get(close + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining;
+                decode_entities(value)
+            }
+            None => String::new(),
+        };
+        attrs.push((key, value));
+    }
+    attrs
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end + 1])?, end + 2)));
+        match entity {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "copy" => '©',
+        _ => return None,
+    })
+}
+
+/// Links take V2EX's relative URLs to the site; other targets (anchors,
+/// `mailto:`) can't be opened and are left out
+pub(super) fn absolute_url(target: &str) -> Option<String> {
+    if target.starts_with("http://") || target.starts_with("https://") {
+        Some(target.to_string())
+    } else if let Some(rest) = target.strip_prefix("//") {
+        Some(format!("https://{}", rest))
+    } else if target.starts_with('/') {
+        Some(format!("https://www.v2ex.com{}", target))
+    } else {
+        None
+    }
+}
+
+/// Byte range of the first address written out in `text`
+fn find_url(text: &str) -> Option<std::ops::Range<usize>> {
+    let start = ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| text.find(scheme))
+        .min()?;
+    let len = text[start..]
+        .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | ')' | ']'))
+        .unwrap_or(text.len() - start);
+    Some(start..start + len)
+}
+
+/// Collapse runs of whitespace the way a browser does outside `<pre>`
+pub(super) fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(c);
+            in_space = false;
+        }
+    }
+    collapsed
+}
+
+#[derive(Debug)]
+struct List {
+    /// Next item number for ordered lists
+    next: Option<usize>,
+    /// Width of the current item's marker, which continuation lines indent by
+    indent: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Default)]
+struct Table {
+    rows: Vec<(Vec<String>, bool)>,
+    row: Vec<String>,
+    header_row: bool,
+    cell: Option<String>,
+    aligns: Vec<Align>,
+}
+
+struct Renderer<'t> {
+    width: usize,
+    theme: &'t Theme,
+    /// Link mode labels for the links, in order
+    labels: &'t [String],
+    lines: Vec<Line<'static>>,
+    links: Vec<String>,
+    /// Inline text of the block being built
+    inline: Vec<(String, Style)>,
+    bold: usize,
+    italic: usize,
+    code: usize,
+    heading: usize,
+    pre: usize,
+    /// Target and text so far of the open link
+    link: Option<(String, String)>,
+    lists: Vec<List>,
+    /// Marker for the first line of the list item just opened
+    marker: Option<String>,
+    quotes: usize,
+    table: Option<Table>,
+}
+
+impl<'t> Renderer<'t> {
+    fn new(width: usize, theme: &'t Theme, labels: &'t [String]) -> Self {
+        Self {
+            width,
+            theme,
+            labels,
+            lines: Vec::new(),
+            links: Vec::new(),
+            inline: Vec::new(),
+            bold: 0,
+            italic: 0,
+            code: 0,
+            heading: 0,
+            pre: 0,
+            link: None,
+            lists: Vec::new(),
+            marker: None,
+            quotes: 0,
+            table: None,
+        }
+    }
+
+    fn token(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.text(&text),
+            Token::Open { name, attrs } => self.open(&name, &attrs),
+            Token::Close(name) => self.close(&name),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = if let Some(table) = &self.table {
+            if table.cell.is_none() {
+                return;
+            }
+            collapse_whitespace(text)
+        } else if self.pre > 0 {
+            // A newline right after <pre> is not part of the content
+            let text = if self.inline.is_empty() {
+                text.strip_prefix('\n').unwrap_or(text)
+            } else {
+                text
+            };
+            text.to_string()
+        } else {
+            collapse_whitespace(text)
+        };
+        if let Some((_, link_text)) = &mut self.link {
+            link_text.push_str(&text);
+            self.push_text(&text);
+            return;
+        }
+        // Addresses written out in the text can be opened too
+        let mut rest = text.as_str();
+        while let Some(url) = find_url(rest) {
+            self.push_text(&rest[..url.start]);
+            self.note_link(&rest[url.clone()]);
+            self.push_text(&rest[url.clone()]);
+            rest = &rest[url.end..];
+        }
+        self.push_text(rest);
+    }
+
+    /// Add text to the open table cell, or else the current block
+    fn push_text(&mut self, text: &str) {
+        match &mut self.table {
+            Some(table) => {
+                if let Some(cell) = &mut table.cell {
+                    cell.push_str(text);
+                }
+            }
+            None => {
+                let style = self.inline_style();
+                self.inline.push((text.to_string(), style));
+            }
+        }
+    }
+
+    /// Record a link target, labelling it if link mode is on
+    fn note_link(&mut self, target: &str) {
+        let Some(url) = absolute_url(target) else {
+            return;
+        };
+        let index = self.links.len();
+        self.links.push(url);
+        let Some(label) = self.labels.get(index) else {
+            return;
+        };
+        let label = format!("[{}] ", label);
+        match &mut self.table {
+            Some(table) => {
+                if let Some(cell) = &mut table.cell {
+                    cell.push_str(&label);
+                }
+            }
+            None => {
+                let style = Style::default()
+                    .fg(self.theme.primary)
+                    .add_modifier(Modifier::BOLD);
+                self.inline.push((label, style));
+            }
+        }
+    }
+
+    fn open(&mut self, name: &str, attrs: &[(String, String)]) {
+        match name {
+            "a" => self.note_link(attr(attrs, "href").unwrap_or_default()),
+            "img" => self.note_link(attr(attrs, "src").unwrap_or_default()),
+            _ => {}
+        }
+        if self.table.is_some() {
+            self.open_in_table(name, attrs);
+            return;
+        }
+        match name {
+            "p" | "div" => self.start_block(),
+            "br" => self.inline.push(("\n".to_string(), Style::default())),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.start_block();
+                self.heading += 1;
+            }
+            "strong" | "b" => self.bold += 1,
+            "em" | "i" => self.italic += 1,
+            "code" => self.code += 1,
+            "pre" => {
+                self.start_block();
+                self.pre += 1;
+            }
+            "a" => {
+                let href = attr(attrs, "href").unwrap_or_default().to_string();
+                self.link = Some((href, String::new()));
+            }
+            "img" => {
+                let src = attr(attrs, "src").unwrap_or_default();
+                let style = Style::default().fg(self.theme.muted);
+                self.inline.push((format!("[image: {}]", src), style));
+            }
+            "hr" => {
+                self.start_block();
+                let width = self.width.saturating_sub(self.prefix_width()).max(1);
+                let rule = Span::styled("─".repeat(width), Style::default().fg(self.theme.muted));
+                self.push_line(vec![rule]);
+                self.start_block();
+            }
+            "blockquote" => {
+                self.start_block();
+                self.quotes += 1;
+            }
+            "ul" | "ol" => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+                let next = (name == "ol").then(|| {
+                    attr(attrs, "start")
+                        .and_then(|start| start.parse().ok())
+                        .unwrap_or(1)
+                });
+                self.lists.push(List { next, indent: 0 });
+            }
+            "li" => {
+                self.flush();
+                let depth = self.lists.len();
+                if let Some(list) = self.lists.last_mut() {
+                    let marker = match &mut list.next {
+                        Some(number) => {
+                            *number += 1;
+                            format!("{}. ", *number - 1)
+                        }
+                        None if depth % 2 == 1 => "• ".to_string(),
+                        None => "◦ ".to_string(),
+                    };
+                    list.indent = marker.width();
+                    self.marker = Some(marker);
+                }
+            }
+            "table" => {
+                self.start_block();
+                self.table = Some(Table::default());
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        if self.table.is_some() {
+            self.close_in_table(name);
+            return;
+        }
+        match name {
+            "p" | "div" => self.start_block(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                self.heading = self.heading.saturating_sub(1);
+                self.blank_line();
+            }
+            "strong" | "b" => self.bold = self.bold.saturating_sub(1),
+            "em" | "i" => self.italic = self.italic.saturating_sub(1),
+            "code" => self.code = self.code.saturating_sub(1),
+            "pre" => {
+                self.flush();
+                self.pre = self.pre.saturating_sub(1);
+                self.blank_line();
+            }
+            "a" => {
+                let Some((href, text)) = self.link.take() else {
+                    return;
+                };
+                // Show where a link goes unless its text already says so
+                if href.starts_with("http") && text.trim() != href {
+                    let style = Style::default().fg(self.theme.muted);
+                    self.inline.push((format!(" <{}>", href), style));
+                }
+            }
+            "blockquote" => {
+                self.flush();
+                self.quotes = self.quotes.saturating_sub(1);
+                self.blank_line();
+            }
+            "ul" | "ol" => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            "li" => {
+                self.flush();
+                // An empty item still shows its marker
+                if self.marker.is_some() {
+                    self.push_line(Vec::new());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_in_table(&mut self, name: &str, attrs: &[(String, String)]) {
+        let Some(table) = &mut self.table else {
+            return;
+        };
+        match name {
+            "tr" => {
+                table.row.clear();
+                table.header_row = false;
+            }
+            "td" | "th" => {
+                if name == "th" {
+                    table.header_row = true;
+                }
+                let column = table.row.len();
+                let style = attr(attrs, "style").unwrap_or_default().replace(' ', "");
+                let align = match attr(attrs, "align") {
+                    Some("right") => Align::Right,
+                    Some("center") => Align::Center,
+                    _ if style.contains("text-align:right") => Align::Right,
+                    _ if style.contains("text-align:center") => Align::Center,
+                    _ => Align::Left,
+                };
+                if table.aligns.len() <= column {
+                    table.aligns.resize(column + 1, Align::Left);
+                    table.aligns[column] = align;
+                }
+                table.cell = Some(String::new());
+            }
+            "br" => {
+                if let Some(cell) = &mut table.cell {
+                    cell.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close_in_table(&mut self, name: &str) {
+        let Some(table) = &mut self.table else {
+            return;
+        };
+        match name {
+            "td" | "th" => {
+                if let Some(cell) = table.cell.take() {
+                    table.row.push(collapse_whitespace(cell.trim()));
+                }
+            }
+            "tr" => {
+                if let Some(cell) = table.cell.take() {
+                    table.row.push(collapse_whitespace(cell.trim()));
+                }
+                let row = std::mem::take(&mut table.row);
+                if !row.is_empty() {
+                    table.rows.push((row, table.header_row));
+                }
+            }
+            "table" => {
+                if let Some(table) = self.table.take() {
+                    self.render_table(table);
+                }
+                self.start_block();
+            }
+            _ => {}
+        }
+    }
+
+    fn render_table(&mut self, table: Table) {
+        let columns = table
+            .rows
+            .iter()
+            .map(|(row, _)| row.len())
+            .max()
+            .unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let mut widths = vec![1; columns];
+        for (row, _) in &table.rows {
+            for (column, cell) in row.iter().enumerate() {
+                widths[column] = widths[column].max(text_width(cell));
+            }
+        }
+
+        // Narrow the widest columns until the table fits
+        let available = self.width.saturating_sub(self.prefix_width());
+        let budget = available.saturating_sub(COLUMN_GAP.width() * (columns - 1));
+        while widths.iter().sum::<usize>() > budget {
+            let Some(widest) = (0..columns).max_by_key(|&column| widths[column]) else {
+                break;
+            };
+            if widths[widest] <= 1 {
+                break;
+            }
+            widths[widest] -= 1;
+        }
+
+        let gap_style = Style::default().fg(self.theme.muted);
+        let mut after_header = false;
+        for (row, header) in &table.rows {
+            if after_header && !header {
+                let rule: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+                self.push_line(vec![Span::styled(rule.join("─┼─"), gap_style)]);
+            }
+            after_header = *header;
+
+            let style = if *header {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let mut spans = Vec::new();
+            for (column, width) in widths.iter().enumerate() {
+                if column > 0 {
+                    spans.push(Span::styled(COLUMN_GAP, gap_style));
+                }
+                let cell = row.get(column).map(String::as_str).unwrap_or("");
+                let align = table.aligns.get(column).copied().unwrap_or(Align::Left);
+                spans.push(Span::styled(fit_cell(cell, *width, align), style));
+            }
+            self.push_line(spans);
+        }
+    }
+
+    fn inline_style(&self) -> Style {
+        let mut style = Style::default();
+        if self.heading > 0 {
+            style = style.fg(self.theme.primary).add_modifier(Modifier::BOLD);
+        }
+        if self.bold > 0 {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if self.italic > 0 {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        if self.code > 0 || self.pre > 0 {
+            style = style.fg(self.theme.secondary);
+        }
+        if self.link.is_some() {
+            style = style
+                .fg(self.theme.accent)
+                .add_modifier(Modifier::UNDERLINED);
+        }
+        style
+    }
+
+    /// Finish the current block and leave a blank line before the next
+    fn start_block(&mut self) {
+        self.flush();
+        self.blank_line();
+    }
+
+    /// Separate blocks, except between the items of a list
+    fn blank_line(&mut self) {
+        let after_blank = self.lines.last().is_none_or(|line| {
+            line.spans
+                .iter()
+                .all(|span| span.content.trim_matches([' ', '│']).is_empty())
+        });
+        if self.lists.is_empty() && !after_blank {
+            let bars = self.quote_bars();
+            self.lines.push(Line::from(bars));
+        }
+    }
+
+    fn quote_bars(&self) -> Vec<Span<'static>> {
+        (0..self.quotes)
+            .map(|_| Span::styled("│ ", Style::default().fg(self.theme.muted)))
+            .collect()
+    }
+
+    fn prefix_width(&self) -> usize {
+        self.quotes * 2 + self.lists.iter().map(|list| list.indent).sum::<usize>()
+    }
+
+    /// Add a line after the quote bars and list indentation, using up the
+    /// pending list marker
+    fn push_line(&mut self, spans: Vec<Span<'static>>) {
+        let mut line = self.quote_bars();
+        if let Some((last, outer)) = self.lists.split_last() {
+            let indent: usize = outer.iter().map(|list| list.indent).sum();
+            if indent > 0 {
+                line.push(Span::raw(" ".repeat(indent)));
+            }
+            let marker = self
+                .marker
+                .take()
+                .unwrap_or_else(|| " ".repeat(last.indent));
+            line.push(Span::styled(marker, Style::default().fg(self.theme.accent)));
+        }
+        line.extend(spans);
+        self.lines.push(Line::from(line));
+    }
+
+    /// Wrap the pending inline text into lines
+    fn flush(&mut self) {
+        let fragments = std::mem::take(&mut self.inline);
+        let width = self.width.saturating_sub(self.prefix_width()).max(1);
+        for spans in wrap(&fragments, width, self.pre > 0) {
+            self.push_line(spans);
+        }
+    }
+
+    fn finish(mut self) -> Rendered {
+        if let Some(table) = self.table.take() {
+            self.render_table(table);
+        }
+        self.flush();
+        while self.lines.last().is_some_and(|line| {
+            line.width() == 0 || line.spans.iter().all(|s| s.content.trim().is_empty())
+        }) {
+            self.lines.pop();
+        }
+        Rendered {
+            lines: self.lines,
+            links: self.links,
+        }
+    }
+}
+
+/// Pad or shorten a table cell to exactly `width` cells
+fn fit_cell(cell: &str, width: usize, align: Align) -> String {
+    let mut text = String::new();
+    if text_width(cell) > width {
+        let (kept, _) = split_at_width(cell, width.saturating_sub(1));
+        text.push_str(kept);
+        text.push('…');
+    } else {
+        text.push_str(cell);
+    }
+
+    let padding = width.saturating_sub(text_width(&text));
+    match align {
+        Align::Left => format!("{}{}", text, " ".repeat(padding)),
+        Align::Right => format!("{}{}", " ".repeat(padding), text),
+        Align::Center => {
+            let left = padding / 2;
+            format!("{}{}{}", " ".repeat(left), text, " ".repeat(padding - left))
+        }
+    }
+}
+
+/// Piece of inline text that is never split across lines, unless it is
+/// wider than a whole line
+enum Atom<'a> {
+    Word(&'a str, Style),
+    Space(&'a str, Style),
+    Break,
+}
+
+fn atoms<'a>(fragments: &'a [(String, Style)], preserve: bool) -> Vec<Atom<'a>> {
+    let mut atoms = Vec::new();
+    for (text, style) in fragments {
+        let mut start = 0;
+        let mut clusters = text.grapheme_indices(true).peekable();
+        while let Some((index, cluster)) = clusters.next() {
+            let end = index + cluster.len();
+            if is_line_break(cluster) {
+                atoms.push(Atom::Break);
+                start = end;
+                continue;
+            }
+            let is_space = cluster.chars().all(char::is_whitespace);
+            // CJK text has no spaces, so every wide character may break
+            let stands_alone = !is_space && grapheme_width(cluster) > 1;
+            let next = clusters.peek().map(|&(_, next)| next);
+            let run_continues = !stands_alone
+                && next.is_some_and(|next| {
+                    !is_line_break(next)
+                        && next.chars().all(char::is_whitespace) == is_space
+                        && (is_space || grapheme_width(next) <= 1)
+                });
+            if run_continues {
+                continue;
+            }
+            let piece = &text[start..end];
+            atoms.push(if is_space {
+                Atom::Space(if preserve { piece } else { " " }, *style)
+            } else {
+                Atom::Word(piece, *style)
+            });
+            start = end;
+        }
+    }
+    atoms
+}
+
+fn is_line_break(cluster: &str) -> bool {
+    cluster == "\n" || cluster == "\r\n"
+}
+
+/// Greedy word wrap keeping each fragment's style
+fn wrap(fragments: &[(String, Style)], width: usize, preserve: bool) -> Vec<Vec<Span<'static>>> {
+    let mut lines = Vec::new();
+    let mut line: Vec<Span<'static>> = Vec::new();
+    let mut used = 0;
+
+    fn push(line: &mut Vec<Span<'static>>, text: &str, style: Style) {
+        match line.last_mut() {
+            Some(last) if last.style == style => last.content.to_mut().push_str(text),
+            _ => line.push(Span::styled(text.to_string(), style)),
+        }
+    }
+    let end_line = |line: &mut Vec<Span<'static>>, lines: &mut Vec<Vec<Span<'static>>>| {
+        if !preserve {
+            if let Some(last) = line.last_mut() {
+                let trimmed = last.content.trim_end().to_string();
+                last.content = trimmed.into();
+            }
+        }
+        lines.push(std::mem::take(line));
+    };
+
+    let atoms = atoms(fragments, preserve);
+    let has_text = atoms.iter().any(|atom| matches!(atom, Atom::Word(..)));
+    if !has_text && !preserve {
+        return lines;
+    }
+    for atom in atoms {
+        match atom {
+            Atom::Break => {
+                end_line(&mut line, &mut lines);
+                used = 0;
+            }
+            Atom::Space(text, style) => {
+                if used == 0 && !preserve {
+                    continue;
+                }
+                if used + text_width(text) > width {
+                    end_line(&mut line, &mut lines);
+                    used = 0;
+                    if !preserve {
+                        continue;
+                    }
+                }
+                push(&mut line, text, style);
+                used += text_width(text);
+            }
+            Atom::Word(text, style) => {
+                let word_width = text_width(text);
+                if used + word_width > width && used > 0 && word_width <= width {
+                    end_line(&mut line, &mut lines);
+                    used = 0;
+                }
+                if used + word_width <= width {
+                    push(&mut line, text, style);
+                    used += word_width;
+                    continue;
+                }
+                // Longer than a line: split wherever the line is full
+                for cluster in graphemes(text) {
+                    let cluster_width = grapheme_width(cluster);
+                    if used + cluster_width > width && used > 0 {
+                        end_line(&mut line, &mut lines);
+                        used = 0;
+                    }
+                    push(&mut line, cluster, style);
+                    used += cluster_width;
+                }
+            }
+        }
+    }
+    if !line.is_empty() {
+        end_line(&mut line, &mut lines);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(html: &str, width: usize) -> Vec<String> {
+        render(html, width, &Theme::default(), &[])
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_paragraphs_wrap_and_decode_entities() {
+        assert_eq!(
+            plain(
+                "<p>one two three four</p><p>a &amp; b&nbsp;&lt;c&gt;</p>",
+                9
+            ),
+            vec!["one two", "three", "four", "", "a & b <c>"]
+        );
+        // CJK text breaks between characters
+        assert_eq!(plain("中文内容测试", 6), vec!["中文内", "容测试"]);
+        // Emoji with modifiers stay whole and count as two columns
+        assert_eq!(plain("好的👍🏽👨‍👩‍👧ok", 6), vec!["好的👍🏽", "👨‍👩‍👧ok"]);
+    }
+
+    #[test]
+    fn test_nested_lists_keep_markers() {
+        let html = "<ol><li>first<ul><li>nested item</li></ul></li><li>second</li></ol>";
+        assert_eq!(
+            plain(html, 40),
+            vec!["1. first", "   ◦ nested item", "2. second"]
+        );
+        // Continuation lines line up with the item text
+        assert_eq!(
+            plain("<ul><li>aaa bbb</li></ul>", 5),
+            vec!["• aaa", "  bbb"]
+        );
+    }
+
+    #[test]
+    fn test_blockquote_bars() {
+        assert_eq!(
+            plain(
+                "<blockquote><p>quoted</p><blockquote>deeper</blockquote></blockquote>after",
+                40
+            ),
+            vec!["│ quoted", "│ ", "│ │ deeper", "│ ", "after"]
+        );
+    }
+
+    #[test]
+    fn test_table_columns_align() {
+        let html = "<table><thead><tr><th>Name</th><th align=\"right\">Price</th></tr></thead>\
+                    <tbody><tr><td>apple</td><td align=\"right\">3</td></tr>\
+                    <tr><td>watermelon</td><td align=\"right\">12</td></tr></tbody></table>";
+        assert_eq!(
+            plain(html, 40),
+            vec![
+                "Name       │ Price",
+                "───────────┼──────",
+                "apple      │     3",
+                "watermelon │    12",
+            ]
+        );
+        // Too wide for the screen: the widest column gives way
+        assert_eq!(plain(html, 12)[3], "wate… │   12");
+    }
+
+    #[test]
+    fn test_links_and_images() {
+        assert_eq!(
+            plain(
+                "<a href=\"https://v2ex.com\">V2EX</a> <a href=\"https://a.b\">https://a.b</a> \
+                 <a href=\"/member/livid\">livid</a><img src=\"https://i.imgur.com/x.png\">",
+                80
+            ),
+            vec!["V2EX <https://v2ex.com> https://a.b livid[image: https://i.imgur.com/x.png]"]
+        );
+    }
+
+    #[test]
+    fn test_links_are_collected_and_labelled() {
+        let html = "<p>see <a href=\"/t/1\">this</a> and https://a.b/c.</p>\
+                    <table><tr><td><a href=\"https://x.y\">x</a></td></tr></table>\
+                    <img src=\"//i.v2ex.co/p.png\"><a href=\"#top\">top</a>";
+        let links = [
+            "https://www.v2ex.com/t/1",
+            "https://a.b/c.",
+            "https://x.y",
+            "https://i.v2ex.co/p.png",
+        ];
+        let theme = Theme::default();
+        assert_eq!(render(html, 80, &theme, &[]).links, links);
+
+        let labels: Vec<String> = ["a", "o", "e", "u"].map(String::from).into();
+        let rendered = render(html, 80, &theme, &labels);
+        // Labels don't change where the links go, wherever lines wrap
+        assert_eq!(rendered.links, links);
+        assert_eq!(render(html, 12, &theme, &labels).links, links);
+        let text: Vec<String> = rendered
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "see [a] this and [o] https://a.b/c.",
+                "",
+                "[e] x",
+                "",
+                "[u] [image: //i.v2ex.co/p.png]top",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let html = "<p>Try <code>inline</code>:</p>\
+                    <pre><code>fn main() {\n    println!(\"&lt;ok&gt;\");\n}\n</code></pre>\
+                    <p>or</p><pre>\nls -l<br>pwd</pre><pre>  </pre>";
+        assert_eq!(
+            code_blocks(html),
+            ["fn main() {\n    println!(\"<ok>\");\n}", "ls -l\npwd"]
+        );
+        assert!(code_blocks("<p>no code</p>").is_empty());
+    }
+
+    #[test]
+    fn test_pre_keeps_whitespace() {
+        assert_eq!(
+            plain("<pre><code>fn main() {\n    ok();\n}</code></pre>", 40),
+            vec!["fn main() {", "    ok();", "}"]
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Turn the HTML of topics and replies back into Markdown for archives.
+//!
+//! Covers what V2EX's own Markdown produces: paragraphs and line breaks,
+//! headings, emphasis, links and images, nested lists and quotes, inline
+//! code and fenced code blocks. Table rows keep their cells on one line.
+
+use super::html::{absolute_url, attr, collapse_whitespace, tokenize, Token};
+
+/// `html` as Markdown, ending in a newline unless it is empty
+pub fn markdown(html: &str) -> String {
+    let mut writer = Writer::default();
+    for token in tokenize(html) {
+        writer.token(token);
+    }
+    let mut out = writer.out;
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Escape what Markdown would read as formatting in plain text, including
+/// block markers when `text` starts a line
+pub fn escape(text: &str, line_start: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        let special = matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<')
+            || (i == 0 && line_start && matches!(c, '#' | '>' | '-' | '+'));
+        if special {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// A link target, in angle brackets when it would end the link early
+fn destination(url: &str) -> String {
+    if url.contains(|c: char| c.is_whitespace() || matches!(c, '(' | ')')) {
+        format!("<{}>", url)
+    } else {
+        url.to_string()
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    /// Line breaks owed before the next text; 2 starts a new block
+    breaks: usize,
+    /// The owed break is a `<br>` inside a paragraph
+    hard_break: bool,
+    /// Quotes the blank line between two blocks is inside
+    gap_quote: usize,
+    /// Depth of `<blockquote>`
+    quote: usize,
+    /// Enclosing lists, with the next number of ordered ones
+    lists: Vec<Option<usize>>,
+    /// Marker of a list item whose text hasn't started yet
+    marker: Option<String>,
+    /// Text of the `<pre>` being read, kept as is
+    code_block: Option<String>,
+    /// Text of the inline `<code>` being read
+    code: Option<String>,
+    /// Targets of the open `<a>` elements; `None` for ones without one
+    links: Vec<Option<String>>,
+}
+
+impl Writer {
+    fn token(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.text(&text),
+            Token::Open { name, attrs } => match name.as_str() {
+                "br" => match &mut self.code_block {
+                    Some(code) => code.push('\n'),
+                    None if !self.out.is_empty() && self.breaks == 0 => {
+                        self.breaks = 1;
+                        self.hard_break = true;
+                    }
+                    None => {}
+                },
+                "p" | "div" | "table" => self.block(),
+                "tr" => self.line(),
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                    self.block();
+                    let level = name[1..].parse().unwrap_or(1);
+                    self.write(&format!("{} ", "#".repeat(level)));
+                }
+                "strong" | "b" => self.write("**"),
+                "em" | "i" => self.write("*"),
+                "a" => {
+                    let href = attr(&attrs, "href").and_then(absolute_url);
+                    if href.is_some() {
+                        self.write("[");
+                    }
+                    self.links.push(href);
+                }
+                "img" => {
+                    if let Some(src) = attr(&attrs, "src").and_then(absolute_url) {
+                        let alt = escape(attr(&attrs, "alt").unwrap_or_default(), false);
+                        self.write(&format!("![{}]({})", alt, destination(&src)));
+                    }
+                }
+                "blockquote" => {
+                    self.block();
+                    self.quote += 1;
+                }
+                "ul" | "ol" => {
+                    if self.lists.is_empty() {
+                        self.block();
+                    } else {
+                        self.line();
+                    }
+                    let start = attr(&attrs, "start").and_then(|start| start.parse().ok());
+                    self.lists
+                        .push((name == "ol").then_some(start.unwrap_or(1)));
+                }
+                "li" => {
+                    self.line();
+                    self.marker = Some(match self.lists.last_mut() {
+                        Some(Some(number)) => {
+                            *number += 1;
+                            format!("{}. ", *number - 1)
+                        }
+                        _ => "- ".to_string(),
+                    });
+                }
+                "hr" => {
+                    self.block();
+                    self.write("---");
+                    self.block();
+                }
+                "pre" => {
+                    self.block();
+                    self.code_block = Some(String::new());
+                }
+                "code" if self.code_block.is_none() => self.code = Some(String::new()),
+                _ => {}
+            },
+            Token::Close(name) => match name.as_str() {
+                "p" | "div" | "table" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => self.block(),
+                "tr" => self.line(),
+                "td" | "th" => self.write(" | "),
+                "strong" | "b" => self.write("**"),
+                "em" | "i" => self.write("*"),
+                "a" => {
+                    if let Some(Some(href)) = self.links.pop() {
+                        self.write(&format!("]({})", destination(&href)));
+                    }
+                }
+                "blockquote" => {
+                    self.quote = self.quote.saturating_sub(1);
+                    self.block();
+                }
+                "ul" | "ol" => {
+                    self.lists.pop();
+                    if self.lists.is_empty() {
+                        self.block();
+                    }
+                }
+                "pre" => {
+                    if let Some(code) = self.code_block.take() {
+                        self.fence(&code);
+                    }
+                }
+                "code" => {
+                    if let Some(code) = self.code.take() {
+                        let tick = if code.contains('`') { "``" } else { "`" };
+                        // A space keeps a backtick at either end off the ticks
+                        let pad = if code.starts_with('`') || code.ends_with('`') {
+                            " "
+                        } else {
+                            ""
+                        };
+                        self.write(&format!("{}{}{}{}{}", tick, pad, code, pad, tick));
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(code) = &mut self.code_block {
+            code.push_str(text);
+            return;
+        }
+        if let Some(code) = &mut self.code {
+            code.push_str(&collapse_whitespace(text));
+            return;
+        }
+        let collapsed = collapse_whitespace(text);
+        let line_start = self.at_line_start();
+        let collapsed = if line_start {
+            collapsed.trim_start()
+        } else {
+            &collapsed
+        };
+        if !collapsed.is_empty() {
+            self.write(&escape(collapsed, line_start));
+        }
+    }
+
+    /// Whether the next text starts a line
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.breaks > 0 || self.marker.is_some()
+    }
+
+    /// End the current block, leaving a blank line before the next
+    fn block(&mut self) {
+        if self.out.is_empty() {
+            return;
+        }
+        self.gap_quote = if self.breaks == 2 {
+            self.gap_quote.min(self.quote)
+        } else {
+            self.quote
+        };
+        self.breaks = 2;
+        self.hard_break = false;
+    }
+
+    /// End the current line
+    fn line(&mut self) {
+        if !self.out.is_empty() {
+            self.breaks = self.breaks.max(1);
+            self.hard_break = false;
+        }
+    }
+
+    /// `> ` for each quote, then room for the markers of enclosing lists
+    fn prefix(&mut self) -> String {
+        let mut prefix = "> ".repeat(self.quote);
+        match self.marker.take() {
+            Some(marker) => {
+                prefix.push_str(&"    ".repeat(self.lists.len().saturating_sub(1)));
+                prefix.push_str(&marker);
+            }
+            None => prefix.push_str(&"    ".repeat(self.lists.len())),
+        }
+        prefix
+    }
+
+    /// Add `text` as it is, after the line breaks owed
+    fn write(&mut self, text: &str) {
+        if !self.out.is_empty() && self.breaks > 0 {
+            if self.hard_break {
+                self.out.push('\\');
+            }
+            for _ in 1..self.breaks {
+                self.out.push('\n');
+                self.out
+                    .push_str(&">".repeat(self.gap_quote.min(self.quote)));
+            }
+            self.out.push('\n');
+        }
+        if self.out.is_empty() || self.breaks > 0 || self.marker.is_some() {
+            let prefix = self.prefix();
+            self.out.push_str(&prefix);
+        }
+        self.breaks = 0;
+        self.hard_break = false;
+        self.out.push_str(text);
+    }
+
+    /// A fenced code block, long enough a fence that the code can't end it
+    fn fence(&mut self, code: &str) {
+        // A newline right after <pre> is not part of the content
+        let code = code.strip_prefix('\n').unwrap_or(code).trim_end();
+        let mut fence = "```".to_string();
+        while code.contains(&fence) {
+            fence.push('`');
+        }
+        self.block();
+        self.write(&fence);
+        for line in code.lines() {
+            self.breaks = 1;
+            self.write(line);
+        }
+        self.breaks = 1;
+        self.write(&fence);
+        self.block();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let html = "<p>Some <strong>bold</strong>, <em>a_b</em> and <code>x*y</code>.<br>\
+                    Next line, <a href=\"/t/1\">a topic</a></p>\
+                    <h2>List</h2><ol><li>one</li><li>two<ul><li>nested</li></ul></li></ol>\
+                    <blockquote><p>quoted</p><p>twice</p></blockquote>\
+                    <pre><code>\nfn main() {\n\n    println!(\"```\");\n}\n</code></pre>\
+                    <img src=\"https://i.example.com/a.png\" alt=\"pic\">";
+        assert_eq!(
+            markdown(html),
+            "Some **bold**, *a\\_b* and `x*y`.\\\n\
+             Next line, [a topic](https://www.v2ex.com/t/1)\n\
+             \n\
+             ## List\n\
+             \n\
+             1. one\n\
+             2. two\n    \
+             - nested\n\
+             \n\
+             > quoted\n\
+             >\n\
+             > twice\n\
+             \n\
+             ````\n\
+             fn main() {\n\
+             \n    \
+             println!(\"```\");\n\
+             }\n\
+             ````\n\
+             \n\
+             ![pic](https://i.example.com/a.png)\n"
+        );
+        assert_eq!(markdown(""), "");
+        assert_eq!(markdown("<p># not a heading</p>"), "\\# not a heading\n");
+    }
+}
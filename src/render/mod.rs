@@ -0,0 +1,116 @@
+//! Converting topic, reply and notification content into text.
+//!
+//! Every view goes through [`styled`] or [`plain`], which remember their
+//! results by content, width and theme, so content that didn't change
+//! isn't converted again on every frame.
+
+pub mod html;
+pub mod markdown;
+
+use ratatui::text::Line;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::ui::Theme;
+
+/// Conversions kept per cache before it is emptied and refilled
+const MAX_ENTRIES: usize = 1024;
+
+/// (content hash, width, theme hash); the theme hash is 0 for plain text
+type Key = (u64, usize, u64);
+
+#[derive(Default)]
+struct Caches {
+    styled: HashMap<Key, Rc<[Line<'static>]>>,
+    plain: HashMap<Key, Rc<str>>,
+    links: HashMap<Key, Rc<[String]>>,
+}
+
+thread_local! {
+    static CACHES: RefCell<Caches> = RefCell::new(Caches::default());
+}
+
+fn hash_of(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cached<V: Clone>(
+    map: impl Fn(&mut Caches) -> &mut HashMap<Key, V>,
+    key: Key,
+    convert: impl FnOnce() -> V,
+) -> V {
+    if let Some(hit) = CACHES.with(|caches| map(&mut caches.borrow_mut()).get(&key).cloned()) {
+        return hit;
+    }
+    let value = convert();
+    CACHES.with(|caches| {
+        let mut caches = caches.borrow_mut();
+        let entries = map(&mut caches);
+        if entries.len() >= MAX_ENTRIES {
+            entries.clear();
+        }
+        entries.insert(key, value.clone());
+    });
+    value
+}
+
+/// HTML as styled lines wrapped to `width`, for display
+pub fn styled(html: &str, width: usize, theme: &Theme) -> Rc<[Line<'static>]> {
+    labelled(html, width, theme, &[])
+}
+
+/// Like [`styled`], with the link mode label for each of the [`links`]
+/// in front of it
+pub fn labelled(html: &str, width: usize, theme: &Theme, labels: &[String]) -> Rc<[Line<'static>]> {
+    let key = (hash_of(&html), width, hash_of(&(theme, labels)));
+    cached(
+        |caches| &mut caches.styled,
+        key,
+        || html::render(html, width, theme, labels).lines.into(),
+    )
+}
+
+/// Where the links shown in `html` go, in the order they appear
+pub fn links(html: &str) -> Rc<[String]> {
+    let key = (hash_of(&html), 0, 0);
+    cached(
+        |caches| &mut caches.links,
+        key,
+        // Wrapping and colours don't change which links there are
+        || html::render(html, 80, &Theme::default(), &[]).links.into(),
+    )
+}
+
+/// HTML as plain text wrapped to `width`, for searching and copying
+pub fn plain(html: &str, width: usize) -> Rc<str> {
+    let key = (hash_of(&html), width, 0);
+    cached(
+        |caches| &mut caches.plain,
+        key,
+        || html2text::from_read(html.as_bytes(), width).into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_are_cached_per_width() {
+        let html = "<p>cached <b>once</b></p>";
+        let first = plain(html, 40);
+        assert!(Rc::ptr_eq(&first, &plain(html, 40)));
+        assert!(!Rc::ptr_eq(&first, &plain(html, 20)));
+
+        let theme = Theme::default();
+        let lines = styled(html, 40, &theme);
+        assert!(Rc::ptr_eq(&lines, &styled(html, 40, &theme)));
+        let other = Theme::preset(crate::config::ThemePreset::Light);
+        assert!(!Rc::ptr_eq(&lines, &styled(html, 40, &other)));
+    }
+}
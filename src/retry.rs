@@ -0,0 +1,164 @@
+use reqwest::{Method, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How failed requests are retried, set with the `retry-*` settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per request including the first (1 = never retry)
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for every further one
+    pub base_delay: Duration,
+    /// Upper bound on the wait between two attempts
+    pub max_delay: Duration,
+    /// Randomise each wait so clients that failed together don't retry together
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `retry` (1-based). With jitter the wait is
+    /// drawn from the upper half of the backoff, using `seed` as randomness.
+    pub fn delay(&self, retry: u32, seed: u64) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if !self.jitter {
+            return backoff;
+        }
+        let half = backoff / 2;
+        let spread = half.as_millis() as u64 + 1;
+        half + Duration::from_millis(seed % spread)
+    }
+}
+
+/// Failures worth another attempt: the server or the network hiccuped.
+/// Auth errors, missing resources and rate limiting are final.
+pub fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::REQUEST_TIMEOUT
+}
+
+pub fn is_transient_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect()
+}
+
+/// Whether sending `method` again is harmless if the first attempt reached
+/// the server. A reply, a thank or a deletion must not happen twice, so
+/// those are only retried when the connection failed before sending.
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD)
+}
+
+/// Whether a failed attempt of a `method` request may be repeated
+pub fn should_retry(method: &Method, outcome: Result<StatusCode, &reqwest::Error>) -> bool {
+    match outcome {
+        Ok(status) => is_idempotent(method) && is_transient_status(status),
+        Err(error) if error.is_connect() => true,
+        Err(error) => is_idempotent(method) && is_transient_error(error),
+    }
+}
+
+/// Random seed for jitter, without pulling in a RNG crate
+pub fn jitter_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// A request waiting to be retried, shown in the status bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryNotice {
+    /// The attempt about to be made
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub reason: String,
+    pub retry_at: Instant,
+}
+
+impl RetryNotice {
+    pub fn label(&self) -> String {
+        let seconds = self
+            .retry_at
+            .saturating_duration_since(Instant::now())
+            .as_secs_f32();
+        format!(
+            "Retrying ({}/{}) in {:.1}s: {}",
+            self.attempt, self.max_attempts, seconds, self.reason
+        )
+    }
+}
+
+/// Latest pending retry across every handle of the API client
+#[derive(Debug, Default)]
+pub struct RetryTracker {
+    pending: Mutex<Option<RetryNotice>>,
+}
+
+impl RetryTracker {
+    pub fn pending(&self) -> Option<RetryNotice> {
+        self.pending.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, notice: RetryNotice) {
+        *self.pending.lock().unwrap() = Some(notice);
+    }
+
+    pub fn clear(&self) {
+        *self.pending.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+        assert_eq!(policy.delay(1, 0), Duration::from_millis(500));
+        assert_eq!(policy.delay(2, 0), Duration::from_secs(1));
+        assert_eq!(policy.delay(3, 0), Duration::from_secs(2));
+        assert_eq!(policy.delay(10, 0), Duration::from_secs(8));
+        assert_eq!(policy.delay(u32::MAX, 0), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_jitter_stays_in_upper_half() {
+        let policy = RetryPolicy::default();
+        for seed in [0, 1, 249, 250, 251, u64::MAX] {
+            let delay = policy.delay(1, seed);
+            assert!(delay >= Duration::from_millis(250), "{:?}", delay);
+            assert!(delay <= Duration::from_millis(500), "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn test_transient_statuses() {
+        assert!(is_transient_status(StatusCode::BAD_GATEWAY));
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_transient_status(StatusCode::REQUEST_TIMEOUT));
+        assert!(!is_transient_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND));
+        assert!(!is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    #[test]
+    fn test_only_idempotent_requests_retry_server_errors() {
+        assert!(should_retry(&Method::GET, Ok(StatusCode::BAD_GATEWAY)));
+        assert!(!should_retry(&Method::GET, Ok(StatusCode::NOT_FOUND)));
+        assert!(!should_retry(&Method::POST, Ok(StatusCode::BAD_GATEWAY)));
+        assert!(!should_retry(&Method::DELETE, Ok(StatusCode::BAD_GATEWAY)));
+    }
+}
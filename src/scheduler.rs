@@ -0,0 +1,400 @@
+use anyhow::Result;
+use reqwest::header::HeaderMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Fraction of the hourly budget held back for user-initiated requests
+const BACKGROUND_RESERVE_DIVISOR: u32 = 5;
+/// Lower bound on the reserve so tiny limits still leave room for the user
+const MIN_BACKGROUND_RESERVE: u32 = 10;
+/// Requests that may go out back to back before throttling starts
+const BURST: f64 = 10.0;
+/// Sustained requests per second while the budget is healthy
+const REFILL_PER_SEC: f64 = 2.0;
+/// Sustained requests per second once only the reserve is left
+const LOW_BUDGET_REFILL_PER_SEC: f64 = 0.5;
+/// Longest a request is queued before it fails instead
+const MAX_QUEUE_WAIT: Duration = Duration::from_secs(20);
+
+/// Who asked for a request; background work yields to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    Interactive,
+    Background,
+}
+
+/// Rate limit window as reported by the `X-Rate-Limit-*` response headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    /// Unix timestamp (seconds) at which the window resets
+    pub reset: i64,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let parse = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<i64>().ok();
+        Some(Self {
+            limit: parse("x-rate-limit-limit")? as u32,
+            remaining: parse("x-rate-limit-remaining")?.max(0) as u32,
+            reset: parse("x-rate-limit-reset")?,
+        })
+    }
+
+    fn background_reserve(&self) -> u32 {
+        (self.limit / BACKGROUND_RESERVE_DIVISOR).max(MIN_BACKGROUND_RESERVE)
+    }
+
+    /// Whether the budget is down to the part kept for the user
+    pub fn is_low(&self) -> bool {
+        self.remaining <= self.background_reserve()
+    }
+
+    /// Seconds until the window resets
+    pub fn resets_in(&self) -> i64 {
+        (self.reset - unix_now()).max(0)
+    }
+}
+
+/// Spreads requests out so bursts (prefetch, paging through replies) don't
+/// burn the hourly budget in a few seconds
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Default for TokenBucket {
+    fn default() -> Self {
+        Self {
+            tokens: BURST,
+            updated: Instant::now(),
+        }
+    }
+}
+
+impl TokenBucket {
+    /// Take a token and return how long to wait before using it, or `None`
+    /// if the queue is already longer than `MAX_QUEUE_WAIT`. Tokens go
+    /// negative so queued requests line up behind each other.
+    fn reserve(&mut self, rate: f64, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(BURST);
+        self.updated = now;
+
+        let wait = if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / rate)
+        };
+        if wait > MAX_QUEUE_WAIT {
+            return None;
+        }
+        self.tokens -= 1.0;
+        Some(wait)
+    }
+}
+
+/// Shared request budget for every handle of the API client.
+///
+/// Interactive requests may spend the whole budget, background requests
+/// (auto-refresh, pollers, prefetch) stop once only the reserve is left.
+#[derive(Debug, Default)]
+pub struct RequestScheduler {
+    rate_limit: Mutex<Option<RateLimit>>,
+    bucket: Mutex<TokenBucket>,
+    /// Least time between any two requests, set with `request-delay`
+    spacing: Duration,
+    /// When the next request may go out under `spacing`
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RequestScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scheduler that also keeps every request at least `spacing` after
+    /// the one before it
+    pub fn with_spacing(spacing: Duration) -> Self {
+        Self {
+            spacing,
+            ..Self::default()
+        }
+    }
+
+    /// Wait for this request's turn under the configured spacing. Unlike
+    /// `acquire`, this applies to every request, on the API or not.
+    pub async fn pace(&self) {
+        let wait = self.pace_at(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Book the next free slot and return how long until it comes
+    fn pace_at(&self, now: Instant) -> Duration {
+        if self.spacing.is_zero() {
+            return Duration::ZERO;
+        }
+        let mut next_slot = self.next_slot.lock().unwrap();
+        let slot = next_slot.map_or(now, |next| next.max(now));
+        *next_slot = Some(slot + self.spacing);
+        slot - now
+    }
+
+    /// Reserve one request from the budget, waiting in line while requests
+    /// are throttled, or explain why it can't be sent
+    pub async fn acquire(&self, priority: Priority) -> Result<()> {
+        let wait = self.reserve_at(priority, unix_now(), Instant::now())?;
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    /// The last budget reported by the server, while its window is current
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        let now = unix_now();
+        self.rate_limit
+            .lock()
+            .unwrap()
+            .filter(|rate_limit| now < rate_limit.reset)
+    }
+
+    /// Whether a request of this priority would currently be admitted
+    pub fn can_schedule(&self, priority: Priority) -> bool {
+        let rate_limit = *self.rate_limit.lock().unwrap();
+        Self::admit(rate_limit, priority, unix_now()).is_ok()
+    }
+
+    /// Record the server's view of the budget after a response
+    pub fn update(&self, headers: &HeaderMap) {
+        if let Some(rate_limit) = RateLimit::from_headers(headers) {
+            *self.rate_limit.lock().unwrap() = Some(rate_limit);
+        }
+    }
+
+    /// Admit a request against the budget and the throttle, returning how
+    /// long it has to wait
+    fn reserve_at(&self, priority: Priority, now: i64, instant: Instant) -> Result<Duration> {
+        let mut guard = self.rate_limit.lock().unwrap();
+        Self::admit(*guard, priority, now)?;
+
+        let low = guard.is_some_and(|r| now < r.reset && r.is_low());
+        let rate = if low {
+            LOW_BUDGET_REFILL_PER_SEC
+        } else {
+            REFILL_PER_SEC
+        };
+        let wait = self
+            .bucket
+            .lock()
+            .unwrap()
+            .reserve(rate, instant)
+            .ok_or_else(|| anyhow::anyhow!("Too many requests queued, try again in a moment"))?;
+
+        Self::spend(&mut guard, now);
+        Ok(wait)
+    }
+
+    /// Count a request optimistically so concurrent callers see it before
+    /// the response headers arrive
+    fn spend(rate_limit: &mut Option<RateLimit>, now: i64) {
+        if let Some(rate_limit) = rate_limit.as_mut().filter(|r| now < r.reset) {
+            rate_limit.remaining = rate_limit.remaining.saturating_sub(1);
+        }
+    }
+
+    fn admit(rate_limit: Option<RateLimit>, priority: Priority, now: i64) -> Result<()> {
+        let Some(rate_limit) = rate_limit else {
+            return Ok(());
+        };
+        if now >= rate_limit.reset {
+            return Ok(());
+        }
+
+        let wait = rate_limit.reset - now;
+        match priority {
+            Priority::Interactive if rate_limit.remaining == 0 => {
+                Err(anyhow::anyhow!("Rate limit exhausted, resets in {}s", wait))
+            }
+            Priority::Background if rate_limit.remaining <= rate_limit.background_reserve() => {
+                Err(anyhow::anyhow!(
+                    "Rate limit low ({}/{} left), background requests paused for {}s",
+                    rate_limit.remaining,
+                    rate_limit.limit,
+                    wait
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler_with(limit: u32, remaining: u32, reset: i64) -> RequestScheduler {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Rate-Limit-Limit", limit.into());
+        headers.insert("X-Rate-Limit-Remaining", remaining.into());
+        headers.insert("X-Rate-Limit-Reset", reset.into());
+        let scheduler = RequestScheduler::new();
+        scheduler.update(&headers);
+        scheduler
+    }
+
+    #[test]
+    fn test_requests_are_spaced_out() {
+        let now = Instant::now();
+        assert_eq!(RequestScheduler::new().pace_at(now), Duration::ZERO);
+
+        let scheduler = RequestScheduler::with_spacing(Duration::from_millis(500));
+        assert_eq!(scheduler.pace_at(now), Duration::ZERO);
+        assert_eq!(scheduler.pace_at(now), Duration::from_millis(500));
+        assert_eq!(scheduler.pace_at(now), Duration::from_millis(1000));
+        // A quiet spell doesn't save up slots
+        let later = now + Duration::from_secs(10);
+        assert_eq!(scheduler.pace_at(later), Duration::ZERO);
+        assert_eq!(scheduler.pace_at(later), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_unknown_budget_admits_everything() {
+        let scheduler = RequestScheduler::new();
+        let start = Instant::now();
+        let reserve = |priority| scheduler.reserve_at(priority, 0, start).unwrap();
+        assert_eq!(reserve(Priority::Background), Duration::ZERO);
+        assert_eq!(reserve(Priority::Interactive), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_background_yields_to_reserve() {
+        // 600/hour keeps 120 for the user
+        let scheduler = scheduler_with(600, 121, 1000);
+        let start = Instant::now();
+        assert!(scheduler.reserve_at(Priority::Background, 0, start).is_ok());
+        assert!(scheduler
+            .reserve_at(Priority::Background, 0, start)
+            .is_err());
+        assert!(scheduler
+            .reserve_at(Priority::Interactive, 0, start)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_interactive_stops_when_exhausted() {
+        let scheduler = scheduler_with(600, 1, 1000);
+        let start = Instant::now();
+        assert!(scheduler
+            .reserve_at(Priority::Interactive, 0, start)
+            .is_ok());
+        assert!(scheduler
+            .reserve_at(Priority::Interactive, 0, start)
+            .is_err());
+    }
+
+    #[test]
+    fn test_budget_resets_after_window() {
+        let scheduler = scheduler_with(600, 0, 1000);
+        let start = Instant::now();
+        assert!(scheduler
+            .reserve_at(Priority::Background, 999, start)
+            .is_err());
+        assert!(scheduler
+            .reserve_at(Priority::Background, 1000, start)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_bursts_wait_for_refill() {
+        let scheduler = RequestScheduler::new();
+        let start = Instant::now();
+        let reserve = |at| scheduler.reserve_at(Priority::Interactive, 0, at).unwrap();
+        for _ in 0..BURST as usize {
+            assert_eq!(reserve(start), Duration::ZERO);
+        }
+        // Past the burst each request waits one refill period more
+        assert_eq!(reserve(start), Duration::from_millis(500));
+        assert_eq!(reserve(start), Duration::from_secs(1));
+        // Five quiet seconds earn ten tokens, two of them owed to the queue
+        let later = start + Duration::from_secs(5);
+        for _ in 0..8 {
+            assert_eq!(reserve(later), Duration::ZERO);
+        }
+        assert_eq!(reserve(later), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_low_budget_slows_the_refill() {
+        // Down to the reserve: the user may still send, but more slowly
+        let scheduler = scheduler_with(600, 100, i64::MAX);
+        let start = Instant::now();
+        let reserve = |at| scheduler.reserve_at(Priority::Interactive, 0, at).unwrap();
+        for _ in 0..BURST as usize {
+            assert_eq!(reserve(start), Duration::ZERO);
+        }
+        assert_eq!(reserve(start), Duration::from_secs(2));
+        // Each request is counted against the budget straight away
+        assert_eq!(scheduler.rate_limit().unwrap().remaining, 89);
+    }
+
+    #[test]
+    fn test_token_bucket_queues_bursts() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket {
+            tokens: BURST,
+            updated: start,
+        };
+        for _ in 0..BURST as usize {
+            assert_eq!(bucket.reserve(REFILL_PER_SEC, start), Some(Duration::ZERO));
+        }
+        // The next two wait one and two refill periods respectively
+        assert_eq!(
+            bucket.reserve(REFILL_PER_SEC, start),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            bucket.reserve(REFILL_PER_SEC, start),
+            Some(Duration::from_secs(1))
+        );
+        // Refilled after a quiet period, but never beyond the burst size
+        let later = start + Duration::from_secs(60);
+        assert_eq!(bucket.reserve(REFILL_PER_SEC, later), Some(Duration::ZERO));
+        assert!(bucket.tokens <= BURST - 1.0);
+    }
+
+    #[test]
+    fn test_token_bucket_refuses_long_queues() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket {
+            tokens: 0.0,
+            updated: start,
+        };
+        let max_queued = (MAX_QUEUE_WAIT.as_secs_f64() * LOW_BUDGET_REFILL_PER_SEC) as usize;
+        for _ in 0..max_queued {
+            assert!(bucket.reserve(LOW_BUDGET_REFILL_PER_SEC, start).is_some());
+        }
+        assert_eq!(bucket.reserve(LOW_BUDGET_REFILL_PER_SEC, start), None);
+    }
+
+    #[test]
+    fn test_rate_limit_is_low() {
+        let scheduler = scheduler_with(600, 120, i64::MAX);
+        assert!(scheduler.rate_limit().unwrap().is_low());
+        let scheduler = scheduler_with(600, 121, i64::MAX);
+        assert!(!scheduler.rate_limit().unwrap().is_low());
+        let scheduler = scheduler_with(600, 121, 0);
+        assert_eq!(scheduler.rate_limit(), None);
+    }
+}
@@ -1,6 +1,15 @@
-use crate::api::RssItem;
+use crate::api::{RssItem, TokenInfo};
+use crate::json_store;
+use crate::minibuffer::Minibuffer;
+use crate::tokens::TokenStore;
+use crate::unfurl::LinkTitles;
+use crate::util::width::{byte_offset, grapheme_count, graphemes, split_at_width, text_width};
+use anyhow::Result;
 use ratatui::widgets::ListState;
-use std::ops::Range;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Default)]
@@ -8,6 +17,8 @@ pub struct AggregateState {
     pub items: Vec<RssItem>,
     pub selected: usize,
     pub current_tab: String,
+    /// When the items shown were fetched, in Unix seconds
+    pub fetched_at: Option<i64>,
 }
 
 impl AggregateState {
@@ -16,6 +27,7 @@ impl AggregateState {
             items: Vec::new(),
             selected: 0,
             current_tab: "index".to_string(),
+            fetched_at: None,
         }
     }
 
@@ -45,6 +57,31 @@ impl AggregateState {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_tabs_state() {
+        let tab = |node: &str| NodeTab {
+            node: node.to_string(),
+            page: 1,
+            ..Default::default()
+        };
+        let mut tabs = TabsState::default();
+        assert!(tabs.switch(tab("python"), 1).is_none());
+
+        tabs.open(tab("python"));
+        assert_eq!(tabs.active, 1);
+        assert_eq!(tabs.labels("rust"), ["python", "rust"]);
+
+        // Switching stashes the live state of the tab being left
+        let shown = tabs.switch(tab("rust"), 1).unwrap();
+        assert_eq!(shown.node, "python");
+        assert_eq!(tabs.active, 0);
+        assert_eq!(tabs.switch(tab("python"), -1).unwrap().node, "rust");
+
+        assert_eq!(tabs.close().unwrap().node, "python");
+        assert!(!tabs.is_tabbed());
+        assert!(tabs.close().is_none());
+    }
+
     #[test]
     fn test_topic_state_next_topic() {
         let mut state = TopicState::default();
@@ -54,11 +91,11 @@ mod tests {
         assert_eq!(state.selected, 0);
 
         // Add topics
-        state.topics = vec![
+        state.set_topics(vec![
             create_test_topic(1),
             create_test_topic(2),
             create_test_topic(3),
-        ];
+        ]);
 
         // Next topic
         state.next_topic();
@@ -81,11 +118,11 @@ mod tests {
         assert_eq!(state.selected, 0);
 
         // Add topics
-        state.topics = vec![
+        state.set_topics(vec![
             create_test_topic(1),
             create_test_topic(2),
             create_test_topic(3),
-        ];
+        ]);
         state.selected = 1;
 
         // Previous topic
@@ -155,9 +192,51 @@ mod tests {
         assert_eq!(state.selected, 1);
     }
 
+    #[test]
+    fn test_notification_pages_and_removal() {
+        let mut state = NotificationState {
+            notifications: vec![create_test_notification(3), create_test_notification(2)],
+            page: 1,
+            ..Default::default()
+        };
+
+        // 2 was pushed onto page 2 by a new notification
+        let page = vec![create_test_notification(2), create_test_notification(1)];
+        assert_eq!(state.append_page(2, page), 1);
+        let ids: Vec<i64> = state.notifications.iter().map(|n| n.id).collect();
+        assert_eq!(ids, [3, 2, 1]);
+        assert_eq!(state.page, 2);
+
+        state.selected = 2;
+        state.remove(1);
+        assert_eq!(state.selected, 1);
+        state.remove(3);
+        state.remove(2);
+        assert_eq!(state.selected, 0);
+    }
+
+    #[test]
+    fn test_first_page_refresh_keeps_later_pages() {
+        let mut state = NotificationState {
+            notifications: [5, 4, 3, 2, 1].map(create_test_notification).to_vec(),
+            page: 2,
+            ..Default::default()
+        };
+
+        // 6 arrived and 4 was deleted, pushing 3 onto the first page
+        state.replace_first_page([6, 5, 3].map(create_test_notification).to_vec());
+        let ids: Vec<i64> = state.notifications.iter().map(|n| n.id).collect();
+        assert_eq!(ids, [6, 5, 3, 2, 1]);
+        assert_eq!(state.page, 2);
+
+        state.page = 1;
+        state.replace_first_page([7, 6].map(create_test_notification).to_vec());
+        assert_eq!(state.notifications.len(), 2);
+    }
+
     #[test]
     fn test_node_state_switch_node() {
-        let mut state = NodeState::new();
+        let mut state = NodeState::new(&crate::config::Config::default());
 
         assert_eq!(state.current_node, "python");
         assert_eq!(state.page, 1);
@@ -165,42 +244,785 @@ mod tests {
         state.switch_node("rust");
         assert_eq!(state.current_node, "rust");
         assert_eq!(state.page, 1);
+        assert_eq!(state.quick_node('8'), Some("javascript"));
+        assert_eq!(
+            state.quick_node('0'),
+            Some(crate::config::ALL_FAVORITES_NODE)
+        );
     }
 
     #[test]
-    fn test_node_state_select_current_node() {
-        let mut state = NodeState::new();
+    fn test_node_state_selected_node() {
+        let mut state = NodeState::new(&crate::config::Config::default());
 
         // Select from favorite nodes
         state.selected = 1; // programmer
-        let result = state.select_current_node();
-        assert_eq!(result, Some("programmer".to_string()));
-        assert_eq!(state.current_node, "programmer");
+        assert_eq!(state.selected_node(), Some("programmer".to_string()));
     }
 
     #[test]
     fn test_token_state_insert_delete() {
-        let mut state = TokenState::default();
+        let mut state = TokenState::default().prompt;
 
         // Insert characters
         state.insert_char('h');
         state.insert_char('i');
-        assert_eq!(state.input, "hi");
-        assert_eq!(state.cursor, 2);
+        assert_eq!(state.input(), "hi");
+        assert_eq!(state.cursor(), 2);
 
         // Move cursor left
-        state.move_cursor_left();
-        assert_eq!(state.cursor, 1);
+        state.move_left();
+        assert_eq!(state.cursor(), 1);
 
         // Insert in middle: cursor at 1, inserts 'e' -> "hei"
         state.insert_char('e');
-        assert_eq!(state.input, "hei");
-        assert_eq!(state.cursor, 2);
+        assert_eq!(state.input(), "hei");
+        assert_eq!(state.cursor(), 2);
 
         // Delete character: cursor at 2, deletes char at position 1 ('e')
+        state.delete_backward();
+        assert_eq!(state.input(), "hi");
+        assert_eq!(state.cursor(), 1);
+
+        assert_eq!(
+            state.submit(),
+            crate::minibuffer::MinibufferEvent::Submit("hi".to_string())
+        );
+        state.clear();
+        assert_eq!(
+            state.submit(),
+            crate::minibuffer::MinibufferEvent::Invalid("Token cannot be empty".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reply_input_multiline_editing() {
+        let mut state = ReplyInputState::default();
+        state.start(42);
+
+        for ch in "hello".chars() {
+            state.insert_char(ch);
+        }
+        state.insert_newline();
+        for ch in "世界".chars() {
+            state.insert_char(ch);
+        }
+        assert_eq!(state.content(), "hello\n世界");
+        assert_eq!((state.cursor_row, state.cursor_col), (1, 2));
+
+        // Moving up clamps the column to the shorter line
+        state.move_line_start();
+        state.move_cursor_up();
+        state.move_line_end();
+        assert_eq!((state.cursor_row, state.cursor_col), (0, 5));
+
+        // Backspace at column 0 joins with the previous line
+        state.move_cursor_down();
+        state.move_line_start();
         state.delete_char();
-        assert_eq!(state.input, "hi");
-        assert_eq!(state.cursor, 1);
+        assert_eq!(state.content(), "hello世界");
+        assert_eq!((state.cursor_row, state.cursor_col), (0, 5));
+        assert_eq!(state.topic_id, Some(42));
+    }
+
+    #[test]
+    fn test_favorites_state_toggle() {
+        let topic = |id| crate::api::Topic {
+            id,
+            node: None,
+            member: None,
+            last_reply_by: None,
+            last_touched: None,
+            title: String::new(),
+            url: String::new(),
+            created: 0,
+            deleted: None,
+            content: None,
+            content_rendered: None,
+            last_modified: None,
+            replies: 0,
+        };
+        let mut state = FavoritesState::default();
+        state.set_topics(vec![topic(1), topic(2)]);
+        state.selected = 1;
+
+        state.set_favorite(2, false);
+        assert_eq!(state.topics.len(), 1);
+        assert_eq!(state.selected, 0);
+
+        // Newly favorited topics show up on the next load
+        state.set_favorite(3, true);
+        assert_eq!(state.topics.len(), 1);
+    }
+
+    #[test]
+    fn test_reply_input_set_content() {
+        let mut state = ReplyInputState::default();
+        assert!(state.is_empty());
+
+        state.set_content("first\nsecond line");
+        assert_eq!(state.lines.len(), 2);
+        assert_eq!((state.cursor_row, state.cursor_col), (1, 11));
+        assert!(!state.is_empty());
+
+        state.clear();
+        assert!(state.is_empty());
+        assert_eq!(state.content(), "");
+    }
+
+    #[test]
+    fn test_reply_submissions_block_duplicates() {
+        let mut draft = ReplyInputState::default();
+        draft.start(1);
+        draft.set_content("hello\nworld");
+        draft.move_cursor_up();
+
+        let mut submissions = ReplySubmissions::default();
+        assert!(submissions.begin(1, draft.clone()));
+        assert!(!submissions.begin(1, ReplyInputState::default()));
+        assert!(submissions.begin(2, ReplyInputState::default()));
+        assert!(submissions.is_in_flight(1));
+
+        let returned = submissions.finish(1).unwrap();
+        assert_eq!(returned.content(), "hello\nworld");
+        assert_eq!(returned.cursor_row, draft.cursor_row);
+        assert!(!submissions.is_in_flight(1));
+        assert!(submissions.begin(1, ReplyInputState::default()));
+    }
+
+    #[test]
+    fn test_notification_state_register_polled() {
+        let mut state = NotificationState::default();
+
+        // First poll is the baseline
+        let first = [create_test_notification(1), create_test_notification(2)];
+        assert!(state.register_polled(&first).is_empty());
+        assert_eq!(state.unread_count, 0);
+
+        let second = [create_test_notification(3), create_test_notification(2)];
+        let fresh = state.register_polled(&second);
+        assert_eq!(fresh.iter().map(|n| n.id).collect::<Vec<_>>(), [3]);
+        assert_eq!(state.unread_count, 1);
+        assert!(state.register_polled(&second).is_empty());
+
+        state.notifications = vec![create_test_notification(4)];
+        state.mark_all_seen();
+        assert_eq!(state.unread_count, 0);
+        assert_eq!(state.last_seen_id, Some(4));
+    }
+
+    #[test]
+    fn test_append_posted_reply() {
+        let reply = |id| crate::api::Reply {
+            id,
+            member: None,
+            content: None,
+            content_rendered: None,
+            created: 0,
+            last_modified: None,
+            thanks: 0,
+        };
+        let mut state = TopicState::default();
+        assert_eq!(state.append_posted_reply(reply(1)), None);
+
+        // All replies loaded: appended and selected as the next floor
+        let mut topic = create_test_topic(1);
+        topic.replies = 2;
+        state.current = Some(topic);
+        state.set_replies(vec![reply(1), reply(2)]);
+        assert_eq!(state.append_posted_reply(reply(3)), Some(3));
+        assert_eq!(state.replies.len(), 3);
+        assert_eq!(state.selected_reply, 2);
+        assert_eq!(state.replies_list_state.selected(), Some(2));
+
+        // Earlier pages missing: only the count moves
+        state.replies.truncate(1);
+        assert_eq!(state.append_posted_reply(reply(4)), Some(4));
+        assert_eq!(state.replies.len(), 1);
+        assert_eq!(state.current.as_ref().unwrap().replies, 4);
+    }
+
+    #[test]
+    fn test_topic_sort_and_filter() {
+        let topic = |id: i64, title: &str, created: i64, replies: i64| crate::api::Topic {
+            title: title.to_string(),
+            created,
+            replies,
+            ..create_test_topic(id)
+        };
+        let mut state = TopicState {
+            topics: vec![
+                topic(1, "Rust 1.80 released", 100, 5),
+                topic(2, "求推荐 Go 书", 300, 1),
+                topic(3, "rust vs go", 200, 9),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(state.topic_order(), [0, 1, 2]);
+        assert_eq!(state.topic_mode_label(), None);
+
+        state.cycle_topic_sort();
+        state.cycle_topic_sort();
+        assert_eq!(state.topic_sort, TopicSort::Created);
+        assert_eq!(state.topic_order(), [1, 2, 0]);
+        state.cycle_topic_sort();
+        assert_eq!(state.topic_order(), [2, 0, 1]);
+
+        // Case-insensitive, and the selection moves off hidden topics
+        state.selected = 1;
+        state.set_topic_filter("RUST");
+        assert_eq!(state.topic_order(), [2, 0]);
+        assert_eq!(state.selected, 2);
+        state.next_topic();
+        assert_eq!(state.selected, 0);
+        assert!(state.at_last_topic());
+        assert_eq!(
+            state.topic_mode_label().as_deref(),
+            Some("by replies, /RUST/")
+        );
+
+        // Regexes work, and text that isn't one is matched as is
+        state.set_topic_filter("^rust v");
+        assert_eq!(state.topic_order(), [2]);
+        state.set_topic_filter("1.80 (");
+        assert!(state.topic_order().is_empty());
+        // Nothing shown means nothing to open, even though `selected` is kept
+        assert!(state.selected_topic().is_none());
+        state.set_topic_filter("");
+        assert_eq!(state.selected_topic().map(|topic| topic.id), Some(3));
+        assert_eq!(state.topic_order().len(), 3);
+    }
+
+    #[test]
+    fn test_topic_previews() {
+        let mut previews = TopicPreviews::default();
+        previews.show(1);
+        assert!(previews.shown().is_none());
+
+        previews.insert(create_test_topic(1));
+        previews.show(1);
+        assert_eq!(previews.shown().map(|topic| topic.id), Some(1));
+        previews.close();
+        assert!(previews.shown().is_none());
+        assert!(previews.contains(1));
+    }
+
+    #[test]
+    fn test_topic_cache_round_trip() {
+        let mut state = TopicState {
+            current: Some(create_test_topic(1)),
+            scroll: 6,
+            ..Default::default()
+        };
+
+        let mut cache = TopicCache::default();
+        cache.insert(state.snapshot().unwrap());
+        for id in 2..=TopicCache::CAPACITY as i64 {
+            state.current = Some(create_test_topic(id));
+            cache.insert(state.snapshot().unwrap());
+        }
+        // Re-storing topic 1 makes it the most recent, so topic 2 is evicted
+        state.current = Some(create_test_topic(1));
+        cache.insert(state.snapshot().unwrap());
+        state.current = Some(create_test_topic(99));
+        cache.insert(state.snapshot().unwrap());
+        assert!(cache.get(2).is_none());
+
+        let mut restored = TopicState::default();
+        restored.restore(cache.get(1).cloned().unwrap());
+        assert_eq!(restored.current.as_ref().map(|topic| topic.id), Some(1));
+        assert_eq!(restored.scroll, 6);
+
+        cache.remove(1);
+        assert!(cache.get(1).is_none());
+    }
+
+    #[test]
+    fn test_parse_reply_mentions() {
+        assert_eq!(
+            parse_reply_mentions("@alice #3 agreed, cc @bob-2 and mail me@example.com"),
+            vec![
+                ReplyMention {
+                    username: "alice".to_string(),
+                    floor: Some(3),
+                },
+                ReplyMention {
+                    username: "bob-2".to_string(),
+                    floor: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clipboard_history() {
+        let mut history = ClipboardHistory::default();
+        for i in 0..25 {
+            history.push(format!("item {}", i), i.to_string());
+        }
+        assert_eq!(history.entries().len(), 20);
+        assert_eq!(history.selected_entry().unwrap().text, "24");
+
+        // Copying a remembered snippet again moves it to the top
+        history.push("again".to_string(), "10".to_string());
+        assert_eq!(history.entries().len(), 20);
+        assert_eq!(history.selected_entry().unwrap().label, "again");
+
+        history.next();
+        history.remove_selected();
+        assert_eq!(history.selected_entry().unwrap().text, "23");
+        assert!(history.entries().iter().all(|entry| entry.text != "24"));
+    }
+
+    #[test]
+    fn test_view_history_collapses_duplicates() {
+        use crate::app::View;
+        let view = |view| HistoryEntry {
+            view,
+            topic_id: None,
+        };
+        let topic = |id| HistoryEntry {
+            view: View::TopicDetail,
+            topic_id: Some(id),
+        };
+
+        let mut history = ViewHistory::new(view(View::Aggregate));
+        history.push(view(View::Notifications));
+        history.push(view(View::Notifications));
+        assert_eq!(history.previous(), Some(view(View::Aggregate)));
+
+        // Topic 1 reached three times through member profiles keeps only
+        // its two latest entries
+        history.push(topic(1));
+        history.push(view(View::Member));
+        history.push(topic(1));
+        history.push(view(View::Member));
+        history.push(topic(1));
+        let mut visited = Vec::new();
+        while let Some(entry) = history.current() {
+            visited.push(entry);
+            if history.back().is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            visited,
+            vec![
+                topic(1),
+                view(View::Member),
+                topic(1),
+                view(View::Member),
+                view(View::Notifications),
+                view(View::Aggregate),
+            ]
+        );
+
+        // A topic replacing the one on screen merges with an identical
+        // entry before it
+        let mut history = ViewHistory::new(view(View::Aggregate));
+        history.push(topic(1));
+        history.push(topic(2));
+        history.replace_current(topic(1));
+        assert_eq!(history.current(), Some(topic(1)));
+        assert_eq!(history.previous(), Some(view(View::Aggregate)));
+        assert_eq!(history.remove_current(), Some(view(View::Aggregate)));
+        assert_eq!(history.remove_current(), None);
+
+        // Jumping keeps the entries on both sides
+        history.push(view(View::Favorites));
+        history.push(topic(3));
+        assert_eq!(history.jump(0), Some(view(View::Aggregate)));
+        assert_eq!(history.entries().len(), 3);
+        assert_eq!(history.forward(), Some(view(View::Favorites)));
+        assert_eq!(history.jump(3), None);
+        assert_eq!(history.position(), 1);
+    }
+
+    #[test]
+    fn test_view_history_resume() {
+        use crate::app::View;
+        let saved: ViewHistory = serde_json::from_str(
+            r#"{"entries": [
+                {"view": "aggregate"},
+                {"view": "topic-detail", "topic_id": 1},
+                {"view": "member"},
+                {"view": "topic-detail", "topic_id": 2},
+                {"view": "help"}
+            ], "position": 3}"#,
+        )
+        .unwrap();
+        let start = HistoryEntry {
+            view: View::Aggregate,
+            topic_id: None,
+        };
+
+        let mut history = saved.resume(start);
+        assert_eq!(history.current(), Some(start));
+        assert_eq!(
+            history.back(),
+            Some(HistoryEntry {
+                view: View::TopicDetail,
+                topic_id: Some(2),
+            })
+        );
+        // The member profile in between could not be rebuilt
+        assert_eq!(history.back().unwrap().topic_id, Some(1));
+        assert_eq!(history.back(), Some(start));
+        assert_eq!(history.back(), None);
+
+        let round_trip: ViewHistory =
+            serde_json::from_str(&serde_json::to_string(&history).unwrap()).unwrap();
+        assert_eq!(round_trip.entries, history.entries);
+    }
+
+    #[test]
+    fn test_fuzzy_rank() {
+        let names = ["livid", "lividx", "kokdemo", "Livid2"];
+        let ranked = fuzzy_rank(names, "livid", 10, |name| vec![*name]);
+        assert_eq!(ranked[0], "livid");
+        assert!(!ranked.contains(&"kokdemo"));
+    }
+
+    #[test]
+    fn test_visited_state_matches() {
+        let visit = |id: i64, title: &str, node: &str| crate::read_tracker::VisitedTopic {
+            id,
+            title: title.to_string(),
+            node: node.to_string(),
+            visited_at: 0,
+        };
+        let entries = [
+            visit(1, "Rust async runtimes", "rust"),
+            visit(2, "Best mechanical keyboard", "hardware"),
+        ];
+        let mut state = VisitedState::default();
+        state.update_matches(&entries);
+        assert_eq!(state.matches, [0, 1]);
+
+        for ch in "keyboard".chars() {
+            state.prompt.handle_key(crossterm::event::KeyEvent::from(
+                crossterm::event::KeyCode::Char(ch),
+            ));
+        }
+        state.update_matches(&entries);
+        assert_eq!(state.selected_entry(), Some(1));
+        state.next();
+        assert_eq!(state.selected_entry(), Some(1));
+    }
+
+    #[test]
+    fn test_bookmarks_state_cycle_tag() {
+        let tags = ["async".to_string(), "rust".to_string()];
+        let mut state = BookmarksState::default();
+        state.cycle_tag(&tags);
+        assert_eq!(state.tag.as_deref(), Some("async"));
+        state.cycle_tag(&tags);
+        assert_eq!(state.tag.as_deref(), Some("rust"));
+        state.cycle_tag(&tags);
+        assert_eq!(state.tag, None);
+    }
+
+    #[test]
+    fn test_merge_node_topics() {
+        let topic = |id: i64, created: i64| -> crate::api::Topic {
+            serde_json::from_value(serde_json::json!({ "id": id, "created": created })).unwrap()
+        };
+        let merged = merge_node_topics(vec![
+            (
+                "go".to_string(),
+                "Go".to_string(),
+                vec![topic(1, 100), topic(3, 300)],
+            ),
+            (
+                "rust".to_string(),
+                "Rust".to_string(),
+                vec![topic(2, 200), topic(3, 300)],
+            ),
+        ]);
+        let ids: Vec<i64> = merged.iter().map(|topic| topic.id).collect();
+        assert_eq!(ids, [3, 2, 1]);
+        assert_eq!(merged[1].node.as_ref().unwrap().name, "rust");
+    }
+
+    #[test]
+    fn test_mention_completion() {
+        let mut state = ReplyInputState {
+            mention_candidates: vec!["alice".to_string(), "bob".to_string()],
+            ..Default::default()
+        };
+        for ch in "thanks @bo".chars() {
+            state.insert_char(ch);
+            state.update_mention();
+        }
+        let mention = state.mention.clone().unwrap();
+        assert_eq!(mention.start_col, 7);
+        assert_eq!(mention.suggestions, ["bob"]);
+
+        state.accept_mention();
+        assert_eq!(state.lines[0], "thanks @bob ");
+        assert_eq!(state.cursor_col, 12);
+        assert!(state.mention.is_none());
+
+        // A bare `@` offers everyone; an e-mail address offers nobody
+        state.insert_char('@');
+        state.update_mention();
+        assert_eq!(state.mention.as_ref().unwrap().suggestions.len(), 2);
+        state.set_content("me@al");
+        state.update_mention();
+        assert!(state.mention.is_none());
+    }
+
+    #[test]
+    fn test_parse_reply_jump() {
+        assert_eq!(ReplyJump::parse("123"), Some(ReplyJump::Floor(123)));
+        assert_eq!(ReplyJump::parse("#45"), Some(ReplyJump::Floor(45)));
+        assert_eq!(ReplyJump::parse("p3"), Some(ReplyJump::Page(3)));
+        assert_eq!(ReplyJump::parse("p3").map(ReplyJump::floor), Some(41));
+        assert_eq!(ReplyJump::parse("0"), None);
+        assert_eq!(ReplyJump::parse("p"), None);
+        assert_eq!(ReplyJump::parse("abc"), None);
+    }
+
+    #[test]
+    fn test_jump_to_quoted_reply() {
+        let reply = |id, user: &str, content: &str| crate::api::Reply {
+            id,
+            member: Some(serde_json::from_value(serde_json::json!({ "username": user })).unwrap()),
+            content: Some(content.to_string()),
+            content_rendered: None,
+            created: 0,
+            last_modified: None,
+            thanks: 0,
+        };
+        let mut state = TopicState {
+            replies: vec![
+                reply(1, "alice", "first"),
+                reply(2, "bob", "second"),
+                reply(3, "alice", "third"),
+                // Floor 1 is alice's, so #1 wins over her latest reply
+                reply(4, "carol", "@alice #1 quoting"),
+                // Floor 2 is not alice's; fall back to her latest earlier reply
+                reply(5, "dave", "@alice #2 wrong floor"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(state.quoted_reply(3), Some(0));
+        assert_eq!(state.quoted_reply(4), Some(2));
+        assert_eq!(state.quoted_reply(0), None);
+
+        state.select_reply(4);
+        assert_eq!(state.jump_to_quoted_reply(), Some(2));
+        assert_eq!(state.selected_reply, 2);
+        assert_eq!(state.jump_to_quoted_reply(), None);
+        assert_eq!(state.jump_back_reply(), Some(4));
+        assert_eq!(state.jump_back_reply(), None);
+    }
+
+    #[test]
+    fn test_toggle_replies_keeps_place() {
+        let reply = |id| crate::api::Reply {
+            id,
+            member: None,
+            content: Some(format!("reply {}", id)),
+            content_rendered: None,
+            created: 0,
+            last_modified: None,
+            thanks: 0,
+        };
+        let mut state = TopicState {
+            current: Some(create_test_topic(1)),
+            replies: vec![reply(1), reply(2), reply(3)],
+            scroll: 9,
+            ..Default::default()
+        };
+        state.toggle_replies();
+        state.select_reply(2);
+        state.toggle_replies();
+        assert!(!state.show_replies);
+        assert_eq!(state.scroll, 9);
+        state.toggle_replies();
+        assert_eq!(state.selected_reply, 2);
+    }
+
+    #[test]
+    fn test_focus_scroll_follows_the_reply() {
+        let reply = |id| crate::api::Reply {
+            id,
+            member: None,
+            content: Some(format!("reply {}", id)),
+            content_rendered: None,
+            created: 0,
+            last_modified: None,
+            thanks: 0,
+        };
+        let mut state = TopicState {
+            current: Some(create_test_topic(1)),
+            replies: vec![reply(1), reply(2)],
+            show_replies: true,
+            ..Default::default()
+        };
+        state.scroll_focus(15);
+        state.scroll_focus(-3);
+        assert_eq!(state.focus_scroll(), 12);
+        state.scroll_focus(-20);
+        assert_eq!(state.focus_scroll(), 0);
+
+        state.scroll_focus(6);
+        state.select_reply(1);
+        assert_eq!(state.focus_scroll(), 0);
+        state.toggle_replies();
+        assert!(state.focused_reply().is_none());
+    }
+
+    #[test]
+    fn test_reply_filter() {
+        let reply = |user: &str, content: &str| crate::api::Reply {
+            id: 0,
+            member: Some(serde_json::from_value(serde_json::json!({ "username": user })).unwrap()),
+            content: Some(content.to_string()),
+            content_rendered: None,
+            created: 0,
+            last_modified: None,
+            thanks: 0,
+        };
+        let mut topic = create_test_topic(1);
+        topic.member =
+            Some(serde_json::from_value(serde_json::json!({ "username": "op" })).unwrap());
+        let mut state = TopicState {
+            current: Some(topic),
+            replies: vec![
+                reply("alice", "first"),
+                reply("op", "thanks @alice"),
+                reply("bob", "@me look"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(state.reply_order(), vec![0, 1, 2]);
+        assert_eq!(state.reply_mode_label(), None);
+        // The cached order follows replies added later
+        state.append_replies(vec![reply("carol", "agreed")]);
+        assert_eq!(state.reply_order(), vec![0, 1, 2, 3]);
+        state.move_reply_selection(5);
+        assert_eq!(state.selected_reply, 3);
+        assert!(!state.has_next_reply());
+        state.select_reply_at(2);
+
+        // The selected reply is filtered out, so the first shown one is picked
+        state.cycle_reply_filter(Some("me"));
+        assert_eq!(state.reply_order(), vec![1]);
+        assert_eq!(state.selected_reply, 1);
+        assert_eq!(state.reply_mode_label().as_deref(), Some("OP only"));
+        state.cycle_reply_filter(Some("me"));
+        assert_eq!(state.reply_order(), vec![2]);
+        state.cycle_reply_filter(Some("me"));
+        assert_eq!(state.reply_filter, ReplyFilter::All);
+        // Without a profile the mentions filter is skipped
+        state.cycle_reply_filter(None);
+        state.cycle_reply_filter(None);
+        assert_eq!(state.reply_filter, ReplyFilter::All);
+    }
+
+    #[test]
+    fn test_reply_sort_by_thanks() {
+        let reply = |id, user: &str| crate::api::Reply {
+            id,
+            member: Some(serde_json::from_value(serde_json::json!({ "username": user })).unwrap()),
+            content: Some("hi".to_string()),
+            content_rendered: None,
+            created: 0,
+            last_modified: None,
+            thanks: 0,
+        };
+        let mut topic = create_test_topic(1);
+        topic.member =
+            Some(serde_json::from_value(serde_json::json!({ "username": "op" })).unwrap());
+        let mut state = TopicState {
+            current: Some(topic),
+            ..Default::default()
+        };
+        state.set_replies(vec![
+            reply(10, "alice"),
+            reply(11, "op"),
+            reply(12, "bob"),
+            reply(13, "op"),
+            reply(14, "carol"),
+        ]);
+        state.cycle_reply_sort();
+        assert_eq!(state.reply_mode_label().as_deref(), Some("by thanks"));
+        // Nothing thanked yet: all tie, so floor order
+        assert_eq!(state.reply_order(), vec![0, 1, 2, 3, 4]);
+
+        // The counts come with the topic page, after the order was cached
+        let cell = |id, thanks| {
+            format!(
+                r#"<div id="r_{}" class="cell"><img src="/static/img/heart.png" /> {}</div>"#,
+                id, thanks
+            )
+        };
+        let page = [cell(11, 2), cell(12, 5), cell(13, 2), cell(14, 5)].concat();
+        state.set_stats(Some(crate::api::TopicStats::from_html(&page)));
+        // Ties keep floor order
+        assert_eq!(state.reply_order(), vec![2, 4, 1, 3, 0]);
+        state.select_reply_at(0);
+        assert_eq!(state.selected_reply, 2);
+        state.next_reply();
+        assert_eq!(state.selected_reply, 4);
+        assert_eq!(state.reply_position(), Some(1));
+
+        state.cycle_reply_filter(None);
+        assert_eq!(state.reply_order(), vec![1, 3]);
+        assert_eq!(
+            state.reply_mode_label().as_deref(),
+            Some("by thanks, OP only")
+        );
+        state.cycle_reply_sort();
+        state.cycle_reply_filter(None);
+        assert_eq!(state.reply_order(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(state.reply_mode_label(), None);
+    }
+
+    #[test]
+    fn test_reply_search() {
+        let reply = |user: &str, content: &str| crate::api::Reply {
+            id: 0,
+            member: Some(serde_json::from_value(serde_json::json!({ "username": user })).unwrap()),
+            content: Some(content.to_string()),
+            content_rendered: None,
+            created: 0,
+            last_modified: None,
+            thanks: 0,
+        };
+        let mut state = TopicState {
+            current: Some(create_test_topic(1)),
+            replies: vec![
+                reply("alice", "Rust is great"),
+                reply("bob", "go is fine"),
+                reply("rusty", "agreed"),
+                reply("carol", "I like rust too"),
+            ],
+            ..Default::default()
+        };
+        assert!(!state.select_reply_match(true, true));
+
+        // Contents and author names both match
+        state.reply_search = SearchPattern::new("rust");
+        assert_eq!(state.reply_matches(), vec![0, 2, 3]);
+        assert!(state.select_reply_match(true, true));
+        assert_eq!(state.selected_reply, 0);
+        state.select_reply_match(true, false);
+        assert_eq!(state.selected_reply, 2);
+        state.select_reply_match(true, false);
+        state.select_reply_match(true, false);
+        assert_eq!(state.selected_reply, 0);
+        state.select_reply_match(false, false);
+        assert_eq!(state.selected_reply, 3);
+
+        state.reply_search = SearchPattern::new("^go");
+        assert_eq!(state.reply_matches(), vec![1]);
+        let ranges = SearchPattern::new("RUST")
+            .unwrap()
+            .find_ranges("rust, Rust");
+        assert_eq!(ranges, vec![0..4, 6..10]);
     }
 
     // Helper functions
@@ -242,6 +1064,9 @@ mod tests {
             date: "2026-02-07 12:00".to_string(),
             author: Some("test".to_string()),
             timestamp: Some(1738920000), // 2026-02-07 12:00 UTC
+            node: None,
+            replies: None,
+            last_touched: None,
         }
     }
 
@@ -311,6 +1136,38 @@ mod tests {
         assert_eq!(state.selected, 0);
     }
 
+    #[test]
+    fn test_retry_belongs_to_the_error_shown() {
+        let mut ui = UiState::new();
+        ui.fail("Failed to load profile".to_string(), Retry::Profile);
+        assert_eq!(ui.retry(), Some(&Retry::Profile));
+
+        // Another error replaces it, so r has nothing to retry
+        ui.error = Some("Failed to copy to clipboard".to_string());
+        assert_eq!(ui.retry(), None);
+
+        ui.fail("Failed to load favorites".to_string(), Retry::Favorites);
+        ui.dismiss_error();
+        assert!(ui.error.is_none());
+        assert_eq!(ui.retry(), None);
+    }
+
+    #[test]
+    fn test_retry_runs_again_once_due() {
+        let mut ui = UiState::new();
+        ui.fail("Rate limited".to_string(), Retry::Favorites);
+        ui.retry_at = Some(std::time::Instant::now() + std::time::Duration::from_secs(60));
+        assert!(ui.retry_in().is_some());
+        assert!(!ui.retry_due());
+
+        ui.retry_at = Some(std::time::Instant::now());
+        assert!(ui.retry_due());
+
+        // Not once the error is replaced by another
+        ui.error = Some("Failed to copy to clipboard".to_string());
+        assert!(!ui.retry_due());
+    }
+
     #[test]
     fn test_topic_state_detect_links() {
         let mut state = TopicState::default();
@@ -318,16 +1175,37 @@ mod tests {
         // Create a test topic with content containing links
         state.current = Some(create_test_topic_with_content(1));
 
-        // Detect links
-        state.detect_links(80); // Test width
+        state.detect_links();
+        let urls: Vec<&str> = state
+            .detected_links
+            .iter()
+            .map(|l| l.url.as_str())
+            .collect();
+        assert_eq!(urls, ["https://example.com", "http://test.org"]);
+        let shortcuts: Vec<&str> = state
+            .detected_links
+            .iter()
+            .map(|l| l.shortcut.as_str())
+            .collect();
+        assert_eq!(shortcuts, ["a", "o"]);
+        assert_eq!(state.link_shortcuts.len(), 2);
+    }
+
+    #[test]
+    fn test_long_link_labels_cut_by_width() {
+        let url = format!("https://zh.wikipedia.org/wiki/{}", "中文".repeat(20));
+        let mut topic = create_test_topic_with_content(1);
+        topic.content = Some(format!("See {}", url));
+        let mut state = TopicState {
+            current: Some(topic),
+            ..Default::default()
+        };
 
-        // Check that links were detected (if regex works)
-        // Note: This test may fail if regex pattern doesn't match,
-        // but at least we test that the method doesn't panic
-        assert_eq!(
-            state.link_shortcuts.len(),
-            state.detected_links.iter().take(9).count()
-        );
+        state.detect_links();
+        let link = &state.detected_links[0];
+        assert_eq!(link.url, url);
+        assert!(link.display_text.ends_with("..."));
+        assert!(crate::util::width::text_width(&link.display_text) <= 50);
     }
 
     fn create_test_topic_with_content(id: i64) -> crate::api::Topic {
@@ -351,21 +1229,32 @@ mod tests {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DetectedLink {
     pub url: String,
     pub shortcut: String,
-    pub text_range: Range<usize>,
     #[allow(dead_code)] // Not currently used, but kept for completeness
     pub display_text: String,
 }
 
+/// What to do with a link picked in link mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkHandler {
+    Browser,
+    Copy,
+    Download,
+    /// Open a V2EX topic or member link here rather than in the browser
+    Internal,
+}
+
 #[derive(Debug)]
 pub struct LinkInputState {
     pub current_input: String,
     pub last_key_time: Option<Instant>,
     pub timeout_duration: Duration,
     pub is_active: bool,
+    /// The link whose label was typed, waiting for what to do with it
+    pub chosen: Option<DetectedLink>,
 }
 
 impl Default for LinkInputState {
@@ -375,64 +1264,768 @@ impl Default for LinkInputState {
             last_key_time: None,
             timeout_duration: Duration::from_secs(2), // Increased from 300ms to 2 seconds
             is_active: false,
+            chosen: None,
         }
     }
 }
 
-#[derive(Debug, Default)]
-pub struct TopicState {
-    pub topics: Vec<crate::api::Topic>,
-    pub selected: usize,
-    pub current: Option<crate::api::Topic>,
-    pub replies: Vec<crate::api::Reply>,
-    pub replies_page: i32,
-    pub scroll: usize,
-    pub selected_reply: usize,
-    pub replies_list_state: ListState,
-    pub show_replies: bool,
-    pub detected_links: Vec<DetectedLink>,
-    pub link_shortcuts: Vec<String>,
-    pub link_input_state: LinkInputState,
-    pub parsed_content_cache: Option<String>,
+/// An `@username` or `@username #N` reference in a reply
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplyMention {
+    pub username: String,
+    pub floor: Option<usize>,
 }
 
-impl TopicState {
-    pub fn next_topic(&mut self) {
-        if !self.topics.is_empty() {
-            self.selected = (self.selected + 1) % self.topics.len();
-        }
+/// Plain text of a reply for finding mentions: the source if the API
+/// gave it, otherwise the rendered HTML as text
+fn reply_text(reply: &crate::api::Reply) -> Option<String> {
+    match reply.content.as_deref() {
+        Some(content) => Some(content.to_string()),
+        None => Some(crate::render::plain(reply.content_rendered.as_deref()?, 1000).to_string()),
     }
+}
 
-    pub fn previous_topic(&mut self) {
-        if !self.topics.is_empty() {
-            self.selected = if self.selected == 0 {
-                self.topics.len() - 1
-            } else {
-                self.selected - 1
-            };
+/// Find the members (and floors) a reply quotes, in order of appearance
+pub fn parse_reply_mentions(text: &str) -> Vec<ReplyMention> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    text.match_indices('@')
+        // Skip the `@` inside e-mail addresses
+        .filter(|(at, _)| !text[..*at].ends_with(is_name_char))
+        .filter_map(|(at, _)| {
+            let rest = &text[at + 1..];
+            let name_len = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+            if name_len == 0 {
+                return None;
+            }
+            let after_name = rest[name_len..].trim_start_matches([' ', '\u{a0}']);
+            let floor = after_name.strip_prefix('#').and_then(|digits| {
+                let len = digits
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(digits.len());
+                digits[..len].parse().ok()
+            });
+            Some(ReplyMention {
+                username: rest[..name_len].to_string(),
+                floor,
+            })
+        })
+        .collect()
+}
+
+/// Target of the `G` jump prompt in topic detail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplyJump {
+    Floor(usize),
+    Page(usize),
+}
+
+impl ReplyJump {
+    /// Parse `123` or `#123` as a reply floor and `p5` as a page
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (page, digits) = match input.strip_prefix(['p', 'P']) {
+            Some(digits) => (true, digits),
+            None => (false, input.strip_prefix('#').unwrap_or(input)),
+        };
+        let number = digits.parse::<usize>().ok().filter(|n| *n > 0)?;
+        Some(if page {
+            Self::Page(number)
+        } else {
+            Self::Floor(number)
+        })
+    }
+
+    /// Floor of the reply to select
+    pub fn floor(self) -> usize {
+        match self {
+            Self::Floor(floor) => floor,
+            Self::Page(page) => (page - 1) * crate::api::REPLIES_PER_PAGE + 1,
+        }
+    }
+}
+
+/// A topic's loaded data, kept so reopening it skips the network
+#[derive(Debug, Clone)]
+pub struct CachedTopic {
+    pub topic: crate::api::Topic,
+    pub stats: Option<crate::api::TopicStats>,
+    pub replies: Vec<crate::api::Reply>,
+    pub replies_page: i32,
+    pub scroll: usize,
+    pub selected_reply: usize,
+    /// When the data was saved, if it came from the disk cache
+    pub cached_at: Option<i64>,
+    pub first_unread: Option<usize>,
+}
+
+/// Recently opened topics keyed by ID, so a topic reached from a node list,
+/// the RSS aggregate, a notification or favorites resolves to one copy
+#[derive(Debug, Default)]
+pub struct TopicCache {
+    entries: HashMap<i64, CachedTopic>,
+    /// Least recently stored first
+    order: VecDeque<i64>,
+}
+
+impl TopicCache {
+    const CAPACITY: usize = 20;
+
+    pub fn insert(&mut self, entry: CachedTopic) {
+        let topic_id = entry.topic.id;
+        self.order.retain(|id| *id != topic_id);
+        self.order.push_back(topic_id);
+        self.entries.insert(topic_id, entry);
+        while self.order.len() > Self::CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, topic_id: i64) -> Option<&CachedTopic> {
+        self.entries.get(&topic_id)
+    }
+
+    pub fn remove(&mut self, topic_id: i64) {
+        self.entries.remove(&topic_id);
+        self.order.retain(|id| *id != topic_id);
+    }
+}
+
+/// Topics previewed over the topic list with SPC, kept so going back and
+/// forth between them fetches each one once
+#[derive(Debug, Default)]
+pub struct TopicPreviews {
+    topics: HashMap<i64, crate::api::Topic>,
+    /// The topic in the popup, while it is open
+    shown: Option<i64>,
+}
+
+impl TopicPreviews {
+    pub fn contains(&self, topic_id: i64) -> bool {
+        self.topics.contains_key(&topic_id)
+    }
+
+    pub fn insert(&mut self, topic: crate::api::Topic) {
+        self.topics.insert(topic.id, topic);
+    }
+
+    /// Open the popup on a stored topic
+    pub fn show(&mut self, topic_id: i64) {
+        self.shown = self.contains(topic_id).then_some(topic_id);
+    }
+
+    pub fn close(&mut self) {
+        self.shown = None;
+    }
+
+    pub fn shown(&self) -> Option<&crate::api::Topic> {
+        self.topics.get(&self.shown?)
+    }
+}
+
+/// Which replies the list shows
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ReplyFilter {
+    #[default]
+    All,
+    /// Only replies by the topic author
+    Op,
+    /// Only replies that `@` the given member, ourselves
+    Mentioning(String),
+}
+
+impl ReplyFilter {
+    /// All, then OP only, then (when logged in as `me`) mentions of me
+    pub fn next(&self, me: Option<&str>) -> Self {
+        match (self, me) {
+            (Self::All, _) => Self::Op,
+            (Self::Op, Some(me)) => Self::Mentioning(me.to_string()),
+            _ => Self::All,
+        }
+    }
+}
+
+/// Order of the replies list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplySort {
+    /// Floor order, as posted
+    #[default]
+    Time,
+    /// Most thanked first, ties in floor order
+    Thanks,
+}
+
+impl ReplySort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Time => Self::Thanks,
+            Self::Thanks => Self::Time,
+        }
+    }
+}
+
+/// Order of the topic list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TopicSort {
+    /// As V2EX listed them
+    #[default]
+    Loaded,
+    /// Most recently replied to first
+    LastTouched,
+    /// Newest first
+    Created,
+    /// Most replies first
+    Replies,
+}
+
+impl TopicSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Loaded => Self::LastTouched,
+            Self::LastTouched => Self::Created,
+            Self::Created => Self::Replies,
+            Self::Replies => Self::Loaded,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Loaded => "as listed",
+            Self::LastTouched => "by last reply",
+            Self::Created => "by newest",
+            Self::Replies => "by replies",
+        }
+    }
+}
+
+/// Text typed into the topic filter or reply search prompt, matched
+/// case-insensitively as a regex, or as plain text when it isn't one
+#[derive(Debug, Clone)]
+pub struct SearchPattern {
+    pub pattern: String,
+    regex: regex::Regex,
+}
+
+impl SearchPattern {
+    /// `None` for an empty pattern
+    pub fn new(pattern: &str) -> Option<Self> {
+        if pattern.is_empty() {
+            return None;
+        }
+        let regex = regex::Regex::new(&format!("(?i){}", pattern))
+            .or_else(|_| regex::Regex::new(&format!("(?i){}", regex::escape(pattern))))
+            .ok()?;
+        Some(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    /// Byte ranges of the non-empty matches in `text`
+    pub fn find_ranges(&self, text: &str) -> Vec<std::ops::Range<usize>> {
+        self.regex
+            .find_iter(text)
+            .map(|found| found.range())
+            .filter(|range| !range.is_empty())
+            .collect()
+    }
+}
+
+/// A list order worked out once and kept while the `K` it was worked out
+/// for stays the same, or until [`OrderCache::clear`]
+#[derive(Debug)]
+struct OrderCache<K> {
+    cached: RefCell<Option<(K, Vec<usize>)>>,
+}
+
+impl<K> Default for OrderCache<K> {
+    fn default() -> Self {
+        Self {
+            cached: RefCell::new(None),
+        }
+    }
+}
+
+impl<K: PartialEq> OrderCache<K> {
+    fn get(&self, key: K, order: impl FnOnce() -> Vec<usize>) -> Vec<usize> {
+        let mut cached = self.cached.borrow_mut();
+        match &*cached {
+            Some((cached_key, cached_order)) if *cached_key == key => cached_order.clone(),
+            _ => {
+                let order = order();
+                *cached = Some((key, order.clone()));
+                order
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        *self.cached.get_mut() = None;
+    }
+}
+
+/// What the reply order depends on besides the replies: the topic author,
+/// the sort and the filter
+type ReplyOrderKey = (Option<String>, ReplySort, ReplyFilter);
+
+#[derive(Debug, Default)]
+pub struct TopicState {
+    /// Changed through [`Self::set_topics`] and [`Self::append_topics`], so
+    /// the cached order is worked out again
+    topics: Vec<crate::api::Topic>,
+    pub selected: usize,
+    /// When `topics` was saved, if the network was down and it came from
+    /// the disk cache
+    pub topics_cached_at: Option<i64>,
+    /// Replies each listed topic gained since the node was last loaded
+    pub reply_deltas: HashMap<i64, i64>,
+    pub current: Option<crate::api::Topic>,
+    /// Same as `topics_cached_at`, for `current` and its replies
+    pub cached_at: Option<i64>,
+    /// Popularity counters of `current`, when the topic page could be
+    /// read; changed through [`Self::set_stats`]
+    pub stats: Option<crate::api::TopicStats>,
+    /// Changed through [`Self::set_replies`] and [`Self::append_replies`],
+    /// like `topics`
+    replies: Vec<crate::api::Reply>,
+    pub replies_page: i32,
+    pub scroll: usize,
+    /// Lines the reply shown in focus mode is scrolled by, with the index of
+    /// that reply; another reply starts at the top
+    focus_scroll: (usize, usize),
+    pub selected_reply: usize,
+    pub replies_list_state: ListState,
+    pub show_replies: bool,
+    pub detected_links: Vec<DetectedLink>,
+    pub link_shortcuts: Vec<String>,
+    pub link_input_state: LinkInputState,
+    /// Replies left by jumping to a quoted reply, most recent last
+    pub reply_jump_stack: Vec<usize>,
+    /// The `G` jump prompt while it is open
+    pub jump_input: Option<Minibuffer>,
+    /// The `M` bookmark tags prompt while it is open
+    pub tag_input: Option<Minibuffer>,
+    /// Index of the first reply posted since the previous visit
+    pub first_unread: Option<usize>,
+    pub reply_sort: ReplySort,
+    pub reply_filter: ReplyFilter,
+    pub topic_sort: TopicSort,
+    /// Titles the topic list is narrowed to, set with `/`
+    pub topic_filter: Option<SearchPattern>,
+    /// The `/` topic filter prompt while it is open
+    pub filter_input: Option<Minibuffer>,
+    /// Replies searched for with `C-s`, highlighted until ESC
+    pub reply_search: Option<SearchPattern>,
+    /// The `C-s` prompt while it is open, with the reply the search started
+    /// from to go back to when it is cancelled
+    pub search_input: Option<(Minibuffer, usize)>,
+    topic_order: OrderCache<(TopicSort, Option<String>)>,
+    reply_order: OrderCache<ReplyOrderKey>,
+    reply_matches: OrderCache<(ReplyOrderKey, Option<String>)>,
+}
+
+impl TopicState {
+    pub fn topics(&self) -> &[crate::api::Topic] {
+        &self.topics
+    }
+
+    pub fn set_topics(&mut self, topics: Vec<crate::api::Topic>) {
+        self.topics = topics;
+        self.topic_order.clear();
+    }
+
+    pub fn append_topics(&mut self, topics: Vec<crate::api::Topic>) {
+        self.topics.extend(topics);
+        self.topic_order.clear();
+    }
+
+    pub fn replies(&self) -> &[crate::api::Reply] {
+        &self.replies
+    }
+
+    pub fn set_replies(&mut self, replies: Vec<crate::api::Reply>) {
+        self.replies = replies;
+        self.reply_order.clear();
+        self.reply_matches.clear();
+        self.fill_reply_thanks();
+    }
+
+    pub fn append_replies(&mut self, replies: Vec<crate::api::Reply>) {
+        self.replies.extend(replies);
+        self.reply_order.clear();
+        self.reply_matches.clear();
+        self.fill_reply_thanks();
+    }
+
+    /// Change the counters of `current`, passing the reply thanks they
+    /// have on to the loaded replies
+    pub fn set_stats(&mut self, stats: Option<crate::api::TopicStats>) {
+        self.stats = stats.filter(|stats| !stats.is_empty());
+        self.fill_reply_thanks();
+    }
+
+    /// Change the thanks count of a reply, or of `current` without
+    /// `reply_id`, while a thank is on its way
+    pub fn add_thanks(&mut self, reply_id: Option<i64>, delta: i64) {
+        let Some(reply_id) = reply_id else {
+            let stats = self.stats.get_or_insert_with(Default::default);
+            stats.thanks = Some(stats.thanks.unwrap_or(0) + delta).filter(|&thanks| thanks > 0);
+            if stats.is_empty() {
+                self.stats = None;
+            }
+            return;
+        };
+        if let Some(reply) = self.replies.iter_mut().find(|reply| reply.id == reply_id) {
+            reply.thanks += delta;
+            self.reply_order.clear();
+            self.reply_matches.clear();
+        }
+    }
+
+    /// Take each reply's thanks from `stats`, where the topic page shows
+    /// them. The order by thanks is worked out again.
+    fn fill_reply_thanks(&mut self) {
+        let Some(stats) = &self.stats else {
+            return;
+        };
+        for reply in &mut self.replies {
+            if let Some(&thanks) = stats.reply_thanks.get(&reply.id) {
+                reply.thanks = thanks;
+            }
         }
+        self.reply_order.clear();
+        self.reply_matches.clear();
     }
 
-    pub fn next_reply(&mut self, width: usize) {
-        if !self.replies.is_empty() {
-            self.selected_reply = (self.selected_reply + 1) % self.replies.len();
+    /// Add a reply we just posted to the open topic without reloading.
+    ///
+    /// The reply is only appended when every earlier reply is loaded, so
+    /// floors (list position + 1) stay correct; otherwise it will arrive
+    /// with the remaining pages. Returns the new reply's floor either way.
+    pub fn append_posted_reply(&mut self, reply: crate::api::Reply) -> Option<usize> {
+        let topic = self.current.as_mut()?;
+        let contiguous = self.replies.len() as i64 >= topic.replies;
+        topic.replies += 1;
+        let floor = topic.replies as usize;
+
+        if contiguous {
+            self.append_replies(vec![reply]);
+            self.selected_reply = self.replies.len() - 1;
             self.replies_list_state.select(Some(self.selected_reply));
-            self.detect_links(width); // Update links for the newly selected reply
         }
+        Some(floor)
     }
 
-    pub fn previous_reply(&mut self, width: usize) {
-        if !self.replies.is_empty() {
-            self.selected_reply = if self.selected_reply == 0 {
-                self.replies.len() - 1
-            } else {
-                self.selected_reply - 1
+    /// Indices of the topics the list shows, in display order
+    pub fn topic_order(&self) -> Vec<usize> {
+        let filter = self.topic_filter.as_ref();
+        let key = (self.topic_sort, filter.map(|filter| filter.pattern.clone()));
+        self.topic_order.get(key, || {
+            let mut order: Vec<usize> = (0..self.topics.len())
+                .filter(|&i| filter.is_none_or(|filter| filter.matches(&self.topics[i].title)))
+                .collect();
+            let key = |i: &usize| {
+                let topic = &self.topics[*i];
+                std::cmp::Reverse(match self.topic_sort {
+                    TopicSort::Loaded => 0,
+                    TopicSort::LastTouched => topic.last_touched.unwrap_or(topic.created),
+                    TopicSort::Created => topic.created,
+                    TopicSort::Replies => topic.replies,
+                })
             };
-            self.replies_list_state.select(Some(self.selected_reply));
-            self.detect_links(width); // Update links for the newly selected reply
+            order.sort_by_key(key);
+            order
+        })
+    }
+
+    /// Where the selected topic is in [`Self::topic_order`], if shown
+    pub fn topic_position(&self) -> Option<usize> {
+        self.topic_order().iter().position(|&i| i == self.selected)
+    }
+
+    /// The selected topic, unless the filter hides it (or every topic)
+    pub fn selected_topic(&self) -> Option<&crate::api::Topic> {
+        self.topic_position()?;
+        self.topics.get(self.selected)
+    }
+
+    /// Select the topic at `position` in the list, clamped to its end
+    pub fn select_topic_at(&mut self, position: usize) {
+        let order = self.topic_order();
+        if let Some(&index) = order.get(position).or(order.last()) {
+            self.selected = index;
+        }
+    }
+
+    /// Move the list selection `delta` topics down (or up), clamped
+    pub fn move_topic_selection(&mut self, delta: isize) {
+        let position = self.topic_position().unwrap_or(0);
+        self.select_topic_at(position.saturating_add_signed(delta));
+    }
+
+    /// Whether the selected topic is the last one shown
+    pub fn at_last_topic(&self) -> bool {
+        let order = self.topic_order();
+        order.last().is_none_or(|&last| last == self.selected)
+    }
+
+    pub fn next_topic(&mut self) {
+        let order = self.topic_order();
+        if !order.is_empty() {
+            let position = match order.iter().position(|&i| i == self.selected) {
+                Some(position) => (position + 1) % order.len(),
+                None => 0,
+            };
+            self.selected = order[position];
+        }
+    }
+
+    pub fn previous_topic(&mut self) {
+        let order = self.topic_order();
+        if !order.is_empty() {
+            let position = match order.iter().position(|&i| i == self.selected) {
+                Some(0) | None => order.len() - 1,
+                Some(position) => position - 1,
+            };
+            self.selected = order[position];
+        }
+    }
+
+    /// Change the sort order, keeping the selected topic
+    pub fn cycle_topic_sort(&mut self) {
+        self.topic_sort = self.topic_sort.next();
+    }
+
+    /// Narrow the list to titles matching `pattern` (empty shows them
+    /// all), selecting the first shown topic if the selected one is hidden
+    pub fn set_topic_filter(&mut self, pattern: &str) {
+        self.topic_filter = SearchPattern::new(pattern);
+        self.show_selection();
+    }
+
+    /// Select the first shown topic if the selected one is filtered out
+    pub fn show_selection(&mut self) {
+        if self.topic_position().is_none() {
+            self.select_topic_at(0);
+        }
+    }
+
+    /// Sort or filter applied, as shown in the topic list title
+    pub fn topic_mode_label(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.topic_sort != TopicSort::Loaded {
+            parts.push(self.topic_sort.label().to_string());
+        }
+        if let Some(filter) = &self.topic_filter {
+            parts.push(format!("/{}/", filter.pattern));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    /// What [`Self::reply_order`] was worked out for
+    fn reply_order_key(&self) -> ReplyOrderKey {
+        let op = self
+            .current
+            .as_ref()
+            .and_then(|topic| topic.member.as_ref())
+            .map(|member| member.username.clone());
+        (op, self.reply_sort, self.reply_filter.clone())
+    }
+
+    /// Indices of the replies the list shows, in display order
+    pub fn reply_order(&self) -> Vec<usize> {
+        let key = self.reply_order_key();
+        self.reply_order.get(key.clone(), || {
+            let (op, sort, filter) = key;
+            let mut order: Vec<usize> = (0..self.replies.len())
+                .filter(|&i| {
+                    let reply = &self.replies[i];
+                    let author = reply.member.as_ref().map(|m| &m.username);
+                    match &filter {
+                        ReplyFilter::All => true,
+                        ReplyFilter::Op => author.is_some() && author == op.as_ref(),
+                        ReplyFilter::Mentioning(me) => reply_text(reply).is_some_and(|text| {
+                            parse_reply_mentions(&text)
+                                .iter()
+                                .any(|mention| mention.username == *me)
+                        }),
+                    }
+                })
+                .collect();
+            if sort == ReplySort::Thanks {
+                order.sort_by_key(|&i| std::cmp::Reverse(self.replies[i].thanks));
+            }
+            order
+        })
+    }
+
+    /// Where the selected reply is in [`Self::reply_order`], if shown
+    pub fn reply_position(&self) -> Option<usize> {
+        self.reply_order()
+            .iter()
+            .position(|&i| i == self.selected_reply)
+    }
+
+    /// Sort and filter applied, as shown in the replies pane title
+    pub fn reply_mode_label(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.reply_sort == ReplySort::Thanks {
+            parts.push("by thanks".to_string());
+        }
+        match &self.reply_filter {
+            ReplyFilter::All => {}
+            ReplyFilter::Op => parts.push("OP only".to_string()),
+            ReplyFilter::Mentioning(me) => parts.push(format!("mentioning @{}", me)),
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    /// Whether a reply follows the selected one in the list
+    pub fn has_next_reply(&self) -> bool {
+        let order = self.reply_order();
+        match order.iter().position(|&i| i == self.selected_reply) {
+            Some(position) => position + 1 < order.len(),
+            None => !order.is_empty(),
+        }
+    }
+
+    /// Whether a reply precedes the selected one in the list
+    pub fn has_previous_reply(&self) -> bool {
+        self.reply_position().is_some_and(|position| position > 0)
+    }
+
+    pub fn next_reply(&mut self) {
+        let order = self.reply_order();
+        if !order.is_empty() {
+            let position = match order.iter().position(|&i| i == self.selected_reply) {
+                Some(position) => (position + 1) % order.len(),
+                None => 0,
+            };
+            self.select_reply(order[position]);
+        }
+    }
+
+    pub fn previous_reply(&mut self) {
+        let order = self.reply_order();
+        if !order.is_empty() {
+            let position = match order.iter().position(|&i| i == self.selected_reply) {
+                Some(0) | None => order.len() - 1,
+                Some(position) => position - 1,
+            };
+            self.select_reply(order[position]);
+        }
+    }
+
+    /// Select the reply at `position` in the list, clamped to its end
+    pub fn select_reply_at(&mut self, position: usize) {
+        let order = self.reply_order();
+        if let Some(&index) = order.get(position).or(order.last()) {
+            self.select_reply(index);
+        }
+    }
+
+    /// Move the list selection `delta` replies down (or up), clamped
+    pub fn move_reply_selection(&mut self, delta: isize) {
+        let position = self.reply_position().unwrap_or(0);
+        self.select_reply_at(position.saturating_add_signed(delta));
+    }
+
+    /// Replies matching the search, by content or author, in list order
+    pub fn reply_matches(&self) -> Vec<usize> {
+        let Some(search) = &self.reply_search else {
+            return Vec::new();
+        };
+        let key = (self.reply_order_key(), Some(search.pattern.clone()));
+        self.reply_matches.get(key, || {
+            self.reply_order()
+                .into_iter()
+                .filter(|&i| {
+                    let reply = &self.replies[i];
+                    reply
+                        .member
+                        .as_ref()
+                        .is_some_and(|member| search.matches(&member.username))
+                        || reply_text(reply).is_some_and(|text| search.matches(&text))
+                })
+                .collect()
+        })
+    }
+
+    /// Select the next matching reply after the selected one (or at it,
+    /// with `here`), wrapping around; backwards with `!forward`. Returns
+    /// whether there was one.
+    pub fn select_reply_match(&mut self, forward: bool, here: bool) -> bool {
+        let Some(position) = self.reply_position().or(forward.then_some(0)) else {
+            return false;
+        };
+        // Matches are in list order, so each is looked for in the list
+        // once, from where the previous one was found
+        let order = self.reply_order();
+        let matches = self.reply_matches();
+        let mut positions = Vec::with_capacity(matches.len());
+        let mut rest = order.iter().enumerate();
+        for &index in &matches {
+            if let Some((p, _)) = rest.find(|&(_, &i)| i == index) {
+                positions.push((p, index));
+            }
+        }
+        let found = if forward {
+            positions
+                .iter()
+                .find(|&&(p, _)| p > position || (here && p == position))
+                .or(positions.first())
+        } else {
+            positions
+                .iter()
+                .rev()
+                .find(|&&(p, _)| p < position)
+                .or(positions.last())
+        };
+        match found {
+            Some(&(_, index)) => {
+                self.select_reply(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switch between floor order and most thanked first. The selected
+    /// reply stays selected wherever it moves to.
+    pub fn cycle_reply_sort(&mut self) {
+        self.reply_sort = self.reply_sort.next();
+    }
+
+    /// Change the filter, selecting the first shown reply if the selected
+    /// one is filtered out
+    pub fn cycle_reply_filter(&mut self, me: Option<&str>) {
+        self.reply_filter = self.reply_filter.next(me);
+        if self.reply_position().is_none() {
+            self.select_reply_at(0);
         }
     }
 
+    pub fn select_reply(&mut self, index: usize) {
+        if index < self.replies.len() {
+            self.selected_reply = index;
+            self.replies_list_state.select(Some(index));
+            self.detect_links();
+        }
+    }
+
+    /// Show or hide the replies pane. Scroll and selection stay where they
+    /// were; only loading a different topic resets them.
+    pub fn toggle_replies(&mut self) {
+        self.show_replies = !self.show_replies;
+        // Links come from the selected reply while replies are shown
+        self.detect_links();
+    }
+
     pub fn scroll_up(&mut self) {
         if self.scroll >= 3 {
             self.scroll -= 3;
@@ -445,9 +2038,35 @@ impl TopicState {
         self.scroll += 3;
     }
 
+    /// The reply focus mode shows, when replies are shown
+    pub fn focused_reply(&self) -> Option<&crate::api::Reply> {
+        self.replies
+            .get(self.selected_reply)
+            .filter(|_| self.show_replies)
+    }
+
+    pub fn focus_scroll(&self) -> usize {
+        match self.focus_scroll {
+            (index, lines) if index == self.selected_reply => lines,
+            _ => 0,
+        }
+    }
+
+    /// Scroll the reply shown in focus mode by `delta` lines
+    pub fn scroll_focus(&mut self, delta: isize) {
+        let lines = self.focus_scroll().saturating_add_signed(delta);
+        self.focus_scroll = (self.selected_reply, lines);
+    }
+
     pub fn reset_scroll(&mut self) {
         self.scroll = 0;
+        self.focus_scroll = (0, 0);
         self.selected_reply = 0;
+        self.reply_jump_stack.clear();
+        self.jump_input = None;
+        self.tag_input = None;
+        self.reply_search = None;
+        self.search_input = None;
         if self.replies.is_empty() {
             self.replies_list_state.select(None);
         } else {
@@ -455,6 +2074,102 @@ impl TopicState {
         }
     }
 
+    /// Copy of the open topic for [`TopicCache`]
+    pub fn snapshot(&self) -> Option<CachedTopic> {
+        Some(CachedTopic {
+            topic: self.current.clone()?,
+            stats: self.stats.clone(),
+            replies: self.replies.clone(),
+            replies_page: self.replies_page,
+            scroll: self.scroll,
+            selected_reply: self.selected_reply,
+            cached_at: self.cached_at,
+            first_unread: self.first_unread,
+        })
+    }
+
+    /// Show a cached topic where it was left
+    pub fn restore(&mut self, cached: CachedTopic) {
+        self.current = Some(cached.topic);
+        self.cached_at = cached.cached_at;
+        self.first_unread = cached.first_unread;
+        self.stats = cached.stats;
+        self.set_replies(cached.replies);
+        self.replies_page = cached.replies_page;
+        self.scroll = cached.scroll;
+        self.focus_scroll = (0, 0);
+        self.selected_reply = cached
+            .selected_reply
+            .min(self.replies.len().saturating_sub(1));
+        self.replies_list_state
+            .select((!self.replies.is_empty()).then_some(self.selected_reply));
+        self.reply_jump_stack.clear();
+        self.jump_input = None;
+        self.tag_input = None;
+        self.reply_search = None;
+        self.search_input = None;
+        self.detect_links();
+    }
+
+    /// Index of the reply that reply `index` answers, if it quotes one.
+    ///
+    /// `@user #N` points at floor N when that floor was written by `user`
+    /// (floors shift when replies are deleted); otherwise, like a bare
+    /// `@user`, it resolves to that member's latest earlier reply.
+    pub fn quoted_reply(&self, index: usize) -> Option<usize> {
+        let text = reply_text(self.replies.get(index)?)?;
+        let author = |i: usize| {
+            self.replies
+                .get(i)
+                .and_then(|r| r.member.as_ref())
+                .map(|m| m.username.as_str())
+        };
+
+        parse_reply_mentions(&text).into_iter().find_map(|mention| {
+            let by_floor = mention
+                .floor
+                .and_then(|floor| floor.checked_sub(1))
+                .filter(|&i| i < index && author(i) == Some(mention.username.as_str()));
+            by_floor.or_else(|| {
+                (0..index)
+                    .rev()
+                    .find(|&i| author(i) == Some(mention.username.as_str()))
+            })
+        })
+    }
+
+    /// Select the reply quoted by the selected one, remembering where we were
+    pub fn jump_to_quoted_reply(&mut self) -> Option<usize> {
+        let target = self.quoted_reply(self.selected_reply)?;
+        self.reply_jump_stack.push(self.selected_reply);
+        self.select_reply(target);
+        Some(target)
+    }
+
+    /// Return to the reply we jumped from
+    pub fn jump_back_reply(&mut self) -> Option<usize> {
+        let index = self.reply_jump_stack.pop()?;
+        self.select_reply(index);
+        Some(index)
+    }
+
+    /// Usernames in the open topic: the author, then repliers, most
+    /// recent first
+    pub fn participants(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let author = self
+            .current
+            .as_ref()
+            .and_then(|topic| topic.member.as_ref());
+        let repliers = self.replies.iter().rev().filter_map(|r| r.member.as_ref());
+        author
+            .into_iter()
+            .chain(repliers)
+            .filter(|member| seen.insert(member.username.as_str()))
+            .map(|member| member.username.clone())
+            .collect()
+    }
+
     pub fn find_current_topic_index(&self) -> Option<usize> {
         if let Some(current_topic) = &self.current {
             self.topics
@@ -465,525 +2180,1383 @@ impl TopicState {
         }
     }
 
-    pub fn detect_links(&mut self, width: usize) {
+    /// Number the links of the selected reply, or of the topic while
+    /// replies are hidden, as the renderer finds them
+    pub fn detect_links(&mut self) {
         self.detected_links.clear();
         self.link_shortcuts.clear();
-        self.parsed_content_cache = None;
 
-        // Determine which content to parse based on current view
-        let content_to_parse = if self.show_replies && !self.replies.is_empty() {
-            // Parse the selected reply
-            self.replies
-                .get(self.selected_reply)
-                .and_then(|reply| reply.content_rendered.as_ref().or(reply.content.as_ref()))
-                .map(|content| content.to_string())
-        } else if let Some(topic) = &self.current {
-            // Parse the topic content
-            topic
-                .content_rendered
+        let links = self.selected_links();
+
+        for (index, url) in links.iter().flat_map(|links| links.iter()).enumerate() {
+            let display_text = if text_width(url) > 50 {
+                format!("{}...", split_at_width(url, 47).0)
+            } else {
+                url.clone()
+            };
+            self.detected_links.push(DetectedLink {
+                url: url.clone(),
+                shortcut: Self::assign_shortcut(index),
+                display_text,
+            });
+        }
+
+        // Generate shortcuts for first 9 links (for backward compatibility)
+        for (i, link) in self.detected_links.iter().take(9).enumerate() {
+            self.link_shortcuts.push(format!("{}: {}", i + 1, link.url));
+        }
+    }
+
+    /// The links of the selected reply, or of the topic while replies are
+    /// hidden
+    pub fn selected_links(&self) -> Option<std::rc::Rc<[String]>> {
+        let content = if self.show_replies && !self.replies.is_empty() {
+            self.replies
+                .get(self.selected_reply)
+                .and_then(|reply| reply.content_rendered.as_ref().or(reply.content.as_ref()))
+        } else {
+            self.current
                 .as_ref()
-                .or(topic.content.as_ref())
-                .map(|content| content.to_string())
+                .and_then(|topic| topic.content_rendered.as_ref().or(topic.content.as_ref()))
+        };
+        content.map(|content| crate::render::links(content))
+    }
+
+    fn assign_shortcut(index: usize) -> String {
+        const HOME_ROW: &[char] = &['a', 'o', 'e', 'u', 'i', 'd', 'h', 't', 'n', 's'];
+
+        if index < HOME_ROW.len() {
+            HOME_ROW[index].to_string()
+        } else {
+            let first_idx = (index - HOME_ROW.len()) / HOME_ROW.len();
+            let second_idx = (index - HOME_ROW.len()) % HOME_ROW.len();
+            format!("{}{}", HOME_ROW[first_idx], HOME_ROW[second_idx])
+        }
+    }
+
+    // Link selection mode methods
+    pub fn enter_link_selection_mode(&mut self) {
+        self.link_input_state.is_active = true;
+        self.link_input_state.chosen = None;
+        self.link_input_state.current_input.clear();
+        self.link_input_state.last_key_time = None;
+        // Detect links with positions in the currently displayed content
+        self.detect_links();
+    }
+
+    /// Labels to show in front of the links while link mode is on, with
+    /// the titles of those that were unfurled
+    pub fn link_labels(&self, titles: &LinkTitles) -> Option<Vec<String>> {
+        self.link_input_state.is_active.then(|| {
+            self.detected_links
+                .iter()
+                .map(|link| titles.label(&link.shortcut, &link.url))
+                .collect()
+        })
+    }
+
+    pub fn exit_link_selection_mode(&mut self) {
+        self.link_input_state.is_active = false;
+        self.link_input_state.chosen = None;
+        self.link_input_state.current_input.clear();
+        self.link_input_state.last_key_time = None;
+    }
+
+    pub fn handle_link_mode_key(&mut self, ch: char) -> (String, bool, bool) {
+        let now = Instant::now();
+        let mut timeout_reset = false;
+        let mut valid_input = false;
+
+        // Check timeout
+        if let Some(last_time) = self.link_input_state.last_key_time {
+            if now.duration_since(last_time) > self.link_input_state.timeout_duration {
+                self.link_input_state.current_input.clear();
+                timeout_reset = true;
+            }
+        }
+
+        // Only accept home row letters
+        if "aoeuidhtns".contains(ch) {
+            self.link_input_state.current_input.push(ch);
+            valid_input = true;
+        }
+
+        self.link_input_state.last_key_time = Some(now);
+        (
+            self.link_input_state.current_input.clone(),
+            timeout_reset,
+            valid_input,
+        )
+    }
+
+    // Helper methods for phase 2 (to be implemented)
+    pub fn find_links_by_prefix(&self, prefix: &str) -> Vec<&DetectedLink> {
+        self.detected_links
+            .iter()
+            .filter(|link| link.shortcut.starts_with(prefix))
+            .collect()
+    }
+
+    #[allow(dead_code)] // Utility function not currently used, but kept for completeness
+    pub fn find_exact_link(&self, shortcut: &str) -> Option<&DetectedLink> {
+        self.detected_links
+            .iter()
+            .find(|link| link.shortcut == shortcut)
+    }
+
+    #[allow(dead_code)] // Not currently used, but kept for future use
+    pub fn get_link_by_shortcut(&self, shortcut: usize) -> Option<&String> {
+        if shortcut >= 1 && shortcut <= self.detected_links.len() {
+            // Temporary: return URL from new DetectedLink struct
+            Some(&self.detected_links[shortcut - 1].url)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NotificationState {
+    pub notifications: Vec<crate::api::Notification>,
+    pub selected: usize,
+    /// Newest notification ID already shown to the user
+    pub last_seen_id: Option<i64>,
+    /// Notifications found by the background poller since the list was last viewed
+    pub unread_count: usize,
+    /// Last page of notifications loaded
+    pub page: i32,
+    /// Notification `d` was pressed on once, to be deleted by pressing it again
+    pub delete_pending: Option<i64>,
+}
+
+impl NotificationState {
+    /// Record a page fetched by the background poller, returning unseen items.
+    ///
+    /// Without a baseline from the last session, the first poll only sets
+    /// it, so old notifications don't all pop up at startup.
+    pub fn register_polled<'a>(
+        &mut self,
+        polled: &'a [crate::api::Notification],
+    ) -> Vec<&'a crate::api::Notification> {
+        let newest = polled.iter().map(|n| n.id).max();
+        let Some(last_seen) = self.last_seen_id else {
+            self.last_seen_id = newest;
+            return Vec::new();
+        };
+
+        let fresh: Vec<_> = polled.iter().filter(|n| n.id > last_seen).collect();
+        self.last_seen_id = newest.map(|id| id.max(last_seen)).or(Some(last_seen));
+        self.unread_count += fresh.len();
+        fresh
+    }
+
+    /// Clear the badge after the list has been (re)loaded
+    pub fn mark_all_seen(&mut self) {
+        let newest = self.notifications.iter().map(|n| n.id).max();
+        self.last_seen_id = self.last_seen_id.max(newest);
+        self.unread_count = 0;
+    }
+
+    /// Add `page` below the loaded notifications, returning how many were
+    /// new. Notifications arriving meanwhile push older ones onto the next
+    /// page, so ones already shown can come back.
+    pub fn append_page(
+        &mut self,
+        page: i32,
+        notifications: Vec<crate::api::Notification>,
+    ) -> usize {
+        let known: HashSet<i64> = self.notifications.iter().map(|n| n.id).collect();
+        let before = self.notifications.len();
+        self.notifications
+            .extend(notifications.into_iter().filter(|n| !known.contains(&n.id)));
+        self.page = page;
+        self.notifications.len() - before
+    }
+
+    /// Show the first page again. Further pages loaded stay below it,
+    /// without what the first page now has.
+    pub fn replace_first_page(&mut self, notifications: Vec<crate::api::Notification>) {
+        let oldest = notifications.iter().map(|n| n.id).min();
+        let mut kept = std::mem::replace(&mut self.notifications, notifications);
+        if self.page > 1 {
+            // Newer ones than the page ends with are on it or were deleted
+            kept.retain(|n| oldest.is_some_and(|oldest| n.id < oldest));
+            self.notifications.extend(kept);
+        } else {
+            self.page = 1;
+        }
+    }
+
+    /// Drop a deleted notification, keeping the selection in the list
+    pub fn remove(&mut self, id: i64) {
+        self.notifications.retain(|n| n.id != id);
+        self.selected = self
+            .selected
+            .min(self.notifications.len().saturating_sub(1));
+    }
+
+    pub fn next(&mut self) {
+        if !self.notifications.is_empty() {
+            self.selected = (self.selected + 1) % self.notifications.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.notifications.is_empty() {
+            self.selected = if self.selected == 0 {
+                self.notifications.len() - 1
+            } else {
+                self.selected - 1
+            };
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FavoritesState {
+    pub topics: Vec<crate::api::Topic>,
+    pub selected: usize,
+}
+
+impl FavoritesState {
+    pub fn set_topics(&mut self, topics: Vec<crate::api::Topic>) {
+        self.topics = topics;
+        self.selected = 0;
+    }
+
+    /// Record a successful toggle; unfavorited topics leave the list
+    pub fn set_favorite(&mut self, topic_id: i64, favorite: bool) {
+        if !favorite {
+            self.topics.retain(|topic| topic.id != topic_id);
+            self.selected = self.selected.min(self.topics.len().saturating_sub(1));
+        }
+    }
+
+    pub fn next(&mut self) {
+        if !self.topics.is_empty() {
+            self.selected = (self.selected + 1) % self.topics.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.topics.is_empty() {
+            self.selected = if self.selected == 0 {
+                self.topics.len() - 1
+            } else {
+                self.selected - 1
+            };
+        }
+    }
+}
+
+/// The bookmarks view: cursor and the tag bookmarks are filtered by
+#[derive(Debug, Default)]
+pub struct BookmarksState {
+    pub selected: usize,
+    pub tag: Option<String>,
+}
+
+impl BookmarksState {
+    /// Filter by the tag after the current one in `tags`, then show all
+    /// bookmarks again after the last
+    pub fn cycle_tag(&mut self, tags: &[String]) {
+        let next = match &self.tag {
+            None => 0,
+            Some(tag) => tags.iter().position(|t| t == tag).map_or(0, |i| i + 1),
+        };
+        self.tag = tags.get(next).cloned();
+        self.selected = 0;
+    }
+}
+
+/// Another member's profile and their recent topics
+#[derive(Debug, Default)]
+pub struct MemberState {
+    pub member: Option<crate::api::Member>,
+    pub topics: Vec<crate::api::Topic>,
+    pub replies: Vec<crate::api::MemberReply>,
+    /// Which of the two lists is shown
+    pub showing: MemberList,
+    /// Selection in the list shown
+    pub selected: usize,
+}
+
+/// The lists of what a member posted lately
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum MemberList {
+    #[default]
+    Topics,
+    Replies,
+}
+
+impl MemberState {
+    /// Length of the list shown
+    pub fn shown_len(&self) -> usize {
+        match self.showing {
+            MemberList::Topics => self.topics.len(),
+            MemberList::Replies => self.replies.len(),
+        }
+    }
+
+    /// The topic of the selected topic or reply
+    pub fn selected_topic_id(&self) -> Option<i64> {
+        match self.showing {
+            MemberList::Topics => self.topics.get(self.selected).map(|topic| topic.id),
+            MemberList::Replies => self.replies.get(self.selected).map(|reply| reply.topic_id),
+        }
+    }
+
+    /// Show the other list, from its top
+    pub fn toggle_list(&mut self) {
+        self.showing = match self.showing {
+            MemberList::Topics => MemberList::Replies,
+            MemberList::Replies => MemberList::Topics,
+        };
+        self.selected = 0;
+    }
+
+    pub fn next(&mut self) {
+        let len = self.shown_len();
+        if len > 0 {
+            self.selected = (self.selected + 1) % len;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.shown_len();
+        if len > 0 {
+            self.selected = if self.selected == 0 {
+                len - 1
+            } else {
+                self.selected - 1
+            };
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NodeState {
+    pub favorite_nodes: Vec<(String, String)>,
+    pub all_nodes: Vec<(String, String)>,
+    pub original_favorite_nodes: Vec<(String, String)>,
+    /// Keys that switch to a node from the topic list, as `(key, node)`
+    pub quick_nodes: Vec<(char, String)>,
+    /// Node details fetched so far, by name
+    pub node_info: HashMap<String, crate::api::Node>,
+    /// Whether the node details popup is open over the topic list
+    pub show_info: bool,
+    pub selected: usize,
+    pub current_node: String,
+    pub page: i32,
+    /// Node name typed in completion mode
+    pub prompt: Minibuffer,
+    pub is_completion_mode: bool,
+}
+
+impl NodeState {
+    pub fn new(config: &crate::config::Config) -> Self {
+        let favorite_nodes = config.favorite_nodes();
+        let current_node = favorite_nodes
+            .first()
+            .map_or_else(|| "python".to_string(), |(name, _)| name.clone());
+
+        let all_nodes = crate::nodes::get_all_nodes().to_vec();
+
+        Self {
+            favorite_nodes: favorite_nodes.clone(),
+            all_nodes,
+            original_favorite_nodes: favorite_nodes,
+            quick_nodes: config.quick_nodes(),
+            node_info: HashMap::new(),
+            show_info: false,
+            selected: 0,
+            current_node,
+            page: 1,
+            prompt: Minibuffer::new("> ")
+                .with_history("node")
+                .completed_by(node_name_completions),
+            is_completion_mode: false,
+        }
+    }
+
+    pub fn next_node(&mut self) {
+        if !self.favorite_nodes.is_empty() {
+            self.selected = (self.selected + 1) % self.favorite_nodes.len();
+        }
+    }
+
+    pub fn previous_node(&mut self) {
+        if !self.favorite_nodes.is_empty() {
+            self.selected = if self.selected == 0 {
+                self.favorite_nodes.len() - 1
+            } else {
+                self.selected - 1
+            };
+        }
+    }
+
+    /// The node picked in the menu, or typed in completion mode
+    pub fn selected_node(&self) -> Option<String> {
+        if let Some((node_name, _)) = self.favorite_nodes.get(self.selected) {
+            Some(node_name.clone())
+        } else if self.is_completion_mode {
+            let node_name = self.prompt.input().trim();
+            (!node_name.is_empty()).then(|| node_name.to_string())
         } else {
             None
+        }
+    }
+
+    /// Details of the node whose topics are listed, once fetched
+    pub fn current_info(&self) -> Option<&crate::api::Node> {
+        self.node_info.get(&self.current_node)
+    }
+
+    /// Node bound to `key` in the topic list header
+    pub fn quick_node(&self, key: char) -> Option<&str> {
+        self.quick_nodes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, node)| node.as_str())
+    }
+
+    pub fn switch_node(&mut self, node: &str) {
+        self.current_node = node.to_string();
+        self.page = 1;
+    }
+
+    pub fn toggle_completion_mode(&mut self) {
+        self.is_completion_mode = !self.is_completion_mode;
+        if self.is_completion_mode {
+            self.update_suggestions();
+        } else {
+            self.favorite_nodes = self.original_favorite_nodes.clone();
+            self.selected = 0;
+        }
+    }
+
+    pub fn update_suggestions(&mut self) {
+        let input = self.prompt.input().trim();
+        if input.is_empty() {
+            self.favorite_nodes = self.all_nodes.iter().take(20).cloned().collect();
+        } else {
+            self.favorite_nodes = fuzzy_rank(self.all_nodes.iter(), input, 20, |(name, title)| {
+                vec![name.as_str(), title.as_str()]
+            })
+            .into_iter()
+            .cloned()
+            .collect();
+        }
+        self.selected = 0;
+    }
+
+    /// Start typing a node name
+    pub fn start_completion(&mut self) {
+        self.prompt.clear();
+        self.is_completion_mode = true;
+    }
+
+    pub fn reset_selection(&mut self) {
+        self.prompt.clear();
+        self.is_completion_mode = false;
+    }
+}
+
+/// Node names starting with `input`, for TAB in the node prompt
+fn node_name_completions(input: &str) -> Vec<String> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+    crate::nodes::get_all_nodes()
+        .iter()
+        .map(|(name, _)| name)
+        .filter(|name| name.starts_with(input))
+        .cloned()
+        .collect()
+}
+
+/// Topic pages of several nodes as one list, newest first, each topic
+/// labelled with the node it came from. `pages` holds `(name, title,
+/// topics)` per node; a topic listed twice is kept once.
+pub fn merge_node_topics(
+    pages: Vec<(String, String, Vec<crate::api::Topic>)>,
+) -> Vec<crate::api::Topic> {
+    let mut seen = HashSet::new();
+    let mut merged: Vec<crate::api::Topic> = pages
+        .into_iter()
+        .flat_map(|(name, title, topics)| {
+            topics.into_iter().map(move |mut topic| {
+                if topic.node.is_none() {
+                    topic.node = Some(crate::api::Node {
+                        name: name.clone(),
+                        title: title.clone(),
+                        ..Default::default()
+                    });
+                }
+                topic
+            })
+        })
+        .filter(|topic| seen.insert(topic.id))
+        .collect();
+    merged.sort_by_key(|topic| std::cmp::Reverse(topic.created));
+    merged
+}
+
+/// Visited topics matching the search prompt in the history view
+#[derive(Debug)]
+pub struct VisitedState {
+    pub prompt: Minibuffer,
+    /// Indices into `VisitedTopics::entries`, best match first
+    pub matches: Vec<usize>,
+    pub selected: usize,
+}
+
+impl Default for VisitedState {
+    fn default() -> Self {
+        Self {
+            prompt: Minibuffer::new("Search: "),
+            matches: Vec::new(),
+            selected: 0,
+        }
+    }
+}
+
+impl VisitedState {
+    /// Rank `entries` against the prompt; an empty prompt lists them all,
+    /// newest first
+    pub fn update_matches(&mut self, entries: &[crate::read_tracker::VisitedTopic]) {
+        let input = self.prompt.input();
+        self.matches = if input.is_empty() {
+            (0..entries.len()).collect()
+        } else {
+            let items = entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| (i, entry, entry.id.to_string()));
+            fuzzy_rank(items, input, entries.len(), |(_, entry, id)| {
+                vec![&entry.title, &entry.node, id]
+            })
+            .into_iter()
+            .map(|(i, _, _)| i)
+            .collect()
         };
+        self.selected = 0;
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
 
-        if let Some(content) = content_to_parse {
-            // Process HTML content: extract images and replace with markdown format
-            let processed_content = self.process_images_in_html(&content);
+    /// Index into the entries of the match under the cursor
+    pub fn selected_entry(&self) -> Option<usize> {
+        self.matches.get(self.selected).copied()
+    }
+}
+
+/// Up to `limit` items whose keys fuzzy-match `input`, best match first.
+///
+/// Shared by node and @mention completion so both rank the same way.
+pub fn fuzzy_rank<T>(
+    items: impl IntoIterator<Item = T>,
+    input: &str,
+    limit: usize,
+    keys: impl Fn(&T) -> Vec<&str>,
+) -> Vec<T> {
+    use fuzzy_matcher::skim::SkimMatcherV2;
+    use fuzzy_matcher::FuzzyMatcher;
+
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(T, i64)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let score = keys(&item)
+                .into_iter()
+                .filter_map(|key| matcher.fuzzy_match(key, input))
+                .max()
+                .unwrap_or(0);
+            (score > 0).then_some((item, score))
+        })
+        .collect();
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(item, _)| item)
+        .collect()
+}
+
+/// A node open in a topic list tab, as it was when another tab was shown
+#[derive(Debug, Clone, Default)]
+pub struct NodeTab {
+    pub node: String,
+    pub page: i32,
+    pub topics: Vec<crate::api::Topic>,
+    pub selected: usize,
+    pub topics_cached_at: Option<i64>,
+    pub reply_deltas: HashMap<i64, i64>,
+}
+
+/// Nodes open as tabs over the topic list. The active tab's entry is stale;
+/// its live state is in `NodeState` and `TopicState` until stashed.
+#[derive(Debug, Default)]
+pub struct TabsState {
+    pub tabs: Vec<NodeTab>,
+    pub active: usize,
+}
+
+impl TabsState {
+    /// Whether more than one tab is open, so the tab bar is worth showing
+    pub fn is_tabbed(&self) -> bool {
+        self.tabs.len() > 1
+    }
+
+    /// Open a copy of `current` in a new tab right after the active one
+    pub fn open(&mut self, current: NodeTab) {
+        if self.tabs.is_empty() {
+            self.tabs.push(current.clone());
+            self.active = 0;
+        } else {
+            self.tabs[self.active] = current.clone();
+        }
+        self.active += 1;
+        self.tabs.insert(self.active, current);
+    }
+
+    /// Stash `current` and move `delta` tabs along, wrapping around.
+    /// Returns the tab to show, or `None` with a single tab.
+    pub fn switch(&mut self, current: NodeTab, delta: isize) -> Option<NodeTab> {
+        if !self.is_tabbed() {
+            return None;
+        }
+        self.tabs[self.active] = current;
+        let len = self.tabs.len() as isize;
+        self.active = (self.active as isize + delta).rem_euclid(len) as usize;
+        Some(self.tabs[self.active].clone())
+    }
+
+    /// Close the active tab, returning the neighbour to show instead
+    pub fn close(&mut self) -> Option<NodeTab> {
+        if !self.is_tabbed() {
+            return None;
+        }
+        self.tabs.remove(self.active);
+        self.active = self.active.min(self.tabs.len() - 1);
+        Some(self.tabs[self.active].clone())
+    }
+
+    /// Node names for the tab bar, with `current` for the active tab
+    pub fn labels(&self, current: &str) -> Vec<String> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| {
+                if i == self.active {
+                    current.to_string()
+                } else {
+                    tab.node.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// The first-run token prompt and the token manager
+#[derive(Debug)]
+pub struct TokenState {
+    pub prompt: Minibuffer,
+    /// Named tokens, one per account
+    pub store: TokenStore,
+    pub selected: usize,
+    /// What `/token` said about each token, by name, once checked
+    pub info: HashMap<String, Result<TokenInfo, String>>,
+    /// Adding a token: its name once given, and the prompt being answered
+    pub adding: Option<(Option<String>, Minibuffer)>,
+}
+
+impl Default for TokenState {
+    fn default() -> Self {
+        Self {
+            prompt: Minibuffer::new("").validated_by(|input| {
+                if input.trim().is_empty() {
+                    Err("Token cannot be empty".to_string())
+                } else {
+                    Ok(())
+                }
+            }),
+            store: TokenStore::default(),
+            selected: 0,
+            info: HashMap::new(),
+            adding: None,
+        }
+    }
+}
+
+impl TokenState {
+    /// Ask for the name of a token to add, then for the token
+    pub fn start_adding(&mut self) {
+        self.adding = Some((None, Minibuffer::new("Name for the new token: ")));
+    }
+
+    /// Name of the highlighted token
+    pub fn selected_name(&self) -> Option<String> {
+        self.store
+            .tokens()
+            .get(self.selected)
+            .map(|token| token.name.clone())
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        use anyhow::Context;
+        let config_dir = crate::api::V2exClient::config_dir()?;
+        let token_path = config_dir.join("token.txt");
+        std::fs::write(&token_path, self.prompt.input().trim())
+            .with_context(|| format!("Failed to write token to {:?}", token_path))?;
+        Ok(())
+    }
+}
+
+/// Multi-line reply composer buffer
+///
+/// The text is kept as one `String` per line; the cursor is tracked as
+/// (row, column) where the column counts characters, not bytes.
+#[derive(Debug, Clone)]
+pub struct ReplyInputState {
+    pub topic_id: Option<i64>,
+    pub lines: Vec<String>,
+    pub cursor_row: usize,
+    /// Cursor column in grapheme clusters, not bytes or display columns
+    pub cursor_col: usize,
+    pub external_edit_requested: bool,
+    /// Why the last attempt to send this draft failed
+    pub last_error: Option<String>,
+    /// Members of the topic being replied to, offered after `@`
+    pub mention_candidates: Vec<String>,
+    /// Completion popup for the `@handle` before the cursor
+    pub mention: Option<MentionCompletion>,
+}
+
+/// Usernames matching a partially typed `@handle`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MentionCompletion {
+    /// Column of the `@`
+    pub start_col: usize,
+    pub suggestions: Vec<String>,
+    pub selected: usize,
+}
+
+impl Default for ReplyInputState {
+    fn default() -> Self {
+        Self {
+            topic_id: None,
+            lines: vec![String::new()],
+            cursor_row: 0,
+            cursor_col: 0,
+            external_edit_requested: false,
+            last_error: None,
+            mention_candidates: Vec::new(),
+            mention: None,
+        }
+    }
+}
+
+impl ReplyInputState {
+    /// Start composing a reply to the given topic, discarding any old buffer
+    pub fn start(&mut self, topic_id: i64) {
+        *self = Self {
+            topic_id: Some(topic_id),
+            ..Self::default()
+        };
+    }
+
+    pub fn clear(&mut self) {
+        let topic_id = self.topic_id;
+        *self = Self {
+            topic_id,
+            ..Self::default()
+        };
+    }
+
+    /// Full buffer content joined with newlines
+    pub fn content(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Replace the buffer, placing the cursor at the end of the text
+    pub fn set_content(&mut self, content: &str) {
+        self.lines = content.lines().map(|line| line.to_string()).collect();
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.cursor_row = self.lines.len() - 1;
+        self.cursor_col = grapheme_count(&self.lines[self.cursor_row]);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.trim().is_empty())
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines.get(row).map_or(0, |line| grapheme_count(line))
+    }
+
+    fn byte_pos(&self, row: usize, col: usize) -> usize {
+        byte_offset(&self.lines[row], col)
+    }
+
+    pub fn insert_char(&mut self, ch: char) {
+        let byte_pos = self.byte_pos(self.cursor_row, self.cursor_col);
+        let line = &mut self.lines[self.cursor_row];
+        line.insert(byte_pos, ch);
+        // A combining mark or joiner extends the grapheme before it
+        self.cursor_col = grapheme_count(&line[..byte_pos + ch.len_utf8()]);
+    }
+
+    pub fn insert_newline(&mut self) {
+        let byte_pos = self.byte_pos(self.cursor_row, self.cursor_col);
+        let rest = self.lines[self.cursor_row].split_off(byte_pos);
+        self.cursor_row += 1;
+        self.cursor_col = 0;
+        self.lines.insert(self.cursor_row, rest);
+    }
+
+    /// Delete the character before the cursor, joining lines at column 0
+    pub fn delete_char(&mut self) {
+        if self.cursor_col > 0 {
+            let start = self.byte_pos(self.cursor_row, self.cursor_col - 1);
+            let end = self.byte_pos(self.cursor_row, self.cursor_col);
+            self.lines[self.cursor_row].drain(start..end);
+            self.cursor_col -= 1;
+        } else if self.cursor_row > 0 {
+            let line = self.lines.remove(self.cursor_row);
+            self.cursor_row -= 1;
+            self.cursor_col = self.line_len(self.cursor_row);
+            self.lines[self.cursor_row].push_str(&line);
+        }
+    }
 
-            // Convert HTML to text using html2text with the actual terminal width
-            // This ensures link positions match the rendered text
-            let converted_text = html2text::from_read(processed_content.as_bytes(), width);
+    pub fn move_cursor_left(&mut self) {
+        if self.cursor_col > 0 {
+            self.cursor_col -= 1;
+        } else if self.cursor_row > 0 {
+            self.cursor_row -= 1;
+            self.cursor_col = self.line_len(self.cursor_row);
+        }
+    }
 
-            // Store converted text for potential use in rendering
-            self.parsed_content_cache = Some(converted_text.clone());
+    pub fn move_cursor_right(&mut self) {
+        if self.cursor_col < self.line_len(self.cursor_row) {
+            self.cursor_col += 1;
+        } else if self.cursor_row + 1 < self.lines.len() {
+            self.cursor_row += 1;
+            self.cursor_col = 0;
+        }
+    }
 
-            // Extract links from converted text
-            self.extract_links_from_converted_text(&converted_text);
+    pub fn move_cursor_up(&mut self) {
+        if self.cursor_row > 0 {
+            self.cursor_row -= 1;
+            self.cursor_col = self.cursor_col.min(self.line_len(self.cursor_row));
         }
+    }
 
-        // Generate shortcuts for first 9 links (for backward compatibility)
-        for (i, link) in self.detected_links.iter().take(9).enumerate() {
-            self.link_shortcuts.push(format!("{}: {}", i + 1, link.url));
+    pub fn move_cursor_down(&mut self) {
+        if self.cursor_row + 1 < self.lines.len() {
+            self.cursor_row += 1;
+            self.cursor_col = self.cursor_col.min(self.line_len(self.cursor_row));
         }
     }
 
-    /// Process HTML content to extract images and replace them with markdown placeholders
-    /// Format: ![Image: filename](url)
-    fn process_images_in_html(&self, html: &str) -> String {
-        use regex::Regex;
+    pub fn move_line_start(&mut self) {
+        self.cursor_col = 0;
+    }
+
+    pub fn move_line_end(&mut self) {
+        self.cursor_col = self.line_len(self.cursor_row);
+    }
 
-        // Regex to match <img> tags with src attribute
-        // Captures the src URL
-        let img_regex = match Regex::new(r#"<img[^>]+src=["']([^"']+)["'][^>]*>"#) {
-            Ok(re) => re,
-            Err(_) => return html.to_string(),
+    /// Column of the `@` and the handle typed after it, when the cursor
+    /// ends an `@handle` that isn't part of an e-mail address
+    fn mention_query(&self) -> Option<(usize, String)> {
+        let is_name_char = |g: &str| {
+            g.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
         };
+        let before: Vec<&str> = graphemes(&self.lines[self.cursor_row])
+            .take(self.cursor_col)
+            .collect();
+        let name_start = before
+            .iter()
+            .rposition(|g| !is_name_char(g))
+            .map_or(0, |i| i + 1);
+        let at = name_start.checked_sub(1)?;
+        if before[at] != "@" || at.checked_sub(1).is_some_and(|i| is_name_char(before[i])) {
+            return None;
+        }
+        Some((at, before[name_start..].concat()))
+    }
 
-        img_regex
-            .replace_all(html, |caps: &regex::Captures| {
-                let url = &caps[1];
-                format!("![Image]({})", url)
+    /// Refresh the @mention popup for the text before the cursor
+    pub fn update_mention(&mut self) {
+        self.mention = self.mention_query().and_then(|(start_col, query)| {
+            let suggestions: Vec<String> = if query.is_empty() {
+                self.mention_candidates.iter().take(10).cloned().collect()
+            } else {
+                fuzzy_rank(self.mention_candidates.iter(), &query, 10, |name| {
+                    vec![name.as_str()]
+                })
+                .into_iter()
+                .cloned()
+                .collect()
+            };
+            (!suggestions.is_empty()).then_some(MentionCompletion {
+                start_col,
+                suggestions,
+                selected: 0,
             })
-            .to_string()
+        });
     }
 
-    /// Extract filename from URL for display purposes
-    #[allow(dead_code)] // Kept for potential future use
-    fn extract_filename_from_url(&self, url: &str) -> String {
-        // Remove query parameters and fragment
-        let url_without_query = url.split('?').next().unwrap_or(url);
-        let url_without_fragment = url_without_query
-            .split('#')
-            .next()
-            .unwrap_or(url_without_query);
-
-        // Get the last path component
-        url_without_fragment
-            .split('/')
-            .next_back()
-            .unwrap_or("image")
-            .to_string()
+    pub fn next_mention(&mut self) {
+        if let Some(mention) = self.mention.as_mut() {
+            mention.selected = (mention.selected + 1) % mention.suggestions.len();
+        }
     }
 
-    fn assign_shortcut(index: usize) -> String {
-        const HOME_ROW: &[char] = &['a', 'o', 'e', 'u', 'i', 'd', 'h', 't', 'n', 's'];
-
-        if index < HOME_ROW.len() {
-            HOME_ROW[index].to_string()
-        } else {
-            let first_idx = (index - HOME_ROW.len()) / HOME_ROW.len();
-            let second_idx = (index - HOME_ROW.len()) % HOME_ROW.len();
-            format!("{}{}", HOME_ROW[first_idx], HOME_ROW[second_idx])
+    pub fn previous_mention(&mut self) {
+        if let Some(mention) = self.mention.as_mut() {
+            mention.selected =
+                (mention.selected + mention.suggestions.len() - 1) % mention.suggestions.len();
         }
     }
 
-    fn extract_links_from_converted_text(&mut self, converted_text: &str) {
-        use regex::Regex;
-
-        // Pattern to match markdown-style images: ![Image](url)
-        // We extract the URL from within the parentheses
-        let image_pattern = r#"!\[Image\]\((https?://[^\s\)]+)\)"#;
-        let image_re = match Regex::new(image_pattern) {
-            Ok(re) => re,
-            Err(_) => return,
+    /// Replace the typed `@handle` with the selected username
+    pub fn accept_mention(&mut self) {
+        let Some(mention) = self.mention.take() else {
+            return;
         };
-
-        // Pattern to match plain URLs (but not inside markdown image syntax)
-        // This excludes URLs that are already captured by the image pattern
-        let url_pattern = "https?://[^\\s<>\"'\\)\\]]+";
-        let url_re = match Regex::new(url_pattern) {
-            Ok(regex) => regex,
-            Err(_) => return, // If regex fails, skip link detection
+        let Some(name) = mention.suggestions.get(mention.selected) else {
+            return;
         };
+        let start = self.byte_pos(self.cursor_row, mention.start_col + 1);
+        let end = self.byte_pos(self.cursor_row, self.cursor_col);
+        self.lines[self.cursor_row].replace_range(start..end, &format!("{} ", name));
+        self.cursor_col = mention.start_col + 1 + grapheme_count(name) + 1;
+    }
+}
 
-        // Clear existing links
-        self.detected_links.clear();
-
-        // Collect all matches with their positions
-        // First, find image patterns (these take precedence)
-        let mut all_matches: Vec<(String, std::ops::Range<usize>, bool)> = Vec::new();
+/// Replies that have been sent but not yet acknowledged, keyed by topic.
+///
+/// Each entry keeps the composer state as it was when sent, so a failed
+/// post can hand the draft back exactly as the user left it.
+#[derive(Debug, Default)]
+pub struct ReplySubmissions {
+    in_flight: HashMap<i64, ReplyInputState>,
+}
 
-        for cap in image_re.captures_iter(converted_text) {
-            if let Some(url_match) = cap.get(1) {
-                if let Some(full_match) = cap.get(0) {
-                    let url = url_match.as_str().to_string();
-                    let range = full_match.start()..full_match.end();
-                    all_matches.push((url, range, true)); // true = is image
-                }
+impl ReplySubmissions {
+    /// Mark a topic as having a reply in flight; false if one already is
+    pub fn begin(&mut self, topic_id: i64, draft: ReplyInputState) -> bool {
+        match self.in_flight.entry(topic_id) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(draft);
+                true
             }
         }
+    }
 
-        // Then find plain URLs, but skip those inside image patterns
-        for cap in url_re.captures_iter(converted_text) {
-            if let Some(matched) = cap.get(0) {
-                let url = matched.as_str().to_string();
-                let range = matched.start()..matched.end();
+    /// Finish a submission, returning the draft that was sent
+    pub fn finish(&mut self, topic_id: i64) -> Option<ReplyInputState> {
+        self.in_flight.remove(&topic_id)
+    }
 
-                // Check if this URL is already part of an image pattern
-                let is_part_of_image = all_matches.iter().any(|(_, img_range, _)| {
-                    range.start >= img_range.start && range.end <= img_range.end
-                });
+    pub fn is_in_flight(&self, topic_id: i64) -> bool {
+        self.in_flight.contains_key(&topic_id)
+    }
+}
 
-                if !is_part_of_image {
-                    all_matches.push((url, range, false)); // false = plain URL
-                }
-            }
-        }
+/// Something copied to the clipboard, kept so it can be copied again
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardEntry {
+    /// What was copied, e.g. "Reply #3 by alice"
+    pub label: String,
+    pub text: String,
+}
 
-        // Sort all matches by position to maintain original order
-        all_matches.sort_by_key(|(_, range, _)| range.start);
+/// Recently copied snippets, newest first, shown in the `C-y` overlay
+#[derive(Debug, Default)]
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardEntry>,
+    pub selected: usize,
+    pub visible: bool,
+}
 
-        // Create DetectedLink objects from all matches
-        for (index, (url, range, _is_image)) in all_matches.into_iter().enumerate() {
-            let shortcut = Self::assign_shortcut(index);
-            let display_text = if url.len() > 50 {
-                format!("{}...", &url[..47])
-            } else {
-                url.clone()
-            };
+impl ClipboardHistory {
+    const CAPACITY: usize = 20;
 
-            self.detected_links.push(DetectedLink {
-                url,
-                shortcut,
-                text_range: range,
-                display_text,
-            });
-        }
+    /// Remember a copy; copying the same text again moves it to the top
+    pub fn push(&mut self, label: String, text: String) {
+        self.entries.retain(|entry| entry.text != text);
+        self.entries.push_front(ClipboardEntry { label, text });
+        self.entries.truncate(Self::CAPACITY);
+        self.selected = 0;
     }
 
-    // Link selection mode methods
-    pub fn enter_link_selection_mode(&mut self, width: usize) {
-        self.link_input_state.is_active = true;
-        self.link_input_state.current_input.clear();
-        self.link_input_state.last_key_time = None;
-        // Detect links with positions in the currently displayed content
-        self.detect_links(width);
+    pub fn entries(&self) -> &VecDeque<ClipboardEntry> {
+        &self.entries
     }
 
-    pub fn exit_link_selection_mode(&mut self) {
-        self.link_input_state.is_active = false;
-        self.link_input_state.current_input.clear();
-        self.link_input_state.last_key_time = None;
+    pub fn selected_entry(&self) -> Option<&ClipboardEntry> {
+        self.entries.get(self.selected)
     }
 
-    pub fn handle_link_mode_key(&mut self, ch: char) -> (String, bool, bool) {
-        let now = Instant::now();
-        let mut timeout_reset = false;
-        let mut valid_input = false;
-
-        // Check timeout
-        if let Some(last_time) = self.link_input_state.last_key_time {
-            if now.duration_since(last_time) > self.link_input_state.timeout_duration {
-                self.link_input_state.current_input.clear();
-                timeout_reset = true;
-            }
-        }
-
-        // Only accept home row letters
-        if "aoeuidhtns".contains(ch) {
-            self.link_input_state.current_input.push(ch);
-            valid_input = true;
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
         }
-
-        self.link_input_state.last_key_time = Some(now);
-        (
-            self.link_input_state.current_input.clone(),
-            timeout_reset,
-            valid_input,
-        )
-    }
-
-    // Helper methods for phase 2 (to be implemented)
-    pub fn find_links_by_prefix(&self, prefix: &str) -> Vec<&DetectedLink> {
-        self.detected_links
-            .iter()
-            .filter(|link| link.shortcut.starts_with(prefix))
-            .collect()
     }
 
-    #[allow(dead_code)] // Utility function not currently used, but kept for completeness
-    pub fn find_exact_link(&self, shortcut: &str) -> Option<&DetectedLink> {
-        self.detected_links
-            .iter()
-            .find(|link| link.shortcut == shortcut)
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
     }
 
-    #[allow(dead_code)] // Not currently used, but kept for future use
-    pub fn get_link_by_shortcut(&self, shortcut: usize) -> Option<&String> {
-        if shortcut >= 1 && shortcut <= self.detected_links.len() {
-            // Temporary: return URL from new DetectedLink struct
-            Some(&self.detected_links[shortcut - 1].url)
-        } else {
-            None
-        }
+    pub fn remove_selected(&mut self) {
+        self.entries.remove(self.selected);
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
     }
 }
 
+/// The `C` overlay offering the code blocks of a post to copy, numbered
+/// so the first nine are one key away
 #[derive(Debug, Default)]
-pub struct NotificationState {
-    pub notifications: Vec<crate::api::Notification>,
+pub struct CodePicker {
+    /// What the blocks came from, such as `reply #3`
+    pub label: String,
+    pub blocks: Vec<String>,
     pub selected: usize,
+    pub visible: bool,
 }
 
-impl NotificationState {
+impl CodePicker {
+    pub fn open(&mut self, label: String, blocks: Vec<String>) {
+        self.label = label;
+        self.blocks = blocks;
+        self.selected = 0;
+        self.visible = true;
+    }
+
     pub fn next(&mut self) {
-        if !self.notifications.is_empty() {
-            self.selected = (self.selected + 1) % self.notifications.len();
+        if self.selected + 1 < self.blocks.len() {
+            self.selected += 1;
         }
     }
 
     pub fn previous(&mut self) {
-        if !self.notifications.is_empty() {
-            self.selected = if self.selected == 0 {
-                self.notifications.len() - 1
-            } else {
-                self.selected - 1
-            };
-        }
+        self.selected = self.selected.saturating_sub(1);
     }
 }
 
+/// The `H` overlay listing the view history, newest at the top
 #[derive(Debug, Default)]
-pub struct NodeState {
-    pub favorite_nodes: Vec<(String, String)>,
-    pub all_nodes: Vec<(String, String)>,
-    pub original_favorite_nodes: Vec<(String, String)>,
+pub struct HistoryBrowser {
+    pub visible: bool,
+    /// Index into the history entries
     pub selected: usize,
-    pub current_node: String,
-    pub page: i32,
-    pub completion_input: String,
-    pub completion_cursor: usize,
-    pub is_completion_mode: bool,
 }
 
-impl NodeState {
-    pub fn new() -> Self {
-        let favorite_nodes = vec![
-            ("python".to_string(), "Python".to_string()),
-            ("programmer".to_string(), "程序员".to_string()),
-            ("share".to_string(), "分享发现".to_string()),
-            ("create".to_string(), "分享创造".to_string()),
-            ("jobs".to_string(), "酷工作".to_string()),
-            ("go".to_string(), "Go 编程语言".to_string()),
-            ("rust".to_string(), "Rust 编程语言".to_string()),
-            ("javascript".to_string(), "JavaScript".to_string()),
-            ("linux".to_string(), "Linux".to_string()),
-        ];
-
-        let all_nodes = crate::nodes::get_all_nodes().to_vec();
+impl HistoryBrowser {
+    pub fn open(&mut self, history: &ViewHistory) {
+        self.visible = true;
+        self.selected = history.position();
+    }
 
-        Self {
-            favorite_nodes: favorite_nodes.clone(),
-            all_nodes,
-            original_favorite_nodes: favorite_nodes,
-            selected: 0,
-            current_node: "python".to_string(),
-            page: 1,
-            completion_input: String::new(),
-            completion_cursor: 0,
-            is_completion_mode: false,
-        }
+    /// Towards older entries, i.e. down the list
+    pub fn older(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
     }
 
-    pub fn next_node(&mut self) {
-        if !self.favorite_nodes.is_empty() {
-            self.selected = (self.selected + 1) % self.favorite_nodes.len();
+    pub fn newer(&mut self, history: &ViewHistory) {
+        if self.selected + 1 < history.entries().len() {
+            self.selected += 1;
         }
     }
+}
 
-    pub fn previous_node(&mut self) {
-        if !self.favorite_nodes.is_empty() {
-            self.selected = if self.selected == 0 {
-                self.favorite_nodes.len() - 1
-            } else {
-                self.selected - 1
-            };
+/// A view in the back/forward history; topic detail entries remember which
+/// topic they showed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub view: crate::app::View,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic_id: Option<i64>,
+}
+
+impl HistoryEntry {
+    /// Whether the entry can be shown again after a restart, when only its
+    /// view and topic are known
+    fn is_restorable(&self) -> bool {
+        use crate::app::View;
+        match self.view {
+            View::TopicDetail => self.topic_id.is_some(),
+            View::Aggregate | View::Notifications | View::Favorites => true,
+            _ => false,
         }
     }
+}
 
-    pub fn select_current_node(&mut self) -> Option<String> {
-        if let Some((node_name, _)) = self.favorite_nodes.get(self.selected) {
-            self.current_node = node_name.clone();
-            self.page = 1;
-            Some(node_name.clone())
-        } else if self.is_completion_mode {
-            let node_name = self.completion_input.trim();
-            if !node_name.is_empty() {
-                self.current_node = node_name.to_string();
-                self.page = 1;
-                Some(node_name.to_string())
-            } else {
-                None
-            }
-        } else {
-            None
+/// Back/forward stack of visited views.
+///
+/// Opening the view already on top does not push it again, and each topic
+/// keeps at most `MAX_TOPIC_OCCURRENCES` entries so backing out of a topic
+/// revisited through profiles and notifications stays short. The stack is
+/// saved to `~/.local/share/v2ex/history.json` on exit.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViewHistory {
+    entries: Vec<HistoryEntry>,
+    position: usize,
+}
+
+impl ViewHistory {
+    const CAPACITY: usize = 50;
+    const MAX_TOPIC_OCCURRENCES: usize = 2;
+
+    pub fn new(initial: HistoryEntry) -> Self {
+        Self {
+            entries: vec![initial],
+            position: 0,
         }
     }
 
-    pub fn switch_node(&mut self, node: &str) {
-        self.current_node = node.to_string();
-        self.page = 1;
+    /// The history saved by the last session, if there is one
+    pub fn load() -> Result<Option<Self>> {
+        json_store::load(&json_store::data_path("history.json")?)
     }
 
-    pub fn insert_char(&mut self, ch: char) {
-        let byte_pos = self
-            .completion_input
-            .char_indices()
-            .nth(self.completion_cursor)
-            .map(|(i, _)| i)
-            .unwrap_or(self.completion_input.len());
-        self.completion_input.insert(byte_pos, ch);
-        self.completion_cursor += 1;
-        if self.is_completion_mode {
-            self.update_suggestions();
-        }
+    pub fn save(&self) -> Result<()> {
+        json_store::save(&json_store::data_path("history.json")?, self)
     }
 
-    pub fn delete_char(&mut self) {
-        if self.completion_cursor > 0 {
-            let byte_pos = self
-                .completion_input
-                .char_indices()
-                .nth(self.completion_cursor - 1)
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-            let next_byte_pos = self
-                .completion_input
-                .char_indices()
-                .nth(self.completion_cursor)
-                .map(|(i, _)| i)
-                .unwrap_or(self.completion_input.len());
-            self.completion_input.drain(byte_pos..next_byte_pos);
-            self.completion_cursor -= 1;
-            if self.is_completion_mode {
-                self.update_suggestions();
-            }
+    /// Continue a saved history with `current` on top. Forward history and
+    /// views that can't be rebuilt after a restart are dropped.
+    pub fn resume(mut self, current: HistoryEntry) -> Self {
+        self.entries.truncate(self.position.saturating_add(1));
+        self.entries.retain(HistoryEntry::is_restorable);
+        if self.entries.is_empty() {
+            return Self::new(current);
         }
-    }
+        self.position = self.entries.len() - 1;
+        self.push(current);
 
-    #[allow(dead_code)] // Not currently used, but kept for future use
-    pub fn move_cursor_left(&mut self) {
-        if self.completion_cursor > 0 {
-            self.completion_cursor -= 1;
-        }
+        let excess = self.entries.len().saturating_sub(Self::CAPACITY);
+        self.entries.drain(0..excess);
+        self.position -= excess;
+        self
     }
 
-    #[allow(dead_code)] // Not currently used, but kept for future use
-    pub fn move_cursor_right(&mut self) {
-        if self.completion_cursor < self.completion_input.chars().count() {
-            self.completion_cursor += 1;
-        }
+    pub fn current(&self) -> Option<HistoryEntry> {
+        self.entries.get(self.position).copied()
     }
 
-    pub fn toggle_completion_mode(&mut self) {
-        self.is_completion_mode = !self.is_completion_mode;
-        if self.is_completion_mode {
-            self.update_suggestions();
-        } else {
-            self.favorite_nodes = self.original_favorite_nodes.clone();
-            self.selected = 0;
-        }
+    /// Oldest first
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
     }
 
-    pub fn update_suggestions(&mut self) {
-        use fuzzy_matcher::skim::SkimMatcherV2;
-        use fuzzy_matcher::FuzzyMatcher;
+    pub fn position(&self) -> usize {
+        self.position
+    }
 
-        let input = self.completion_input.trim();
-        if input.is_empty() {
-            self.favorite_nodes = self.all_nodes.iter().take(20).cloned().collect();
-        } else {
-            let matcher = SkimMatcherV2::default();
-            let mut scored_nodes: Vec<((String, String), i64)> = self
-                .all_nodes
-                .iter()
-                .filter_map(|(name, title)| {
-                    let name_score = matcher.fuzzy_match(name, input);
-                    let title_score = matcher.fuzzy_match(title, input);
-                    let score = name_score.unwrap_or(0).max(title_score.unwrap_or(0));
-
-                    if score > 0 {
-                        Some(((name.clone(), title.clone()), score))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+    /// Move straight to entry `index`, keeping the rest of the stack
+    pub fn jump(&mut self, index: usize) -> Option<HistoryEntry> {
+        let entry = self.entries.get(index).copied()?;
+        self.position = index;
+        Some(entry)
+    }
 
-            scored_nodes.sort_by(|a, b| b.1.cmp(&a.1));
+    /// The entry `back` would return to
+    pub fn previous(&self) -> Option<HistoryEntry> {
+        self.position
+            .checked_sub(1)
+            .and_then(|index| self.entries.get(index).copied())
+    }
 
-            self.favorite_nodes = scored_nodes
-                .into_iter()
-                .take(20)
-                .map(|(node, _)| node)
-                .collect();
+    /// Visit `entry`, dropping any forward history
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.entries.truncate(self.position + 1);
+        self.entries.push(entry);
+        self.position = self.entries.len() - 1;
+        self.collapse();
+
+        if self.entries.len() > Self::CAPACITY {
+            let excess = self.entries.len() - Self::CAPACITY;
+            self.entries.drain(0..excess);
+            self.position -= excess;
         }
-        self.selected = 0;
     }
 
-    pub fn reset_selection(&mut self) {
-        self.completion_input.clear();
-        self.completion_cursor = 0;
-        self.is_completion_mode = false;
+    /// Point the current entry at another topic, e.g. when a topic replaces
+    /// the one on screen
+    pub fn replace_current(&mut self, entry: HistoryEntry) {
+        if let Some(current) = self.entries.get_mut(self.position) {
+            *current = entry;
+            self.collapse();
+        }
     }
-}
-
-#[derive(Debug, Default)]
-pub struct TokenState {
-    pub input: String,
-    pub cursor: usize,
-}
 
-impl TokenState {
-    pub fn insert_char(&mut self, ch: char) {
-        let byte_pos = self
-            .input
-            .char_indices()
-            .nth(self.cursor)
-            .map(|(i, _)| i)
-            .unwrap_or(self.input.len());
-        self.input.insert(byte_pos, ch);
-        self.cursor += 1;
+    pub fn back(&mut self) -> Option<HistoryEntry> {
+        self.position = self.position.checked_sub(1)?;
+        self.current()
     }
 
-    pub fn delete_char(&mut self) {
-        if self.cursor > 0 {
-            let byte_pos = self
-                .input
-                .char_indices()
-                .nth(self.cursor - 1)
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-            let next_byte_pos = self
-                .input
-                .char_indices()
-                .nth(self.cursor)
-                .map(|(i, _)| i)
-                .unwrap_or(self.input.len());
-            self.input.drain(byte_pos..next_byte_pos);
-            self.cursor -= 1;
+    pub fn forward(&mut self) -> Option<HistoryEntry> {
+        if self.position + 1 >= self.entries.len() {
+            return None;
         }
+        self.position += 1;
+        self.current()
     }
 
-    pub fn move_cursor_left(&mut self) {
-        if self.cursor > 0 {
-            self.cursor -= 1;
+    /// Drop the current entry and return the one before it, or `None` when
+    /// there is nothing to go back to
+    pub fn remove_current(&mut self) -> Option<HistoryEntry> {
+        if self.position == 0 {
+            return None;
         }
+        self.entries.remove(self.position);
+        self.position -= 1;
+        self.current()
     }
 
-    pub fn move_cursor_right(&mut self) {
-        if self.cursor < self.input.chars().count() {
-            self.cursor += 1;
+    /// Cap occurrences of each topic, oldest first, then merge neighbours
+    /// that became identical. The current entry always survives.
+    fn collapse(&mut self) {
+        let mut index = self.entries.len();
+        let mut seen: HashMap<i64, usize> = HashMap::new();
+        while index > 0 {
+            index -= 1;
+            let Some(topic_id) = self.entries[index].topic_id else {
+                continue;
+            };
+            let count = seen.entry(topic_id).or_default();
+            *count += 1;
+            if *count > Self::MAX_TOPIC_OCCURRENCES && index != self.position {
+                self.remove_at(index);
+            }
+        }
+
+        let mut index = 1;
+        while index < self.entries.len() {
+            if self.entries[index] == self.entries[index - 1] {
+                // Keep whichever copy is current
+                let duplicate = if self.position == index - 1 {
+                    index
+                } else {
+                    index - 1
+                };
+                self.remove_at(duplicate);
+            } else {
+                index += 1;
+            }
         }
     }
 
-    pub fn save(&self) -> anyhow::Result<()> {
-        use anyhow::Context;
-        let config_dir = crate::api::V2exClient::config_dir()?;
-        let token_path = config_dir.join("token.txt");
-        std::fs::write(&token_path, self.input.trim())
-            .with_context(|| format!("Failed to write token to {:?}", token_path))?;
-        Ok(())
+    fn remove_at(&mut self, index: usize) {
+        self.entries.remove(index);
+        if index < self.position {
+            self.position -= 1;
+        }
     }
 }
 
 #[derive(Debug, Default)]
 pub struct UiState {
-    pub loading: bool,
     pub error: Option<String>,
     pub status_message: String,
     pub theme: crate::ui::Theme,
+    /// Preset `theme` was built from, switched with C-t
+    pub theme_preset: crate::config::ThemePreset,
+    /// Topic detail shows only the text, without borders or status bar
+    pub focus_mode: bool,
+    /// Show posts by ignored users instead of collapsing them
+    pub reveal_blocked: bool,
+    /// API budget last reported by the server, shown in the status bar
+    pub api_quota: Option<crate::scheduler::RateLimit>,
+    /// Request waiting for its next attempt, shown in the status bar
+    pub api_retry: Option<crate::retry::RetryNotice>,
+    /// Topic and replies panes in topic detail
+    pub split: crate::ui::SplitLayout,
+    /// The load that failed with the error being shown, and that error
+    pub failed: Option<(String, Retry)>,
+    /// When the failed load runs again on its own, after a rate limit
+    pub retry_at: Option<std::time::Instant>,
+}
+
+/// A failed load that the error banner can run again
+#[derive(Debug, Clone, PartialEq)]
+pub enum Retry {
+    Topics { append: bool },
+    Topic(i64),
+    Replies { topic_id: i64, append: bool },
+    Notifications { append: bool },
+    Favorites,
+    Member(String),
+    Profile,
+    Aggregate,
 }
 
 impl UiState {
     pub fn new() -> Self {
         Self {
-            loading: false,
             error: None,
             status_message: "Press '?' for help".to_string(),
             theme: crate::ui::Theme::default(),
+            theme_preset: crate::config::ThemePreset::default(),
+            focus_mode: false,
+            reveal_blocked: false,
+            api_quota: None,
+            api_retry: None,
+            split: crate::ui::SplitLayout::default(),
+            failed: None,
+            retry_at: None,
         }
     }
+
+    /// Show `message` as an error that `retry` may clear up
+    pub fn fail(&mut self, message: String, retry: Retry) {
+        self.error = Some(message.clone());
+        self.failed = Some((message, retry));
+        self.retry_at = None;
+    }
+
+    /// Time left before the failed load runs again on its own
+    pub fn retry_in(&self) -> Option<std::time::Duration> {
+        self.retry()?;
+        let at = self.retry_at?;
+        Some(at.saturating_duration_since(std::time::Instant::now()))
+    }
+
+    /// Whether the failed load should run again on its own now
+    pub fn retry_due(&self) -> bool {
+        self.retry_in().is_some_and(|wait| wait.is_zero())
+    }
+
+    /// The load to run again, while its error is still the one shown
+    pub fn retry(&self) -> Option<&Retry> {
+        self.failed
+            .as_ref()
+            .filter(|(message, _)| self.error.as_ref() == Some(message))
+            .map(|(_, retry)| retry)
+    }
+
+    pub fn dismiss_error(&mut self) {
+        self.error = None;
+        self.failed = None;
+        self.retry_at = None;
+    }
 }
@@ -32,6 +32,24 @@ impl TerminalManager {
         &mut self.terminal
     }
 
+    /// Temporarily hand the terminal back to the shell (e.g. to run $EDITOR)
+    pub fn suspend(&mut self) -> Result<()> {
+        self.shutdown()
+    }
+
+    /// Re-enter raw mode and the alternate screen after `suspend`
+    pub fn resume(&mut self) -> Result<()> {
+        enable_raw_mode().context("Failed to enable raw mode")?;
+        execute!(
+            self.terminal.backend_mut(),
+            EnterAlternateScreen,
+            EnableMouseCapture
+        )
+        .context("Failed to initialize terminal")?;
+        self.terminal.clear().context("Failed to clear terminal")?;
+        Ok(())
+    }
+
     /// Clean shutdown with automatic restoration
     pub fn shutdown(&mut self) -> Result<()> {
         disable_raw_mode().context("Failed to disable raw mode")?;
@@ -0,0 +1,221 @@
+//! Named personal access tokens, one per account, kept in
+//! `~/.config/v2ex/tokens.json`. The one in use is also written to
+//! `token.txt` as before, so the CLI and older setups keep working.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::api::V2exClient;
+use crate::json_store;
+
+/// Name given to a token found in `token.txt` but not in the list
+const DEFAULT_NAME: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedToken {
+    pub name: String,
+    pub token: String,
+}
+
+impl NamedToken {
+    /// The token with all but its ends hidden, for display
+    pub fn masked(&self) -> String {
+        let chars: Vec<char> = self.token.chars().collect();
+        if chars.len() <= 8 {
+            return "*".repeat(chars.len());
+        }
+        let start: String = chars[..4].iter().collect();
+        let end: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}…{}", start, end)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Saved {
+    active: Option<String>,
+    tokens: Vec<NamedToken>,
+}
+
+/// The tokens and which one is in use
+#[derive(Debug, Default)]
+pub struct TokenStore {
+    tokens: Vec<NamedToken>,
+    /// Name of the token in use
+    active: Option<String>,
+    /// Where changes are saved; `None` keeps them in memory only
+    dir: Option<PathBuf>,
+}
+
+impl TokenStore {
+    /// Load the saved tokens, making sure `current` (the token the client
+    /// started with) is among them and marked as in use
+    pub fn load(current: &str) -> Result<Self> {
+        let dir = V2exClient::config_dir()?;
+        let path = dir.join("tokens.json");
+        let saved: Saved = json_store::load(&path)?.unwrap_or_default();
+        let mut store = Self {
+            tokens: saved.tokens,
+            active: saved.active,
+            dir: Some(dir),
+        };
+        store.adopt(current);
+        Ok(store)
+    }
+
+    /// Mark `current` as in use, listing it under a free name if it is new
+    fn adopt(&mut self, current: &str) {
+        if current.is_empty() {
+            return;
+        }
+        if let Some(known) = self.tokens.iter().find(|entry| entry.token == current) {
+            self.active = Some(known.name.clone());
+            return;
+        }
+        let mut name = DEFAULT_NAME.to_string();
+        let mut n = 2;
+        while self.tokens.iter().any(|entry| entry.name == name) {
+            name = format!("{}-{}", DEFAULT_NAME, n);
+            n += 1;
+        }
+        self.tokens.push(NamedToken {
+            name: name.clone(),
+            token: current.to_string(),
+        });
+        self.active = Some(name);
+    }
+
+    pub fn tokens(&self) -> &[NamedToken] {
+        &self.tokens
+    }
+
+    pub fn is_active(&self, name: &str) -> bool {
+        self.active.as_deref() == Some(name)
+    }
+
+    /// Add a token under `name`, or replace the token of that name
+    pub fn add(&mut self, name: &str, token: &str) -> Result<()> {
+        let (name, token) = (name.trim(), token.trim());
+        if name.is_empty() || token.is_empty() {
+            bail!("A token needs a name and a value");
+        }
+        match self.tokens.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => entry.token = token.to_string(),
+            None => self.tokens.push(NamedToken {
+                name: name.to_string(),
+                token: token.to_string(),
+            }),
+        }
+        self.save()
+    }
+
+    /// Forget the token called `name`; the one in use can't be removed
+    pub fn remove(&mut self, name: &str) -> Result<()> {
+        if self.is_active(name) {
+            bail!("'{}' is in use; switch to another token first", name);
+        }
+        self.tokens.retain(|entry| entry.name != name);
+        self.save()
+    }
+
+    /// Use the token called `name` from now on and return it
+    pub fn activate(&mut self, name: &str) -> Result<String> {
+        let token = self
+            .tokens
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.token.clone())
+            .with_context(|| format!("No token called '{}'", name))?;
+        self.active = Some(name.to_string());
+        self.save()?;
+        if let Some(dir) = &self.dir {
+            let path = dir.join("token.txt");
+            write_private(&path, &token)
+                .with_context(|| format!("Failed to write token to {:?}", path))?;
+        }
+        Ok(token)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let path = dir.join("tokens.json");
+        let saved = Saved {
+            active: self.active.clone(),
+            tokens: self.tokens.clone(),
+        };
+        write_private(&path, &serde_json::to_string_pretty(&saved)?)
+            .with_context(|| format!("Failed to write {:?}", path))
+    }
+}
+
+/// Write `contents` to `path` readable by the owner only, since tokens
+/// grant access to the account. A file made readable by others before is
+/// restricted too.
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        (&file).write_all(contents.as_bytes())
+    }
+    #[cfg(not(unix))]
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_store() {
+        let mut store = TokenStore::default();
+        store.adopt("first-token-value");
+        assert_eq!(store.tokens()[0].name, "default");
+        assert!(store.is_active("default"));
+
+        store.add(" work ", "second-token-value").unwrap();
+        assert!(store.add("empty", " ").is_err());
+        assert_eq!(store.tokens().len(), 2);
+        assert!(store.remove("default").is_err());
+
+        assert_eq!(store.activate("work").unwrap(), "second-token-value");
+        assert!(store.is_active("work"));
+        store.remove("default").unwrap();
+        assert_eq!(store.tokens().len(), 1);
+        assert!(store.activate("default").is_err());
+
+        // A token.txt edited by hand is picked up under a free name
+        store.add("default", "x").unwrap();
+        store.adopt("third-token-value");
+        assert!(store.is_active("default-2"));
+
+        let token = &store.tokens()[0];
+        assert_eq!(token.masked(), "seco…alue");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_token_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!(
+            "v2ex-token-{}-{:x}",
+            std::process::id(),
+            crate::retry::jitter_seed()
+        ));
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, "secret").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "secret");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
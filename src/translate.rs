@@ -0,0 +1,114 @@
+//! Translating posts through an external command, for readers who don't
+//! read Chinese
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tokio::sync::oneshot;
+
+/// A finished translation, shown over the view until a key closes it
+#[derive(Debug)]
+pub struct Translation {
+    /// What was translated, such as `reply #3`
+    pub label: String,
+    pub text: String,
+    pub scroll: u16,
+}
+
+impl Translation {
+    pub fn scroll_down(&mut self) {
+        let last = self.text.lines().count().saturating_sub(1);
+        self.scroll = self
+            .scroll
+            .saturating_add(1)
+            .min(last.min(u16::MAX as usize) as u16);
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
+
+/// The translation being made and the last one finished
+#[derive(Debug, Default)]
+pub struct Translator {
+    running: Option<(String, oneshot::Receiver<Result<String>>)>,
+    pub shown: Option<Translation>,
+}
+
+impl Translator {
+    /// Translate `text` with `command` without waiting for it, replacing a
+    /// translation still being made
+    pub fn start(&mut self, command: &str, label: String, text: String) {
+        let (tx, rx) = oneshot::channel();
+        let command = command.to_string();
+        tokio::task::spawn_blocking(move || {
+            let _ = tx.send(run(&command, &text));
+        });
+        self.running = Some((label, rx));
+    }
+
+    /// The label and outcome of the translation started last, once the
+    /// command finished
+    pub fn finished(&mut self) -> Option<(String, Result<String>)> {
+        let (label, rx) = self.running.as_mut()?;
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return None,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Err(anyhow::anyhow!("translation was interrupted"))
+            }
+        };
+        let label = std::mem::take(label);
+        self.running = None;
+        Some((label, result))
+    }
+}
+
+/// Run `command` through the shell with `text` on stdin and return what it
+/// printed. A command that fails or prints nothing is an error.
+pub fn run(command: &str, text: &str) -> Result<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let text = text.to_string();
+        // Written from another thread so a command printing as it reads
+        // can't fill its output pipe while we are still writing
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(text.as_bytes());
+        });
+    }
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("'{}' {}: {}", command, output.status, stderr.trim());
+    }
+    let translated = String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string();
+    if translated.trim().is_empty() {
+        bail!("'{}' printed nothing", command);
+    }
+    Ok(translated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_translate_command() {
+        assert_eq!(run("tr a-z A-Z", "hello\nworld\n").unwrap(), "HELLO\nWORLD");
+        let error = run("echo 'no network' >&2; exit 3", "hi").unwrap_err();
+        assert!(error.to_string().contains("no network"));
+        assert!(run("cat >/dev/null", "hi").is_err());
+    }
+}
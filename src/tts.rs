@@ -0,0 +1,84 @@
+//! Reading posts aloud through an external text-to-speech command
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+
+/// The speech command currently running, stopped when dropped so nothing
+/// keeps talking after the app exits
+#[derive(Debug, Default)]
+pub struct ReadAloud {
+    child: Option<Child>,
+}
+
+impl ReadAloud {
+    /// Whether a previous `start` is still speaking
+    pub fn is_speaking(&mut self) -> bool {
+        match self.child.as_mut().map(Child::try_wait) {
+            Some(Ok(None)) => true,
+            Some(_) => {
+                self.child = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Run `command` through the shell and feed it `text` on stdin.
+    ///
+    /// Output is discarded so it can't draw over the TUI. The text is written
+    /// from a separate thread because speech commands read it as they speak.
+    pub fn start(&mut self, command: &str, text: String) -> Result<()> {
+        self.stop();
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run '{}'", command))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            std::thread::spawn(move || {
+                // The command may exit early (e.g. stopped by the user)
+                let _ = stdin.write_all(text.as_bytes());
+            });
+        }
+        self.child = Some(child);
+        Ok(())
+    }
+
+    /// Stop speaking; returns whether anything was running
+    pub fn stop(&mut self) -> bool {
+        let speaking = self.is_speaking();
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        speaking
+    }
+}
+
+impl Drop for ReadAloud {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_aloud_start_and_stop() {
+        let mut read_aloud = ReadAloud::default();
+        assert!(!read_aloud.is_speaking());
+
+        read_aloud
+            .start("cat >/dev/null; sleep 5", "hello".to_string())
+            .unwrap();
+        assert!(read_aloud.is_speaking());
+        assert!(read_aloud.stop());
+        assert!(!read_aloud.is_speaking());
+    }
+}
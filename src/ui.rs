@@ -1,12 +1,19 @@
+use anyhow::{anyhow, bail, Result};
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-#[derive(Debug)]
+use crate::config::{CustomTheme, ThemePreset};
+use crate::minibuffer::Minibuffer;
+use crate::scheduler::RateLimit;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Theme {
     pub primary: Color,
     pub secondary: Color,
@@ -15,20 +22,137 @@ pub struct Theme {
     pub foreground: Color,
     pub muted: Color,
     pub error: Color,
+    /// Fill behind every view; `Color::Reset` keeps the terminal's own
+    pub canvas: Color,
+    /// Background of the "OP" badge on replies by the topic author
+    pub op_badge: Color,
+    /// Thanks counts on replies
+    pub thanks: Color,
+    /// Background and text of the selected reply
+    pub selection: Color,
+    pub selection_text: Color,
 }
 
 impl Default for Theme {
     fn default() -> Self {
+        Self::preset(ThemePreset::Dark)
+    }
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Self {
+                primary: Color::Cyan,
+                secondary: Color::Blue,
+                accent: Color::Yellow,
+                background: Color::Black,
+                foreground: Color::White,
+                muted: Color::Gray,
+                error: Color::Red,
+                canvas: Color::Reset,
+                op_badge: Color::Magenta,
+                thanks: Color::LightRed,
+                selection: Color::Cyan,
+                selection_text: Color::Black,
+            },
+            ThemePreset::Light => Self {
+                primary: Color::Rgb(0x00, 0x5f, 0xaf),
+                secondary: Color::Rgb(0x5f, 0x00, 0x87),
+                accent: Color::Rgb(0xaf, 0x5f, 0x00),
+                background: Color::Rgb(0xfa, 0xfa, 0xfa),
+                foreground: Color::Rgb(0x26, 0x26, 0x26),
+                muted: Color::Rgb(0x6c, 0x6c, 0x6c),
+                error: Color::Rgb(0xd7, 0x00, 0x00),
+                canvas: Color::Rgb(0xfa, 0xfa, 0xfa),
+                op_badge: Color::Rgb(0x00, 0x87, 0x5f),
+                thanks: Color::Rgb(0xd7, 0x00, 0x5f),
+                selection: Color::Rgb(0x00, 0x5f, 0xaf),
+                selection_text: Color::Rgb(0xfa, 0xfa, 0xfa),
+            },
+            ThemePreset::Solarized => Self {
+                primary: Color::Rgb(0x26, 0x8b, 0xd2),
+                secondary: Color::Rgb(0x2a, 0xa1, 0x98),
+                accent: Color::Rgb(0xb5, 0x89, 0x00),
+                background: Color::Rgb(0x00, 0x2b, 0x36),
+                foreground: Color::Rgb(0x83, 0x94, 0x96),
+                muted: Color::Rgb(0x58, 0x6e, 0x75),
+                error: Color::Rgb(0xdc, 0x32, 0x2f),
+                canvas: Color::Rgb(0x00, 0x2b, 0x36),
+                op_badge: Color::Rgb(0x6c, 0x71, 0xc4),
+                thanks: Color::Rgb(0xd3, 0x36, 0x82),
+                selection: Color::Rgb(0x26, 0x8b, 0xd2),
+                selection_text: Color::Rgb(0x00, 0x2b, 0x36),
+            },
+            ThemePreset::Gruvbox => Self {
+                primary: Color::Rgb(0x83, 0xa5, 0x98),
+                secondary: Color::Rgb(0x8e, 0xc0, 0x7c),
+                accent: Color::Rgb(0xfa, 0xbd, 0x2f),
+                background: Color::Rgb(0x28, 0x28, 0x28),
+                foreground: Color::Rgb(0xeb, 0xdb, 0xb2),
+                muted: Color::Rgb(0x92, 0x83, 0x74),
+                error: Color::Rgb(0xfb, 0x49, 0x34),
+                canvas: Color::Rgb(0x28, 0x28, 0x28),
+                op_badge: Color::Rgb(0xd3, 0x86, 0x9b),
+                thanks: Color::Rgb(0xfe, 0x80, 0x19),
+                selection: Color::Rgb(0x83, 0xa5, 0x98),
+                selection_text: Color::Rgb(0x28, 0x28, 0x28),
+            },
+        }
+    }
+
+    /// A preset with the colours from `set-theme-color` laid over it
+    pub fn with_custom(preset: ThemePreset, custom: &CustomTheme) -> Self {
+        let base = Self::preset(preset);
         Self {
-            primary: Color::Cyan,
-            secondary: Color::Blue,
-            accent: Color::Yellow,
-            background: Color::Black,
-            foreground: Color::White,
-            muted: Color::Gray,
-            error: Color::Red,
+            primary: custom.primary.unwrap_or(base.primary),
+            secondary: custom.secondary.unwrap_or(base.secondary),
+            accent: custom.accent.unwrap_or(base.accent),
+            background: custom.background.unwrap_or(base.background),
+            foreground: custom.foreground.unwrap_or(base.foreground),
+            muted: custom.muted.unwrap_or(base.muted),
+            error: custom.error.unwrap_or(base.error),
+            canvas: custom.canvas.unwrap_or(base.canvas),
+            op_badge: custom.op_badge.unwrap_or(base.op_badge),
+            thanks: custom.thanks.unwrap_or(base.thanks),
+            selection: custom.selection.unwrap_or(base.selection),
+            selection_text: custom.selection_text.unwrap_or(base.selection_text),
+        }
+    }
+}
+
+/// Parse a config colour: `#rrggbb` or a terminal colour name such as
+/// `light-blue` (`reset` means the terminal default)
+pub fn parse_color(value: &str) -> Result<Color> {
+    let value = value.trim().to_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            bail!("expected a colour like #rrggbb, got '{}'", value);
         }
+        let rgb = u32::from_str_radix(hex, 16)
+            .map_err(|_| anyhow!("expected a colour like #rrggbb, got '{}'", value))?;
+        return Ok(Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
     }
+    Ok(match value.replace('_', "-").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark-gray" | "dark-grey" => Color::DarkGray,
+        "light-red" => Color::LightRed,
+        "light-green" => Color::LightGreen,
+        "light-yellow" => Color::LightYellow,
+        "light-blue" => Color::LightBlue,
+        "light-magenta" => Color::LightMagenta,
+        "light-cyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => bail!("unknown colour '{}'", value),
+    })
 }
 
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -51,46 +175,330 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn render_loading(frame: &mut Frame, area: Rect, theme: &Theme) {
-    let loading = Paragraph::new("Loading...")
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.accent)),
-        )
-        .style(Style::default().fg(theme.accent));
+/// How topic detail shares the screen between the topic and its replies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitLayout {
+    /// Share of the screen given to the topic, in percent
+    pub topic_percent: u16,
+    /// Replies first (left or top), topic second
+    pub swapped: bool,
+    /// Always one pane above the other, even on wide terminals
+    pub stacked: bool,
+}
 
-    frame.render_widget(loading, area);
+impl Default for SplitLayout {
+    fn default() -> Self {
+        Self {
+            topic_percent: 40,
+            swapped: false,
+            stacked: false,
+        }
+    }
 }
 
-pub fn render_error(frame: &mut Frame, area: Rect, error: &str, theme: &Theme) {
-    let error_widget = Paragraph::new(error)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.error))
-                .title(" Error "),
-        )
-        .style(Style::default().fg(theme.error));
+impl SplitLayout {
+    pub const MIN_PERCENT: u16 = 20;
+    pub const MAX_PERCENT: u16 = 80;
+    /// Percent moved by each resize key
+    pub const STEP: u16 = 5;
+
+    /// Give the topic pane `STEP` percent more, or less when `grow` is false
+    pub fn resize(&mut self, grow: bool) {
+        self.topic_percent = if grow {
+            self.topic_percent.saturating_add(Self::STEP)
+        } else {
+            self.topic_percent.saturating_sub(Self::STEP)
+        }
+        .clamp(Self::MIN_PERCENT, Self::MAX_PERCENT);
+    }
 
-    frame.render_widget(error_widget, area);
+    /// The topic and replies panes within `area`. Terminals narrower than
+    /// 100 columns always stack them.
+    pub fn areas(&self, area: Rect) -> (Rect, Rect) {
+        let direction = if self.stacked || area.width < 100 {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        let topic = Constraint::Percentage(self.topic_percent);
+        let replies = Constraint::Percentage(100 - self.topic_percent);
+        let constraints = if self.swapped {
+            [replies, topic]
+        } else {
+            [topic, replies]
+        };
+        let chunks = Layout::default()
+            .direction(direction)
+            .constraints(constraints)
+            .split(area);
+        if self.swapped {
+            (chunks[1], chunks[0])
+        } else {
+            (chunks[0], chunks[1])
+        }
+    }
 }
 
-pub fn render_status_bar(frame: &mut Frame, area: Rect, message: &str, theme: &Theme) {
-    let status =
-        Paragraph::new(message).style(Style::default().fg(theme.background).bg(theme.primary));
+/// What is loading while the view keeps showing what it had
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loading {
+    /// Topics, notifications, a feed and the like
+    List,
+    /// A topic and its replies
+    Replies,
+}
 
-    frame.render_widget(status, area);
+impl Loading {
+    fn label(self) -> &'static str {
+        match self {
+            Loading::List => "loading list",
+            Loading::Replies => "loading replies",
+        }
+    }
+}
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// The spinner frame for `elapsed` time, or a still mark when animating
+/// would cost a redraw every turn
+fn spinner_frame(elapsed: Duration, animated: bool) -> &'static str {
+    if !animated {
+        return "…";
+    }
+    SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()]
 }
 
-pub fn render_token_input(
+/// Rows the error banner takes at most; longer errors are cut off
+const MAX_BANNER_HEIGHT: u16 = 3;
+
+/// The error across the top of `area`, with the keys that deal with it
+/// (`"r retry"` and the like), returning the space left below for the view
+pub fn render_error_banner(
     frame: &mut Frame,
     area: Rect,
-    token: &str,
-    cursor_position: usize,
+    error: &str,
+    keys: &[String],
+    retry_in: Option<Duration>,
+    theme: &Theme,
+) -> Rect {
+    let mut keys = keys.join(" · ");
+    if let Some(wait) = retry_in {
+        keys = format!("retrying in {}s · {}", wait.as_secs() + 1, keys);
+    }
+    let style = Style::default().fg(theme.background).bg(theme.error);
+    let line = Line::from(vec![
+        Span::styled(format!(" ✗ {} ", error), style.add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" {} ", keys), style),
+    ]);
+    let width = area.width.max(1) as usize;
+    let rows = line.width().div_ceil(width).max(1) as u16;
+    let height = rows.min(MAX_BANNER_HEIGHT).min(area.height);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height), Constraint::Min(0)])
+        .split(area);
+
+    let banner = Paragraph::new(line)
+        .style(style)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    frame.render_widget(banner, chunks[0]);
+    chunks[1]
+}
+
+/// Strip a rendered area down to what is cheap to send over a slow link.
+///
+/// Box-drawing characters become ASCII, colours fall back to the terminal
+/// defaults and anything drawn on a coloured background (the selection)
+/// is shown reversed instead, so cells stop churning SGR colour codes.
+pub fn simplify_for_low_bandwidth(buffer: &mut Buffer, area: Rect, theme: &Theme) {
+    let area = area.intersection(buffer.area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let cell = &mut buffer[(x, y)];
+            let highlighted =
+                cell.bg != Color::Reset && cell.bg != theme.background && cell.bg != theme.canvas;
+            if let Some(ascii) = ascii_box_drawing(cell.symbol()) {
+                cell.set_char(ascii);
+            }
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+            if highlighted {
+                cell.modifier.insert(Modifier::REVERSED);
+            } else {
+                cell.modifier.remove(Modifier::REVERSED);
+            }
+        }
+    }
+}
+
+fn ascii_box_drawing(symbol: &str) -> Option<char> {
+    Some(match symbol {
+        "─" | "━" | "═" => '-',
+        "│" | "┃" | "║" => '|',
+        "┌" | "┐" | "└" | "┘" | "╭" | "╮" | "╰" | "╯" | "┏" | "┓" | "┗" | "┛" | "╔" | "╗" | "╚"
+        | "╝" | "├" | "┤" | "┬" | "┴" | "┼" => '+',
+        _ => return None,
+    })
+}
+
+/// Network state shown at the right end of the status bar
+#[derive(Debug, Clone, Copy)]
+pub enum NetworkStatus<'a> {
+    Offline,
+    /// The view shows data from the disk cache saved at this Unix time
+    Cached {
+        saved_at: i64,
+        offline: bool,
+    },
+    Quota(&'a RateLimit),
+}
+
+impl NetworkStatus<'_> {
+    /// Remaining API budget, with the reset time once low
+    pub fn label(&self) -> String {
+        match self {
+            NetworkStatus::Offline => "OFFLINE".to_string(),
+            NetworkStatus::Cached { saved_at, offline } => format!(
+                "{}CACHED {}",
+                if *offline { "OFFLINE · " } else { "" },
+                crate::util::format_relative_time(*saved_at)
+            ),
+            NetworkStatus::Quota(rate_limit) => {
+                let label = format!("API {}/{}", rate_limit.remaining, rate_limit.limit);
+                if rate_limit.is_low() {
+                    let minutes = (rate_limit.resets_in() + 59) / 60;
+                    format!("{} · resets in {}m", label, minutes)
+                } else {
+                    label
+                }
+            }
+        }
+    }
+
+    fn needs_attention(&self) -> bool {
+        match self {
+            NetworkStatus::Offline | NetworkStatus::Cached { .. } => true,
+            NetworkStatus::Quota(rate_limit) => rate_limit.is_low(),
+        }
+    }
+}
+
+/// What the `status-format` placeholders stand for
+#[derive(Debug, Default)]
+pub struct StatusFields<'a> {
+    /// The status message, with links and retries as shown by default
+    pub message: &'a str,
+    pub view: &'a str,
+    pub node: &'a str,
+    pub page: i32,
+    pub unread: usize,
+    pub time: String,
+}
+
+/// Fill in the `{message}`, `{view}`, `{node}`, `{page}`, `{unread}` and
+/// `{time}` placeholders of a `status-format` template. Anything else,
+/// unknown placeholders included, is kept as written; an empty template
+/// shows just the message.
+pub fn format_status(template: &str, fields: &StatusFields) -> String {
+    if template.is_empty() {
+        return fields.message.to_string();
+    }
+    let mut out = String::with_capacity(template.len() + fields.message.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        match &rest[1..end] {
+            "message" => out.push_str(fields.message),
+            "view" => out.push_str(fields.view),
+            "node" => out.push_str(fields.node),
+            "page" => out.push_str(&fields.page.to_string()),
+            "unread" => out.push_str(&fields.unread.to_string()),
+            "time" => out.push_str(&fields.time),
+            _ => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn render_status_bar(
+    frame: &mut Frame,
+    area: Rect,
+    message: &str,
+    unread_notifications: usize,
+    loading: Option<Loading>,
+    network: Option<NetworkStatus>,
+    low_bandwidth: bool,
     theme: &Theme,
 ) {
+    let mut spans = Vec::new();
+    if let Some(loading) = loading {
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let style = match loading {
+            Loading::List => Style::default().fg(theme.background).bg(theme.accent),
+            Loading::Replies => Style::default().fg(theme.background).bg(theme.secondary),
+        };
+        spans.push(Span::styled(
+            format!(
+                " {} {} ",
+                spinner_frame(elapsed, !low_bandwidth),
+                loading.label()
+            ),
+            style.add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if unread_notifications > 0 {
+        spans.push(Span::styled(
+            format!(" ✉ {} new (m) ", unread_notifications),
+            Style::default()
+                .fg(theme.background)
+                .bg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    spans.push(Span::raw(message));
+
+    // Right-aligned, and only if it fits after the message
+    if let Some(network) = network {
+        let label = format!(" {} ", network.label());
+        let used: usize = spans.iter().map(|span| span.width()).sum();
+        let free = (area.width as usize).saturating_sub(used);
+        if free > label.chars().count() {
+            spans.push(Span::raw(" ".repeat(free - label.chars().count())));
+            let style = if network.needs_attention() {
+                Style::default()
+                    .fg(theme.background)
+                    .bg(theme.error)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            spans.push(Span::styled(label, style));
+        }
+    }
+
+    // A full-width coloured bar is repainted whenever the message changes
+    let style = if low_bandwidth {
+        Style::default()
+    } else {
+        Style::default().fg(theme.background).bg(theme.primary)
+    };
+    let status = Paragraph::new(Line::from(spans)).style(style);
+
+    frame.render_widget(status, area);
+}
+
+pub fn render_token_input(frame: &mut Frame, area: Rect, prompt: &Minibuffer, theme: &Theme) {
     use ratatui::layout::Alignment;
 
     let chunks = Layout::default()
@@ -140,37 +548,10 @@ pub fn render_token_input(
         Style::default().fg(theme.primary),
     )])];
 
-    // Show token as plain text with cursor
-    let mut token_spans = vec![];
-    let token_len = token.chars().count();
-
-    for (i, ch) in token.chars().enumerate() {
-        if i == cursor_position {
-            // Cursor position - highlight
-            token_spans.push(Span::styled(
-                ch.to_string(),
-                Style::default().bg(theme.primary).fg(theme.background),
-            ));
-        } else {
-            token_spans.push(Span::styled(
-                ch.to_string(),
-                Style::default().fg(theme.foreground),
-            ));
-        }
-    }
-
-    // If cursor is at the end
-    if cursor_position == token_len {
-        token_spans.push(Span::styled(
-            " ",
-            Style::default().bg(theme.primary).fg(theme.background),
-        ));
-    }
-
-    input_lines.push(Line::from(token_spans));
+    input_lines.push(prompt.line(theme));
     input_lines.push(Line::from(""));
     input_lines.push(Line::from(vec![Span::styled(
-        "Press Enter to save, Ctrl+C to quit",
+        "Press Enter to save, Esc or Ctrl+C to quit",
         Style::default().fg(theme.muted),
     )]));
 
@@ -187,3 +568,133 @@ pub fn render_token_input(
 
     frame.render_widget(input_widget, input_area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::widgets::Widget;
+
+    #[test]
+    fn test_spinner_frames() {
+        assert_eq!(spinner_frame(Duration::from_millis(0), true), "⠋");
+        assert_eq!(spinner_frame(Duration::from_millis(1050), true), "⠋");
+        assert_eq!(spinner_frame(Duration::from_millis(250), true), "⠹");
+        assert_eq!(spinner_frame(Duration::from_millis(250), false), "…");
+    }
+
+    #[test]
+    fn test_split_layout() {
+        let mut split = SplitLayout::default();
+        let wide = Rect::new(0, 0, 200, 50);
+        let (topic, replies) = split.areas(wide);
+        assert_eq!((topic.x, topic.width, replies.x), (0, 80, 80));
+
+        split.swapped = true;
+        let (topic, replies) = split.areas(wide);
+        assert_eq!((replies.x, topic.x), (0, 120));
+
+        split.stacked = true;
+        split.resize(true);
+        let (topic, replies) = split.areas(wide);
+        assert_eq!((replies.y, topic.y, topic.height), (0, 28, 22));
+
+        for _ in 0..20 {
+            split.resize(false);
+        }
+        assert_eq!(split.topic_percent, SplitLayout::MIN_PERCENT);
+    }
+
+    #[test]
+    fn test_simplify_for_low_bandwidth() {
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 6, 3);
+        let mut buffer = Buffer::empty(area);
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary))
+            .render(area, &mut buffer);
+        buffer.set_string(
+            1,
+            1,
+            "sel",
+            Style::default().bg(theme.primary).fg(theme.background),
+        );
+
+        simplify_for_low_bandwidth(&mut buffer, area, &theme);
+
+        let mut expected = Buffer::with_lines(["+----+", "|sel |", "+----+"]);
+        expected.set_style(Rect::new(1, 1, 3, 1), Modifier::REVERSED);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(
+            parse_color("#FE8019").unwrap(),
+            Color::Rgb(0xfe, 0x80, 0x19)
+        );
+        assert_eq!(parse_color("light_blue").unwrap(), Color::LightBlue);
+        assert_eq!(parse_color("Grey").unwrap(), Color::Gray);
+        assert!(parse_color("#fe80").is_err());
+        assert!(parse_color("#gggggg").is_err());
+        assert!(parse_color("teal").is_err());
+    }
+
+    #[test]
+    fn test_theme_with_custom() {
+        let custom = CustomTheme {
+            accent: Some(Color::Magenta),
+            ..Default::default()
+        };
+        let theme = Theme::with_custom(ThemePreset::Gruvbox, &custom);
+        assert_eq!(theme.accent, Color::Magenta);
+        assert_eq!(theme.primary, Theme::preset(ThemePreset::Gruvbox).primary);
+        assert_eq!(Theme::default().canvas, Color::Reset);
+    }
+
+    #[test]
+    fn test_format_status() {
+        let fields = StatusFields {
+            message: "Loaded 20 topics",
+            view: "topic-list",
+            node: "rust",
+            page: 2,
+            unread: 3,
+            time: "09:41".to_string(),
+        };
+        assert_eq!(format_status("", &fields), "Loaded 20 topics");
+        assert_eq!(
+            format_status(
+                "[{view}] {node} p{page} ✉{unread} {time} | {message}",
+                &fields
+            ),
+            "[topic-list] rust p2 ✉3 09:41 | Loaded 20 topics"
+        );
+        // Unknown or unclosed placeholders are left alone
+        assert_eq!(format_status("{nope} {page", &fields), "{nope} {page");
+    }
+
+    #[test]
+    fn test_network_status_label() {
+        let healthy = RateLimit {
+            limit: 600,
+            remaining: 432,
+            reset: i64::MAX,
+        };
+        assert_eq!(NetworkStatus::Quota(&healthy).label(), "API 432/600");
+
+        let low = RateLimit {
+            remaining: 12,
+            ..healthy
+        };
+        assert!(NetworkStatus::Quota(&low)
+            .label()
+            .starts_with("API 12/600 · resets in "));
+        assert_eq!(NetworkStatus::Offline.label(), "OFFLINE");
+        let cached = NetworkStatus::Cached {
+            saved_at: chrono::Utc::now().timestamp() - 7200,
+            offline: true,
+        };
+        assert_eq!(cached.label(), "OFFLINE · CACHED 2 hours ago");
+    }
+}
@@ -0,0 +1,151 @@
+//! Link unfurling: titles of the GitHub, YouTube and Twitter links in the
+//! post being read, looked up in the background and shown next to the
+//! link mode labels. The latest titles found are kept in the disk cache
+//! when the app exits.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::mpsc;
+
+use crate::api::{LinkSite, V2exClient};
+use crate::util::width::{split_at_width, text_width};
+
+/// Widest title shown next to a label, in columns
+const MAX_TITLE_WIDTH: usize = 40;
+/// Titles kept, dropping the oldest found beyond it
+const MAX_TITLES: usize = 500;
+
+/// Titles known so far and the lookups still running
+#[derive(Debug)]
+pub struct LinkTitles {
+    titles: HashMap<String, String>,
+    /// Links of `titles`, oldest found first
+    order: VecDeque<String>,
+    /// Whether titles were found since the last save
+    unsaved: bool,
+    /// Links looked up this session, so failures aren't tried again
+    asked: HashSet<String>,
+    tx: mpsc::UnboundedSender<(String, Result<String>)>,
+    rx: mpsc::UnboundedReceiver<(String, Result<String>)>,
+}
+
+impl Default for LinkTitles {
+    fn default() -> Self {
+        Self::new(HashMap::new())
+    }
+}
+
+impl LinkTitles {
+    /// Start from titles saved by an earlier session
+    pub fn new(saved: HashMap<String, String>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut titles = Self {
+            titles: HashMap::new(),
+            order: VecDeque::new(),
+            unsaved: false,
+            asked: HashSet::new(),
+            tx,
+            rx,
+        };
+        for (url, title) in saved.into_iter().take(MAX_TITLES) {
+            titles.order.push_back(url.clone());
+            titles.titles.insert(url, title);
+        }
+        titles
+    }
+
+    pub fn get(&self, url: &str) -> Option<&str> {
+        self.titles.get(url).map(String::as_str)
+    }
+
+    /// Every title kept, if any were found since the last call
+    pub fn unsaved(&mut self) -> Option<&HashMap<String, String>> {
+        std::mem::take(&mut self.unsaved).then_some(&self.titles)
+    }
+
+    /// Look up the title of each of `urls` on a supported site that has
+    /// none yet, without waiting
+    pub fn start<'u>(&mut self, client: &V2exClient, urls: impl IntoIterator<Item = &'u String>) {
+        for url in urls {
+            if LinkSite::of(url).is_none()
+                || self.titles.contains_key(url)
+                || !self.asked.insert(url.clone())
+            {
+                continue;
+            }
+            let client = client.clone();
+            let url = url.clone();
+            let tx = self.tx.clone();
+            tokio::spawn(async move {
+                let result = client.get_link_title(&url).await;
+                let _ = tx.send((url, result));
+            });
+        }
+    }
+
+    /// The next lookup that finished, if any
+    pub fn finished(&mut self) -> Option<(String, Result<String>)> {
+        self.rx.try_recv().ok()
+    }
+
+    pub fn insert(&mut self, url: String, title: String) {
+        if self.titles.insert(url.clone(), title).is_none() {
+            self.order.push_back(url);
+        }
+        while self.order.len() > MAX_TITLES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.titles.remove(&oldest);
+            }
+        }
+        self.unsaved = true;
+    }
+
+    /// A link mode label with the link's title after it, when known
+    pub fn label(&self, shortcut: &str, url: &str) -> String {
+        match self.get(url) {
+            Some(title) => format!("{}: {}", shortcut, shorten(title, MAX_TITLE_WIDTH)),
+            None => shortcut.to_string(),
+        }
+    }
+}
+
+/// `title` cut down to `width` columns, ending in `…` when shortened
+fn shorten(title: &str, width: usize) -> String {
+    if text_width(title) <= width {
+        return title.to_string();
+    }
+    let (kept, _) = split_at_width(title, width.saturating_sub(1));
+    format!("{}…", kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_with_titles() {
+        let mut titles = LinkTitles::default();
+        let url = "https://github.com/rust-lang/rust";
+        assert_eq!(titles.label("a", url), "a");
+        titles.insert(url.to_string(), "GitHub - rust-lang/rust".to_string());
+        assert_eq!(titles.label("a", url), "a: GitHub - rust-lang/rust");
+
+        assert_eq!(shorten("short", 10), "short");
+        assert_eq!(shorten("exactly ten", 11), "exactly ten");
+        assert_eq!(shorten("a much longer title", 10), "a much lo…");
+        assert_eq!(shorten("中文标题很长很长", 7), "中文标…");
+    }
+
+    #[test]
+    fn test_oldest_titles_dropped() {
+        let mut titles = LinkTitles::default();
+        assert!(titles.unsaved().is_none());
+        for i in 0..=MAX_TITLES {
+            titles.insert(format!("https://github.com/{}", i), i.to_string());
+        }
+        assert_eq!(titles.get("https://github.com/0"), None);
+        assert_eq!(titles.get("https://github.com/1"), Some("1"));
+        assert_eq!(titles.unsaved().map(HashMap::len), Some(MAX_TITLES));
+        assert!(titles.unsaved().is_none());
+    }
+}
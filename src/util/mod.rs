@@ -1,5 +1,9 @@
+pub mod width;
+
 use chrono::{Local, TimeZone};
 
+use crate::config::CountFormat;
+
 /// Format a Unix timestamp as relative time (e.g., "2 hours ago")
 pub fn format_relative_time(timestamp: i64) -> String {
     let now = Local::now();
@@ -34,7 +38,6 @@ pub fn format_relative_time(timestamp: i64) -> String {
 }
 
 /// Format a Unix timestamp as absolute time (e.g., "2026-02-09 14:30")
-#[allow(dead_code)] // Reserved for future configuration option
 pub fn format_absolute_time(timestamp: i64) -> String {
     let dt = Local.timestamp_opt(timestamp, 0).single();
 
@@ -44,6 +47,26 @@ pub fn format_absolute_time(timestamp: i64) -> String {
     }
 }
 
+/// Shorten a count for display: `1234` is `1.2k` in western style and
+/// `12345` is `1.2万` in Chinese style. Smaller counts are shown in full,
+/// and the digit after the point is cut rather than rounded, so a count
+/// never looks bigger than it is.
+pub fn format_count(count: i64, format: CountFormat) -> String {
+    let units: &[(i64, &str)] = match format {
+        CountFormat::Western => &[(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "k")],
+        CountFormat::Chinese => &[(100_000_000, "亿"), (10_000, "万")],
+    };
+    let Some(&(unit, suffix)) = units.iter().find(|(unit, _)| count >= *unit) else {
+        return count.to_string();
+    };
+    let tenths = count / (unit / 10);
+    if tenths % 10 == 0 {
+        format!("{}{}", tenths / 10, suffix)
+    } else {
+        format!("{}.{}{}", tenths / 10, tenths % 10, suffix)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,4 +96,16 @@ mod tests {
         let result = format_relative_time(five_min_ago);
         assert!(result.contains("min ago"));
     }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(999, CountFormat::Western), "999");
+        assert_eq!(format_count(1_000, CountFormat::Western), "1k");
+        assert_eq!(format_count(1_299, CountFormat::Western), "1.2k");
+        assert_eq!(format_count(999_999, CountFormat::Western), "999.9k");
+        assert_eq!(format_count(3_450_000, CountFormat::Western), "3.4M");
+        assert_eq!(format_count(9_999, CountFormat::Chinese), "9999");
+        assert_eq!(format_count(34_000, CountFormat::Chinese), "3.4万");
+        assert_eq!(format_count(120_000_000, CountFormat::Chinese), "1.2亿");
+    }
 }
@@ -0,0 +1,108 @@
+//! Text measured the way a terminal draws it: in grapheme clusters (an
+//! emoji with its skin tone or a ZWJ family is one), each one or two
+//! columns wide
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// The user-perceived characters of `text`
+pub fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    text.graphemes(true)
+}
+
+/// Columns a grapheme cluster takes; emoji sequences count as two
+pub fn grapheme_width(grapheme: &str) -> usize {
+    let width = grapheme.width();
+    if grapheme.chars().count() > 1 && width > 2 {
+        2
+    } else {
+        width
+    }
+}
+
+/// Columns `text` takes
+pub fn text_width(text: &str) -> usize {
+    graphemes(text).map(grapheme_width).sum()
+}
+
+pub fn grapheme_count(text: &str) -> usize {
+    graphemes(text).count()
+}
+
+/// Byte offset of grapheme `index` in `text`, or its length past the end
+pub fn byte_offset(text: &str, index: usize) -> usize {
+    text.grapheme_indices(true)
+        .nth(index)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+/// `text` before grapheme `index`, that grapheme (if any) and the rest,
+/// for drawing a block cursor over it
+pub fn split_at_cursor(text: &str, index: usize) -> (&str, Option<&str>, &str) {
+    let start = byte_offset(text, index);
+    let (before, rest) = text.split_at(start);
+    match graphemes(rest).next() {
+        Some(at) => (before, Some(at), &rest[at.len()..]),
+        None => (before, None, ""),
+    }
+}
+
+/// Split `text` after the graphemes that fit in `width` columns
+pub fn split_at_width(text: &str, width: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += grapheme_width(grapheme);
+        if used > width {
+            return text.split_at(index);
+        }
+    }
+    (text, "")
+}
+
+/// Where `text` breaks into rows of `width` columns, as the grapheme
+/// index each row starts at. A wide character that doesn't fit at the end
+/// of a row moves to the next one.
+pub fn row_starts(text: &str, width: usize) -> Vec<usize> {
+    let width = width.max(2);
+    let mut starts = vec![0];
+    let mut used = 0;
+    for (index, grapheme) in graphemes(text).enumerate() {
+        let grapheme_width = grapheme_width(grapheme);
+        if used + grapheme_width > width {
+            starts.push(index);
+            used = 0;
+        }
+        used += grapheme_width;
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widths() {
+        assert_eq!(text_width("abc"), 3);
+        assert_eq!(text_width("中文"), 4);
+        assert_eq!(text_width("👍🏽"), 2);
+        assert_eq!(text_width("👨‍👩‍👧"), 2);
+        assert_eq!(text_width("❤️ ok"), 5);
+        assert_eq!(grapheme_count("e\u{301}👨‍👩‍👧x"), 3);
+        assert_eq!(byte_offset("a中b", 2), "a中".len());
+        assert_eq!(byte_offset("a中b", 9), "a中b".len());
+        assert_eq!(split_at_cursor("a👍🏽b", 1), ("a", Some("👍🏽"), "b"));
+        assert_eq!(split_at_cursor("ab", 2), ("ab", None, ""));
+        assert_eq!(split_at_width("ab中文", 3), ("ab", "中文"));
+        assert_eq!(split_at_width("ab中文", 4), ("ab中", "文"));
+    }
+
+    #[test]
+    fn test_row_starts() {
+        assert_eq!(row_starts("", 4), vec![0]);
+        assert_eq!(row_starts("abcdef", 4), vec![0, 4]);
+        // The second wide character would straddle the edge
+        assert_eq!(row_starts("a中文", 4), vec![0, 2]);
+        assert_eq!(row_starts("👍🏽👍🏽👍🏽", 4), vec![0, 2]);
+    }
+}
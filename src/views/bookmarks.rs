@@ -0,0 +1,155 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::keymap::{BookmarksKeyMap, KeyMap};
+use crate::{
+    app::App,
+    bookmarks::Bookmark,
+    mouse::{ListRegion, MouseRegions},
+    state::BookmarksState,
+    ui::Theme,
+    util::format_relative_time,
+    views::Component,
+};
+
+/// Topics bookmarked locally, optionally narrowed to one tag
+pub struct BookmarksView;
+
+impl BookmarksView {
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        bookmarks: &[&Bookmark],
+        state: &BookmarksState,
+        theme: &Theme,
+    ) -> MouseRegions {
+        let title = match &state.tag {
+            Some(tag) => format!(" Bookmarks #{} ({}) - t next tag ", tag, bookmarks.len()),
+            None => format!(" Bookmarks ({}) - t filter by tag ", bookmarks.len()),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary))
+            .title(title);
+
+        if bookmarks.is_empty() {
+            let empty = Paragraph::new("No bookmarks yet. Press M in a topic to bookmark it.")
+                .style(Style::default().fg(theme.muted))
+                .block(block);
+            frame.render_widget(empty, area);
+            return MouseRegions::default();
+        }
+
+        let items: Vec<ListItem> = bookmarks
+            .iter()
+            .map(|bookmark| {
+                let mut spans = Vec::new();
+                if !bookmark.node.is_empty() {
+                    spans.push(Span::styled(
+                        format!("[{}] ", bookmark.node),
+                        Style::default().fg(theme.accent),
+                    ));
+                }
+                spans.push(Span::styled(
+                    bookmark.title.clone(),
+                    Style::default().fg(theme.foreground),
+                ));
+                for tag in &bookmark.tags {
+                    spans.push(Span::styled(
+                        format!(" #{}", tag),
+                        Style::default().fg(theme.secondary),
+                    ));
+                }
+                spans.push(Span::styled(
+                    format!("  {}", format_relative_time(bookmark.added_at)),
+                    Style::default().fg(theme.muted),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items).block(block).highlight_style(
+            Style::default()
+                .bg(theme.primary)
+                .fg(theme.background)
+                .add_modifier(Modifier::BOLD),
+        );
+        let mut list_state = ListState::default();
+        list_state.select(Some(state.selected));
+        frame.render_stateful_widget(list, area, &mut list_state);
+
+        MouseRegions::with_list(ListRegion::bordered_with_heights(
+            area,
+            list_state.offset(),
+            vec![1; bookmarks.len()],
+        ))
+    }
+}
+
+impl Component for BookmarksView {
+    fn name(&self) -> &'static str {
+        "BookmarksView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&BookmarksKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        self.render(
+            frame,
+            area,
+            &app.visible_bookmarks(),
+            &app.bookmarks_state,
+            &app.ui_state.theme,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn draw(bookmarks: &[&Bookmark], state: &BookmarksState) -> (String, MouseRegions) {
+        let mut terminal = Terminal::new(TestBackend::new(60, 6)).unwrap();
+        let mut regions = MouseRegions::default();
+        terminal
+            .draw(|frame| {
+                regions =
+                    BookmarksView.render(frame, frame.area(), bookmarks, state, &Theme::default());
+            })
+            .unwrap();
+        let screen = crate::views::screen_rows(terminal.backend().buffer()).join("\n");
+        (screen, regions)
+    }
+
+    #[test]
+    fn test_bookmarks_show_node_tags_and_filter() {
+        let bookmark = Bookmark {
+            id: 1,
+            title: "Rust 2024".to_string(),
+            node: "rust".to_string(),
+            tags: vec!["lang".to_string()],
+            added_at: 0,
+        };
+        let state = BookmarksState {
+            selected: 0,
+            tag: Some("lang".to_string()),
+        };
+        let (screen, regions) = draw(&[&bookmark], &state);
+        assert!(screen.contains("Bookmarks #lang (1)"));
+        assert!(screen.contains("[rust] Rust 2024 #lang"));
+        assert_eq!(regions.list.unwrap().item_at(1, 1), Some(0));
+
+        let (screen, regions) = draw(&[], &BookmarksState::default());
+        assert!(screen.contains("No bookmarks yet"));
+        assert!(regions.list.is_none());
+    }
+}
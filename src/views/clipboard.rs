@@ -0,0 +1,95 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::{state::ClipboardHistory, ui::centered_rect, ui::Theme};
+
+/// Popup listing recent copies over whatever view is open
+pub struct ClipboardView;
+
+impl ClipboardView {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, history: &ClipboardHistory, theme: &Theme) {
+        let area = centered_rect(70, 60, area);
+        let items: Vec<ListItem> = history
+            .entries()
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let style = if i == history.selected {
+                    Style::default()
+                        .bg(theme.primary)
+                        .fg(theme.background)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.foreground)
+                };
+                let preview = entry
+                    .text
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .unwrap_or("");
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", entry.label), style),
+                    Span::styled(preview.to_string(), Style::default().fg(theme.muted)),
+                ]))
+            })
+            .collect();
+
+        let title = if items.is_empty() {
+            " Clipboard History (nothing copied yet) ".to_string()
+        } else {
+            " Clipboard History - RET copy, d delete, q close ".to_string()
+        };
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .style(Style::default().bg(theme.background))
+                .title(title),
+        );
+
+        // Scrolled so the selection stays in view in a short terminal
+        let mut list_state = ListState::default();
+        list_state.select(Some(history.selected));
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut list_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_selection_scrolls_into_view() {
+        let mut history = ClipboardHistory::default();
+        for i in 0..20 {
+            history.push(format!("entry-{:02}", i), format!("text {}", i));
+        }
+        // The oldest entry, last in the list
+        history.selected = history.entries().len() - 1;
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+        terminal
+            .draw(|frame| {
+                ClipboardView::new().render(frame, frame.area(), &history, &Theme::default())
+            })
+            .unwrap();
+
+        let screen = crate::views::screen_rows(terminal.backend().buffer()).join("\n");
+        assert!(screen.contains("entry-00"));
+        assert!(!screen.contains("entry-19"));
+    }
+}
@@ -0,0 +1,91 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::{state::CodePicker, ui::centered_rect, ui::Theme};
+
+/// Popup numbering the code blocks of a post, to copy one
+pub struct CodePickerView;
+
+impl CodePickerView {
+    pub fn render(&self, frame: &mut Frame, area: Rect, picker: &CodePicker, theme: &Theme) {
+        let area = centered_rect(70, 60, area);
+        let items: Vec<ListItem> = picker
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let style = if i == picker.selected {
+                    Style::default()
+                        .bg(theme.primary)
+                        .fg(theme.background)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.foreground)
+                };
+                let first_line = block
+                    .lines()
+                    .find(|line| !line.trim().is_empty())
+                    .unwrap_or("");
+                let lines = block.lines().count();
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:>2}. ", i + 1), Style::default().fg(theme.accent)),
+                    Span::styled(first_line.to_string(), style),
+                    Span::styled(
+                        format!("  ({} line{})", lines, if lines == 1 { "" } else { "s" }),
+                        Style::default().fg(theme.muted),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .style(Style::default().bg(theme.background))
+                .title(format!(
+                    " Code in {} - 1-9 or RET copy, q close ",
+                    picker.label
+                )),
+        );
+
+        // Scrolled so the selection stays in view in a short terminal
+        let mut list_state = ListState::default();
+        list_state.select(Some(picker.selected));
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut list_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_selection_scrolls_into_view() {
+        let mut picker = CodePicker::default();
+        picker.open(
+            "the topic".to_string(),
+            (0..15)
+                .map(|i| format!("let block_{:02} = {};", i, i))
+                .collect(),
+        );
+        picker.selected = 14;
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+        terminal
+            .draw(|frame| CodePickerView.render(frame, frame.area(), &picker, &Theme::default()))
+            .unwrap();
+
+        let screen = crate::views::screen_rows(terminal.backend().buffer()).join("\n");
+        assert!(screen.contains("15. let block_14"));
+        assert!(!screen.contains("block_00"));
+    }
+}
@@ -0,0 +1,141 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::keymap::{FavoritesKeyMap, KeyMap};
+use crate::{
+    api::Topic,
+    app::App,
+    config::CountFormat,
+    mouse::{ListRegion, MouseRegions},
+    ui::Theme,
+    util::{format_count, format_relative_time},
+    views::Component,
+};
+
+pub struct FavoritesView;
+
+impl FavoritesView {
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        topics: &[Topic],
+        selected: usize,
+        counts: CountFormat,
+        theme: &Theme,
+    ) -> MouseRegions {
+        let items: Vec<ListItem> = topics
+            .iter()
+            .enumerate()
+            .map(|(i, topic)| {
+                let style = if i == selected {
+                    Style::default()
+                        .bg(theme.primary)
+                        .fg(theme.background)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.foreground)
+                };
+
+                let line = Line::from(vec![
+                    Span::styled(
+                        format!("[{}] ", topic.node_title()),
+                        Style::default().fg(theme.secondary),
+                    ),
+                    Span::styled(topic.title.clone(), style),
+                    Span::styled(
+                        format!(" ({} replies)", format_count(topic.replies, counts)),
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::styled(
+                        format!(" • {}", format_relative_time(topic.created)),
+                        Style::default().fg(theme.muted),
+                    ),
+                ]);
+
+                ListItem::new(line)
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .title(format!(" Favorites [{}] ", topics.len())),
+        );
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(selected));
+        frame.render_stateful_widget(list, area, &mut list_state);
+
+        MouseRegions::with_list(ListRegion::bordered(
+            area,
+            list_state.offset(),
+            topics.len(),
+        ))
+    }
+}
+
+impl Component for FavoritesView {
+    fn name(&self) -> &'static str {
+        "FavoritesView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&FavoritesKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        self.render(
+            frame,
+            area,
+            &app.favorites_state.topics,
+            app.favorites_state.selected,
+            app.config.count_format,
+            &app.ui_state.theme,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_clicks_follow_the_scrolled_list() {
+        let topics: Vec<Topic> = (0..10)
+            .map(|id| {
+                serde_json::from_value(serde_json::json!({
+                    "id": id, "title": format!("Topic {}", id), "created": 0, "replies": 0
+                }))
+                .unwrap()
+            })
+            .collect();
+        let mut terminal = Terminal::new(TestBackend::new(40, 6)).unwrap();
+        let mut regions = MouseRegions::default();
+        terminal
+            .draw(|frame| {
+                regions = FavoritesView.render(
+                    frame,
+                    frame.area(),
+                    &topics,
+                    8,
+                    CountFormat::default(),
+                    &Theme::default(),
+                );
+            })
+            .unwrap();
+
+        // Four rows fit, so the list scrolled to show topic 8 at the bottom
+        let list = regions.list.unwrap();
+        assert_eq!(list.item_at(1, 1), Some(5));
+        assert_eq!(list.item_at(1, 4), Some(8));
+    }
+}
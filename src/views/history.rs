@@ -0,0 +1,99 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::{ui::centered_rect, ui::Theme};
+
+/// Popup listing the view history, newest first, over whatever view is open
+pub struct HistoryView;
+
+impl HistoryView {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `labels` are oldest first; `position` is the entry on screen and
+    /// `selected` the one the cursor is on
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        labels: &[String],
+        position: usize,
+        selected: usize,
+        theme: &Theme,
+    ) {
+        let area = centered_rect(70, 60, area);
+        let items: Vec<ListItem> = labels
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, label)| {
+                let style = if i == selected {
+                    Style::default()
+                        .bg(theme.primary)
+                        .fg(theme.background)
+                        .add_modifier(Modifier::BOLD)
+                } else if i > position {
+                    // Forward history, reachable with r
+                    Style::default().fg(theme.muted)
+                } else {
+                    Style::default().fg(theme.foreground)
+                };
+                let marker = if i == position { "▶ " } else { "  " };
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(marker, Style::default().fg(theme.accent)),
+                    Span::styled(label.clone(), style),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .style(Style::default().bg(theme.background))
+                .title(" History - RET go, q close "),
+        );
+        let mut state = ListState::default();
+        state.select(Some(
+            labels.len().saturating_sub(1).saturating_sub(selected),
+        ));
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_history_lists_newest_first() {
+        let labels = [
+            "Topics".to_string(),
+            "Topic 1".to_string(),
+            "Help".to_string(),
+        ];
+        let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+        terminal
+            .draw(|frame| {
+                HistoryView::new().render(frame, frame.area(), &labels, 1, 1, &Theme::default())
+            })
+            .unwrap();
+
+        let rows: Vec<String> = crate::views::screen_rows(terminal.backend().buffer())
+            .into_iter()
+            .filter(|row| row.contains("Topic") || row.contains("Help"))
+            .map(|row| row.trim_matches(|c: char| c == ' ' || c == '│').to_string())
+            .collect();
+        assert_eq!(rows, ["Help", "▶ Topic 1", "Topics"]);
+    }
+}
@@ -0,0 +1,217 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::keymap::{KeyMap, MemberKeyMap};
+use crate::{
+    api::{Member, MemberReply, Topic},
+    app::App,
+    config::CountFormat,
+    mouse::{ListRegion, MouseRegions},
+    state::{MemberList, MemberState},
+    ui::Theme,
+    util::{format_count, format_relative_time},
+    views::{profile::ProfileView, Component},
+};
+
+pub struct MemberView;
+
+impl MemberView {
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &MemberState,
+        member: &Member,
+        counts: CountFormat,
+        theme: &Theme,
+    ) -> MouseRegions {
+        let profile_lines = ProfileView::new().lines(member, theme);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(profile_lines.len() as u16 + 2),
+                Constraint::Min(3),
+            ])
+            .split(area);
+
+        let profile = Paragraph::new(Text::from(profile_lines)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .title(format!(" Member: {} ", member.username)),
+        );
+        frame.render_widget(profile, chunks[0]);
+
+        let selected_style = |i: usize| {
+            if i == state.selected {
+                Style::default()
+                    .bg(theme.primary)
+                    .fg(theme.background)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.foreground)
+            }
+        };
+        let (items, heights, title): (Vec<ListItem>, Vec<u16>, String) = match state.showing {
+            MemberList::Topics => (
+                state
+                    .topics
+                    .iter()
+                    .enumerate()
+                    .map(|(i, topic)| topic_item(topic, selected_style(i), counts, theme))
+                    .collect(),
+                vec![1; state.topics.len()],
+                format!(" Recent Topics [{}] · TAB: replies ", state.topics.len()),
+            ),
+            MemberList::Replies => (
+                state
+                    .replies
+                    .iter()
+                    .enumerate()
+                    .map(|(i, reply)| reply_item(reply, selected_style(i), theme))
+                    .collect(),
+                vec![2; state.replies.len()],
+                format!(" Recent Replies [{}] · TAB: topics ", state.replies.len()),
+            ),
+        };
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .title(title),
+        );
+        let mut list_state = ListState::default();
+        list_state.select(Some(state.selected));
+        frame.render_stateful_widget(list, chunks[1], &mut list_state);
+
+        MouseRegions::with_list(ListRegion::bordered_with_heights(
+            chunks[1],
+            list_state.offset(),
+            heights,
+        ))
+    }
+}
+
+fn topic_item(
+    topic: &Topic,
+    style: Style,
+    counts: CountFormat,
+    theme: &Theme,
+) -> ListItem<'static> {
+    ListItem::new(Line::from(vec![
+        Span::styled(
+            format!("[{}] ", topic.node_title()),
+            Style::default().fg(theme.secondary),
+        ),
+        Span::styled(topic.title.clone(), style),
+        Span::styled(
+            format!(" ({} replies)", format_count(topic.replies, counts)),
+            Style::default().fg(theme.accent),
+        ),
+        Span::styled(
+            format!(" • {}", format_relative_time(topic.created)),
+            Style::default().fg(theme.muted),
+        ),
+    ]))
+}
+
+/// The topic replied to, then the start of the reply
+fn reply_item(reply: &MemberReply, style: Style, theme: &Theme) -> ListItem<'static> {
+    let when = reply
+        .created
+        .map(format_relative_time)
+        .unwrap_or_else(|| reply.ago.clone());
+    let first_line = reply.content.lines().next().unwrap_or_default();
+    ListItem::new(vec![
+        Line::from(vec![
+            Span::styled(
+                format!("[{}] ", reply.node.as_deref().unwrap_or("?")),
+                Style::default().fg(theme.secondary),
+            ),
+            Span::styled(reply.topic_title.clone(), style),
+            Span::styled(format!(" • {}", when), Style::default().fg(theme.muted)),
+        ]),
+        Line::from(Span::styled(
+            format!("  {}", first_line),
+            Style::default().fg(theme.foreground),
+        )),
+    ])
+}
+
+impl Component for MemberView {
+    fn name(&self) -> &'static str {
+        "MemberView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&MemberKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        let state = &app.member_state;
+        let Some(member) = &state.member else {
+            return MouseRegions::default();
+        };
+        self.render(
+            frame,
+            area,
+            state,
+            member,
+            app.config.count_format,
+            &app.ui_state.theme,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_member_view_switches_to_replies() {
+        let reply = |topic_id, title: &str, content: &str| MemberReply {
+            topic_id,
+            topic_title: title.to_string(),
+            node: Some("python".to_string()),
+            content: content.to_string(),
+            created: None,
+            ago: "3 天前".to_string(),
+        };
+        let mut state = MemberState {
+            member: Some(
+                serde_json::from_value(serde_json::json!({ "username": "alice" })).unwrap(),
+            ),
+            replies: vec![reply(1, "Asyncio", "Use the docs"), reply(2, "GIL", "+1")],
+            ..Default::default()
+        };
+        state.toggle_list();
+        state.next();
+        assert_eq!(state.selected_topic_id(), Some(2));
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal
+            .draw(|frame| {
+                let member = state.member.as_ref().unwrap();
+                MemberView.render(
+                    frame,
+                    frame.area(),
+                    &state,
+                    member,
+                    CountFormat::default(),
+                    &Theme::default(),
+                );
+            })
+            .unwrap();
+        let screen = crate::views::screen_rows(terminal.backend().buffer()).join("\n");
+        assert!(screen.contains("Recent Replies [2]"));
+        assert!(screen.contains("[python] Asyncio"));
+        assert!(screen.contains("Use the docs"));
+    }
+}
@@ -1,7 +1,168 @@
+//! The screens of the app. Every [`View`] is a [`Component`] that draws
+//! what it shows from the [`App`], names its [`KeyMap`] and runs the
+//! actions bound while it is shown; [`component`] says which, so adding a
+//! view touches neither `App::render` nor `EventHandler`. A view's state
+//! stays on `App`, next to the loads that fill it.
+
 pub mod aggregate;
+pub mod bookmarks;
+pub mod clipboard;
+pub mod code_picker;
+pub mod favorites;
 pub mod help;
+pub mod history;
+pub mod member;
+pub mod node_info;
 pub mod node_select;
 pub mod notifications;
 pub mod profile;
+pub mod reply_input;
+pub mod token_input;
+pub mod tokens;
 pub mod topic_detail;
 pub mod topic_list;
+pub mod topic_preview;
+pub mod translation;
+pub mod visited_topics;
+
+use anyhow::Result;
+use ratatui::{layout::Rect, Frame};
+
+use crate::api::V2exClient;
+use crate::app::{App, View};
+use crate::keymap::{parse_key_sequence, same_keys, Action, KeyMap};
+use crate::mouse::MouseRegions;
+
+/// What a component did with an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handled {
+    /// It ran
+    Done,
+    /// It ran and quits the app
+    Quit,
+    /// Not the view's to run as a whole: its keys are pressed one at a
+    /// time, through the keys every view shares and then the keymap
+    Pass,
+}
+
+impl Handled {
+    fn from_quit(quit: bool) -> Self {
+        if quit {
+            Self::Quit
+        } else {
+            Self::Done
+        }
+    }
+}
+
+/// A screen: how it is drawn from the app's state and what its keys do
+pub trait Component {
+    /// Label of its render scope in profiler reports
+    fn name(&self) -> &'static str;
+
+    /// The built-in keys of the view; `None` when main.rs reads them
+    fn keymap(&self) -> Option<&'static dyn KeyMap>;
+
+    /// Draw into `area`, returning where mouse clicks land
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions;
+
+    /// Run an action bound with `define-key` while the view is shown. A
+    /// `define-action` action and a key sequence the keymap takes as a
+    /// whole run here; other keys are passed back to be pressed in turn.
+    fn handle_action(
+        &mut self,
+        action: &Action,
+        app: &mut App,
+        client: &V2exClient,
+    ) -> Result<Handled> {
+        match action {
+            Action::Keys(keys) => match self.keymap() {
+                Some(keymap) if keys.len() > 1 && handles_sequence(keymap, keys) => keymap
+                    .handle_sequence(app, keys, client)
+                    .map(Handled::from_quit),
+                _ => Ok(Handled::Pass),
+            },
+            Action::Custom(name) => {
+                app.run_custom_action(client, name);
+                Ok(Handled::Done)
+            }
+        }
+    }
+}
+
+/// Whether `keys` are one of the sequences `keymap` handles itself
+fn handles_sequence(keymap: &dyn KeyMap, keys: &[crossterm::event::KeyEvent]) -> bool {
+    keymap
+        .sequences()
+        .iter()
+        .filter_map(|notation| parse_key_sequence(notation).ok())
+        .any(|sequence| same_keys(&sequence, keys))
+}
+
+/// The component of `view`. Components keep no state of their own, so
+/// this is called whenever one is needed; boxing them allocates nothing.
+pub fn component(view: View) -> Box<dyn Component> {
+    match view {
+        View::TopicList => Box::new(topic_list::TopicListView),
+        View::TopicDetail => Box::new(topic_detail::TopicDetailView),
+        View::Notifications => Box::new(notifications::NotificationsView),
+        View::Profile => Box::new(profile::ProfileView),
+        View::Help => Box::new(help::HelpView),
+        View::NodeSelect => Box::new(node_select::NodeSelectView),
+        View::TokenInput => Box::new(token_input::TokenInputView),
+        View::Aggregate => Box::new(aggregate::AggregateView),
+        View::ReplyInput => Box::new(reply_input::ReplyInputView),
+        View::Favorites => Box::new(favorites::FavoritesView),
+        View::Member => Box::new(member::MemberView),
+        View::History => Box::new(visited_topics::VisitedTopicsView),
+        View::Bookmarks => Box::new(bookmarks::BookmarksView),
+        View::Tokens => Box::new(tokens::TokensView),
+    }
+}
+
+/// The text on each row of a test terminal, for checking what was drawn
+#[cfg(test)]
+pub fn screen_rows(buffer: &ratatui::buffer::Buffer) -> Vec<String> {
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_for_view() {
+        assert_eq!(component(View::Bookmarks).name(), "BookmarksView::render");
+        assert!(component(View::Bookmarks).keymap().is_some());
+        assert!(component(View::TokenInput).keymap().is_none());
+    }
+
+    #[test]
+    fn test_handle_action() {
+        let client = V2exClient::new(String::new()).offline();
+        let mut app = crate::app::App::new(crate::config::Config::default());
+        let keys = |notation| Action::Keys(parse_key_sequence(notation).unwrap());
+        let mut detail = component(View::TopicDetail);
+
+        // A sequence of the view's own runs as a whole
+        let handled = detail.handle_action(&keys("C-w x"), &mut app, &client);
+        assert_eq!(handled.unwrap(), Handled::Done);
+        assert!(app.ui_state.split.swapped);
+        // Other keys are pressed one by one by the caller
+        let handled = detail.handle_action(&keys("n"), &mut app, &client);
+        assert_eq!(handled.unwrap(), Handled::Pass);
+        let handled = detail.handle_action(&keys("C-x C-s"), &mut app, &client);
+        assert_eq!(handled.unwrap(), Handled::Pass);
+
+        let custom = Action::Custom("missing".to_string());
+        let handled = component(View::TokenInput).handle_action(&custom, &mut app, &client);
+        assert_eq!(handled.unwrap(), Handled::Done);
+        assert_eq!(app.ui_state.status_message, "Unknown action 'missing'");
+    }
+}
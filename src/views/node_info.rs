@@ -0,0 +1,118 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    api::Node,
+    render,
+    ui::{centered_rect, Theme},
+    util::format_absolute_time,
+};
+
+/// Popup with everything V2EX says about the current node
+pub struct NodeInfoView;
+
+impl NodeInfoView {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, node: &Node, theme: &Theme) {
+        let area = centered_rect(70, 60, area);
+        let width = area.width.saturating_sub(2) as usize;
+        let label = Style::default().fg(theme.muted);
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    node.title.clone(),
+                    Style::default()
+                        .fg(theme.primary)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("  /go/{}", node.name), label),
+            ]),
+            Line::from(""),
+        ];
+        if let Some(alternative) = node.title_alternative.as_deref() {
+            if !alternative.is_empty() && alternative != node.title {
+                lines.push(Line::from(vec![
+                    Span::styled("Also known as: ", label),
+                    Span::raw(alternative.to_string()),
+                ]));
+            }
+        }
+        lines.push(Line::from(vec![
+            Span::styled("Topics: ", label),
+            Span::styled(node.topics.to_string(), Style::default().fg(theme.accent)),
+        ]));
+        if node.created > 0 {
+            lines.push(Line::from(vec![
+                Span::styled("Created: ", label),
+                Span::raw(format_absolute_time(node.created)),
+            ]));
+        }
+        if !node.url.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("URL: ", label),
+                Span::raw(node.url.clone()),
+            ]));
+        }
+        for html in [&node.header, &node.footer].into_iter().flatten() {
+            if !html.trim().is_empty() {
+                lines.push(Line::from(""));
+                lines.extend(render::styled(html, width, theme).iter().cloned());
+            }
+        }
+
+        let popup = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .style(Style::default().fg(theme.foreground))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .style(Style::default().bg(theme.background))
+                    .title(" Node - any key closes "),
+            );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_node_info_shows_details() {
+        let node: Node = serde_json::from_value(serde_json::json!({
+            "id": 90,
+            "name": "python",
+            "title": "Python",
+            "title_alternative": "Python",
+            "topics": 15000,
+            "header": "<p>All things <b>Python</b></p>",
+        }))
+        .unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal
+            .draw(|frame| NodeInfoView::new().render(frame, frame.area(), &node, &Theme::default()))
+            .unwrap();
+
+        let screen = crate::views::screen_rows(terminal.backend().buffer()).join("\n");
+        assert!(screen.contains("Python  /go/python"));
+        assert!(screen.contains("Topics: 15000"));
+        assert!(screen.contains("All things Python"));
+        // An alternative title that repeats the title says nothing new
+        assert!(!screen.contains("Also known as"));
+        // Nor does a creation time V2EX left out
+        assert!(!screen.contains("Created:"));
+    }
+}
@@ -2,19 +2,23 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
-use crate::ui::Theme;
+use crate::keymap::{KeyMap, NodeSelectKeyMap};
+use crate::{
+    app::{App, View},
+    keymap::{prompt_hints, NODE_COMPLETION_HINTS},
+    minibuffer::Minibuffer,
+    mouse::{ListRegion, MouseRegions},
+    ui::Theme,
+    views::Component,
+};
 
 pub struct NodeSelectView;
 
 impl NodeSelectView {
-    pub fn new() -> Self {
-        Self
-    }
-
     #[allow(clippy::too_many_arguments)] // Required for all the parameters
     pub fn render(
         &self,
@@ -23,21 +27,26 @@ impl NodeSelectView {
         nodes: &[(String, String)],
         selected: usize,
         current_node: &str,
-        completion_input: &str,
-        _completion_cursor: usize,
+        prompt: &Minibuffer,
         is_completion_mode: bool,
+        hints: &str,
         theme: &Theme,
-    ) {
+    ) -> MouseRegions {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(3), Constraint::Min(0)])
             .split(area);
 
-        // Input field
+        // Input field; an empty prompt lists its editing keys
         let input_text = if is_completion_mode {
-            format!("> {}", completion_input)
+            let mut line = prompt.line(theme);
+            if prompt.is_empty() {
+                line.spans
+                    .push(Span::styled(hints, Style::default().fg(theme.muted)));
+            }
+            line
         } else {
-            format!("Current node: {}", current_node)
+            Line::from(format!("Current node: {}", current_node))
         };
 
         let input = Paragraph::new(input_text).block(
@@ -88,7 +97,43 @@ impl NodeSelectView {
                     .add_modifier(Modifier::BOLD),
             );
 
-        frame.render_widget(list, chunks[1]);
+        let mut list_state = ListState::default();
+        list_state.select(Some(selected));
+        frame.render_stateful_widget(list, chunks[1], &mut list_state);
+
+        MouseRegions::with_list(ListRegion::bordered(
+            chunks[1],
+            list_state.offset(),
+            nodes.len(),
+        ))
+    }
+}
+
+impl Component for NodeSelectView {
+    fn name(&self) -> &'static str {
+        "NodeSelectView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&NodeSelectKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        self.render(
+            frame,
+            area,
+            &app.node_state.favorite_nodes,
+            app.node_state.selected,
+            &app.node_state.current_node,
+            &app.node_state.prompt,
+            app.node_state.is_completion_mode,
+            &prompt_hints(
+                &app.config.key_bindings,
+                View::NodeSelect,
+                NODE_COMPLETION_HINTS,
+            ),
+            &app.ui_state.theme,
+        )
     }
 }
 
@@ -98,7 +143,7 @@ mod tests {
 
     #[test]
     fn test_node_select_view_new() {
-        let _view = NodeSelectView::new();
+        let _view = NodeSelectView;
         // Simple test to verify the view can be created
         assert!(true); // Placeholder assertion
     }
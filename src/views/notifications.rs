@@ -1,41 +1,50 @@
 use ratatui::{
     layout::Rect,
-    style::Style,
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
 
-use crate::{api::Notification, ui::Theme};
+use crate::keymap::{KeyMap, NotificationsKeyMap};
+use crate::{
+    api::Notification,
+    app::App,
+    mouse::{ListRegion, MouseRegions},
+    profiler,
+    read_tracker::ReadTracker,
+    render,
+    ui::Theme,
+    views::Component,
+};
 
 pub struct NotificationsView;
 
 impl NotificationsView {
-    pub fn new() -> Self {
-        Self
-    }
-
     pub fn render(
         &self,
         frame: &mut Frame,
         area: Rect,
         notifications: &[Notification],
         selected: usize,
+        read: &ReadTracker,
         theme: &Theme,
-    ) {
+    ) -> MouseRegions {
+        let items_scope = profiler::scope("notification list html");
         let items: Vec<ListItem> = notifications
             .iter()
             .enumerate()
             .map(|(i, notif)| {
-                let style = if i == selected {
+                let mut style = if i == selected {
                     Style::default().bg(theme.primary).fg(theme.background)
                 } else {
                     Style::default().fg(theme.foreground)
                 };
+                if !read.is_read(notif.id) {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
 
-                let raw_text = notif.text.clone();
-                // Convert HTML to plain text
-                let text = html2text::from_read(raw_text.as_bytes(), 80);
+                let text = render::plain(&notif.text, 80);
 
                 let body = notif
                     .payload
@@ -61,14 +70,49 @@ impl NotificationsView {
             })
             .collect();
 
+        drop(items_scope);
+
         let list = List::new(items).block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(theme.primary))
-                .title(format!(" Notifications [{}] ", notifications.len())),
+                .title(format!(
+                    " Notifications [{}, {} unread] ",
+                    notifications.len(),
+                    notifications.iter().filter(|n| !read.is_read(n.id)).count()
+                )),
         );
 
-        frame.render_widget(list, area);
+        let mut list_state = ListState::default();
+        list_state.select(Some(selected));
+        frame.render_stateful_widget(list, area, &mut list_state);
+
+        MouseRegions::with_list(ListRegion::bordered(
+            area,
+            list_state.offset(),
+            notifications.len(),
+        ))
+    }
+}
+
+impl Component for NotificationsView {
+    fn name(&self) -> &'static str {
+        "NotificationsView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&NotificationsKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        self.render(
+            frame,
+            area,
+            &app.notification_state.notifications,
+            app.notification_state.selected,
+            &app.read_notifications,
+            &app.ui_state.theme,
+        )
     }
 }
 
@@ -78,7 +122,7 @@ mod tests {
 
     #[test]
     fn test_notifications_view_new() {
-        let _view = NotificationsView::new();
+        let _view = NotificationsView;
         // Simple test to verify the view can be created
         assert!(true); // Placeholder assertion
     }
@@ -6,7 +6,8 @@ use ratatui::{
     Frame,
 };
 
-use crate::{api::Member, ui::Theme};
+use crate::keymap::{KeyMap, ProfileKeyMap};
+use crate::{api::Member, app::App, mouse::MouseRegions, ui::Theme, views::Component};
 
 pub struct ProfileView;
 
@@ -16,7 +17,19 @@ impl ProfileView {
     }
 
     pub fn render(&self, frame: &mut Frame, area: Rect, member: &Member, theme: &Theme) {
-        let lines = vec![
+        let profile = Paragraph::new(Text::from(self.lines(member, theme))).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .title(" Profile "),
+        );
+
+        frame.render_widget(profile, area);
+    }
+
+    /// Profile fields, one per line
+    pub fn lines<'a>(&self, member: &'a Member, theme: &Theme) -> Vec<Line<'a>> {
+        vec![
             Line::from(vec![
                 Span::styled("Username: ", Style::default().fg(theme.primary)),
                 Span::styled(
@@ -72,16 +85,24 @@ impl ProfileView {
                     Style::default().fg(theme.secondary),
                 ),
             ]),
-        ];
+        ]
+    }
+}
 
-        let profile = Paragraph::new(Text::from(lines)).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.primary))
-                .title(" Profile "),
-        );
+impl Component for ProfileView {
+    fn name(&self) -> &'static str {
+        "ProfileView::render"
+    }
 
-        frame.render_widget(profile, area);
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&ProfileKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        if let Some(profile) = &app.profile {
+            self.render(frame, area, profile, &app.ui_state.theme);
+        }
+        MouseRegions::default()
     }
 }
 
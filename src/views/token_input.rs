@@ -0,0 +1,25 @@
+use ratatui::{layout::Rect, Frame};
+
+use super::Component;
+use crate::app::App;
+use crate::keymap::KeyMap;
+use crate::mouse::MouseRegions;
+use crate::ui::render_token_input;
+
+/// The prompt asking for a personal access token
+pub struct TokenInputView;
+
+impl Component for TokenInputView {
+    fn name(&self) -> &'static str {
+        "render_token_input"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        None
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        render_token_input(frame, area, &app.token_state.prompt, &app.ui_state.theme);
+        MouseRegions::default()
+    }
+}
@@ -0,0 +1,159 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::keymap::{KeyMap, TokensKeyMap};
+use crate::{
+    api::TokenInfo,
+    app::App,
+    mouse::{ListRegion, MouseRegions},
+    state::TokenState,
+    ui::Theme,
+    util::format_relative_time,
+    views::Component,
+};
+
+/// The saved tokens, one per account, with what `/token` said about them
+pub struct TokensView;
+
+impl TokensView {
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &TokenState,
+        theme: &Theme,
+    ) -> MouseRegions {
+        let tokens = state.store.tokens();
+        let items: Vec<ListItem> = tokens
+            .iter()
+            .map(|token| {
+                let active = state.store.is_active(&token.name);
+                let mut first = vec![
+                    Span::styled(
+                        if active { "* " } else { "  " },
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::styled(
+                        token.name.clone(),
+                        Style::default()
+                            .fg(theme.foreground)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("  {}", token.masked()),
+                        Style::default().fg(theme.muted),
+                    ),
+                ];
+                if active {
+                    first.push(Span::styled("  in use", Style::default().fg(theme.accent)));
+                }
+                let details = match state.info.get(&token.name) {
+                    Some(Ok(info)) => {
+                        Span::styled(describe(info), Style::default().fg(theme.secondary))
+                    }
+                    Some(Err(e)) => Span::styled(e.clone(), Style::default().fg(theme.error)),
+                    None => Span::styled("not checked", Style::default().fg(theme.muted)),
+                };
+                ListItem::new(vec![
+                    Line::from(first),
+                    Line::from(vec![Span::raw("    "), details]),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .title(format!(
+                        " Tokens ({}) - RET switch, a add, d remove, g check ",
+                        tokens.len()
+                    )),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.primary)
+                    .fg(theme.background)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let mut list_state = ListState::default();
+        list_state.select((!tokens.is_empty()).then_some(state.selected));
+        frame.render_stateful_widget(list, area, &mut list_state);
+
+        MouseRegions::with_list(ListRegion::bordered_with_heights(
+            area,
+            list_state.offset(),
+            vec![2; tokens.len()],
+        ))
+    }
+}
+
+/// Scope, expiry and use of a token, on one line
+fn describe(info: &TokenInfo) -> String {
+    let now = chrono::Utc::now().timestamp();
+    let expiry = if info.expiration <= 0 {
+        "no expiry".to_string()
+    } else if info.created + info.expiration <= now {
+        "expired".to_string()
+    } else {
+        let days = (info.created + info.expiration - now) / 86400;
+        format!("expires in {} days", days)
+    };
+    let last_used = match info.last_used {
+        Some(time) => format!(", last used {}", format_relative_time(time)),
+        None => String::new(),
+    };
+    format!(
+        "scope {}, {}, used {} times{}",
+        info.scope, expiry, info.total_used, last_used
+    )
+}
+
+impl Component for TokensView {
+    fn name(&self) -> &'static str {
+        "TokensView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&TokensKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        self.render(frame, area, &app.token_state, &app.ui_state.theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_token() {
+        let now = chrono::Utc::now().timestamp();
+        let info = TokenInfo {
+            token: String::new(),
+            scope: "everything".to_string(),
+            expiration: 30 * 86400,
+            good_for_days: 30,
+            total_used: 12,
+            last_used: None,
+            last_use_ip: None,
+            created: now - 86400 - 60,
+        };
+        assert_eq!(
+            describe(&info),
+            "scope everything, expires in 28 days, used 12 times"
+        );
+        let expired = TokenInfo {
+            created: now - 31 * 86400,
+            ..info
+        };
+        assert!(describe(&expired).contains("expired"));
+    }
+}
@@ -1,12 +1,27 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
     Frame,
 };
-
-use crate::{api::Topic, state::DetectedLink, ui::Theme, util::format_relative_time};
+use unicode_width::UnicodeWidthStr;
+
+use crate::keymap::{KeyMap, TopicDetailKeyMap};
+use crate::{
+    api::{Topic, TopicStats, REPLIES_PER_PAGE},
+    app::App,
+    config::{CountFormat, IgnoreList},
+    mouse::{ListRegion, MouseRegions},
+    profiler, render,
+    state::SearchPattern,
+    ui::Theme,
+    util::{format_count, format_relative_time},
+    views::Component,
+};
 
 pub struct TopicDetailView;
 
@@ -21,12 +36,12 @@ impl TopicDetailView {
         frame: &mut Frame,
         area: Rect,
         topic: &Topic,
+        stats: Option<&TopicStats>,
         scroll: usize,
-        detected_links: &[DetectedLink],
-        is_link_mode_active: bool,
-        parsed_content: Option<&str>,
+        link_labels: Option<&[String]>,
+        counts: CountFormat,
         theme: &Theme,
-    ) {
+    ) -> MouseRegions {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(5), Constraint::Min(5)])
@@ -54,7 +69,11 @@ impl TopicDetailView {
                 Span::styled(" | Node: ", Style::default().fg(theme.muted)),
                 Span::styled(node_name, Style::default().fg(theme.secondary)),
                 Span::styled(
-                    format!(" | Replies: {}", topic.replies),
+                    format!(" | Replies: {}", format_count(topic.replies, counts)),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::styled(
+                    stats_summary(stats, counts),
                     Style::default().fg(theme.muted),
                 ),
             ]),
@@ -83,25 +102,17 @@ impl TopicDetailView {
             .or(topic.content.as_deref())
             .unwrap_or("No content");
 
-        let content_text = if is_link_mode_active {
-            // Use parsed content if available (from link detection)
-            // Otherwise convert with consistent width
-            if let Some(parsed) = parsed_content {
-                parsed.to_string()
-            } else {
-                let width = std::cmp::min(100, area.width as usize);
-                html2text::from_read(content.as_bytes(), width)
-            }
-        } else {
-            html2text::from_read(content.as_bytes(), area.width as usize)
+        let content_scope = profiler::scope("topic content html");
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let lines = match link_labels {
+            Some(labels) => render::labelled(content, inner_width, theme, labels),
+            None => render::styled(content, inner_width, theme),
         };
+        let content_display = Text::from(lines.to_vec());
 
-        // Build text with link highlighting if link mode is active
-        let content_display = if is_link_mode_active && !detected_links.is_empty() {
-            self.build_highlighted_text(&content_text, detected_links, theme)
-        } else {
-            Text::from(content_text)
-        };
+        drop(content_scope);
+        let content_height =
+            wrapped_height(&content_display, chunks[1].width.saturating_sub(2) as usize);
 
         let content_para = Paragraph::new(content_display)
             .block(
@@ -110,106 +121,53 @@ impl TopicDetailView {
                     .border_style(Style::default().fg(theme.secondary))
                     .title(" Content "),
             )
-            .wrap(Wrap { trim: true })
+            // The HTML renderer lays out its own indentation
+            .wrap(Wrap { trim: false })
             .scroll((scroll as u16, 0));
 
         frame.render_widget(content_para, chunks[1]);
+        render_scrollbar(frame, chunks[1], content_height, scroll, theme);
+
+        MouseRegions {
+            content: Some(chunks[1]),
+            ..Default::default()
+        }
     }
 
-    fn build_highlighted_text<'a>(
+    /// Distraction-free reading: just the heading and text in a centred
+    /// column at most `width` cells wide (0 = the whole screen)
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_focus(
         &self,
-        text: &'a str,
-        links: &[DetectedLink],
+        frame: &mut Frame,
+        area: Rect,
+        heading: &str,
+        html: Option<&str>,
+        scroll: usize,
+        width: u16,
         theme: &Theme,
-    ) -> Text<'a> {
-        // Sort links by their start position
-        let mut sorted_links: Vec<&DetectedLink> = links.iter().collect();
-        sorted_links.sort_by_key(|link| link.text_range.start);
-
-        let mut lines = Vec::new();
-        let mut current_byte_pos = 0;
-        let mut link_idx = 0;
-
-        // Split text by newline, keeping the newline character to track positions accurately
-        let line_iter = text.split_inclusive('\n');
-
-        for line_with_newline in line_iter {
-            let line_start = current_byte_pos;
-            let line_len_with_newline = line_with_newline.len();
-            let line_end = line_start + line_len_with_newline;
-
-            // Remove trailing newline for display (ratatui handles line breaks)
-            let line_content = line_with_newline
-                .strip_suffix('\n')
-                .unwrap_or(line_with_newline);
-
-            let mut line_spans = Vec::new();
-            let mut line_last_pos = line_start;
-
-            // Process all links that start within this line
-            while link_idx < sorted_links.len() {
-                let link = sorted_links[link_idx];
-
-                // If link starts at or after the end of this line, move to next line
-                if link.text_range.start >= line_end {
-                    break;
-                }
-
-                // Link should be within this line (URLs shouldn't span multiple lines)
-                if link.text_range.start >= line_start && link.text_range.end <= line_end {
-                    // Add text before link
-                    if link.text_range.start > line_last_pos {
-                        let start_in_line = link.text_range.start - line_start;
-                        let end_in_line = line_last_pos - line_start;
-                        line_spans.push(Span::raw(&line_content[end_in_line..start_in_line]));
-                    }
-
-                    // Add shortcut label
-                    line_spans.push(Span::styled(
-                        format!("[{}] ", link.shortcut),
-                        Style::default()
-                            .fg(theme.primary)
-                            .add_modifier(Modifier::BOLD),
-                    ));
-
-                    // Add link text
-                    let link_start_in_line = link.text_range.start - line_start;
-                    let link_end_in_line = link.text_range.end - line_start;
-                    let link_text = if link_end_in_line <= line_content.len() {
-                        &line_content[link_start_in_line..link_end_in_line]
-                    } else {
-                        // Link includes newline? Shouldn't happen but handle gracefully
-                        &line_content[link_start_in_line..]
-                    };
-                    line_spans.push(Span::styled(
-                        link_text,
-                        Style::default()
-                            .bg(theme.foreground)
-                            .fg(theme.background)
-                            .add_modifier(Modifier::REVERSED),
-                    ));
-
-                    line_last_pos = link.text_range.end;
-                    link_idx += 1;
-                } else {
-                    // Link spans multiple lines or is malformed, skip it
-                    link_idx += 1;
-                }
-            }
-
-            // Add remaining text in line after last link
-            if line_last_pos < line_end {
-                let start_in_line = line_last_pos - line_start;
-                if start_in_line < line_content.len() {
-                    line_spans.push(Span::raw(&line_content[start_in_line..]));
-                }
-            }
-
-            lines.push(Line::from(line_spans));
-            current_byte_pos = line_end;
-        }
+    ) -> Rect {
+        let column = focus_column(area, width);
+        let _scope = profiler::scope("focus html");
+        let body = render::styled(html.unwrap_or(""), column.width as usize, theme);
+
+        let mut lines = vec![
+            Line::from(Span::styled(
+                heading.to_string(),
+                Style::default()
+                    .fg(theme.foreground)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        lines.extend(body.iter().cloned());
 
-        Text::from(lines)
+        let paragraph = Paragraph::new(Text::from(lines))
+            .style(Style::default().fg(theme.foreground))
+            .wrap(Wrap { trim: false })
+            .scroll((scroll as u16, 0));
+        frame.render_widget(paragraph, column);
+        column
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -219,35 +177,50 @@ impl TopicDetailView {
         topic_area: Rect,
         replies_area: Rect,
         topic: &Topic,
+        stats: Option<&TopicStats>,
         scroll: usize,
-        detected_links: &[DetectedLink],
-        is_link_mode_active: bool,
-        parsed_content: Option<&str>,
+        link_labels: Option<&[String]>,
         replies: &[crate::api::Reply],
+        order: &[usize],
+        mode: Option<&str>,
+        search: Option<(&SearchPattern, usize)>,
         list_state: &mut ListState,
+        first_unread: Option<usize>,
+        blocked: Option<&IgnoreList>,
+        counts: CountFormat,
         theme: &Theme,
-    ) {
-        self.render(
+    ) -> MouseRegions {
+        // Link mode numbers the selected reply's links while there is one
+        let (topic_labels, reply_labels) = if replies.is_empty() {
+            (link_labels, None)
+        } else {
+            (None, link_labels)
+        };
+        let mut regions = self.render(
             frame,
             topic_area,
             topic,
+            stats,
             scroll,
-            detected_links,
-            is_link_mode_active,
-            parsed_content,
+            topic_labels,
+            counts,
             theme,
         );
-        self.render_replies(
+        regions.list = Some(self.render_replies(
             frame,
             replies_area,
             topic,
             replies,
+            order,
+            mode,
+            search,
             list_state,
-            detected_links,
-            is_link_mode_active,
-            parsed_content,
+            first_unread,
+            reply_labels,
+            blocked,
             theme,
-        );
+        ));
+        regions
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -257,20 +230,54 @@ impl TopicDetailView {
         area: Rect,
         topic: &Topic,
         replies: &[crate::api::Reply],
+        order: &[usize],
+        mode: Option<&str>,
+        search: Option<(&SearchPattern, usize)>,
         list_state: &mut ListState,
-        _detected_links: &[DetectedLink],
-        _is_link_mode_active: bool,
-        _parsed_content: Option<&str>,
+        first_unread: Option<usize>,
+        link_labels: Option<&[String]>,
+        blocked: Option<&IgnoreList>,
         theme: &Theme,
-    ) {
+    ) -> ListRegion {
         let total_replies = topic.replies as usize;
         let loaded_replies = replies.len();
-        let has_more = loaded_replies < total_replies;
-        let items: Vec<ListItem> = replies
+        let replies_scope = profiler::scope("reply list html");
+        let op = topic.member.as_ref().map(|m| m.username.as_str());
+        let row_width = area.width.saturating_sub(2) as usize;
+        // `order` holds reply indices, so floors stay right when sorted
+        let items: Vec<ListItem> = order
             .iter()
             .enumerate()
-            .map(|(index, reply)| {
-                let is_selected = list_state.selected() == Some(index);
+            .map(|(position, &index)| {
+                let reply = &replies[index];
+                let is_selected = list_state.selected() == Some(position);
+                let mut all_lines = Vec::new();
+                if first_unread == Some(index) {
+                    all_lines.push(Line::styled(
+                        "── new replies below ──",
+                        Style::default()
+                            .fg(theme.accent)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                }
+
+                let blocked_author = reply
+                    .member
+                    .as_ref()
+                    .map(|m| m.username.as_str())
+                    .filter(|name| blocked.is_some_and(|list| list.contains(name)));
+                if let Some(name) = blocked_author {
+                    let line = Line::styled(
+                        format!("#{} [blocked: {}]", index + 1, name),
+                        Style::default().fg(theme.muted),
+                    );
+                    all_lines.push(if is_selected {
+                        highlight_line(line, row_width, theme)
+                    } else {
+                        line
+                    });
+                    return ListItem::new(all_lines);
+                }
 
                 let content_text = reply
                     .content_rendered
@@ -278,49 +285,27 @@ impl TopicDetailView {
                     .or(reply.content.as_deref())
                     .unwrap_or("No content");
 
-                // TODO: Add link highlighting for replies (requires ownership fix in build_highlighted_text)
-                // For now, use normal rendering even in link mode
-                let content = html2text::from_read(
-                    content_text.as_bytes(),
-                    area.width.saturating_sub(4) as usize,
-                );
-
-                let lines: Vec<Line> = content
-                    .lines()
-                    .map(|line| {
-                        if is_selected {
-                            Line::styled(
-                                line.to_string(),
-                                Style::default().bg(theme.primary).fg(theme.background),
-                            )
-                        } else {
-                            Line::from(line.to_string())
-                        }
-                    })
-                    .collect();
-
-                let author = reply
-                    .member
-                    .as_ref()
-                    .map(|m| m.username.as_str())
-                    .unwrap_or("Unknown");
-                let reply_time = format_relative_time(reply.created);
-
-                let header_line = Line::from(vec![
-                    Span::styled(
-                        format!("Reply #{} by ", index + 1),
-                        Style::default()
-                            .fg(theme.primary)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled(author, Style::default().fg(theme.accent)),
-                    Span::styled(
-                        format!(" • {}", reply_time),
-                        Style::default().fg(theme.muted),
-                    ),
-                ]);
+                let content_width = area.width.saturating_sub(4) as usize;
+                let content = match link_labels.filter(|_| is_selected) {
+                    Some(labels) => render::labelled(content_text, content_width, theme, labels),
+                    None => render::styled(content_text, content_width, theme),
+                };
+
+                let mut lines = vec![reply_header(index, reply, op, theme)];
+                lines.extend(content.iter().cloned());
+                if let Some((search, _)) = search {
+                    lines = lines
+                        .into_iter()
+                        .map(|line| highlight_matches(line, search, theme))
+                        .collect();
+                }
+                if is_selected {
+                    lines = lines
+                        .into_iter()
+                        .map(|line| highlight_line(line, row_width, theme))
+                        .collect();
+                }
 
-                let mut all_lines = vec![header_line];
                 all_lines.extend(lines);
                 all_lines.push(Line::from(""));
 
@@ -328,15 +313,19 @@ impl TopicDetailView {
             })
             .collect();
 
-        // Build title with reply count info
-        let title = if has_more {
-            format!(
-                " Replies ({}/{} - press + for more) ",
-                loaded_replies, total_replies
-            )
-        } else {
-            format!(" Replies ({}/{}) ", loaded_replies, total_replies)
-        };
+        drop(replies_scope);
+        let heights: Vec<u16> = items.iter().map(|item| item.height() as u16).collect();
+
+        let selected = list_state
+            .selected()
+            .and_then(|position| order.get(position));
+        let mut title = replies_title(selected.copied(), loaded_replies, total_replies);
+        if let Some(mode) = mode {
+            title = format!("{}[{}: {} shown] ", title, mode, order.len());
+        }
+        if let Some((search, matches)) = search {
+            title = format!("{}[/{}/: {} matching] ", title, search.pattern, matches);
+        }
 
         let list = List::new(items)
             .block(
@@ -345,14 +334,292 @@ impl TopicDetailView {
                     .border_style(Style::default().fg(theme.primary))
                     .title(title),
             )
-            .highlight_style(
-                Style::default()
-                    .bg(theme.primary)
-                    .fg(theme.background)
-                    .add_modifier(Modifier::BOLD),
-            );
+            // Rows of the selected reply carry their own colours
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
         frame.render_stateful_widget(list, area, list_state);
+        let total_height = heights.iter().map(|&h| h as usize).sum();
+        let position = heights
+            .iter()
+            .take(list_state.offset())
+            .map(|&h| h as usize)
+            .sum();
+        render_scrollbar(frame, area, total_height, position, theme);
+
+        ListRegion::bordered_with_heights(area, list_state.offset(), heights)
+    }
+}
+
+/// Paint `line` in the selection colours across `width` cells. Padding is
+/// measured in display width, so rows with CJK text fill exactly; spans
+/// with a background of their own (the OP badge) keep it.
+fn highlight_line(line: Line<'static>, width: usize, theme: &Theme) -> Line<'static> {
+    let selected = Style::default()
+        .bg(theme.selection)
+        .fg(theme.selection_text);
+    let padding = width.saturating_sub(line.width());
+    let mut spans: Vec<Span<'static>> = line
+        .spans
+        .into_iter()
+        .map(|span| {
+            if span.style.bg.is_some() {
+                span
+            } else {
+                let style = span.style.patch(selected);
+                span.style(style)
+            }
+        })
+        .collect();
+    if padding > 0 {
+        spans.push(Span::styled(" ".repeat(padding), selected));
+    }
+    Line::from(spans)
+}
+
+/// `line` with the text `search` finds picked out. Each line is searched
+/// as drawn, so a match broken across lines isn't marked.
+fn highlight_matches(line: Line<'static>, search: &SearchPattern, theme: &Theme) -> Line<'static> {
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let ranges = search.find_ranges(&text);
+    if ranges.is_empty() {
+        return line;
+    }
+    let found = Style::default()
+        .fg(theme.background)
+        .bg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let line_style = line.style;
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for span in line.spans {
+        let end = start + span.content.len();
+        // Split the span where matches begin and end inside it
+        let mut cuts = vec![start, end];
+        for range in &ranges {
+            cuts.extend(
+                [range.start, range.end]
+                    .into_iter()
+                    .filter(|&at| at > start && at < end),
+            );
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+        for piece in cuts.windows(2) {
+            let (from, to) = (piece[0], piece[1]);
+            let matched = ranges
+                .iter()
+                .any(|range| range.start <= from && to <= range.end);
+            let style = if matched {
+                span.style.patch(found)
+            } else {
+                span.style
+            };
+            spans.push(Span::styled(
+                span.content[from - start..to - start].to_string(),
+                style,
+            ));
+        }
+        start = end;
+    }
+    Line::from(spans).style(line_style)
+}
+
+/// `Reply #N by user [OP] • 3 hours ago [• ♥ N]`
+fn reply_header(
+    index: usize,
+    reply: &crate::api::Reply,
+    op: Option<&str>,
+    theme: &Theme,
+) -> Line<'static> {
+    let author = reply
+        .member
+        .as_ref()
+        .map(|m| m.username.as_str())
+        .unwrap_or("Unknown");
+    let mut spans = vec![
+        Span::styled(
+            format!("Reply #{} by ", index + 1),
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(author.to_string(), Style::default().fg(theme.accent)),
+    ];
+    if reply.member.is_some() && op == Some(author) {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            " OP ",
+            Style::default()
+                .fg(theme.background)
+                .bg(theme.op_badge)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.push(Span::styled(
+        format!(" • {}", format_relative_time(reply.created)),
+        Style::default().fg(theme.muted),
+    ));
+    if reply.thanks > 0 {
+        spans.push(Span::styled(
+            format!(" • ♥ {}", reply.thanks),
+            Style::default().fg(theme.thanks),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// `" | Clicks: N | Favorites: N | Thanks: N"` for the counters that are known
+fn stats_summary(stats: Option<&TopicStats>, counts: CountFormat) -> String {
+    let Some(stats) = stats else {
+        return String::new();
+    };
+    [
+        ("Clicks", stats.clicks),
+        ("Favorites", stats.favorites),
+        ("Thanks", stats.thanks),
+    ]
+    .iter()
+    .filter_map(|(label, count)| {
+        count.map(|count| format!(" | {}: {}", label, format_count(count, counts)))
+    })
+    .collect()
+}
+
+/// Rows `text` takes once wrapped to `width` cells
+fn wrapped_height(text: &Text, width: usize) -> usize {
+    let width = width.max(1);
+    text.lines
+        .iter()
+        .map(|line| {
+            let line_width: usize = line.spans.iter().map(|span| span.content.width()).sum();
+            line_width.div_ceil(width).max(1)
+        })
+        .sum()
+}
+
+/// Scrollbar over the right border of the bordered `area`, for `total` rows
+/// scrolled down by `position`; nothing when everything fits
+fn render_scrollbar(frame: &mut Frame, area: Rect, total: usize, position: usize, theme: &Theme) {
+    let viewport = area.height.saturating_sub(2) as usize;
+    if total <= viewport {
+        return;
+    }
+    let max_scroll = total - viewport;
+    let mut state = ScrollbarState::new(max_scroll + 1)
+        .viewport_content_length(viewport)
+        .position(position.min(max_scroll));
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_style(Style::default().fg(theme.muted))
+        .thumb_style(Style::default().fg(theme.accent));
+    frame.render_stateful_widget(
+        scrollbar,
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
+
+/// Centred column of at most `width` cells, leaving a margin of one cell
+fn focus_column(area: Rect, width: u16) -> Rect {
+    let available = area.width.saturating_sub(2);
+    let width = if width == 0 {
+        available
+    } else {
+        width.min(available)
+    };
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y.saturating_add(1).min(area.bottom()),
+        width,
+        height: area.height.saturating_sub(1),
+    }
+}
+
+/// Replies pane title locating the selected reply among all pages
+fn replies_title(selected: Option<usize>, loaded: usize, total: usize) -> String {
+    let pages = total.div_ceil(REPLIES_PER_PAGE).max(1);
+    let mut title = match selected {
+        Some(index) => format!(
+            " Replies: page {} of {} ({}/{}",
+            index / REPLIES_PER_PAGE + 1,
+            pages,
+            index + 1,
+            total
+        ),
+        None => format!(" Replies ({}", total),
+    };
+    if loaded < total {
+        title.push_str(&format!(", {} loaded - + for more, G to jump", loaded));
+    }
+    title.push_str(") ");
+    title
+}
+
+impl Component for TopicDetailView {
+    fn name(&self) -> &'static str {
+        "TopicDetailView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&TopicDetailKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        let Some(topic) = &app.topic_state.current else {
+            return MouseRegions::default();
+        };
+        let link_labels = app.topic_state.link_labels(&app.link_titles);
+        if !app.topic_state.show_replies {
+            return self.render(
+                frame,
+                area,
+                topic,
+                app.topic_state.stats.as_ref(),
+                app.topic_state.scroll,
+                link_labels.as_deref(),
+                app.config.count_format,
+                &app.ui_state.theme,
+            );
+        }
+        let (topic_area, replies_area) = app.ui_state.split.areas(area);
+        let reply_order = app.topic_state.reply_order();
+        let reply_mode = app.topic_state.reply_mode_label();
+        let reply_matches = app.topic_state.reply_matches().len();
+        let position = app.topic_state.reply_position();
+        // Rendered from a copy, as the replies stay borrowed meanwhile
+        let mut replies_list_state = app.topic_state.replies_list_state.clone();
+        replies_list_state.select(position);
+        let rendered = self.render_split(
+            frame,
+            topic_area,
+            replies_area,
+            topic,
+            app.topic_state.stats.as_ref(),
+            app.topic_state.scroll,
+            link_labels.as_deref(),
+            app.topic_state.replies(),
+            &reply_order,
+            reply_mode.as_deref(),
+            app.topic_state
+                .reply_search
+                .as_ref()
+                .map(|search| (search, reply_matches)),
+            &mut replies_list_state,
+            app.topic_state.first_unread,
+            app.config.blocked_users(app.ui_state.reveal_blocked),
+            app.config.count_format,
+            &app.ui_state.theme,
+        );
+        app.topic_state.replies_list_state = replies_list_state;
+        rendered
     }
 }
 
@@ -366,4 +633,187 @@ mod tests {
         // Simple test to verify the view can be created
         assert!(true); // Placeholder assertion
     }
+
+    #[test]
+    fn test_highlight_matches() {
+        let theme = Theme::default();
+        let line = Line::from(vec![Span::raw("I like "), Span::raw("Rust, rust")]);
+        let search = SearchPattern::new("rust").unwrap();
+        let highlighted = highlight_matches(line, &search, &theme);
+        let pieces: Vec<(&str, bool)> = highlighted
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.bg.is_some()))
+            .collect();
+        assert_eq!(
+            pieces,
+            [
+                ("I like ", false),
+                ("Rust", true),
+                (", ", false),
+                ("rust", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_summary() {
+        let stats = TopicStats {
+            clicks: Some(120),
+            favorites: None,
+            thanks: Some(3),
+            ..Default::default()
+        };
+        let counts = CountFormat::Western;
+        assert_eq!(
+            stats_summary(Some(&stats), counts),
+            " | Clicks: 120 | Thanks: 3"
+        );
+        assert_eq!(stats_summary(None, counts), "");
+    }
+
+    #[test]
+    fn test_focus_column() {
+        let area = Rect::new(0, 0, 120, 40);
+        assert_eq!(focus_column(area, 80), Rect::new(20, 1, 80, 39));
+        // Narrow screens and width 0 use everything but the margin
+        assert_eq!(focus_column(area, 0), Rect::new(1, 1, 118, 39));
+        assert_eq!(
+            focus_column(Rect::new(0, 0, 60, 20), 80),
+            Rect::new(1, 1, 58, 19)
+        );
+    }
+
+    #[test]
+    fn test_reply_header() {
+        let theme = Theme::default();
+        let reply: crate::api::Reply = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "member": { "username": "alice" },
+            "created": 0,
+        }))
+        .unwrap();
+        let mut thanked = reply.clone();
+        thanked.thanks = 3;
+        let text = |line: Line| {
+            line.spans
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect::<String>()
+        };
+
+        let header = reply_header(4, &reply, Some("alice"), &theme);
+        assert!(header
+            .spans
+            .iter()
+            .any(|s| s.style.bg == Some(theme.op_badge)));
+        let header = text(header);
+        assert!(
+            header.starts_with("Reply #5 by alice  OP  • "),
+            "{}",
+            header
+        );
+
+        let header = text(reply_header(0, &reply, Some("bob"), &theme));
+        assert!(!header.contains("OP"));
+        assert!(!header.contains('♥'));
+
+        let header = reply_header(0, &thanked, None, &theme);
+        let last = header.spans.last().unwrap();
+        assert_eq!(last.content, " • ♥ 3");
+        assert_eq!(last.style.fg, Some(theme.thanks));
+    }
+
+    #[test]
+    fn test_reply_thanks_from_the_topic_page_are_drawn() {
+        use crate::{config::Config, views::screen_rows};
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut app = App::new(Config::default());
+        app.topic_state.current = Some(
+            serde_json::from_value(serde_json::json!({ "id": 1, "title": "Thanks", "replies": 2 }))
+                .unwrap(),
+        );
+        app.topic_state.set_replies(
+            [(10, "alice"), (11, "bob")]
+                .iter()
+                .map(|(id, user)| {
+                    serde_json::from_value(serde_json::json!({
+                        "id": id,
+                        "member": { "username": user },
+                        "content": "hello",
+                    }))
+                    .unwrap()
+                })
+                .collect(),
+        );
+        app.topic_state.show_replies = true;
+        // The counts arrive with the page after the replies are shown
+        app.topic_state.set_stats(Some(TopicStats::from_html(
+            r#"<div id="r_11" class="cell"><img src="/static/img/heart.png" /> 4</div>"#,
+        )));
+        assert_eq!(app.topic_state.replies()[1].thanks, 4);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| {
+                TopicDetailView.draw(frame, frame.area(), &mut app);
+            })
+            .unwrap();
+        let screen = screen_rows(terminal.backend().buffer());
+        let header = |user: &str| {
+            screen
+                .iter()
+                .find(|row| row.contains(&format!(" by {}", user)))
+                .unwrap_or_else(|| panic!("no header for {}: {:#?}", user, screen))
+                .clone()
+        };
+        assert!(header("bob").contains("• ♥ 4"));
+        assert!(!header("alice").contains('♥'));
+    }
+
+    #[test]
+    fn test_highlight_line_fills_cjk_rows() {
+        let theme = Theme::default();
+        let line = Line::from(vec![
+            Span::raw("中文"),
+            Span::styled(" OP ", Style::default().bg(theme.op_badge)),
+        ]);
+        let highlighted = highlight_line(line, 12, &theme);
+        assert_eq!(highlighted.width(), 12);
+        assert_eq!(highlighted.spans[0].style.bg, Some(theme.selection));
+        assert_eq!(highlighted.spans[0].style.fg, Some(theme.selection_text));
+        assert_eq!(highlighted.spans[1].style.bg, Some(theme.op_badge));
+        assert_eq!(highlighted.spans[2].content, "    ");
+
+        // Lines already as wide as the row get no padding
+        let full = highlight_line(Line::from("中文中文中文"), 12, &theme);
+        assert_eq!(full.spans.len(), 1);
+    }
+
+    #[test]
+    fn test_wrapped_height() {
+        let text = Text::from(vec![
+            Line::from("0123456789"),
+            Line::from(""),
+            Line::from(vec![Span::raw("中文"), Span::raw("abcdefg")]),
+        ]);
+        // 10 cells -> 3 rows, blank -> 1, 11 cells -> 3 rows
+        assert_eq!(wrapped_height(&text, 4), 7);
+        assert_eq!(wrapped_height(&text, 80), 3);
+        assert_eq!(wrapped_height(&text, 0), 22);
+    }
+
+    #[test]
+    fn test_replies_title() {
+        assert_eq!(
+            replies_title(Some(44), 60, 350),
+            " Replies: page 3 of 18 (45/350, 60 loaded - + for more, G to jump) "
+        );
+        assert_eq!(
+            replies_title(Some(0), 12, 12),
+            " Replies: page 1 of 1 (1/12) "
+        );
+        assert_eq!(replies_title(None, 0, 0), " Replies (0) ");
+    }
 }
@@ -0,0 +1,88 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    api::Topic,
+    render,
+    ui::{centered_rect, Theme},
+    util::format_relative_time,
+};
+
+/// Popup over the topic list with the start of a topic's content
+pub struct TopicPreviewView;
+
+impl TopicPreviewView {
+    pub fn render(&self, frame: &mut Frame, area: Rect, topic: &Topic, lines: u64, theme: &Theme) {
+        let area = centered_rect(80, 60, area);
+        let width = area.width.saturating_sub(2) as usize;
+        let label = Style::default().fg(theme.muted);
+
+        let content = topic
+            .content_rendered
+            .as_deref()
+            .or(topic.content.as_deref())
+            .unwrap_or("No content");
+        let rendered = render::styled(content, width, theme);
+        let (shown, more) = first_lines(&rendered, lines as usize);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                topic.title.clone(),
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "{} · {} · {} replies · {}",
+                    topic.node_title(),
+                    topic.author_name(),
+                    topic.replies,
+                    format_relative_time(topic.created)
+                ),
+                label,
+            )),
+            Line::from(""),
+        ];
+        text.extend(shown.iter().cloned());
+        if more {
+            text.push(Line::from(Span::styled("…", label)));
+        }
+
+        let popup = Paragraph::new(text)
+            .style(Style::default().fg(theme.foreground))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .style(Style::default().bg(theme.background))
+                    .title(" Preview - n/p next/previous · RET open · any other key closes "),
+            );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+}
+
+/// The first `count` lines and whether any were left out
+fn first_lines<'a>(lines: &'a [Line<'static>], count: usize) -> (&'a [Line<'static>], bool) {
+    (&lines[..count.min(lines.len())], lines.len() > count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_lines() {
+        let lines = vec![Line::from("a"), Line::from("b"), Line::from("c")];
+        assert_eq!(first_lines(&lines, 2), (&lines[..2], true));
+        assert_eq!(first_lines(&lines, 3), (&lines[..], false));
+        assert_eq!(first_lines(&lines, 9), (&lines[..], false));
+    }
+}
@@ -0,0 +1,37 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    translate::Translation,
+    ui::{centered_rect, Theme},
+};
+
+/// Popup with the translation of a reply or the topic
+pub struct TranslationView;
+
+impl TranslationView {
+    pub fn render(&self, frame: &mut Frame, area: Rect, translation: &Translation, theme: &Theme) {
+        let area = centered_rect(80, 70, area);
+        let popup = Paragraph::new(translation.text.as_str())
+            .wrap(Wrap { trim: false })
+            .scroll((translation.scroll, 0))
+            .style(Style::default().fg(theme.foreground))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary))
+                    .style(Style::default().bg(theme.background))
+                    .title(format!(
+                        " Translation of {} - n/p scroll, any other key closes ",
+                        translation.label
+                    )),
+            );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+}
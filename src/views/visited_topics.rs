@@ -0,0 +1,169 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::keymap::{KeyMap, VisitedTopicsKeyMap};
+use crate::{
+    app::{App, View},
+    keymap::{prompt_hints, VISITED_TOPICS_HINTS},
+    mouse::{ListRegion, MouseRegions},
+    read_tracker::VisitedTopic,
+    state::VisitedState,
+    ui::Theme,
+    util::format_relative_time,
+    views::Component,
+};
+
+/// Every topic opened before, searchable by title, node or id
+pub struct VisitedTopicsView;
+
+impl VisitedTopicsView {
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        entries: &[VisitedTopic],
+        state: &VisitedState,
+        hints: &str,
+        theme: &Theme,
+    ) -> MouseRegions {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let mut input_text = state.prompt.line(theme);
+        if state.prompt.is_empty() {
+            input_text
+                .spans
+                .push(Span::styled(hints, Style::default().fg(theme.muted)));
+        }
+        let input = Paragraph::new(input_text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary))
+                .title(" Visited Topics "),
+        );
+        frame.render_widget(input, chunks[0]);
+
+        let items: Vec<ListItem> = state
+            .matches
+            .iter()
+            .map(|&i| {
+                let entry = &entries[i];
+                let mut spans = vec![Span::styled(
+                    format!("{:>10} ", format_relative_time(entry.visited_at)),
+                    Style::default().fg(theme.muted),
+                )];
+                if !entry.node.is_empty() {
+                    spans.push(Span::styled(
+                        format!("[{}] ", entry.node),
+                        Style::default().fg(theme.accent),
+                    ));
+                }
+                spans.push(Span::styled(
+                    entry.title.clone(),
+                    Style::default().fg(theme.foreground),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.secondary))
+                    .title(format!(" {} of {} ", state.matches.len(), entries.len())),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.primary)
+                    .fg(theme.background)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let mut list_state = ListState::default();
+        if !state.matches.is_empty() {
+            list_state.select(Some(state.selected));
+        }
+        frame.render_stateful_widget(list, chunks[1], &mut list_state);
+
+        MouseRegions::with_list(ListRegion::bordered_with_heights(
+            chunks[1],
+            list_state.offset(),
+            vec![1; state.matches.len()],
+        ))
+    }
+}
+
+impl Component for VisitedTopicsView {
+    fn name(&self) -> &'static str {
+        "VisitedTopicsView::render"
+    }
+
+    fn keymap(&self) -> Option<&'static dyn KeyMap> {
+        Some(&VisitedTopicsKeyMap)
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &mut App) -> MouseRegions {
+        self.render(
+            frame,
+            area,
+            app.visited_topics.entries(),
+            &app.visited_state,
+            &prompt_hints(
+                &app.config.key_bindings,
+                View::History,
+                VISITED_TOPICS_HINTS,
+            ),
+            &app.ui_state.theme,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_only_matches_are_listed() {
+        let entry = |id, title: &str, node: &str| VisitedTopic {
+            id,
+            title: title.to_string(),
+            node: node.to_string(),
+            visited_at: 0,
+        };
+        let entries = [entry(1, "Rust 2024", "rust"), entry(2, "Go modules", "go")];
+        let mut state = VisitedState::default();
+        for ch in "rust".chars() {
+            state.prompt.insert_char(ch);
+        }
+        state.update_matches(&entries);
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal
+            .draw(|frame| {
+                VisitedTopicsView.render(
+                    frame,
+                    frame.area(),
+                    &entries,
+                    &state,
+                    "RET open",
+                    &Theme::default(),
+                );
+            })
+            .unwrap();
+
+        let screen = crate::views::screen_rows(terminal.backend().buffer()).join("\n");
+        assert!(screen.contains("[rust] Rust 2024"));
+        assert!(!screen.contains("Go modules"));
+        assert!(screen.contains(" 1 of 2 "));
+        // Hints only show while nothing is typed
+        assert!(!screen.contains("RET open"));
+    }
+}